@@ -0,0 +1,81 @@
+// Serial vs parallel timings for the rayon-backed analytics
+//
+//   cargo bench --features parallel
+//
+// Each workload runs once on a single-thread pool and once on the default
+// pool (one thread per core) over the same generated graph.
+
+use chrono::{TimeZone, Utc};
+use project_manager::analytics::{monte_carlo, workload, SimulationConfig};
+use project_manager::core::graph::{DependencyType, ProjectGraph};
+use project_manager::core::{NodeBuilder, Scope, Timeline};
+use project_manager::scheduler::{critical_path, schedule};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+// One project, `epics` epics of 10 stories with 10 chained tasks each
+fn generate(epics: u128) -> (ProjectGraph, Uuid){
+    let mut graph = ProjectGraph::new();
+    let mut next = 0u128;
+    let mut id = || { next += 1; Uuid::from_u128(next) };
+    let start = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let timeline = |day: i64| Timeline::from_start_end(start + chrono::Duration::days(day), start + chrono::Duration::days(day + 2));
+
+    let project = NodeBuilder::new().with_id(id()).with_name("Bench".into()).build_project().unwrap();
+    graph.add_node(&project).unwrap();
+
+    for e in 0..epics{
+        let epic = NodeBuilder::new().with_id(id()).with_name(format!("Epic {}", e)).with_timeline(timeline(0)).build_epic().unwrap();
+        graph.add_node(&epic).unwrap();
+        graph.connect(project.get_id(), epic.get_id(), DependencyType::Contains).unwrap();
+
+        for s in 0..10{
+            let story = NodeBuilder::new().with_id(id()).with_name(format!("Story {}.{}", e, s)).with_timeline(timeline(0)).build_userstory().unwrap();
+            graph.add_node(&story).unwrap();
+            graph.connect(epic.get_id(), story.get_id(), DependencyType::Contains).unwrap();
+
+            let mut previous: Option<Uuid> = None;
+            for t in 0..10{
+                let task = NodeBuilder::new().with_id(id()).with_name(format!("Task {}.{}.{}", e, s, t))
+                    .with_timeline(timeline(0)).with_owner(format!("dev{}", (e + t) % 12)).with_points(t as u32 % 5 + 1)
+                    .build_tasks().unwrap();
+                graph.add_node(&task).unwrap();
+                graph.connect(story.get_id(), task.get_id(), DependencyType::Contains).unwrap();
+                if let Some(prev) = previous{
+                    graph.connect(prev, task.get_id(), DependencyType::Blocks).unwrap();
+                }
+                previous = Some(task.get_id());
+            }
+        }
+    }
+    (graph, project.get_id())
+}
+
+fn time<T>(threads: usize, f: impl Fn() -> T + Send + Sync) -> Duration where T: Send{
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+    pool.install(|| {
+        let started = Instant::now();
+        std::hint::black_box(f());
+        started.elapsed()
+    })
+}
+
+fn compare<T: Send>(name: &str, f: impl Fn() -> T + Send + Sync){
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let serial = time(1, &f);
+    let parallel = time(cores, &f);
+    println!("{:<16} 1 thread {:>10.1?}   {} threads {:>10.1?}   speedup {:.2}x",
+        name, serial, cores, parallel, serial.as_secs_f64() / parallel.as_secs_f64());
+}
+
+fn main(){
+    let (graph, project) = generate(20);
+    println!("{} nodes", graph.nodes().count());
+
+    let sched = schedule(&graph).unwrap();
+    compare("critical_path", || critical_path(&graph, &sched));
+    compare("workload", || workload(&graph, &Scope::All));
+
+    let config = SimulationConfig{ trials: 64, ..SimulationConfig::default() };
+    compare("monte_carlo", || monte_carlo(&graph, project, &config).unwrap());
+}
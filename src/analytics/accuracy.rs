@@ -0,0 +1,128 @@
+// Estimate accuracy - how planned durations and points compared to what
+// finished work actually took, per owner or team
+//
+// Actual start is the first time a node left Not Started and actual finish
+// the last time it became Done, both from the event log. Points are turned
+// into expected hours through the owner's team (hours_per_day over
+// points_per_day). Bias factors are ratios of totals, so a long task weighs
+// more than a short one; above 1.0 means work took longer than planned.
+
+use crate::core::estimate;
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Status};
+use chrono::TimeDelta;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject{
+    Owner(String),
+    Team(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct AccuracySample{
+    pub node: Uuid,
+    pub owner: String,
+    pub planned: TimeDelta,
+    pub actual: TimeDelta,
+    // Points as estimated before work started, when known
+    pub points: Option<u32>,
+    pub expected_hours: Option<f64>,
+    pub logged_hours: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccuracyReport{
+    pub samples: Vec<AccuracySample>,
+    // Actual over planned duration
+    pub duration_bias: Option<f64>,
+    // Logged over expected hours, for samples with points and worklogs
+    pub effort_bias: Option<f64>,
+}
+
+impl AccuracyReport{
+    // Multiplier to apply to planned durations; 1.0 without history
+    pub fn bias(&self) -> f64{
+        self.duration_bias.unwrap_or(1.0)
+    }
+}
+
+// Points as they stood when work began: the consensus of votes cast before
+// the actual start, else whatever the node carries now
+fn original_points(graph: &ProjectGraph, node: &Node, started: chrono::DateTime<chrono::Utc>) -> Option<u32>{
+    let early: Vec<_> = graph.get_estimates(node.get_id()).iter()
+        .filter(|e| e.timestamp <= started)
+        .cloned()
+        .collect();
+    estimate::consensus(&early, graph.get_consensus_method()).or(node.get_points())
+}
+
+fn sample(graph: &ProjectGraph, node: &Node) -> Option<AccuracySample>{
+    let owner = node.get_owner()?;
+    if !node.is_done() || !graph.get_children(node.get_id()).is_empty(){
+        return None;
+    }
+    let timeline = node.get_timeline()?;
+    let planned = timeline.end? - timeline.start;
+    let events = graph.get_events();
+    let started = events.first_transition_from(node.get_id(), Status::NotStarted)?;
+    let finished = events.last_transition_to(node.get_id(), Status::Done)?;
+    if planned <= TimeDelta::zero() || finished < started{
+        return None;
+    }
+
+    let points = original_points(graph, node, started);
+    let expected_hours = points.map(|p| {
+        let team = graph.team_of(owner).cloned().unwrap_or_else(|| graph.get_settings().team(String::new()));
+        p as f64 * team.hours_per_day / team.points_per_day
+    });
+    Some(AccuracySample{
+        node: node.get_id(),
+        owner: owner.to_string(),
+        planned,
+        actual: finished - started,
+        points,
+        expected_hours,
+        logged_hours: graph.get_worklogs(node.get_id()).iter().map(|w| w.hours).sum(),
+    })
+}
+
+fn report(samples: Vec<AccuracySample>) -> AccuracyReport{
+    let planned: i64 = samples.iter().map(|s| s.planned.num_seconds()).sum();
+    let actual: i64 = samples.iter().map(|s| s.actual.num_seconds()).sum();
+    let duration_bias = (planned > 0).then(|| actual as f64 / planned as f64);
+
+    let (expected, logged) = samples.iter()
+        .filter(|s| s.logged_hours > 0.0)
+        .filter_map(|s| s.expected_hours.map(|e| (e, s.logged_hours)))
+        .fold((0.0, 0.0), |(e, l), (se, sl)| (e + se, l + sl));
+    let effort_bias = (expected > 0.0).then(|| logged / expected);
+
+    AccuracyReport{ samples, duration_bias, effort_bias }
+}
+
+pub fn estimation_accuracy(graph: &ProjectGraph, subject: &Subject) -> Result<AccuracyReport,&'static str>{
+    let members: Vec<String> = match subject{
+        Subject::Owner(owner) => vec![owner.clone()],
+        Subject::Team(name) => graph.get_team(name).ok_or("The team does not exist")?.members.clone(),
+    };
+    let mut samples: Vec<AccuracySample> = members.iter()
+        .flat_map(|m| graph.query().owner(m).run())
+        .filter_map(|n| sample(graph, n))
+        .collect();
+    samples.sort_by_key(|s| s.node);
+    Ok(report(samples))
+}
+
+// Duration bias of every owner with finished, tracked work; feed it to
+// SimulationConfig::with_bias
+pub fn owner_bias(graph: &ProjectGraph) -> HashMap<String,f64>{
+    let mut by_owner: BTreeMap<String,Vec<AccuracySample>> = BTreeMap::new();
+    for s in graph.nodes().filter_map(|n| sample(graph, n)){
+        by_owner.entry(s.owner.clone()).or_default().push(s);
+    }
+    by_owner.into_iter()
+        .filter_map(|(owner, samples)| report(samples).duration_bias.map(|b| (owner, b)))
+        .collect()
+}
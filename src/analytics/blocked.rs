@@ -0,0 +1,37 @@
+// Blocked time - how long work spent Blocked, by the reason it was blocked
+// for; periods still open count up to `now`
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Scope;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedTime{
+    pub reason: String,
+    pub periods: usize,
+    pub nodes: usize,
+    // Periods not over yet
+    pub open: usize,
+    pub total: TimeDelta,
+}
+
+// Longest total first
+pub fn blocked_time(graph: &ProjectGraph, scope: &Scope, now: DateTime<Utc>) -> Vec<BlockedTime>{
+    let mut by_reason: BTreeMap<String,(BTreeSet<Uuid>, usize, usize, TimeDelta)> = BTreeMap::new();
+    for node in graph.nodes_in_scope(scope){
+        for period in graph.blocked_periods(node.get_id()){
+            let entry = by_reason.entry(period.category().to_string()).or_default();
+            entry.0.insert(node.get_id());
+            entry.1 += 1;
+            entry.2 += usize::from(period.end.is_none());
+            entry.3 += period.duration(now);
+        }
+    }
+    let mut rows: Vec<BlockedTime> = by_reason.into_iter()
+        .map(|(reason, (nodes, periods, open, total))| BlockedTime{ reason, periods, nodes: nodes.len(), open, total })
+        .collect();
+    rows.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.reason.cmp(&b.reason)));
+    rows
+}
@@ -0,0 +1,222 @@
+// Scope churn - points added to and removed from an Epic or Release after
+// planning, week by week
+//
+// Planning ends when the release is cut, or for an Epic at the start of its
+// timeline. After that, from the event log:
+//   - a leaf joining the Epic (or an item in the release) adds its points,
+//     and one leaving it, moved elsewhere or deleted, removes them
+//   - a re-estimate adds or removes the difference
+//   - an item put into or taken out of a cut release adds or removes the
+//     points of its open and done leaves alike
+// Whether a leaf was in scope at the time of an event, and its points then,
+// are worked back from the later parent and points changes it logged. Items
+// moved in or out of a release are taken at today's points, since the log
+// records the move but not the size at the time.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::release::ScopeChangeKind;
+use crate::core::{Event, EventKind, Node};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChurnSubject{
+    Epic(Uuid),
+    Release(Uuid),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChurnWeek{
+    // Monday the week starts on
+    pub week: NaiveDate,
+    pub added: u32,
+    pub removed: u32,
+}
+
+impl ChurnWeek{
+    pub fn net(&self) -> i64{
+        self.added as i64 - self.removed as i64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeChurn{
+    pub subject: ChurnSubject,
+    pub name: String,
+    pub planned_at: DT,
+    // Points when planning ended: today's points less the net change since
+    pub planned_points: i64,
+    pub current_points: u32,
+    // Every week from planning to the last change, quiet weeks included
+    pub weeks: Vec<ChurnWeek>,
+}
+
+impl ScopeChurn{
+    pub fn added(&self) -> u32{
+        self.weeks.iter().map(|w| w.added).sum()
+    }
+
+    pub fn removed(&self) -> u32{
+        self.weeks.iter().map(|w| w.removed).sum()
+    }
+
+    // Points changed in either direction per planned point
+    pub fn ratio(&self) -> Option<f64>{
+        (self.planned_points > 0).then(|| (self.added() + self.removed()) as f64 / self.planned_points as f64)
+    }
+}
+
+fn monday(at: DT) -> NaiveDate{
+    let day = at.date_naive();
+    day - Days::new(day.weekday().num_days_from_monday() as u64)
+}
+
+fn leaves(graph: &ProjectGraph, root: Uuid) -> Vec<Uuid>{
+    graph.get_subtree(root).into_iter()
+        .filter(|id| graph.get_children(*id).is_empty())
+        .collect()
+}
+
+fn leaf_points(graph: &ProjectGraph, root: Uuid) -> u32{
+    leaves(graph, root).into_iter()
+        .filter_map(|id| graph.get_node(id).and_then(|n| n.get_points()))
+        .sum()
+}
+
+// The first change to `field` the node logged after event `seq`
+fn next_change<'a>(graph: &'a ProjectGraph, id: Uuid, field: &'a str, seq: u64) -> Option<&'a Event>{
+    graph.get_events().field_changes(id, field).filter(|e| e.seq > seq).min_by_key(|e| e.seq)
+}
+
+// The last change to `field` the node logged, for nodes that are gone
+fn last_change<'a>(graph: &'a ProjectGraph, id: Uuid, field: &'a str) -> Option<&'a Event>{
+    graph.get_events().field_changes(id, field).max_by_key(|e| e.seq)
+}
+
+fn changed_from(event: &Event) -> &Value{
+    match &event.kind{
+        EventKind::FieldChanged{ from, .. } => from,
+        _ => &Value::Null,
+    }
+}
+
+fn changed_to(event: &Event) -> &Value{
+    match &event.kind{
+        EventKind::FieldChanged{ to, .. } => to,
+        _ => &Value::Null,
+    }
+}
+
+fn as_uuid(value: &Value) -> Option<Uuid>{
+    value.as_str().and_then(|v| v.parse().ok())
+}
+
+// The node's parent right after event `seq`
+fn parent_after(graph: &ProjectGraph, id: Uuid, seq: u64) -> Option<Uuid>{
+    match next_change(graph, id, "parent", seq){
+        Some(next) => as_uuid(changed_from(next)),
+        None if graph.get_node(id).is_some() => graph.get_parent(id),
+        None => last_change(graph, id, "parent").and_then(|e| as_uuid(changed_to(e))),
+    }
+}
+
+// The node's points right after event `seq`
+fn points_after(graph: &ProjectGraph, id: Uuid, seq: u64) -> u32{
+    let points = |v: &Value| v.as_u64().unwrap_or(0) as u32;
+    match next_change(graph, id, "points", seq){
+        Some(next) => points(changed_from(next)),
+        None => match graph.get_node(id){
+            Some(node) => node.get_points().unwrap_or(0),
+            None => last_change(graph, id, "points").map(|e| points(changed_to(e))).unwrap_or(0),
+        },
+    }
+}
+
+pub fn scope_churn(graph: &ProjectGraph, subject: ChurnSubject) -> Result<ScopeChurn,&'static str>{
+    // (roots whose leaves are in scope, when planning ended, release moves)
+    let (name, roots, planned_at, moves) = match subject{
+        ChurnSubject::Epic(id) => {
+            let Some(epic @ Node::Epic{..}) = graph.get_node(id) else {
+                return Err("Scope churn is tracked for Epics and Releases");
+            };
+            let start = epic.get_timeline().map(|tl| tl.start).ok_or("The epic has no timeline to plan against")?;
+            (epic.get_name().to_string(), vec![id], start, Vec::new())
+        }
+        ChurnSubject::Release(id) => {
+            let release = graph.get_release(id).ok_or("The release does not exist")?;
+            let cut = release.cut_date.ok_or("The release has not been cut yet")?;
+            let mut roots: Vec<Uuid> = release.get_scope().iter().copied().collect();
+            roots.sort();
+            let moves = release.scope_changes_after_cut().into_iter()
+                .map(|c| (c.at, c.kind, leaf_points(graph, c.node)))
+                .collect();
+            (release.name.clone(), roots, cut, moves)
+        }
+    };
+
+    let in_scope: HashSet<Uuid> = roots.iter().flat_map(|r| leaves(graph, *r)).collect();
+    let containers: HashSet<Uuid> = roots.iter().flat_map(|r| graph.get_subtree(*r)).collect();
+    let is_leaf = |id: Uuid| graph.get_node(id).is_none() || graph.get_children(id).is_empty();
+    let in_scope_after = |id: Uuid, seq: u64| roots.contains(&id) || parent_after(graph, id, seq).is_some_and(|p| containers.contains(&p));
+    let mut weeks: BTreeMap<NaiveDate,(u32,u32)> = BTreeMap::new();
+    let points = |v: &Value| v.as_u64().unwrap_or(0) as u32;
+
+    for event in graph.get_events().since(planned_at).filter(|e| e.at > planned_at && is_leaf(e.node)){
+        let (added, removed) = match &event.kind{
+            EventKind::FieldChanged{ field, from, to } if field == "points" => {
+                if !in_scope_after(event.node, event.seq){
+                    continue;
+                }
+                let (from, to) = (points(from), points(to));
+                (to.saturating_sub(from), from.saturating_sub(to))
+            }
+            // Moves between containers both in or both out of scope are not churn
+            EventKind::FieldChanged{ field, from, to } if field == "parent" => {
+                let joined = as_uuid(to).is_some_and(|p| containers.contains(&p));
+                let left = as_uuid(from).is_some_and(|p| containers.contains(&p));
+                let size = points_after(graph, event.node, event.seq);
+                match (joined, left){
+                    (true, false) => (size, 0),
+                    (false, true) => (0, size),
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        let week = weeks.entry(monday(event.at)).or_default();
+        week.0 += added;
+        week.1 += removed;
+    }
+    for (at, kind, points) in moves{
+        let week = weeks.entry(monday(at)).or_default();
+        match kind{
+            ScopeChangeKind::Added => week.0 += points,
+            ScopeChangeKind::Removed => week.1 += points,
+        }
+    }
+
+    let mut filled = Vec::new();
+    if let Some(last) = weeks.keys().next_back().copied(){
+        let mut week = monday(planned_at);
+        while week <= last{
+            let (added, removed) = weeks.get(&week).copied().unwrap_or_default();
+            filled.push(ChurnWeek{ week, added, removed });
+            week = week + Days::new(7);
+        }
+    }
+
+    let current_points: u32 = in_scope.iter().filter_map(|id| graph.get_node(*id).and_then(|n| n.get_points())).sum();
+    let net: i64 = filled.iter().map(|w| w.net()).sum();
+    Ok(ScopeChurn{
+        subject,
+        name,
+        planned_at,
+        planned_points: current_points as i64 - net,
+        current_points,
+        weeks: filled,
+    })
+}
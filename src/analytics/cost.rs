@@ -0,0 +1,134 @@
+// Budget vs burn per Project/Epic, with the basic earned value (EVM) figures
+//
+// Earned value at a past date leaves out work whose actual finish came after it.
+// Every amount is in the project's base currency, see core/money.rs.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Scope};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct CostRow{
+    pub id: Uuid,
+    pub name: String,
+    // Budget at completion: the node's own estimate, or the sum of its children's
+    pub budget: f64,
+    pub actual: f64,
+    pub planned_value: f64,
+    pub earned_value: f64,
+    // No other row in the report contains this one
+    pub top_level: bool,
+}
+
+impl CostRow{
+    pub fn variance(&self) -> f64{
+        self.budget - self.actual
+    }
+
+    // Cost performance index, EV / AC
+    pub fn cpi(&self) -> Option<f64>{
+        (self.actual > 0.0).then(|| self.earned_value / self.actual)
+    }
+
+    // Schedule performance index, EV / PV
+    pub fn spi(&self) -> Option<f64>{
+        (self.planned_value > 0.0).then(|| self.earned_value / self.planned_value)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CostReport{
+    pub rows: Vec<CostRow>,
+}
+
+// Totals count the top-level rows only; the rows below them are already in
+// their sums
+impl CostReport{
+    pub fn total_budget(&self) -> f64{
+        self.rows.iter().filter(|r| r.top_level).map(|r| r.budget).sum()
+    }
+
+    pub fn total_actual(&self) -> f64{
+        self.rows.iter().filter(|r| r.top_level).map(|r| r.actual).sum()
+    }
+}
+
+pub fn cost(graph: &ProjectGraph, scope: &Scope) -> CostReport{
+    cost_at(graph, scope, Utc::now())
+}
+
+// Same as `cost`, with planned value measured at `at` rather than now
+pub fn cost_at(graph: &ProjectGraph, scope: &Scope, at: DateTime<Utc>) -> CostReport{
+    let mut rows: Vec<CostRow> = graph.nodes_in_scope(scope)
+        .into_iter()
+        .filter(|n| matches!(n, Node::Project{..} | Node::Epic{..}))
+        .map(|n| {
+            let id = n.get_id();
+            let budget = budget(graph, id);
+            let actual = graph.get_subtree(id).into_iter().map(|d| graph.actual_cost(d)).sum();
+            let completion = completion_at(graph, id, at) / 100.0;
+
+            CostRow{
+                id,
+                name: n.get_name().to_string(),
+                budget,
+                actual,
+                planned_value: budget * planned_fraction(n, at),
+                earned_value: budget * completion,
+                top_level: true,
+            }
+        })
+        .collect();
+
+    let ids: HashSet<Uuid> = rows.iter().map(|r| r.id).collect();
+    for row in &mut rows{
+        row.top_level = !graph.get_ancestors(row.id).iter().any(|a| ids.contains(a));
+    }
+
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    CostReport{ rows }
+}
+
+fn completion_at(graph: &ProjectGraph, id: Uuid, at: DateTime<Utc>) -> f64{
+    let Some(mut rollup) = graph.rollup(id) else {
+        return 0.0;
+    };
+    for d in graph.get_subtree(id){
+        let Some(node) = graph.get_node(d).filter(|n| d != id && n.is_done()) else {
+            continue;
+        };
+        if graph.actual_finish(d).is_some_and(|finish| finish > at){
+            rollup.done_points = rollup.done_points.saturating_sub(node.get_points().unwrap_or(0));
+            rollup.done_items = rollup.done_items.saturating_sub(1);
+        }
+    }
+    if graph.actual_finish(id).is_some_and(|finish| finish > at){
+        rollup.self_done = false;
+    }
+    rollup.completion()
+}
+
+fn budget(graph: &ProjectGraph, id: Uuid) -> f64{
+    match graph.cost_in_base(id){
+        Some(cost) => cost,
+        None => graph.get_children(id).into_iter().map(|c| budget(graph, c)).sum(),
+    }
+}
+
+// Share of the node's timeline elapsed at `at`, assuming linear spend
+fn planned_fraction(node: &Node, at: DateTime<Utc>) -> f64{
+    let Some(tl) = node.get_timeline() else {
+        return 0.0;
+    };
+    let end = tl.end.unwrap_or(tl.start);
+
+    if at <= tl.start{
+        0.0
+    }else if at >= end{
+        1.0
+    }else{
+        (at - tl.start).num_seconds() as f64 / (end - tl.start).num_seconds() as f64
+    }
+}
@@ -0,0 +1,162 @@
+// Flow metrics from the status history
+//
+// Lead time runs from when an item was created (the timestamp in its v6 id
+// or its first logged status change, whichever is earlier) until it was last marked Done; cycle
+// time from when it first left Not Started until then. Blocked time adds up
+// every stretch spent in Blocked, counting a still-blocked item up to `now`.
+// Only leaf work items are measured; containers are summaries of them.
+// Recorded actual start and finish dates win over the log where present.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{EventKind, Node, Scope, Status};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone)]
+pub struct NodeFlow{
+    pub node: Uuid,
+    pub lead_time: Option<TimeDelta>,
+    pub cycle_time: Option<TimeDelta>,
+    pub blocked: TimeDelta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Percentiles{
+    pub p50: TimeDelta,
+    pub p85: TimeDelta,
+    pub p95: TimeDelta,
+}
+
+impl Percentiles{
+    fn of(mut values: Vec<TimeDelta>) -> Option<Self>{
+        if values.is_empty(){
+            return None;
+        }
+        values.sort();
+        let at = |p: f64| values[(p * (values.len() - 1) as f64).round() as usize];
+        Some(Percentiles{ p50: at(0.5), p85: at(0.85), p95: at(0.95) })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlowSummary{
+    // Epic key (name if it has none) or team name
+    pub group: String,
+    pub items: usize,
+    pub finished: usize,
+    pub lead_time: Option<Percentiles>,
+    pub cycle_time: Option<Percentiles>,
+    pub blocked: Option<Percentiles>,
+    pub total_blocked: TimeDelta,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlowReport{
+    pub nodes: Vec<NodeFlow>,
+    pub overall: FlowSummary,
+    pub by_epic: Vec<FlowSummary>,
+    pub by_team: Vec<FlowSummary>,
+}
+
+impl FlowReport{
+    pub fn get(&self, id: Uuid) -> Option<&NodeFlow>{
+        self.nodes.iter().find(|n| n.node == id)
+    }
+}
+
+fn created_at(graph: &ProjectGraph, id: Uuid) -> Option<DT>{
+    let from_id = id.get_timestamp().and_then(|ts| {
+        let (secs, nanos) = ts.to_unix();
+        DateTime::from_timestamp(secs as i64, nanos)
+    });
+    let first_event = graph.get_events().for_node(id).next().map(|e| e.at);
+    // Backdated history can predate the id
+    match (from_id, first_event){
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn blocked_time(graph: &ProjectGraph, id: Uuid, now: DT) -> TimeDelta{
+    let mut total = TimeDelta::zero();
+    let mut since: Option<DT> = None;
+    for e in graph.get_events().for_node(id){
+        let EventKind::StatusChanged{ from, to } = e.kind else {
+            continue;
+        };
+        if to == Status::Blocked && since.is_none(){
+            since = Some(e.at);
+        }else if from == Status::Blocked{
+            if let Some(start) = since.take(){
+                total += e.at - start;
+            }
+        }
+    }
+    if let Some(start) = since{
+        total += (now - start).max(TimeDelta::zero());
+    }
+    total
+}
+
+pub fn node_flow(graph: &ProjectGraph, id: Uuid, now: DT) -> Option<NodeFlow>{
+    let node = graph.get_node(id)?;
+    let events = graph.get_events();
+    let done = node.is_done().then(|| graph.actual_finish(id).or_else(|| events.last_transition_to(id, Status::Done))).flatten();
+    let started = graph.actual_start(id).or_else(|| events.first_transition_from(id, Status::NotStarted));
+    Some(NodeFlow{
+        node: id,
+        lead_time: done.zip(created_at(graph, id)).map(|(d, c)| d - c),
+        cycle_time: done.zip(started).map(|(d, s)| d - s),
+        blocked: blocked_time(graph, id, now),
+    })
+}
+
+fn summarize(group: String, flows: &[&NodeFlow]) -> FlowSummary{
+    FlowSummary{
+        group,
+        items: flows.len(),
+        finished: flows.iter().filter(|f| f.cycle_time.is_some() || f.lead_time.is_some()).count(),
+        lead_time: Percentiles::of(flows.iter().filter_map(|f| f.lead_time).collect()),
+        cycle_time: Percentiles::of(flows.iter().filter_map(|f| f.cycle_time).collect()),
+        blocked: Percentiles::of(flows.iter().map(|f| f.blocked).collect()),
+        total_blocked: flows.iter().map(|f| f.blocked).sum(),
+    }
+}
+
+fn epic_of(graph: &ProjectGraph, id: Uuid) -> Option<String>{
+    graph.get_ancestors(id).into_iter()
+        .filter_map(|a| graph.get_node(a))
+        .find(|n| matches!(n, Node::Epic{..}))
+        .map(|n| graph.get_key(n.get_id()).unwrap_or(n.get_name()).to_string())
+}
+
+pub fn flow_metrics(graph: &ProjectGraph, scope: &Scope, now: DT) -> FlowReport{
+    let items: Vec<&Node> = graph.nodes_in_scope(scope).into_iter()
+        .filter(|n| n.get_status().is_some() && graph.get_children(n.get_id()).is_empty())
+        .collect();
+    let mut nodes: Vec<NodeFlow> = items.iter().filter_map(|n| node_flow(graph, n.get_id(), now)).collect();
+    nodes.sort_by_key(|f| f.node);
+
+    let mut by_epic: BTreeMap<String,Vec<&NodeFlow>> = BTreeMap::new();
+    let mut by_team: BTreeMap<String,Vec<&NodeFlow>> = BTreeMap::new();
+    for flow in &nodes{
+        if let Some(epic) = epic_of(graph, flow.node){
+            by_epic.entry(epic).or_default().push(flow);
+        }
+        let team = graph.get_node(flow.node)
+            .and_then(|n| n.get_owner())
+            .and_then(|o| graph.team_of(o));
+        if let Some(team) = team{
+            by_team.entry(team.name.clone()).or_default().push(flow);
+        }
+    }
+
+    let all: Vec<&NodeFlow> = nodes.iter().collect();
+    let overall = summarize("All".to_string(), &all);
+    let by_epic = by_epic.into_iter().map(|(g, f)| summarize(g, &f)).collect();
+    let by_team = by_team.into_iter().map(|(g, f)| summarize(g, &f)).collect();
+    FlowReport{ nodes, overall, by_epic, by_team }
+}
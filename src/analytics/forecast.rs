@@ -0,0 +1,123 @@
+// Completion forecast for an epic
+//
+// Velocity comes from finished sprints: points of their items that were Done
+// by the sprint's end, per working day of the sprint. The remaining points of
+// the epic are burned down at the median velocity for the expected date and
+// at the best and worst tenth of sprints for the confidence band, starting no
+// earlier than now or the scheduled end of any unfinished predecessor of the
+// epic or its ancestors. Every sprint that ends feeds the next forecast.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Sprint, Status};
+use crate::scheduler::schedule;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone)]
+pub struct Forecast{
+    pub epic: Uuid,
+    pub remaining_points: u32,
+    // Earliest date work can continue, after blocking predecessors
+    pub start: DT,
+    pub sprints_sampled: usize,
+    // Median points per working day over the sampled sprints
+    pub velocity: f64,
+    pub expected: DT,
+    // P90 velocity; the date is only this early if the team keeps its best pace
+    pub optimistic: DT,
+    // P10 velocity; None when the slowest sprints finished no points at all
+    pub pessimistic: Option<DT>,
+}
+
+fn sprint_velocity(graph: &ProjectGraph, sprint: &Sprint) -> Option<f64>{
+    let days = graph.get_calendar().working_days(sprint.start.date_naive(), sprint.end.date_naive()).len();
+    if days == 0{
+        return None;
+    }
+    let done: u32 = sprint.get_items().iter()
+        .filter_map(|id| graph.get_node(*id))
+        .filter(|n| n.is_done())
+        .filter(|n| graph.get_events().last_transition_to(n.get_id(), Status::Done).is_none_or(|at| at <= sprint.end))
+        .filter_map(|n| n.get_points())
+        .sum();
+    Some(done as f64 / days as f64)
+}
+
+// Points per working day of every sprint that ended by `now`, slowest first
+pub fn velocity_history(graph: &ProjectGraph, now: DT) -> Vec<f64>{
+    let mut velocities: Vec<f64> = graph.sprints()
+        .filter(|s| s.end <= now)
+        .filter_map(|s| sprint_velocity(graph, s))
+        .collect();
+    velocities.sort_by(|a, b| a.total_cmp(b));
+    velocities
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64{
+    let rank = p * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+fn finish(graph: &ProjectGraph, start: DT, remaining: u32, velocity: f64) -> Option<DT>{
+    if remaining == 0{
+        return Some(start);
+    }
+    if velocity <= 0.0{
+        return None;
+    }
+    let days = (remaining as f64 / velocity).ceil() as u64;
+    let day: NaiveDate = graph.get_calendar().add_working_days(start.date_naive(), days)?;
+    Some(day.and_time(start.time()).and_utc())
+}
+
+// Unfinished predecessors of the epic or anything containing it hold it back
+fn blocked_until(graph: &ProjectGraph, epic: Uuid) -> Result<Option<DT>,&'static str>{
+    let sched = schedule(graph)?;
+    let until = std::iter::once(epic)
+        .chain(graph.get_ancestors(epic))
+        .flat_map(|id| graph.get_predecessors(id))
+        .filter(|(pred, _)| !graph.get_node(*pred).is_some_and(|n| n.is_done()))
+        .filter_map(|(pred, lag)| sched.get(pred).map(|s| s.end + lag))
+        .max();
+    Ok(until)
+}
+
+pub fn forecast(graph: &ProjectGraph, epic_id: Uuid, now: DT) -> Result<Forecast,&'static str>{
+    match graph.get_node(epic_id){
+        Some(Node::Epic{..}) => {}
+        Some(_) => return Err("Forecasts are made for Epics"),
+        None => return Err("The node does not exist in the graph"),
+    }
+    let rollup = graph.rollup(epic_id).ok_or("The node does not exist in the graph")?;
+    // An epic without children carries its own estimate
+    let remaining_points = match graph.get_node(epic_id){
+        Some(epic) if rollup.items == 0 && !epic.is_done() => epic.get_points().unwrap_or(0),
+        _ => rollup.total_points - rollup.done_points,
+    };
+
+    let velocities = velocity_history(graph, now);
+    if velocities.is_empty(){
+        return Err("No finished sprints to derive a velocity from");
+    }
+    let velocity = percentile(&velocities, 0.5);
+    let start = blocked_until(graph, epic_id)?.map_or(now, |b| b.max(now));
+
+    let expected = finish(graph, start, remaining_points, velocity)
+        .ok_or("The team has not finished any points in past sprints")?;
+    let optimistic = finish(graph, start, remaining_points, percentile(&velocities, 0.9)).unwrap_or(expected);
+    let pessimistic = finish(graph, start, remaining_points, percentile(&velocities, 0.1));
+
+    Ok(Forecast{
+        epic: epic_id,
+        remaining_points,
+        start,
+        sprints_sampled: velocities.len(),
+        velocity,
+        expected,
+        optimistic,
+        pessimistic,
+    })
+}
@@ -0,0 +1,168 @@
+// Project health - one 0-100 figure for portfolio dashboards, with the
+// factors behind it
+//
+// Each factor scores 0-100 on its own and the score is their weighted mean:
+//   schedule  how far the scheduled finish is past the planned end, as a share
+//             of the planned length; half the length late scores 0
+//   blocked   share of open leaves marked Blocked; half blocked scores 0
+//   overdue   share of open leaves past their end; half overdue scores 0
+//   churn     points re-estimated in the last four weeks plus points moved in
+//             or out of cut releases, as a share of all points; half scores 0
+//   risk      summed exposure of the project's risks; 10 (two certain
+//             worst-case risks) scores 0
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{EventKind, Node, Status};
+use crate::scheduler::schedule;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+const CHURN_WINDOW_DAYS: i64 = 28;
+const RISK_CEILING: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthComponent{
+    pub name: &'static str,
+    // Share of the total score, the weights add up to 1
+    pub weight: f64,
+    // 0-100, higher is healthier
+    pub score: f64,
+    // The measurement the score came from, e.g. "3 of 12 open items blocked"
+    pub detail: String,
+}
+
+impl HealthComponent{
+    // Points the factor took off the total
+    pub fn penalty(&self) -> f64{
+        self.weight * (100.0 - self.score)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthScore{
+    pub project: Uuid,
+    pub score: f64,
+    pub components: Vec<HealthComponent>,
+}
+
+impl HealthScore{
+    pub fn rating(&self) -> &'static str{
+        match self.score{
+            s if s >= 75.0 => "healthy",
+            s if s >= 50.0 => "at risk",
+            _ => "critical",
+        }
+    }
+
+    pub fn component(&self, name: &str) -> Option<&HealthComponent>{
+        self.components.iter().find(|c| c.name == name)
+    }
+
+    // The factor costing the most, if any costs anything
+    pub fn weakest(&self) -> Option<&HealthComponent>{
+        self.components.iter()
+            .filter(|c| c.penalty() > 0.0)
+            .max_by(|a, b| a.penalty().total_cmp(&b.penalty()))
+    }
+}
+
+// 100 at 0, falling linearly to 0 at `ceiling`
+fn falling(value: f64, ceiling: f64) -> f64{
+    100.0 * (1.0 - (value / ceiling).clamp(0.0, 1.0))
+}
+
+fn ratio(part: usize, whole: usize) -> f64{
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+pub fn health_score(graph: &ProjectGraph, project: Uuid) -> Result<HealthScore,&'static str>{
+    health_score_at(graph, project, Utc::now())
+}
+
+// Same as `health_score`, judged as of `now`
+pub fn health_score_at(graph: &ProjectGraph, project: Uuid, now: DT) -> Result<HealthScore,&'static str>{
+    let Some(root @ Node::Project{..}) = graph.get_node(project) else {
+        return Err("Health scores are computed for Projects");
+    };
+    let sched = schedule(graph)?;
+    let subtree: HashSet<Uuid> = graph.get_subtree(project).into_iter().collect();
+    let leaves: Vec<&Node> = subtree.iter()
+        .filter(|id| graph.get_children(**id).is_empty())
+        .filter_map(|id| graph.get_node(*id))
+        .filter(|n| n.get_status().is_some())
+        .collect();
+    let open: Vec<&&Node> = leaves.iter().filter(|n| !n.is_done()).collect();
+
+    let mut components = Vec::new();
+
+    let planned = root.get_timeline().and_then(|tl| tl.end.map(|end| (tl.start, end)));
+    let finish = sched.get(project).map(|s| s.end);
+    components.push(match (planned, finish){
+        (Some((start, end)), Some(finish)) => {
+            let slip = (finish - end).max(TimeDelta::zero());
+            let length = (end - start).num_seconds().max(1) as f64;
+            HealthComponent{
+                name: "schedule",
+                weight: 0.3,
+                score: falling(slip.num_seconds() as f64 / length, 0.5),
+                detail: format!("finishing {} days after the planned end", slip.num_days()),
+            }
+        }
+        _ => HealthComponent{ name: "schedule", weight: 0.3, score: 100.0, detail: "no planned end".to_string() },
+    });
+
+    let blocked = open.iter().filter(|n| n.get_status() == Some(Status::Blocked)).count();
+    components.push(HealthComponent{
+        name: "blocked",
+        weight: 0.2,
+        score: falling(ratio(blocked, open.len()), 0.5),
+        detail: format!("{} of {} open items blocked", blocked, open.len()),
+    });
+
+    let end_of = |n: &Node| n.get_timeline().and_then(|tl| tl.end).or_else(|| sched.get(n.get_id()).map(|s| s.end));
+    let overdue = open.iter().filter(|n| end_of(n).is_some_and(|end| end < now)).count();
+    components.push(HealthComponent{
+        name: "overdue",
+        weight: 0.2,
+        score: falling(ratio(overdue, open.len()), 0.5),
+        detail: format!("{} of {} open items overdue", overdue, open.len()),
+    });
+
+    let total: u32 = leaves.iter().filter_map(|n| n.get_points()).sum();
+    let since = now - TimeDelta::days(CHURN_WINDOW_DAYS);
+    let points = |v: &serde_json::Value| v.as_u64().unwrap_or(0) as i64;
+    let reestimated: i64 = graph.get_events().since(since)
+        .filter(|e| e.at <= now && subtree.contains(&e.node))
+        .filter_map(|e| match &e.kind{
+            EventKind::FieldChanged{ field, from, to } if field == "points" => Some((points(to) - points(from)).abs()),
+            _ => None,
+        })
+        .sum();
+    let moved: i64 = graph.releases()
+        .flat_map(|r| r.scope_changes_after_cut())
+        .filter(|c| subtree.contains(&c.node))
+        .filter_map(|c| graph.get_node(c.node).and_then(|n| n.get_points()))
+        .map(i64::from)
+        .sum();
+    let churned = reestimated + moved;
+    components.push(HealthComponent{
+        name: "churn",
+        weight: 0.15,
+        score: if total == 0 { 100.0 } else { falling(churned as f64 / total as f64, 0.5) },
+        detail: format!("{} of {} points changed", churned, total),
+    });
+
+    let exposure = graph.project_risk_score(project).unwrap_or(0.0);
+    components.push(HealthComponent{
+        name: "risk",
+        weight: 0.15,
+        score: falling(exposure, RISK_CEILING),
+        detail: format!("risk exposure {:.1}", exposure),
+    });
+
+    let score = components.iter().map(|c| c.weight * c.score).sum();
+    Ok(HealthScore{ project, score, components })
+}
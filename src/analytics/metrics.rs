@@ -0,0 +1,95 @@
+// Prometheus metrics - the graph's state in the text exposition format, for
+// scraping or for node_exporter's textfile collector
+//
+// Gauges are recomputed from the graph on every call; the counters count the
+// event log, so they only ever grow while the log is kept.
+
+use super::health::health_score_at;
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{EventKind, Node, Status};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+fn label(value: &str) -> String{
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str){
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn status_label(status: Status) -> &'static str{
+    match status{
+        Status::NotStarted => "not_started",
+        Status::InProgress => "in_progress",
+        Status::Blocked => "blocked",
+        Status::Done => "done",
+    }
+}
+
+pub fn prometheus_metrics(graph: &ProjectGraph, now: DateTime<Utc>) -> String{
+    let mut out = String::new();
+    let open = |n: &Node| n.get_status().is_some() && !n.is_done();
+    let leaf = |n: &Node| graph.get_children(n.get_id()).is_empty();
+
+    let mut by_status: BTreeMap<(String,&str),usize> = BTreeMap::new();
+    for node in graph.nodes(){
+        if let Some(status) = node.get_status(){
+            *by_status.entry((node.get_key_prefix().to_lowercase(), status_label(status))).or_default() += 1;
+        }
+    }
+    family(&mut out, "pm_nodes", "gauge", "Nodes by kind and status.");
+    for ((kind, status), count) in &by_status{
+        let _ = writeln!(out, "pm_nodes{{kind=\"{}\",status=\"{}\"}} {}", kind, status, count);
+    }
+
+    let overdue = graph.nodes()
+        .filter(|n| open(n) && leaf(n))
+        .filter(|n| n.get_timeline().and_then(|tl| tl.end).is_some_and(|end| end < now))
+        .count();
+    family(&mut out, "pm_overdue_items", "gauge", "Open leaf work past its planned end.");
+    let _ = writeln!(out, "pm_overdue_items {}", overdue);
+
+    let blockers = graph.edges()
+        .filter(|(from, to, d)| d.kind == DependencyType::Blocks && [from, to].iter().all(|id| graph.get_node(**id).is_some_and(open)))
+        .count();
+    family(&mut out, "pm_open_blockers", "gauge", "Blocks dependencies with both ends still open.");
+    let _ = writeln!(out, "pm_open_blockers {}", blockers);
+
+    let leaves: Vec<&Node> = graph.nodes().filter(|n| n.get_status().is_some() && leaf(n)).collect();
+    family(&mut out, "pm_points", "gauge", "Points of leaf work, all and done.");
+    let _ = writeln!(out, "pm_points{{state=\"total\"}} {}", leaves.iter().filter_map(|n| n.get_points()).sum::<u32>());
+    let _ = writeln!(out, "pm_points{{state=\"done\"}} {}", leaves.iter().filter(|n| n.is_done()).filter_map(|n| n.get_points()).sum::<u32>());
+
+    let mut projects: Vec<(String,f64)> = graph.nodes()
+        .filter(|n| matches!(n, Node::Project{..}))
+        .filter_map(|n| health_score_at(graph, n.get_id(), now).ok().map(|h| (graph.get_key(n.get_id()).unwrap_or(n.get_name()).to_string(), h.score)))
+        .collect();
+    projects.sort_by(|a, b| a.0.cmp(&b.0));
+    family(&mut out, "pm_project_health", "gauge", "Project health score, 0 to 100.");
+    for (project, score) in projects{
+        let _ = writeln!(out, "pm_project_health{{project=\"{}\"}} {:.1}", label(&project), score);
+    }
+
+    let mut transitions: BTreeMap<&str,usize> = BTreeMap::new();
+    for event in graph.get_events().iter(){
+        if let EventKind::StatusChanged{ to, .. } = event.kind{
+            *transitions.entry(status_label(to)).or_default() += 1;
+        }
+    }
+    family(&mut out, "pm_status_changes_total", "counter", "Logged status changes by the status moved to.");
+    for (to, count) in transitions{
+        let _ = writeln!(out, "pm_status_changes_total{{to=\"{}\"}} {}", to, count);
+    }
+
+    let plugin = graph.plugin_metrics();
+    if !plugin.is_empty(){
+        family(&mut out, "pm_plugin_metric", "gauge", "Metrics contributed by graph plugins.");
+        for (name, metric) in plugin{
+            let _ = writeln!(out, "pm_plugin_metric{{plugin=\"{}\",name=\"{}\"}} {}", label(&name), label(&metric.name), metric.value);
+        }
+    }
+    out
+}
@@ -0,0 +1,25 @@
+// Analytics module - metrics computed over the project graph
+
+pub mod accuracy;
+pub mod blocked;
+pub mod churn;
+pub mod cost;
+pub mod flow;
+pub mod forecast;
+pub mod health;
+pub mod metrics;
+pub mod simulation;
+pub mod variance;
+pub mod workload;
+
+pub use accuracy::{estimation_accuracy, owner_bias, AccuracyReport, AccuracySample, Subject};
+pub use blocked::{blocked_time, BlockedTime};
+pub use churn::{scope_churn, ChurnSubject, ChurnWeek, ScopeChurn};
+pub use cost::{cost, CostReport, CostRow};
+pub use flow::{flow_metrics, node_flow, FlowReport, FlowSummary, NodeFlow, Percentiles};
+pub use forecast::{forecast, velocity_history, Forecast};
+pub use health::{health_score, health_score_at, HealthComponent, HealthScore};
+pub use metrics::prometheus_metrics;
+pub use simulation::{monte_carlo, SimulationConfig, SimulationResult};
+pub use variance::{date_variance, DateVariance};
+pub use workload::{daily_load, workload, DailyLoad, WorkloadReport, WorkloadRow};
@@ -0,0 +1,124 @@
+// Monte Carlo schedule simulation
+//
+// Each trial scales every leaf's planned duration by a factor drawn from a
+// triangular distribution (optimistic, 1.0, pessimistic) and reschedules the
+// graph. The factor for a node in a trial is derived from the seed, trial
+// number and node id alone, so results are reproducible and independent of
+// how trials are spread across threads. Owners with a known estimation
+// bias (see accuracy::owner_bias) have their nodes scaled by it as well.
+
+use crate::core::graph::ProjectGraph;
+use crate::scheduler::schedule_with;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct SimulationConfig{
+    pub trials: usize,
+    // Duration multipliers for the best and worst case; the most likely is 1.0
+    pub optimistic: f64,
+    pub pessimistic: f64,
+    pub seed: u64,
+    // Extra duration multiplier per owner, from their historical accuracy
+    pub bias: HashMap<String,f64>,
+}
+
+impl SimulationConfig{
+    pub fn with_bias(mut self, bias: HashMap<String,f64>) -> Self{
+        self.bias = bias;
+        self
+    }
+}
+
+impl Default for SimulationConfig{
+    fn default() -> Self{
+        SimulationConfig{ trials: 1000, optimistic: 0.8, pessimistic: 1.5, seed: 0, bias: HashMap::new() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationResult{
+    // Finish date of each trial, earliest first
+    pub finishes: Vec<DateTime<Utc>>,
+}
+
+impl SimulationResult{
+    // Date by which `p` (0..=1) of the trials had finished, e.g. 0.85 for P85
+    pub fn percentile(&self, p: f64) -> Option<DateTime<Utc>>{
+        if self.finishes.is_empty(){
+            return None;
+        }
+        let rank = (p.clamp(0.0, 1.0) * (self.finishes.len() - 1) as f64).round() as usize;
+        Some(self.finishes[rank])
+    }
+
+    pub fn mean(&self) -> Option<DateTime<Utc>>{
+        let first = *self.finishes.first()?;
+        let total: i64 = self.finishes.iter().map(|f| (*f - first).num_seconds()).sum();
+        Some(first + TimeDelta::seconds(total / self.finishes.len() as i64))
+    }
+}
+
+// SplitMix64 finalizer, mapped to [0, 1)
+fn unit_sample(seed: u64, trial: u64, id: Uuid) -> f64{
+    let (hi, lo) = id.as_u64_pair();
+    let mut z = seed ^ trial.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ hi ^ lo.rotate_left(32);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// Inverse CDF of the triangular distribution (low, 1.0, high)
+fn triangular(u: f64, low: f64, high: f64) -> f64{
+    let mode = 1.0;
+    if high <= low{
+        return mode;
+    }
+    let split = (mode - low) / (high - low);
+    if u < split{
+        low + (u * (high - low) * (mode - low)).sqrt()
+    }else{
+        high - ((1.0 - u) * (high - low) * (high - mode)).sqrt()
+    }
+}
+
+fn run_trial(graph: &ProjectGraph, target: Uuid, config: &SimulationConfig, trial: u64) -> Result<Option<DateTime<Utc>>,&'static str>{
+    let scale = |id: Uuid, planned: TimeDelta| {
+        let bias = graph.get_node(id)
+            .and_then(|n| n.get_owner())
+            .and_then(|o| config.bias.get(o))
+            .copied()
+            .unwrap_or(1.0);
+        let factor = bias * triangular(unit_sample(config.seed, trial, id), config.optimistic, config.pessimistic);
+        TimeDelta::seconds((planned.num_seconds() as f64 * factor).round() as i64)
+    };
+    Ok(schedule_with(graph, &scale)?.get(target).map(|n| n.end))
+}
+
+// Distribution of `target`'s finish date over `config.trials` runs
+pub fn monte_carlo(graph: &ProjectGraph, target: Uuid, config: &SimulationConfig) -> Result<SimulationResult,&'static str>{
+    if graph.get_node(target).is_none(){
+        return Err("The node does not exist in the graph");
+    }
+    if config.optimistic > 1.0 || config.pessimistic < 1.0 || config.optimistic < 0.0{
+        return Err("Expected optimistic <= 1.0 <= pessimistic");
+    }
+    if config.bias.values().any(|b| !b.is_finite() || *b <= 0.0){
+        return Err("Bias factors must be positive numbers");
+    }
+
+    #[cfg(feature = "parallel")]
+    let trials = (0..config.trials as u64).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let trials = 0..config.trials as u64;
+
+    let results: Result<Vec<_>,_> = trials.map(|t| run_trial(graph, target, config, t)).collect();
+    let mut finishes: Vec<DateTime<Utc>> = results?.into_iter().flatten().collect();
+    finishes.sort();
+    Ok(SimulationResult{ finishes })
+}
@@ -0,0 +1,54 @@
+// Date variance - planned against actual start and finish per work item
+//
+// Planned dates are the item's own timeline; actual ones are those recorded
+// on status changes or set by hand. A positive slip means later than planned.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Scope;
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateVariance{
+    pub id: Uuid,
+    pub planned_start: Option<DT>,
+    pub actual_start: Option<DT>,
+    pub planned_finish: Option<DT>,
+    pub actual_finish: Option<DT>,
+}
+
+impl DateVariance{
+    pub fn start_slip(&self) -> Option<TimeDelta>{
+        self.actual_start.zip(self.planned_start).map(|(a, p)| a - p)
+    }
+
+    pub fn finish_slip(&self) -> Option<TimeDelta>{
+        self.actual_finish.zip(self.planned_finish).map(|(a, p)| a - p)
+    }
+}
+
+// Items in scope with a status and at least one actual date, in id order
+pub fn date_variance(graph: &ProjectGraph, scope: &Scope) -> Vec<DateVariance>{
+    let mut rows: Vec<DateVariance> = graph.nodes_in_scope(scope).into_iter()
+        .filter(|n| n.get_status().is_some())
+        .filter_map(|n| {
+            let id = n.get_id();
+            let actuals = graph.get_actuals(id);
+            if actuals.is_empty(){
+                return None;
+            }
+            let tl = n.get_timeline();
+            Some(DateVariance{
+                id,
+                planned_start: tl.map(|tl| tl.start),
+                actual_start: actuals.start,
+                planned_finish: tl.and_then(|tl| tl.end),
+                actual_finish: actuals.finish,
+            })
+        })
+        .collect();
+    rows.sort_by_key(|r| r.id);
+    rows
+}
@@ -0,0 +1,177 @@
+// Work assigned per owner: item and point totals, what is still open, and
+// hours logged against it; plus each owner's hours per day of the schedule
+//
+// Daily load spreads each item's effort the way the scheduler does: the
+// owner's allocation of a working day, on every day they work between the
+// scheduled start and finish, shaped by the effort's curve. Items without
+// effort count as full-time. Work that has no such day to go to (the owner
+// is away for all of it) stays on the working days they are away, where
+// any of it is an overload.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Scope};
+use crate::scheduler::schedule;
+use chrono::{NaiveDate, Weekday};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkloadRow{
+    pub owner: String,
+    pub items: usize,
+    pub open_items: usize,
+    pub points: u32,
+    pub open_points: u32,
+    pub logged_hours: f64,
+    pub effort_hours: f64,
+    pub open_effort_hours: f64,
+}
+
+impl WorkloadRow{
+    fn add(&mut self, other: &WorkloadRow){
+        self.effort_hours += other.effort_hours;
+        self.open_effort_hours += other.open_effort_hours;
+        self.items += other.items;
+        self.open_items += other.open_items;
+        self.points += other.points;
+        self.open_points += other.open_points;
+        self.logged_hours += other.logged_hours;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkloadReport{
+    // One row per owner, sorted by name
+    pub rows: Vec<WorkloadRow>,
+}
+
+impl WorkloadReport{
+    pub fn get(&self, owner: &str) -> Option<&WorkloadRow>{
+        self.rows.iter().find(|r| r.owner == owner)
+    }
+}
+
+fn row_for(graph: &ProjectGraph, node: &Node) -> Option<(String, WorkloadRow)>{
+    let owner = node.get_owner()?;
+    // Containers are summaries of their children, not assigned work
+    if node.get_status().is_none() || !graph.get_children(node.get_id()).is_empty(){
+        return None;
+    }
+    let points = node.get_points().unwrap_or(0);
+    let effort = graph.get_effort(node.get_id()).map(|e| e.hours).unwrap_or(0.0);
+    let open = !node.is_done();
+    let row = WorkloadRow{
+        owner: owner.to_string(),
+        items: 1,
+        open_items: open as usize,
+        points,
+        open_points: if open { points } else { 0 },
+        logged_hours: graph.get_worklogs(node.get_id()).iter().map(|w| w.hours).sum(),
+        effort_hours: effort,
+        open_effort_hours: if open { effort } else { 0.0 },
+    };
+    Some((owner.to_string(), row))
+}
+
+fn merge(mut acc: HashMap<String,WorkloadRow>, (owner, row): (String, WorkloadRow)) -> HashMap<String,WorkloadRow>{
+    acc.entry(owner).or_insert_with(|| WorkloadRow{ owner: row.owner.clone(), ..WorkloadRow::default() }).add(&row);
+    acc
+}
+
+pub fn workload(graph: &ProjectGraph, scope: &Scope) -> WorkloadReport{
+    let nodes = graph.nodes_in_scope(scope);
+
+    #[cfg(feature = "parallel")]
+    let totals = nodes.par_iter()
+        .filter_map(|n| row_for(graph, n))
+        .fold(HashMap::new, merge)
+        .reduce(HashMap::new, |a, b| b.into_iter().fold(a, merge));
+    #[cfg(not(feature = "parallel"))]
+    let totals = nodes.iter()
+        .filter_map(|n| row_for(graph, n))
+        .fold(HashMap::new(), merge);
+
+    let mut rows: Vec<WorkloadRow> = totals.into_values().collect();
+    rows.sort_by(|a, b| a.owner.cmp(&b.owner));
+    WorkloadReport{ rows }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DailyLoad{
+    // Scheduled hours by owner and working day
+    pub hours: BTreeMap<String,BTreeMap<NaiveDate,f64>>,
+    // Length of a working day in the project's settings
+    pub hours_per_day: f64,
+    // Days owners have work scheduled while they are away
+    pub away: BTreeMap<String,BTreeSet<NaiveDate>>,
+}
+
+impl DailyLoad{
+    pub fn of(&self, owner: &str, date: NaiveDate) -> f64{
+        self.hours.get(owner).and_then(|days| days.get(&date)).copied().unwrap_or(0.0)
+    }
+
+    // Hours the owner can work on the day: none while away
+    pub fn capacity(&self, owner: &str, date: NaiveDate) -> f64{
+        match self.away.get(owner).is_some_and(|days| days.contains(&date)){
+            true => 0.0,
+            false => self.hours_per_day,
+        }
+    }
+
+    // Scheduled hours by owner and the Monday of each week
+    pub fn weekly(&self) -> BTreeMap<String,BTreeMap<NaiveDate,f64>>{
+        self.hours.iter()
+            .map(|(owner, days)| {
+                let mut weeks: BTreeMap<NaiveDate,f64> = BTreeMap::new();
+                for (date, hours) in days{
+                    *weeks.entry(date.week(Weekday::Mon).first_day()).or_default() += hours;
+                }
+                (owner.clone(), weeks)
+            })
+            .collect()
+    }
+
+    // Days on which someone has more scheduled than a working day holds
+    pub fn overloaded(&self) -> Vec<(&str,NaiveDate,f64)>{
+        self.hours.iter()
+            .flat_map(|(owner, days)| days.iter().map(move |(date, hours)| (owner.as_str(), *date, *hours)))
+            .filter(|(owner, date, hours)| *hours > self.capacity(owner, *date) + 1e-9)
+            .collect()
+    }
+}
+
+// Open leaf work only; done work no longer takes anyone's time
+pub fn daily_load(graph: &ProjectGraph, scope: &Scope) -> Result<DailyLoad,&'static str>{
+    let scheduled = schedule(graph)?;
+    let hours_per_day = graph.get_settings().hours_per_day;
+    let mut hours: BTreeMap<String,BTreeMap<NaiveDate,f64>> = BTreeMap::new();
+    let mut away: BTreeMap<String,BTreeSet<NaiveDate>> = BTreeMap::new();
+    for node in graph.nodes_in_scope(scope){
+        let (Some(owner), Some(span)) = (node.get_owner(), scheduled.get(node.get_id())) else {
+            continue;
+        };
+        if node.is_done() || node.get_status().is_none() || !graph.get_children(node.get_id()).is_empty(){
+            continue;
+        }
+        let effort = graph.get_effort(node.get_id());
+        let daily = effort.map(|e| e.hours_per_day(hours_per_day)).unwrap_or(hours_per_day);
+        let end = span.end.date_naive().max(span.start.date_naive());
+        let span_days: Vec<NaiveDate> = span.start.date_naive().iter_days()
+            .take_while(|d| *d < end || *d == span.start.date_naive())
+            .collect();
+        let mut days: Vec<NaiveDate> = span_days.iter().copied().filter(|d| graph.is_available_on(Some(owner), *d)).collect();
+        if days.is_empty(){
+            let region = graph.get_person(owner).and_then(|p| p.region.as_deref());
+            days = span_days.into_iter().filter(|d| graph.get_calendar().is_working_day_in(*d, region)).collect();
+            away.entry(owner.to_string()).or_default().extend(days.iter().copied());
+        }
+        let shares = effort.map(|e| e.curve).unwrap_or_default().daily_shares(days.len());
+        for (date, share) in days.iter().zip(shares){
+            *hours.entry(owner.to_string()).or_default().entry(*date).or_default() += daily * days.len() as f64 * share;
+        }
+    }
+    Ok(DailyLoad{ hours, hours_per_day, away })
+}
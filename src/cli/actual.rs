@@ -0,0 +1,62 @@
+// `pm actuals` - when work on a node really started and finished, and how far
+// that is from its timeline, for one node or as a variance report across a
+// subtree or the whole project
+
+use super::create::parse_date;
+use super::output::{Output, OutputFormat};
+use crate::analytics::{date_variance, DateVariance};
+use crate::core::{Actuals, Scope};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn actuals(path: &Path, node: Option<&str>, start: Option<String>, finish: Option<String>, clear: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = node.map(|n| graph.resolve_id(n).ok_or_else(|| anyhow!("No node '{}'", n))).transpose()?;
+    let setting = clear || start.is_some() || finish.is_some();
+    let rows = match id{
+        Some(id) if setting => {
+            let mut actuals = if clear { Actuals::default() } else { graph.get_actuals(id) };
+            if let Some(start) = &start{
+                actuals.start = Some(parse_date(start, graph.get_calendar())?);
+            }
+            if let Some(finish) = &finish{
+                actuals.finish = Some(parse_date(finish, graph.get_calendar())?);
+            }
+            graph.set_actuals(id, actuals).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+
+            let actuals = graph.get_actuals(id);
+            let timeline = graph.get_node(id).and_then(|n| n.get_timeline());
+            vec![DateVariance{
+                id,
+                planned_start: timeline.map(|tl| tl.start),
+                actual_start: actuals.start,
+                planned_finish: timeline.and_then(|tl| tl.end),
+                actual_finish: actuals.finish,
+            }]
+        }
+        None if setting => bail!("Name the node whose actual dates to set"),
+        Some(id) => date_variance(&graph, &Scope::Subtree(id)),
+        None => date_variance(&graph, &Scope::All),
+    };
+
+    let day = |d: Option<DateTime<Utc>>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+    let slip = |d: Option<TimeDelta>| d.map(|d| d.num_days().to_string()).unwrap_or_default();
+    let mut output = Output::new(vec!["key", "planned_start", "actual_start", "start_slip", "planned_finish", "actual_finish", "finish_slip"]);
+    for row in &rows{
+        output.push(vec![
+            graph.get_key(row.id).unwrap_or_default().to_string(),
+            day(row.planned_start),
+            day(row.actual_start),
+            slip(row.start_slip()),
+            day(row.planned_finish),
+            day(row.actual_finish),
+            slip(row.finish_slip()),
+        ]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,142 @@
+// `pm approval` - gates that need sign-off before work is Done or a release
+// is cut, who may give it, and what is waiting on it
+
+use super::output::{Output, OutputFormat};
+use super::rule::kind_prefix;
+use crate::core::graph::ProjectGraph;
+use crate::core::{ApprovalGate, GateTarget, Person};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum ApprovalCommand{
+    /// Add a gate on a kind of node moving to Done, or on cutting releases
+    Gate{
+        name: String,
+        /// project, epic, story or task
+        #[arg(long, conflicts_with = "release_cut", required_unless_present = "release_cut")]
+        done: Option<String>,
+        #[arg(long)]
+        release_cut: bool,
+        /// A role that must approve; repeatable
+        #[arg(long = "role", required = true)]
+        roles: Vec<String>,
+    },
+    /// The gates and the roles each needs
+    Gates,
+    RemoveGate{
+        name: String,
+    },
+    /// Let a person approve for a role
+    Grant{
+        person: String,
+        role: String,
+    },
+    /// Approve a node (key or id) or release (name or id) for a role, as --actor
+    Approve{
+        subject: String,
+        gate: String,
+        #[arg(long)]
+        role: String,
+    },
+    /// Gated transitions still waiting on approval
+    Pending,
+    /// Approvals given for a node or release, and those still missing
+    Show{
+        subject: String,
+    },
+}
+
+fn resolve(graph: &ProjectGraph, value: &str) -> Result<Uuid>{
+    if let Some(id) = graph.resolve_id(value){
+        return Ok(id);
+    }
+    graph.releases()
+        .find(|r| r.name == value || r.id.to_string() == value)
+        .map(|r| r.id)
+        .ok_or_else(|| anyhow!("No node or release '{}'", value))
+}
+
+fn label(graph: &ProjectGraph, subject: Uuid) -> String{
+    match graph.get_release(subject){
+        Some(release) => format!("release {}", release.name),
+        None => graph.get_key(subject).map(str::to_string).unwrap_or_else(|| subject.to_string()),
+    }
+}
+
+fn gates(graph: &ProjectGraph) -> Output{
+    let mut output = Output::new(vec!["name", "transition", "roles"]);
+    for gate in &graph.get_settings().approval_gates{
+        let transition = match &gate.target{
+            GateTarget::Done(prefix) => format!("{} to Done", prefix),
+            GateTarget::ReleaseCut => "release cut".to_string(),
+        };
+        output.push(vec![gate.name.clone(), transition, gate.roles.join(", ")]);
+    }
+    output
+}
+
+pub fn run(path: &Path, command: ApprovalCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        ApprovalCommand::Gate{ name, done, release_cut, roles } => {
+            let target = match (done, release_cut){
+                (Some(kind), false) => GateTarget::Done(kind_prefix(&kind)?.to_string()),
+                (None, true) => GateTarget::ReleaseCut,
+                _ => bail!("Give either --done KIND or --release-cut"),
+            };
+            let mut settings = graph.get_settings().clone();
+            settings.approval_gates.push(ApprovalGate{ name, target, roles });
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            gates(&graph).print(format)?;
+        }
+        ApprovalCommand::Gates => gates(&graph).print(format)?,
+        ApprovalCommand::RemoveGate{ name } => {
+            let mut settings = graph.get_settings().clone();
+            let before = settings.approval_gates.len();
+            settings.approval_gates.retain(|g| g.name != name);
+            if settings.approval_gates.len() == before{
+                bail!("No approval gate '{}'", name);
+            }
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        ApprovalCommand::Grant{ person, role } => {
+            if graph.get_person(&person).is_none(){
+                graph.add_person(Person::new(person.clone())).map_err(|e| anyhow!(e))?;
+            }
+            graph.add_person_role(&person, &role).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        ApprovalCommand::Approve{ subject, gate, role } => {
+            let id = resolve(&graph, &subject)?;
+            graph.approve_at(id, &gate, &role, Utc::now()).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        ApprovalCommand::Pending => {
+            let mut output = Output::new(vec!["subject", "gate", "missing"]);
+            for pending in graph.pending_approvals(){
+                output.push(vec![label(&graph, pending.subject), pending.gate, pending.missing.join(", ")]);
+            }
+            output.print(format)?;
+        }
+        ApprovalCommand::Show{ subject } => {
+            let id = resolve(&graph, &subject)?;
+            let mut output = Output::new(vec!["gate", "role", "by", "at"]);
+            for approval in graph.approvals_for(id){
+                output.push(vec![approval.gate.clone(), approval.role.clone(), approval.by.clone(), approval.at.format("%Y-%m-%d %H:%M").to_string()]);
+            }
+            for (gate, role) in graph.missing_approvals(id){
+                output.push(vec![gate, role, "-".to_string(), "-".to_string()]);
+            }
+            output.print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,55 @@
+// `pm backlog` and `pm rank` - the ranked backlog, and moving work within it
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::{validate, Warning};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn print_backlog(graph: &ProjectGraph, format: OutputFormat) -> Result<()>{
+    let report = validate(graph);
+    let mut output = Output::new(vec!["rank", "key", "name", "status", "points", "waits_on"]);
+    for (position, id) in graph.backlog().iter().enumerate(){
+        let node = graph.get_node(*id).ok_or_else(|| anyhow!("The backlog refers to a missing node"))?;
+        let waits_on: Vec<String> = report.warnings.iter()
+            .filter_map(|w| match w{
+                Warning::RankedAboveDependency{ item, dependency, .. } if item == id => Some(*dependency),
+                _ => None,
+            })
+            .map(|d| graph.get_key(d).map(str::to_string).unwrap_or_else(|| d.to_string()))
+            .collect();
+        output.push(vec![
+            (position + 1).to_string(),
+            graph.get_key(*id).unwrap_or_default().to_string(),
+            node.get_name().to_string(),
+            node.get_status().map(|s| s.to_string()).unwrap_or_default(),
+            node.get_points().map(|p| p.to_string()).unwrap_or_default(),
+            waits_on.join(" "),
+        ]);
+    }
+    output.print(format)
+}
+
+pub fn backlog(path: &Path, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    print_backlog(&graph, format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn rank(path: &Path, node: &str, rank: Option<usize>, clear: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+    if clear{
+        if graph.clear_rank(id).is_none(){
+            bail!("The node is not in the backlog");
+        }
+    }else{
+        // Without a rank the node goes to the bottom
+        graph.set_rank(id, rank.unwrap_or(usize::MAX)).map_err(|e| anyhow!(e))?;
+    }
+    storage::write(&graph, path)?;
+    print_backlog(&graph, format)?;
+    Ok(ExitCode::SUCCESS)
+}
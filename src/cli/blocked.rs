@@ -0,0 +1,63 @@
+// `pm block` and `pm blocked` - blocking work for a reason from the taxonomy,
+// and blocked time by reason
+
+use super::output::{Output, OutputFormat};
+use crate::analytics::blocked_time;
+use crate::core::{BlockReason, Blocker, Scope};
+use crate::storage;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn block(path: &Path, node: &str, reason: &str, by: Option<&str>, note: Option<&str>, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+    let mut reason = BlockReason::new(reason);
+    if let Some(by) = by{
+        // @name for a person, else a node key or id
+        let blocker = match by.strip_prefix('@'){
+            Some(person) => Blocker::Person(person.to_string()),
+            None => Blocker::Node(graph.resolve_id(by).ok_or_else(|| anyhow!("No node '{}'", by))?),
+        };
+        reason = reason.with_blocker(blocker);
+    }
+    if let Some(note) = note{
+        reason = reason.with_note(note);
+    }
+    graph.block_at(id, reason, Utc::now()).map_err(|e| {
+        anyhow!("{} (reasons: {})", e, graph.get_settings().blocked_reasons.categories.join(", "))
+    })?;
+    storage::write(&graph, path)?;
+
+    let mut output = Output::new(vec!["key", "status", "reason", "by", "note"]);
+    output.push(vec![
+        graph.get_key(id).unwrap_or_default().to_string(),
+        graph.get_node(id).and_then(|n| n.get_status()).map(|s| s.to_string()).unwrap_or_default(),
+        graph.blocked_periods(id).last().map(|p| p.category().to_string()).unwrap_or_default(),
+        by.unwrap_or_default().to_string(),
+        note.unwrap_or_default().to_string(),
+    ]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn blocked(path: &Path, root: Option<&str>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match root{
+        Some(root) => Scope::Subtree(graph.resolve_id(root).ok_or_else(|| anyhow!("No node '{}'", root))?),
+        None => Scope::All,
+    };
+    let mut output = Output::new(vec!["reason", "nodes", "periods", "open", "days"]);
+    for row in blocked_time(&graph, &scope, Utc::now()){
+        output.push(vec![
+            row.reason,
+            row.nodes.to_string(),
+            row.periods.to_string(),
+            row.open.to_string(),
+            format!("{:.1}", row.total.num_minutes() as f64 / 1440.0),
+        ]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
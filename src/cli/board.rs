@@ -0,0 +1,149 @@
+// `pm board`, `pm report` and `pm dsm` - work sliced into swimlanes, and
+// the dependencies between it
+
+use super::catalog::catalog_for;
+use super::output::{Output, OutputFormat};
+use super::template::{load_template, RenderArgs};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Scope, Status, TemplateReport};
+use crate::storage;
+use crate::views::{board as build_board, dsm as build_dsm, status_report_by, Grouping};
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy{
+    #[default]
+    None,
+    Epic,
+    Owner,
+    Tag,
+    Priority,
+}
+
+impl From<GroupBy> for Grouping{
+    fn from(by: GroupBy) -> Self{
+        match by{
+            GroupBy::None => Grouping::None,
+            GroupBy::Epic => Grouping::Epic,
+            GroupBy::Owner => Grouping::Owner,
+            GroupBy::Tag => Grouping::Tag,
+            GroupBy::Priority => Grouping::Priority,
+        }
+    }
+}
+
+fn resolve(graph: &ProjectGraph, id_or_key: &str) -> Result<Uuid>{
+    graph.resolve_id(id_or_key).ok_or_else(|| anyhow!("No node '{}'", id_or_key))
+}
+
+pub fn board(path: &Path, scope: Option<&str>, by: GroupBy, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match scope{
+        Some(root) => Scope::Subtree(resolve(&graph, root)?),
+        None => Scope::All,
+    };
+    let board = build_board(&graph, &scope, by.into());
+
+    if format == OutputFormat::Table{
+        print!("{}", board.render_markdown());
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut output = Output::new(vec!["lane", "column", "key", "name", "owner", "points"]);
+    for lane in &board.lanes{
+        for (column, cards) in board.columns.iter().zip(&lane.cells){
+            for card in cards{
+                output.push(vec![
+                    lane.name.clone(),
+                    column.clone(),
+                    card.key.clone().unwrap_or_default(),
+                    card.name.clone(),
+                    card.owner.clone().unwrap_or_default(),
+                    card.size.clone().unwrap_or_default(),
+                ]);
+            }
+        }
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn report(path: &Path, project: Option<&str>, by: GroupBy, render: &RenderArgs, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let project_id = match project{
+        Some(p) => resolve(&graph, p)?,
+        None => graph.nodes()
+            .find(|n| matches!(n, Node::Project{..}) && graph.get_parent(n.get_id()).is_none())
+            .map(|n| n.get_id())
+            .ok_or_else(|| anyhow!("The file has no project"))?,
+    };
+    let mut report = status_report_by(&graph, project_id, by.into()).ok_or_else(|| anyhow!("Reports are made for Projects"))?;
+    if let Some(lang) = &render.lang{
+        report.messages = catalog_for(&graph, lang)?;
+    }
+
+    if render.context{
+        println!("{}", serde_json::to_string_pretty(&report.context())?);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(name) = &render.template{
+        let (template, html) = load_template(&graph, name, TemplateReport::Status)?;
+        print!("{}", report.render_with(&template, html)?);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if render.html{
+        print!("{}", report.render_html());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if format == OutputFormat::Table{
+        print!("{}", report.render_markdown());
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut output = Output::new(vec!["lane", "status", "items"]);
+    let lanes: Vec<(&str, &[(Status, usize)])> = if report.lanes.is_empty(){
+        vec![("All", &report.status_counts)]
+    }else{
+        report.lanes.iter().map(|l| (l.name.as_str(), l.status_counts.as_slice())).collect()
+    };
+    for (lane, counts) in lanes{
+        for (status, count) in counts{
+            output.push(vec![lane.to_string(), status.to_string(), count.to_string()]);
+        }
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn dsm(path: &Path, scope: Option<&str>, html: bool, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match scope{
+        Some(root) => Scope::Subtree(resolve(&graph, root)?),
+        None => Scope::All,
+    };
+    let matrix = build_dsm(&graph, &scope);
+
+    if html{
+        print!("{}", matrix.render_html());
+        return Ok(ExitCode::SUCCESS);
+    }
+    match format{
+        OutputFormat::Table => print!("{}", matrix.render_text()),
+        OutputFormat::Csv => print!("{}", matrix.render_csv()),
+        OutputFormat::Json => {
+            let mut output = Output::new(vec!["row", "column", "marks", "feedback"]);
+            for &(row, column) in matrix.marks.keys(){
+                output.push(vec![
+                    matrix.entries[row].label.clone(),
+                    matrix.entries[column].label.clone(),
+                    matrix.cell(row, column),
+                    (column > row).to_string(),
+                ]);
+            }
+            output.print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,74 @@
+// `pm brief` - a project on one page for a steering committee packet, as
+// markdown, an HTML fragment, or with --page a standalone page to print or
+// save as PDF
+
+use super::catalog::catalog_for;
+use super::output::{Output, OutputFormat};
+use super::template::{load_template, RenderArgs};
+use crate::core::{Node, TemplateReport};
+use crate::storage;
+use crate::views::brief as build_brief;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn brief(path: &Path, project: Option<&str>, render: &RenderArgs, page: bool, out: Option<&Path>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let project_id = match project{
+        Some(p) => graph.resolve_id(p).ok_or_else(|| anyhow!("No node '{}'", p))?,
+        None => graph.nodes()
+            .find(|n| matches!(n, Node::Project{..}) && graph.get_parent(n.get_id()).is_none())
+            .map(|n| n.get_id())
+            .ok_or_else(|| anyhow!("The file has no project"))?,
+    };
+    let mut brief = build_brief(&graph, project_id, Utc::now()).ok_or_else(|| anyhow!("Briefs are made for Projects"))?;
+    if let Some(lang) = &render.lang{
+        brief.messages = catalog_for(&graph, lang)?;
+    }
+
+    let text = if render.context{
+        format!("{}\n", serde_json::to_string_pretty(&brief.context())?)
+    }else if let Some(name) = &render.template{
+        let (template, html) = load_template(&graph, name, TemplateReport::Brief)?;
+        // Only an HTML body can go on the page
+        if page && !html{
+            bail!("--page needs an HTML template; '{}' is markdown", name);
+        }
+        let body = brief.render_with(&template, html)?;
+        if page { brief.render_page(&body) } else { body }
+    }else if page{
+        brief.render_page(&brief.render_html())
+    }else if render.html{
+        brief.render_html()
+    }else if format == OutputFormat::Table{
+        brief.render_markdown()
+    }else{
+        let mut output = Output::new(vec!["section", "item", "value"]);
+        output.push(vec!["timeline".to_string(), "start".to_string(), brief.start.map(|d| d.date_naive().to_string()).unwrap_or_default()]);
+        output.push(vec!["timeline".to_string(), "planned_end".to_string(), brief.planned_end.map(|d| d.date_naive().to_string()).unwrap_or_default()]);
+        output.push(vec!["timeline".to_string(), "forecast_end".to_string(), brief.forecast_end.map(|d| d.date_naive().to_string()).unwrap_or_default()]);
+        output.push(vec!["status".to_string(), "completion".to_string(), format!("{:.0}", brief.completion)]);
+        output.push(vec!["status".to_string(), "health".to_string(), brief.health.map(|h| format!("{:.0}", h)).unwrap_or_default()]);
+        for (status, count) in &brief.status_counts{
+            output.push(vec!["status".to_string(), status.to_string(), count.to_string()]);
+        }
+        for m in &brief.milestones{
+            output.push(vec!["milestone".to_string(), m.name.clone(), m.date.date_naive().to_string()]);
+        }
+        for r in &brief.top_risks{
+            output.push(vec!["risk".to_string(), r.title.clone(), format!("{:.1}", r.score)]);
+        }
+        for p in &brief.team{
+            output.push(vec!["team".to_string(), p.name.clone(), p.team.clone().unwrap_or_default()]);
+        }
+        output.print(format)?;
+        return Ok(ExitCode::SUCCESS);
+    };
+    match out{
+        Some(out) => fs::write(out, text).with_context(|| format!("writing {}", out.display()))?,
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
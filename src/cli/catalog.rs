@@ -0,0 +1,117 @@
+// `pm catalog` - the message catalogs reports are written with: the built-in
+// languages, and catalogs a project adds or rewords them with
+//
+// `export` writes a language's messages as "key = message" lines for a
+// translator to fill in; `import` reads them back into the project file.
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::i18n::{message_keys, parse_catalog, BUILT_IN_LANGUAGES};
+use crate::core::Catalog;
+use crate::storage;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum CatalogCommand{
+    /// Languages reports can be written in
+    List,
+    /// Every message in a language, English where it has none
+    Show{
+        language: String,
+    },
+    /// Write a language's messages as "key = message" lines, to translate
+    Export{
+        language: String,
+    },
+    /// Read "key = message" lines into the project's catalog for a language
+    Import{
+        language: String,
+        path: PathBuf,
+    },
+    /// Drop the project's catalog for a language
+    Remove{
+        language: String,
+    },
+}
+
+// The catalog for a --lang flag
+pub fn catalog_for(graph: &ProjectGraph, language: &str) -> Result<Catalog>{
+    let i18n = &graph.get_settings().i18n;
+    if !i18n.has_language(language){
+        bail!("No catalog for '{}'; built in are {}, or add one with pm catalog import", language, BUILT_IN_LANGUAGES.join(", "));
+    }
+    Ok(i18n.catalog(Some(language)))
+}
+
+pub fn run(path: &Path, command: CatalogCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        CatalogCommand::List => {
+            let i18n = &graph.get_settings().i18n;
+            let mut languages: Vec<&str> = BUILT_IN_LANGUAGES.to_vec();
+            languages.extend(i18n.catalogs.keys().map(String::as_str).filter(|l| !BUILT_IN_LANGUAGES.contains(l)));
+            let mut output = Output::new(vec!["language", "built_in", "project_messages", "default"]);
+            for language in languages{
+                output.push(vec![
+                    language.to_string(),
+                    BUILT_IN_LANGUAGES.contains(&language).to_string(),
+                    i18n.catalogs.get(language).map(|c| c.len()).unwrap_or(0).to_string(),
+                    if i18n.language == language { "yes".to_string() } else { String::new() },
+                ]);
+            }
+            output.print(format)?;
+        }
+        CatalogCommand::Show{ language } => {
+            let catalog = catalog_for(&graph, &language)?;
+            let mut output = Output::new(vec!["key", "message"]);
+            for key in message_keys(){
+                output.push(vec![key.to_string(), catalog.get(key).to_string()]);
+            }
+            output.print(format)?;
+        }
+        CatalogCommand::Export{ language } => {
+            let catalog = graph.get_settings().i18n.catalog(Some(&language));
+            // The lines to translate for people, the messages as rows for scripts
+            if format == OutputFormat::Table{
+                let mut text = format!("# Report messages for {}; keep the {{placeholders}} as they are\n", language);
+                for key in message_keys(){
+                    text.push_str(&format!("{} = {}\n", key, catalog.get(key)));
+                }
+                print!("{}", text);
+            }else{
+                let mut output = Output::new(vec!["key", "message"]);
+                for key in message_keys(){
+                    output.push(vec![key.to_string(), catalog.get(key).to_string()]);
+                }
+                output.print(format)?;
+            }
+        }
+        CatalogCommand::Import{ language, path: file } => {
+            let text = std::fs::read_to_string(&file).with_context(|| format!("Could not read {}", file.display()))?;
+            let messages = parse_catalog(&text).map_err(|e| anyhow!(e))?;
+            if let Some(unknown) = messages.keys().find(|k| !message_keys().any(|m| m == k.as_str())){
+                bail!("'{}' is not a report message; see pm catalog show en", unknown);
+            }
+            let mut settings = graph.get_settings().clone();
+            let count = messages.len();
+            settings.i18n.catalogs.insert(language.clone(), messages);
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            let mut output = Output::new(vec!["language", "imported"]);
+            output.push(vec![language, count.to_string()]);
+            output.print(format)?;
+        }
+        CatalogCommand::Remove{ language } => {
+            let mut settings = graph.get_settings().clone();
+            if settings.i18n.catalogs.remove(&language).is_none(){
+                bail!("The project has no catalog for '{}'", language);
+            }
+            graph.set_settings(settings).map_err(|e| anyhow!("{}: change the report language first", e))?;
+            storage::write(&graph, path)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,46 @@
+// `pm chain` - the critical chain once owners work one thing at a time, with buffer suggestions
+
+use super::output::{Output, OutputFormat};
+use crate::scheduler::{critical_chain, BufferMethod, ChainLink};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::TimeDelta;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn parse_method(value: &str) -> Result<BufferMethod>{
+    match value.trim().to_ascii_lowercase().as_str(){
+        "cut-and-paste" | "half" => Ok(BufferMethod::CutAndPaste),
+        "root-square" | "rse" => Ok(BufferMethod::RootSquareError),
+        _ => bail!("Unknown buffer method '{}'; use cut-and-paste or root-square", value),
+    }
+}
+
+fn hours(delta: TimeDelta) -> String{
+    format!("{:.1}h", delta.num_minutes() as f64 / 60.0)
+}
+
+pub fn chain(path: &Path, method: &str, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let chain = critical_chain(&graph, parse_method(method)?).map_err(|e| anyhow!(e))?;
+    let key = |id| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+
+    let mut output = Output::new(vec!["kind", "key", "start", "end", "link", "buffer"]);
+    for step in &chain.steps{
+        let link = match &step.link{
+            ChainLink::Start => "-".to_string(),
+            ChainLink::Dependency => "dependency".to_string(),
+            ChainLink::Resource(owner) => format!("owner {}", owner),
+        };
+        output.push(vec!["chain".to_string(), key(step.id), step.start.to_rfc3339(), step.end.to_rfc3339(), link, String::new()]);
+    }
+    for buffer in &chain.feeding_buffers{
+        let feeding: Vec<String> = buffer.chain.iter().map(|id| key(*id)).collect();
+        output.push(vec!["feeding".to_string(), feeding.join(","), String::new(), String::new(), format!("joins {}", key(buffer.joins)), hours(buffer.size)]);
+    }
+    if let Some(finish) = chain.buffered_finish(){
+        output.push(vec!["project".to_string(), String::new(), String::new(), finish.to_rfc3339(), String::new(), hours(chain.project_buffer)]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
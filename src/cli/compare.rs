@@ -0,0 +1,49 @@
+// `pm compare` - a baseline and a scenario side by side
+
+use super::catalog::catalog_for;
+use super::output::{Output, OutputFormat};
+use super::template::{load_template, RenderArgs};
+use crate::core::TemplateReport;
+use crate::storage;
+use crate::views::compare as compare_scenarios;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn name_of(path: &Path) -> String{
+    path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| path.display().to_string())
+}
+
+pub fn compare(baseline: &Path, scenario: &Path, render: &RenderArgs, format: OutputFormat) -> Result<ExitCode>{
+    let (before, after) = (storage::open(baseline)?, storage::open(scenario)?);
+    let (before_name, after_name) = (name_of(baseline), name_of(scenario));
+    let mut comparison = compare_scenarios(&before, &after, (&before_name, &after_name)).map_err(|e| anyhow!(e))?;
+    if let Some(lang) = &render.lang{
+        comparison.messages = catalog_for(&before, lang)?;
+    }
+
+    if render.context{
+        println!("{}", serde_json::to_string_pretty(&comparison.context())?);
+        return Ok(ExitCode::SUCCESS);
+    }
+    // Templates come from the baseline, like the language
+    if let Some(name) = &render.template{
+        let (template, html) = load_template(&before, name, TemplateReport::Comparison)?;
+        print!("{}", comparison.render_with(&template, html)?);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if render.html{
+        print!("{}", comparison.render_html());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if format == OutputFormat::Table{
+        print!("{}", comparison.render_markdown());
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut output = Output::new(vec!["metric", "baseline", "scenario", "change"]);
+    for row in comparison.summary_rows(){
+        output.push(row.to_vec());
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
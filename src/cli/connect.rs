@@ -0,0 +1,41 @@
+// `pm connect` - dependencies between nodes of the same file
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::DependencyType;
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn parse_kind(value: &str) -> Result<DependencyType>{
+    match value.trim().to_ascii_lowercase().as_str(){
+        "blocks" => Ok(DependencyType::Blocks),
+        "resources" | "resources-required-for" => Ok(DependencyType::ResourcesRequiredFor),
+        "contains" => Ok(DependencyType::Contains),
+        "specifies" | "specified-by" => Ok(DependencyType::SpecifiedBy),
+        _ => bail!("Unknown dependency '{}'; use blocks, resources, contains or specifies", value),
+    }
+}
+
+// FROM blocks (or provides resources for, contains or specifies) TO
+pub fn connect(path: &Path, from: &str, to: &str, kind: &str, force: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let kind = parse_kind(kind)?;
+    let from = graph.resolve_id(from).ok_or_else(|| anyhow!("No node '{}'", from))?;
+    let to = graph.resolve_id(to).ok_or_else(|| anyhow!("No node '{}'", to))?;
+    let warnings = graph.connect_checked(from, to, kind, force)
+        .map_err(|e| anyhow!(e))?;
+    storage::write(&graph, path)?;
+    for warning in &warnings{
+        eprintln!("warning: {}", warning.message(&graph));
+    }
+
+    let mut output = Output::new(vec!["from", "to", "kind"]);
+    output.push(vec![
+        graph.get_key(from).unwrap_or_default().to_string(),
+        graph.get_key(to).unwrap_or_default().to_string(),
+        format!("{:?}", kind),
+    ]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,40 @@
+// `pm coordination` - what a team waits on from other teams and what they wait on from it
+
+use super::output::{Output, OutputFormat};
+use crate::storage;
+use crate::views::{coordination as build_coordination, Party};
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn coordination(path: &Path, team: Option<String>, owner: Option<String>, html: bool, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let party = match (team, owner){
+        (Some(team), _) => Party::Team(team),
+        (None, Some(owner)) => Party::Owner(owner),
+        (None, None) => return Err(anyhow!("Give a --team or an --owner")),
+    };
+    let report = build_coordination(&graph, party).map_err(|e| anyhow!(e))?;
+
+    if html{
+        print!("{}", report.render_html(&graph));
+        return Ok(ExitCode::SUCCESS);
+    }
+    if format == OutputFormat::Table{
+        print!("{}", report.render_markdown(&graph));
+        return Ok(ExitCode::SUCCESS);
+    }
+    let key = |id| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+    let owner = |id| graph.get_node(id).and_then(|n| n.get_owner()).unwrap_or_default().to_string();
+    let mut output = Output::new(vec!["team", "direction", "blocker", "blocker_owner", "blocked", "blocked_owner"]);
+    for counterpart in &report.counterparts{
+        let team = counterpart.team.clone().unwrap_or_default();
+        for (direction, handoffs) in [("waiting-on", &counterpart.waiting_on), ("holding-up", &counterpart.holding_up)]{
+            for h in handoffs{
+                output.push(vec![team.clone(), direction.to_string(), key(h.blocker), owner(h.blocker), key(h.blocked), owner(h.blocked)]);
+            }
+        }
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,174 @@
+// `pm cost` - budgets and spend in the project's base currency, the currency
+// each cost and hourly rate is in, and the exchange rates and number locale
+// reports use
+//
+// Tables and CSV write amounts for the locale ("1.234,50 €"); JSON keeps
+// plain numbers for scripts.
+
+use super::output::{Output, OutputFormat};
+use crate::analytics::cost;
+use crate::core::graph::ProjectGraph;
+use crate::core::{format_money, format_number, MoneySettings, Person, Scope};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum CostCommand{
+    /// Budget, spend and earned value per project and epic
+    Show{
+        /// Key or id of the subtree to report; everything by default
+        root: Option<String>,
+    },
+    /// Set a node's estimated cost
+    Set{
+        node: String,
+        amount: f64,
+        /// ISO 4217 code, e.g. EUR; the base currency by default
+        #[arg(long)]
+        currency: Option<String>,
+    },
+    /// Set or clear a person's hourly rate
+    Rate{
+        person: String,
+        amount: Option<f64>,
+        /// ISO 4217 code, e.g. EUR; the base currency by default
+        #[arg(long)]
+        currency: Option<String>,
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Show or change the base currency, exchange rates and number locale
+    Currency{
+        /// New base currency, with the rates restated against it; costs and
+        /// rates without a currency stay in the old base
+        #[arg(long)]
+        base: Option<String>,
+        /// CODE=RATE, what one unit of CODE is worth in the (new) base; repeatable
+        #[arg(long = "rate")]
+        rates: Vec<String>,
+        /// Drop the rate for a currency no cost or rate uses; repeatable
+        #[arg(long = "drop-rate")]
+        drop: Vec<String>,
+        /// How numbers are written, e.g. en-US, de-DE, fr-FR
+        #[arg(long)]
+        locale: Option<String>,
+    },
+}
+
+fn code(value: &str) -> String{
+    value.trim().to_ascii_uppercase()
+}
+
+fn amount(money: &MoneySettings, value: f64, format: OutputFormat) -> String{
+    match format{
+        // An empty sum is -0.0
+        OutputFormat::Json => format!("{:.2}", value + 0.0),
+        _ => money.format(value),
+    }
+}
+
+fn report(graph: &ProjectGraph, root: Option<&str>, format: OutputFormat) -> Result<()>{
+    let scope = match root{
+        Some(root) => Scope::Subtree(graph.resolve_id(root).ok_or_else(|| anyhow!("No node '{}'", root))?),
+        None => Scope::All,
+    };
+    let money = &graph.get_settings().money;
+    let report = cost(graph, &scope);
+    let mut output = Output::new(vec!["key", "name", "budget", "actual", "variance", "cpi", "spi"]);
+    let index = |value: Option<f64>| value.map(|v| match format{
+        OutputFormat::Json => format!("{:.2}", v),
+        _ => format_number(v, 2, &money.locale),
+    }).unwrap_or_default();
+    for row in &report.rows{
+        output.push(vec![
+            graph.get_key(row.id).unwrap_or_default().to_string(),
+            row.name.clone(),
+            amount(money, row.budget, format),
+            amount(money, row.actual, format),
+            amount(money, row.variance(), format),
+            index(row.cpi()),
+            index(row.spi()),
+        ]);
+    }
+    output.print(format)
+}
+
+fn currencies(money: &MoneySettings, format: OutputFormat) -> Result<()>{
+    let mut output = Output::new(vec!["currency", "rate", "example"]);
+    output.push(vec![money.currency.clone(), "base".to_string(), money.format(1234.5)]);
+    for (currency, rate) in &money.rates{
+        let rate = match format{
+            OutputFormat::Json => rate.to_string(),
+            _ => format_number(*rate, 4, &money.locale),
+        };
+        output.push(vec![currency.clone(), rate, format_money(1234.5, currency, &money.locale)]);
+    }
+    output.print(format)
+}
+
+pub fn run(path: &Path, command: CostCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        CostCommand::Show{ root } => report(&graph, root.as_deref(), format)?,
+        CostCommand::Set{ node, amount: value, currency } => {
+            let id = graph.resolve_id(&node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+            let currency = currency.as_deref().map(code);
+            graph.set_estimated_cost_in(id, value, currency.as_deref()).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            let money = &graph.get_settings().money;
+            let mut output = Output::new(vec!["key", "cost", "currency", "in_base"]);
+            output.push(vec![
+                graph.get_key(id).unwrap_or_default().to_string(),
+                match format{
+                    OutputFormat::Json => format!("{:.2}", value),
+                    _ => format_money(value, graph.get_cost_currency(id), &money.locale),
+                },
+                graph.get_cost_currency(id).to_string(),
+                graph.cost_in_base(id).map(|c| amount(money, c, format)).unwrap_or_default(),
+            ]);
+            output.print(format)?;
+        }
+        CostCommand::Rate{ person, amount: value, currency, clear } => {
+            let rate = match (value, clear){
+                (Some(_), true) => bail!("Give a rate or --clear, not both"),
+                (None, false) => bail!("Give the hourly rate, or --clear to remove it"),
+                (value, _) => value,
+            };
+            if rate.is_some() && graph.get_person(&person).is_none(){
+                graph.add_person(Person::new(person.clone())).map_err(|e| anyhow!(e))?;
+            }
+            let currency = currency.as_deref().map(code);
+            graph.set_person_rate(&person, rate, currency.as_deref()).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        CostCommand::Currency{ base, rates, drop, locale } => {
+            if base.is_some() || !rates.is_empty() || !drop.is_empty() || locale.is_some(){
+                let mut settings = graph.get_settings().clone();
+                if let Some(base) = base{
+                    settings.money.rebase(&code(&base)).map_err(|e| anyhow!(e))?;
+                }
+                for pair in rates{
+                    let (currency, rate) = pair.split_once('=').ok_or_else(|| anyhow!("Write exchange rates as CODE=RATE, e.g. EUR=1.08"))?;
+                    let rate: f64 = rate.trim().parse().map_err(|_| anyhow!("'{}' is not a number", rate.trim()))?;
+                    settings.money.rates.insert(code(currency), rate);
+                }
+                for currency in drop{
+                    settings.money.rates.remove(&code(&currency));
+                }
+                // The base is worth exactly one of itself
+                let base = settings.money.currency.clone();
+                settings.money.rates.remove(&base);
+                if let Some(locale) = locale{
+                    settings.money.locale = locale;
+                }
+                graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+                storage::write(&graph, path)?;
+            }
+            currencies(&graph.get_settings().money, format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,259 @@
+// `pm init` and `pm new` - creating projects and nodes, either from flags or
+// by prompting for whatever is missing with --interactive; `pm add` for a
+// task typed as one line
+
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::dates::parse_human;
+use crate::core::{parse_quickadd_at, Calendar, Effort, Node, NodeBuilder, PointScale, Timeline};
+use super::output::{Output, OutputFormat};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Days, Utc};
+use clap::{Args, ValueEnum};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Input;
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+// Node id suffix for nodes created from the command line
+pub(super) const NODE_ID: &[u8; 6] = b"pmcli0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NodeKind{
+    Project,
+    Spec,
+    Epic,
+    Story,
+    Task,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct NodeArgs{
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Start date, YYYY-MM-DD or e.g. "next monday", "in 2 weeks"
+    #[arg(long)]
+    pub start: Option<String>,
+    /// End date, YYYY-MM-DD or e.g. "end of Q2"
+    #[arg(long)]
+    pub end: Option<String>,
+    #[arg(long)]
+    pub owner: Option<String>,
+    /// Key (e.g. EPIC-2) or id of the node that will contain this one
+    #[arg(long)]
+    pub parent: Option<String>,
+    /// Points, or a size on the project's t-shirt scale (e.g. M)
+    #[arg(long)]
+    pub points: Option<String>,
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Hours of work, from which the schedule derives the node's length
+    #[arg(long)]
+    pub effort: Option<f64>,
+    /// Percent of the owner's working day spent on the node
+    #[arg(long, requires = "effort")]
+    pub allocation: Option<u8>,
+    /// Prompt for anything not given as a flag
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+pub(crate) fn parse_date(value: &str, calendar: &Calendar) -> Result<DateTime<Utc>>{
+    let date = parse_human(value, Utc::now(), calendar).map_err(|e| anyhow!("'{}': {}", value, e))?;
+    Ok(date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc())
+}
+
+fn prompt_text(label: &str, default: Option<String>, required: bool) -> Result<Option<String>>{
+    let theme = ColorfulTheme::default();
+    let mut input = Input::<String>::with_theme(&theme).with_prompt(label).allow_empty(!required);
+    if let Some(default) = default{
+        input = input.default(default);
+    }
+    if required{
+        input = input.validate_with(|v: &String| if v.trim().is_empty() { Err("required") } else { Ok(()) });
+    }
+    let value = input.interact_text()?;
+    Ok((!value.trim().is_empty()).then(|| value.trim().to_string()))
+}
+
+fn prompt_date(label: &str, default: DateTime<Utc>, not_before: Option<DateTime<Utc>>, calendar: &Calendar) -> Result<DateTime<Utc>>{
+    let value = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(label)
+        .default(default.format("%Y-%m-%d").to_string())
+        .validate_with(|v: &String| match parse_date(v, calendar){
+            Ok(date) if not_before.is_some_and(|min| date < min) => Err("must not be before the start date".to_string()),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        })
+        .interact_text()?;
+    parse_date(&value, calendar)
+}
+
+fn prompt_points(scale: &PointScale) -> Result<Option<u32>>{
+    let prompt = match scale{
+        PointScale::Any => "Points (blank for none)".to_string(),
+        scale => format!("Points, one of {} (blank for none)", scale),
+    };
+    let value = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(|v: &String| if v.trim().is_empty() { Ok(()) } else { scale.parse(v).map(|_| ()) })
+        .interact_text()?;
+    Ok(scale.parse(&value).ok())
+}
+
+// Asks for a parent until the answer exists and may contain `node`
+fn prompt_parent(graph: &ProjectGraph, node: &Node) -> Result<Option<Uuid>>{
+    let value = Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt("Parent key or id (blank for none)")
+        .allow_empty(true)
+        .validate_with(|v: &String| if v.trim().is_empty() { Ok(()) } else { check_parent(graph, node, v).map(|_| ()) })
+        .interact_text()?;
+    if value.trim().is_empty(){
+        return Ok(None);
+    }
+    check_parent(graph, node, &value).map(Some).map_err(|e| anyhow!(e))
+}
+
+fn check_parent(graph: &ProjectGraph, node: &Node, parent: &str) -> std::result::Result<Uuid,String>{
+    let id = graph.resolve_id(parent).ok_or_else(|| format!("no node '{}'", parent.trim()))?;
+    let mut trial = graph.clone();
+    trial.create_node(node).map_err(str::to_string)?;
+    trial.connect(id, node.get_id(), DependencyType::Contains)
+        .map_err(|e| format!("{} cannot contain this node: {}", parent.trim(), e))?;
+    Ok(id)
+}
+
+fn build(kind: NodeKind, builder: NodeBuilder) -> Result<Node>{
+    match kind{
+        NodeKind::Project => builder.build_project(),
+        NodeKind::Spec => builder.build_spec(),
+        NodeKind::Epic => builder.build_epic(),
+        NodeKind::Story => builder.build_userstory(),
+        NodeKind::Task => builder.build_tasks(),
+    }.map_err(|e| anyhow!(e))
+}
+
+pub fn init(path: &Path, args: NodeArgs, format: OutputFormat) -> Result<ExitCode>{
+    if path.exists(){
+        bail!("{} already exists", path.display());
+    }
+    let mut graph = ProjectGraph::new();
+    let id = add(&mut graph, NodeKind::Project, args)?;
+    storage::write(&graph, path)?;
+    created(&graph, id, path).print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn new_node(path: &Path, kind: NodeKind, args: NodeArgs, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = add(&mut graph, kind, args)?;
+    storage::write(&graph, path)?;
+    created(&graph, id, path).print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn quick_add(path: &Path, line: &str, parent: Option<&str>, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let quick = parse_quickadd_at(line, Utc::now(), graph.get_calendar(), &graph.get_settings().point_scale).map_err(|e| anyhow!(e))?;
+    let id = Uuid::now_v6(NODE_ID);
+    let node = quick.builder.with_id(id).build_tasks().map_err(|e| anyhow!(e))?;
+    let parent = parent.map(|p| check_parent(&graph, &node, p)).transpose().map_err(|e| anyhow!(e))?;
+
+    graph.create_node(&node).map_err(|e| anyhow!(e))?;
+    if let Some(parent) = parent{
+        graph.connect(parent, id, DependencyType::Contains).map_err(|e| anyhow!(e))?;
+    }
+    if let Some(priority) = quick.priority{
+        graph.set_priority(id, priority).map_err(|e| anyhow!(e))?;
+    }
+    storage::write(&graph, path)?;
+    created(&graph, id, path).print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn created(graph: &ProjectGraph, id: Uuid, path: &Path) -> Output{
+    let mut output = Output::new(vec!["key", "id", "name", "file"]);
+    let name = graph.get_node(id).map(|n| n.get_name().to_string()).unwrap_or_default();
+    output.push(vec![graph.get_key(id).unwrap_or_default().to_string(), id.to_string(), name, path.display().to_string()]);
+    output
+}
+
+fn add(graph: &mut ProjectGraph, kind: NodeKind, args: NodeArgs) -> Result<Uuid>{
+    let interactive = args.interactive;
+    let label = format!("{:?} name", kind);
+
+    let name = match args.name{
+        Some(name) => name,
+        None if interactive => prompt_text(&label, None, true)?.expect("required input"),
+        None => bail!("--name is required (or use --interactive)"),
+    };
+
+    // Projects may leave their dates open and specs have none; everything
+    // else needs a timeline
+    let start = match &args.start{
+        _ if kind == NodeKind::Spec => None,
+        Some(start) => Some(parse_date(start, graph.get_calendar())?),
+        None if interactive => Some(prompt_date("Start date", Utc::now(), None, graph.get_calendar())?),
+        None if kind == NodeKind::Project => None,
+        None => bail!("--start is required (or use --interactive)"),
+    };
+    let end = match (&args.end, start){
+        (Some(end), _) => Some(parse_date(end, graph.get_calendar())?),
+        (None, Some(start)) if interactive => {
+            let default = start.checked_add_days(Days::new(14)).unwrap_or(start);
+            Some(prompt_date("End date", default, Some(start), graph.get_calendar())?)
+        }
+        (None, start) => start,
+    };
+    if let (Some(start), Some(end)) = (start, end){
+        if end < start{
+            bail!("The end date is before the start date");
+        }
+    }
+
+    let owner = match args.owner{
+        Some(owner) => Some(owner),
+        None if interactive => prompt_text("Owner (blank for none)", None, false)?,
+        None => None,
+    };
+    let scale = &graph.get_settings().point_scale;
+    let points = match args.points{
+        Some(points) => Some(scale.parse(&points).map_err(|e| anyhow!("'{}': {}", points, e))?),
+        None if interactive && !matches!(kind, NodeKind::Project | NodeKind::Spec) => prompt_points(scale)?,
+        None => None,
+    };
+
+    let id = Uuid::now_v6(NODE_ID);
+    let mut builder = NodeBuilder::new().with_id(id).with_name(name);
+    if let (Some(start), Some(end)) = (start, end){
+        builder = builder.with_timeline(Timeline::from_start_end(start, end));
+    }
+    if let Some(owner) = owner{
+        builder = builder.with_owner(owner);
+    }
+    if let Some(points) = points{
+        builder = builder.with_points(points);
+    }
+    let node = build(kind, builder)?;
+
+    let parent = match &args.parent{
+        Some(parent) => Some(check_parent(graph, &node, parent).map_err(|e| anyhow!(e))?),
+        None if interactive && graph.nodes().next().is_some() => prompt_parent(graph, &node)?,
+        None => None,
+    };
+
+    graph.create_node(&node).map_err(|e| anyhow!(e))?;
+    if let Some(parent) = parent{
+        graph.connect(parent, id, DependencyType::Contains).map_err(|e| anyhow!(e))?;
+    }
+    if let Some(description) = args.description{
+        graph.set_description(id, &description).map_err(|e| anyhow!(e))?;
+    }
+    if let Some(hours) = args.effort{
+        let effort = Effort::new(hours).and_then(|e| e.with_allocation(args.allocation.unwrap_or(100))).map_err(|e| anyhow!(e))?;
+        graph.set_effort(id, effort).map_err(|e| anyhow!(e))?;
+    }
+    Ok(id)
+}
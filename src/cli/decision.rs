@@ -0,0 +1,176 @@
+// `pm decision` - architectural and product decisions kept in the graph: the
+// question, the options weighed, what was chosen and by whom, and the work
+// it affects
+
+use super::create::{parse_date, NODE_ID};
+use super::output::{Output, OutputFormat};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Actor, Node, NodeBuilder, Scope};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Debug, Subcommand)]
+pub enum DecisionCommand{
+    /// Record an open question
+    New{
+        question: String,
+        /// An option being weighed; repeatable
+        #[arg(long = "option")]
+        options: Vec<String>,
+        /// Key or id of the project or epic the decision belongs to
+        #[arg(long)]
+        parent: Option<String>,
+        #[arg(long)]
+        owner: Option<String>,
+    },
+    /// Add an option to a decision
+    Option{
+        decision: String,
+        option: String,
+    },
+    /// Record the chosen option
+    Decide{
+        decision: String,
+        option: String,
+        /// Who decided; repeatable, the acting person by default
+        #[arg(long = "by")]
+        deciders: Vec<String>,
+        /// The day it was decided (YYYY-MM-DD or e.g. "last friday"); today by default
+        #[arg(long)]
+        on: Option<String>,
+    },
+    /// Link a decision to work it affects
+    Link{
+        decision: String,
+        node: String,
+    },
+    Unlink{
+        decision: String,
+        node: String,
+    },
+    /// Decisions in a subtree or affecting its work; all by default
+    List{
+        root: Option<String>,
+    },
+    /// A decision's options, outcome and the work it affects, or the
+    /// decisions affecting a node
+    Show{
+        node: String,
+    },
+}
+
+fn resolve(graph: &ProjectGraph, value: &str) -> Result<Uuid>{
+    graph.resolve_id(value).ok_or_else(|| anyhow!("No node '{}'", value))
+}
+
+fn resolve_decision(graph: &ProjectGraph, value: &str) -> Result<Uuid>{
+    let id = resolve(graph, value)?;
+    match graph.get_node(id){
+        Some(Node::Decision{..}) => Ok(id),
+        _ => bail!("{} is not a decision", value),
+    }
+}
+
+fn key(graph: &ProjectGraph, id: Uuid) -> String{
+    graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string())
+}
+
+fn outcome(node: &Node) -> (String, String, String){
+    match node.get_decision(){
+        Some((chosen, on)) => (chosen.to_string(), on.to_string(), node.get_deciders().join(", ")),
+        None => ("open".to_string(), "-".to_string(), "-".to_string()),
+    }
+}
+
+fn list(graph: &ProjectGraph, decisions: &[&Node]) -> Output{
+    let mut output = Output::new(vec!["key", "question", "chosen", "on", "by", "affects"]);
+    for decision in decisions{
+        let (chosen, on, by) = outcome(decision);
+        let affects: Vec<String> = graph.affected_by(decision.get_id()).into_iter().map(|id| key(graph, id)).collect();
+        output.push(vec![key(graph, decision.get_id()), decision.get_name().to_string(), chosen, on, by, affects.join(", ")]);
+    }
+    output
+}
+
+pub fn run(path: &Path, command: DecisionCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        DecisionCommand::New{ question, options, parent, owner } => {
+            let parent = parent.map(|p| resolve(&graph, &p)).transpose()?;
+            let id = Uuid::now_v6(NODE_ID);
+            let mut builder = NodeBuilder::new().with_id(id).with_name(question);
+            for option in options{
+                builder = builder.with_option(option);
+            }
+            if let Some(owner) = owner{
+                builder = builder.with_owner(owner);
+            }
+            let node = builder.build_decision().map_err(|e| anyhow!(e))?;
+            graph.create_node(&node).map_err(|e| anyhow!(e))?;
+            if let Some(parent) = parent{
+                graph.connect(parent, id, DependencyType::Contains).map_err(|e| anyhow!("{}: only projects and epics hold decisions", e))?;
+            }
+            storage::write(&graph, path)?;
+            list(&graph, &[&node]).print(format)?;
+        }
+        DecisionCommand::Option{ decision, option } => {
+            let id = resolve_decision(&graph, &decision)?;
+            graph.add_decision_option(id, &option).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DecisionCommand::Decide{ decision, option, deciders, on } => {
+            let id = resolve_decision(&graph, &decision)?;
+            let deciders = match (deciders.is_empty(), Actor::current()){
+                (false, _) => deciders,
+                (true, Some(Actor::Person(name))) => vec![name],
+                (true, _) => bail!("Give who decided with --by"),
+            };
+            let at = match on{
+                Some(on) => parse_date(&on, graph.get_calendar())?,
+                None => Utc::now(),
+            };
+            graph.decide_at(id, &option, &deciders, at).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DecisionCommand::Link{ decision, node } => {
+            let id = resolve_decision(&graph, &decision)?;
+            let node = resolve(&graph, &node)?;
+            graph.link_decision(id, node).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DecisionCommand::Unlink{ decision, node } => {
+            let id = resolve_decision(&graph, &decision)?;
+            let node = resolve(&graph, &node)?;
+            graph.unlink_decision(id, node).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DecisionCommand::List{ root } => {
+            let scope = match root{
+                Some(root) => Scope::Subtree(resolve(&graph, &root)?),
+                None => Scope::All,
+            };
+            list(&graph, &graph.decisions_in_scope(&scope)).print(format)?;
+        }
+        DecisionCommand::Show{ node } => {
+            let id = resolve(&graph, &node)?;
+            let Some(decision @ Node::Decision{..}) = graph.get_node(id) else {
+                let decisions: Vec<&Node> = graph.decisions_affecting(id).into_iter().filter_map(|d| graph.get_node(d)).collect();
+                list(&graph, &decisions).print(format)?;
+                return Ok(ExitCode::SUCCESS);
+            };
+            let (chosen, _, _) = outcome(decision);
+            let mut output = Output::new(vec!["option", "chosen"]);
+            for option in decision.get_options(){
+                output.push(vec![option.clone(), if *option == chosen { "yes" } else { "" }.to_string()]);
+            }
+            list(&graph, &[decision]).print(format)?;
+            output.print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
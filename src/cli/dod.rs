@@ -0,0 +1,139 @@
+// `pm dod` - definition-of-done templates per kind, and the checklists nodes
+// get from them
+
+use super::output::{Output, OutputFormat};
+use super::rule::kind_prefix;
+use crate::core::graph::ProjectGraph;
+use crate::core::DodPolicy;
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::{Subcommand, ValueEnum};
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PolicyArg{
+    /// Allow Done; `pm validate` lists what was left unticked
+    Warn,
+    /// Refuse Done until every item is ticked
+    Enforce,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DodCommand{
+    /// Set the items new nodes of a kind start with; no items clears the template
+    Template{
+        /// project, epic, story or task
+        kind: String,
+        items: Vec<String>,
+    },
+    /// Templates by kind
+    Templates,
+    /// Whether Done waits for the checklist; shows the policy without one
+    Policy{
+        policy: Option<PolicyArg>,
+    },
+    /// A node's checklist
+    Show{
+        node: String,
+    },
+    /// Add an item to a node's checklist
+    Add{
+        node: String,
+        text: String,
+    },
+    /// Tick off item N (from `show`), or untick it with --undo
+    Tick{
+        node: String,
+        item: usize,
+        #[arg(long)]
+        undo: bool,
+    },
+    Remove{
+        node: String,
+        item: usize,
+    },
+}
+
+fn resolve(graph: &ProjectGraph, value: &str) -> Result<Uuid>{
+    graph.resolve_id(value).ok_or_else(|| anyhow!("No node '{}'", value))
+}
+
+// Items are numbered from 1 on the command line
+fn index(item: usize) -> Result<usize>{
+    item.checked_sub(1).ok_or_else(|| anyhow!("Items are numbered from 1"))
+}
+
+fn checklist(graph: &ProjectGraph, id: Uuid) -> Output{
+    let mut output = Output::new(vec!["#", "done", "item"]);
+    for (i, item) in graph.get_checklist(id).iter().enumerate(){
+        output.push(vec![(i + 1).to_string(), if item.done { "x" } else { "" }.to_string(), item.text.clone()]);
+    }
+    output
+}
+
+pub fn run(path: &Path, command: DodCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        DodCommand::Template{ kind, items } => {
+            let prefix = kind_prefix(&kind)?;
+            let mut settings = graph.get_settings().clone();
+            if items.is_empty(){
+                settings.definition_of_done.templates.remove(prefix);
+            }else{
+                settings.definition_of_done.templates.insert(prefix.to_string(), items);
+            }
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DodCommand::Templates => {
+            let dod = &graph.get_settings().definition_of_done;
+            let mut output = Output::new(vec!["kind", "items"]);
+            for (prefix, items) in &dod.templates{
+                output.push(vec![prefix.clone(), items.join("; ")]);
+            }
+            output.print(format)?;
+        }
+        DodCommand::Policy{ policy: None } => {
+            let mut output = Output::new(vec!["policy"]);
+            output.push(vec![format!("{:?}", graph.get_settings().definition_of_done.policy)]);
+            output.print(format)?;
+        }
+        DodCommand::Policy{ policy: Some(policy) } => {
+            let mut settings = graph.get_settings().clone();
+            settings.definition_of_done.policy = match policy{
+                PolicyArg::Warn => DodPolicy::Warn,
+                PolicyArg::Enforce => DodPolicy::Enforce,
+            };
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        DodCommand::Show{ node } => {
+            let id = resolve(&graph, &node)?;
+            if graph.get_checklist(id).is_empty(){
+                bail!("{} has no checklist", node);
+            }
+            checklist(&graph, id).print(format)?;
+        }
+        DodCommand::Add{ node, text } => {
+            let id = resolve(&graph, &node)?;
+            graph.add_checklist_item(id, &text).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            checklist(&graph, id).print(format)?;
+        }
+        DodCommand::Tick{ node, item, undo } => {
+            let id = resolve(&graph, &node)?;
+            graph.set_checklist_item_done(id, index(item)?, !undo).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            checklist(&graph, id).print(format)?;
+        }
+        DodCommand::Remove{ node, item } => {
+            let id = resolve(&graph, &node)?;
+            graph.remove_checklist_item(id, index(item)?).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            checklist(&graph, id).print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,81 @@
+// `pm effort` - hours of work on a node, the owner's allocation to it and
+// how the hours fall over its days
+// `pm load` - the hours that puts on each owner, by day or week
+
+use super::output::{Output, OutputFormat};
+use crate::analytics::daily_load;
+use crate::core::{Effort, EffortCurve, Scope};
+use crate::scheduler::schedule;
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::Days;
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn effort(path: &Path, node: &str, hours: Option<f64>, allocation: Option<u8>, curve: Option<&str>, clear: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+    if clear{
+        if graph.clear_effort(id).is_none(){
+            bail!("The node has no effort to clear");
+        }
+        storage::write(&graph, path)?;
+    }else if hours.is_some() || allocation.is_some() || curve.is_some(){
+        let hours = match (hours, graph.get_effort(id)){
+            (Some(hours), _) => hours,
+            (None, Some(current)) => current.hours,
+            (None, None) => bail!("Give the hours of effort before an allocation or curve"),
+        };
+        let allocation = allocation.or(graph.get_effort(id).map(|e| e.allocation)).unwrap_or(100);
+        let curve = match curve{
+            Some(curve) => curve.parse::<EffortCurve>().map_err(|e| anyhow!(e))?,
+            None => graph.get_effort(id).map(|e| e.curve).unwrap_or_default(),
+        };
+        let effort = Effort::new(hours).and_then(|e| e.with_allocation(allocation)).map_err(|e| anyhow!(e))?.with_curve(curve);
+        graph.set_effort(id, effort).map_err(|e| anyhow!(e))?;
+        storage::write(&graph, path)?;
+    }
+
+    let scheduled = schedule(&graph).map_err(|e| anyhow!(e))?;
+    let effort = graph.get_effort(id);
+    let mut output = Output::new(vec!["key", "effort_hours", "allocation", "curve", "start", "finish"]);
+    output.push(vec![
+        graph.get_key(id).unwrap_or_default().to_string(),
+        effort.map(|e| e.hours.to_string()).unwrap_or_default(),
+        effort.map(|e| format!("{}%", e.allocation)).unwrap_or_default(),
+        effort.map(|e| e.curve.to_string()).unwrap_or_default(),
+        scheduled.get(id).map(|n| n.start.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+        scheduled.get(id).map(|n| n.end.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+    ]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn load(path: &Path, scope: Option<&str>, weekly: bool, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match scope{
+        Some(root) => Scope::Subtree(graph.resolve_id(root).ok_or_else(|| anyhow!("No node '{}'", root))?),
+        None => Scope::All,
+    };
+    let load = daily_load(&graph, &scope).map_err(|e| anyhow!(e))?;
+
+    let mut output = Output::new(vec![if weekly { "week_of" } else { "date" }, "owner", "hours", "over"]);
+    if weekly{
+        // A week holds as many working days as the owner is available
+        for (owner, weeks) in load.weekly(){
+            for (week, hours) in weeks{
+                let over = hours - graph.available_days(&owner, week, week + Days::new(6)).len() as f64 * load.hours_per_day;
+                output.push(vec![week.to_string(), owner.clone(), format!("{:.1}", hours), if over > 1e-9 { format!("{:.1}", over) } else { String::new() }]);
+            }
+        }
+    }else{
+        for (owner, days) in &load.hours{
+            for (date, hours) in days{
+                let over = hours - load.capacity(owner, *date);
+                output.push(vec![date.to_string(), owner.clone(), format!("{:.1}", hours), if over > 1e-9 { format!("{:.1}", over) } else { String::new() }]);
+            }
+        }
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
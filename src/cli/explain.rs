@@ -0,0 +1,46 @@
+// `pm explain` - why a node is scheduled where it is
+
+use super::output::{Output, OutputFormat};
+use crate::scheduler::{explain as explain_schedule, LengthSource, StartCause};
+use crate::storage;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn explain(path: &Path, node: &str, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+    let why = explain_schedule(&graph, id).map_err(|e| anyhow!(e))?;
+    if let OutputFormat::Table = format{
+        print!("{}", why.render_text(&graph));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let key = |id| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+    let (cause, by) = match &why.cause{
+        StartCause::Planned => ("planned", String::new()),
+        StartCause::Constraint{ on, .. } => ("constraint", key(*on)),
+        StartCause::Remote{ remote, .. } => ("remote", format!("{}:{}", remote.source, remote.node)),
+        StartCause::Predecessor{ id, .. } => ("predecessor", key(*id)),
+        StartCause::Children{ first } => ("children", key(*first)),
+        StartCause::Resource{ after, .. } => ("resource", key(*after)),
+        StartCause::Away{ owner, .. } => ("away", owner.clone()),
+    };
+    let length = match why.length{
+        LengthSource::Effort(_) => "effort",
+        LengthSource::Timeline => "timeline",
+        LengthSource::Children => "children",
+    };
+    let mut output = Output::new(vec!["key", "start", "end", "cause", "by", "length", "days_off"]);
+    output.push(vec![
+        key(id),
+        why.start.to_rfc3339(),
+        why.end.to_rfc3339(),
+        cause.to_string(),
+        by,
+        length.to_string(),
+        why.gaps.len().to_string(),
+    ]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,78 @@
+// `pm gantt` - the schedule as a Gantt chart: text, SVG or Mermaid, at a
+// day, week, month or quarter scale
+
+use super::output::{Output, OutputFormat};
+use crate::core::Scope;
+use crate::storage;
+use crate::views::{gantt as build_gantt, TimeScale};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+// Most columns a text chart gets when the scale is left to fit
+const TEXT_COLUMNS: i64 = 100;
+// Most periods an SVG chart gets when the scale is left to fit
+const SVG_COLUMNS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Scale{
+    // The finest scale the plan fits at
+    #[default]
+    Auto,
+    Day,
+    Week,
+    Month,
+    Quarter,
+}
+
+impl Scale{
+    fn time_scale(self) -> Option<TimeScale>{
+        match self{
+            Scale::Auto => None,
+            Scale::Day => Some(TimeScale::Day),
+            Scale::Week => Some(TimeScale::Week),
+            Scale::Month => Some(TimeScale::Month),
+            Scale::Quarter => Some(TimeScale::Quarter),
+        }
+    }
+}
+
+pub fn gantt(path: &Path, scope: Option<&str>, scale: Scale, svg: bool, mermaid: bool, out: Option<&Path>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match scope{
+        Some(root) => Scope::Subtree(graph.resolve_id(root).ok_or_else(|| anyhow!("No node '{}'", root))?),
+        None => Scope::All,
+    };
+    let chart = build_gantt(&graph, &scope).map_err(|e| anyhow!(e))?;
+    let today = Utc::now().date_naive();
+
+    let rendered = match (svg, mermaid, format){
+        (true, _, _) => chart.render_svg(scale.time_scale().unwrap_or_else(|| chart.fit(SVG_COLUMNS)), today),
+        (_, true, _) => chart.render_mermaid(scale.time_scale().unwrap_or_else(|| chart.fit(SVG_COLUMNS))),
+        (_, _, OutputFormat::Table) => chart.render_text(scale.time_scale().unwrap_or_else(|| chart.fit(TEXT_COLUMNS)), today),
+        _ => {
+            let mut output = Output::new(vec!["key", "name", "depth", "start", "end", "critical", "allocation"]);
+            for row in &chart.rows{
+                output.push(vec![
+                    graph.get_key(row.id).unwrap_or_default().to_string(),
+                    graph.get_node(row.id).map(|n| n.get_name().to_string()).unwrap_or_default(),
+                    row.depth.to_string(),
+                    row.start.format("%Y-%m-%d").to_string(),
+                    row.end.format("%Y-%m-%d").to_string(),
+                    row.critical.to_string(),
+                    row.allocation.map(|a| a.to_string()).unwrap_or_default(),
+                ]);
+            }
+            output.print(format)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    match out{
+        Some(out) => fs::write(out, rendered).with_context(|| format!("writing {}", out.display()))?,
+        None => print!("{}", rendered),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,85 @@
+// `pm github` - linking pull requests to work and moving the work along as
+// they are reviewed and merged
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::publish::CurlTransport;
+use crate::storage;
+use crate::sync::{linked_pulls, watch_pulls, GitHubApi, PullRef};
+use anyhow::{anyhow, Result};
+use chrono::{TimeDelta, Utc};
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum GithubCommand{
+    /// Link a pull request to a node
+    Link{
+        /// Key or id of the node
+        node: String,
+        /// owner/repo#42 or the PR's URL
+        pull: String,
+    },
+    /// Linked pull requests with their state as last looked up
+    List,
+    /// Look up linked pull requests and move their nodes to review or Done;
+    /// reads PM_GITHUB_TOKEN when set
+    Sync{
+        /// Minutes before an open pull request is looked up again
+        #[arg(long, default_value_t = 15)]
+        refresh: i64,
+    },
+}
+
+fn key(graph: &ProjectGraph, id: uuid::Uuid) -> String{
+    graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string())
+}
+
+pub fn run(path: &Path, command: GithubCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        GithubCommand::Link{ node, pull } => {
+            let id = graph.resolve_id(&node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+            let pull = PullRef::parse(&pull).ok_or_else(|| anyhow!("'{}' is not owner/repo#N or a pull request URL", pull))?;
+            graph.add_external_ref(id, pull.external_ref()).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        GithubCommand::List => {
+            let mut output = Output::new(vec!["node", "pull", "state", "checked"]);
+            for (id, pulls) in linked_pulls(&graph){
+                for pull in pulls{
+                    let cached = graph.get_sync_state().pull(&pull.key());
+                    output.push(vec![
+                        key(&graph, id),
+                        pull.key(),
+                        cached.map(|c| format!("{:?}", c.state)).unwrap_or_else(|| "unknown".to_string()),
+                        cached.map(|c| c.checked.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+                    ]);
+                }
+            }
+            output.print(format)?;
+        }
+        GithubCommand::Sync{ refresh } => {
+            let token = std::env::var("PM_GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+            let mut transport = CurlTransport;
+            let report = watch_pulls(&mut graph, &mut GitHubApi::new(&mut transport, token), TimeDelta::minutes(refresh.max(0)), Utc::now());
+            storage::write(&graph, path)?;
+            for error in &report.errors{
+                eprintln!("{}", error);
+            }
+            if report.deferred > 0{
+                eprintln!("GitHub's rate limit was reached; {} pull requests are left for the next sync", report.deferred);
+            }
+            let mut output = Output::new(vec!["node", "moved_to"]);
+            for moved in &report.moved{
+                output.push(vec![key(&graph, moved.node), moved.to.clone()]);
+            }
+            output.print(format)?;
+            if !report.errors.is_empty(){
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
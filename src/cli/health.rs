@@ -0,0 +1,32 @@
+// `pm health` - a project's 0-100 health score and what it is made of
+
+use super::output::{Output, OutputFormat};
+use crate::analytics::health_score;
+use crate::core::Node;
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn health(path: &Path, project: Option<&str>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let projects: Vec<_> = match project{
+        Some(key) => vec![graph.resolve_id(key).ok_or_else(|| anyhow!("No node '{}'", key))?],
+        None => graph.nodes().filter(|n| matches!(n, Node::Project{..})).map(|n| n.get_id()).collect(),
+    };
+    if projects.is_empty(){
+        bail!("The file has no Project");
+    }
+
+    let mut output = Output::new(vec!["project", "factor", "weight", "score", "detail"]);
+    for id in projects{
+        let health = health_score(&graph, id).map_err(|e| anyhow!(e))?;
+        let key = graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+        for c in &health.components{
+            output.push(vec![key.clone(), c.name.to_string(), format!("{:.2}", c.weight), format!("{:.0}", c.score), c.detail.clone()]);
+        }
+        output.push(vec![key, "total".to_string(), String::new(), format!("{:.0}", health.score), health.rating().to_string()]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,69 @@
+// `pm holidays` - public holidays for everyone or per region
+
+use super::output::{Output, OutputFormat};
+use crate::core::holidays::{parse_csv, parse_ics};
+use crate::core::Person;
+use crate::storage;
+use anyhow::{anyhow, Context, Result};
+use clap::Subcommand;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum HolidaysCommand{
+    /// Add the holidays of an .ics or .csv (date,name) feed to the calendar
+    Import{
+        feed: PathBuf,
+        /// Only people in this region get the days off; everyone when left out
+        #[arg(long)]
+        region: Option<String>,
+    },
+    /// Holidays in the calendar
+    List{
+        /// A region's holidays instead of everyone's
+        #[arg(long)]
+        region: Option<String>,
+    },
+    /// Put a person in a region, or take them out of any when REGION is left out
+    Assign{
+        person: String,
+        region: Option<String>,
+    },
+}
+
+pub fn run(path: &Path, command: HolidaysCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        HolidaysCommand::Import{ feed, region } => {
+            let text = fs::read_to_string(&feed).with_context(|| format!("Could not read {}", feed.display()))?;
+            let is_ics = feed.extension().is_some_and(|e| e.eq_ignore_ascii_case("ics"));
+            let holidays = if is_ics { parse_ics(&text) } else { parse_csv(&text) }.map_err(|e| anyhow!(e))?;
+            let added = graph.get_calendar_mut().import(region.as_deref(), &holidays);
+            storage::write(&graph, path)?;
+            let mut output = Output::new(vec!["region", "read", "added"]);
+            output.push(vec![region.unwrap_or_else(|| "all".to_string()), holidays.len().to_string(), added.to_string()]);
+            output.print(format)?;
+        }
+        HolidaysCommand::List{ region } => {
+            let calendar = graph.get_calendar();
+            let dates = match region.as_deref(){
+                Some(region) => calendar.get_regional_holidays(region).ok_or_else(|| anyhow!("No holidays for region '{}'", region))?,
+                None => calendar.get_holidays(),
+            };
+            let mut output = Output::new(vec!["date", "weekday"]);
+            for date in dates{
+                output.push(vec![date.format("%Y-%m-%d").to_string(), date.format("%a").to_string()]);
+            }
+            output.print(format)?;
+        }
+        HolidaysCommand::Assign{ person, region } => {
+            if graph.get_person(&person).is_none(){
+                graph.add_person(Person::new(person.clone())).map_err(|e| anyhow!(e))?;
+            }
+            graph.set_person_region(&person, region.as_deref()).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
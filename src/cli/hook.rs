@@ -0,0 +1,54 @@
+// `pm hook` - status changes from a commit message, for .git/hooks/post-commit:
+//
+//   #!/bin/sh
+//   pm hook -f project.json
+
+use super::output::{Output, OutputFormat};
+use crate::core::{apply_commit_message, CommitChange};
+use crate::storage;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+// The message of the commit just made, from the repository the project file is in
+fn last_commit_message(path: &Path) -> Result<String>{
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = Command::new("git").args(["log", "-1", "--format=%B"]).current_dir(dir)
+        .output()
+        .context("Could not run git")?;
+    if !output.status.success(){
+        bail!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn hook(path: &Path, message: Option<String>, dry_run: bool, format: OutputFormat) -> Result<ExitCode>{
+    let message = match message{
+        Some(message) => message,
+        None => last_commit_message(path)?,
+    };
+    let mut graph = storage::open(path)?;
+    let outcome = apply_commit_message(&mut graph, &message, Utc::now());
+    if !dry_run && !outcome.applied.is_empty(){
+        storage::write(&graph, path)?;
+    }
+
+    for key in &outcome.unknown{
+        eprintln!("{}: no such node", key);
+    }
+    for (key, reason) in &outcome.refused{
+        eprintln!("{}: {}", key, reason);
+    }
+    let mut output = Output::new(vec!["node", "change"]);
+    for (id, change) in &outcome.applied{
+        let change = match change{
+            CommitChange::Close => "done".to_string(),
+            CommitChange::Start => "started".to_string(),
+            CommitChange::Progress(percent) => format!("{}%", percent),
+        };
+        output.push(vec![graph.get_key(*id).unwrap_or_default().to_string(), change]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
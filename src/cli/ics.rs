@@ -0,0 +1,40 @@
+// `pm ics` - a project's or an owner's dates as an iCalendar feed
+
+use super::output::{Output, OutputFormat};
+use crate::core::Node;
+use crate::storage;
+use crate::views::{feed_events, ics_feed, FeedScope};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn ics(path: &Path, project: Option<&str>, owner: Option<String>, out: Option<&Path>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let scope = match (owner, project){
+        (Some(owner), _) => FeedScope::Owner(owner),
+        (None, Some(p)) => FeedScope::Project(graph.resolve_id(p).ok_or_else(|| anyhow!("No node '{}'", p))?),
+        (None, None) => FeedScope::Project(graph.nodes()
+            .find(|n| matches!(n, Node::Project{..}) && graph.get_parent(n.get_id()).is_none())
+            .map(|n| n.get_id())
+            .ok_or_else(|| anyhow!("The file has no project"))?),
+    };
+    // The feed itself for people and calendars, its events for scripts
+    let feed = match format{
+        OutputFormat::Table => ics_feed(&graph, &scope, Utc::now()).map_err(|e| anyhow!(e))?,
+        _ => {
+            let (_, events) = feed_events(&graph, &scope).map_err(|e| anyhow!(e))?;
+            let mut output = Output::new(vec!["uid", "date", "summary"]);
+            for event in events{
+                output.push(vec![event.uid, event.day.format("%Y-%m-%d").to_string(), event.summary]);
+            }
+            output.render(format)?
+        }
+    };
+    match out{
+        Some(out) => fs::write(out, feed).with_context(|| format!("writing {}", out.display()))?,
+        None => print!("{}", feed),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,90 @@
+// `pm import` and `pm export` - a Markdown or org-mode outline added as nodes,
+// and nodes written out as an outline that imports back onto them, or with
+// `--svg` as a drawing of their dependencies and with `--html` as a page to
+// explore that drawing in
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::Scope;
+use crate::import::{export_outline, import_outline, OutlineFormat};
+use crate::storage;
+use crate::views::{explorer, network};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+pub fn import(path: &Path, outline: &Path, parent: Option<&str>, org: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let text = fs::read_to_string(outline).with_context(|| format!("reading {}", outline.display()))?;
+    let parent = parent.map(|p| graph.resolve_id(p).ok_or_else(|| anyhow!("No node '{}'", p))).transpose()?;
+    let outline_format = if org { OutlineFormat::Org } else { OutlineFormat::from_path(outline) };
+    let imported = import_outline(&mut graph, &text, outline_format, parent, Utc::now())?;
+    storage::write(&graph, path)?;
+
+    let mut output = Output::new(vec!["key", "name", "status", "action"]);
+    let rows = imported.created.iter().map(|id| (*id, "created"))
+        .chain(imported.updated.iter().map(|id| (*id, "updated")))
+        .chain(imported.unchanged.iter().map(|id| (*id, "unchanged")));
+    for (id, action) in rows{
+        let node = graph.get_node(id);
+        output.push(vec![
+            graph.get_key(id).unwrap_or_default().to_string(),
+            node.map(|n| n.get_name().to_string()).unwrap_or_default(),
+            node.and_then(|n| n.get_status()).map(|s| s.to_string()).unwrap_or_default(),
+            action.to_string(),
+        ]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// The outline's nodes in the order export_outline writes them
+fn outline_rows(graph: &ProjectGraph, root: Option<Uuid>) -> Output{
+    let mut output = Output::new(vec!["key", "id", "depth", "status", "name"]);
+    let mut stack: Vec<(Uuid, usize)> = match root{
+        Some(root) => vec![(root, 0)],
+        None => graph.nodes().map(|n| n.get_id()).filter(|id| graph.get_parent(*id).is_none()).map(|id| (id, 0)).collect(),
+    };
+    // Popped from the end, so in reverse
+    stack.sort();
+    stack.reverse();
+    while let Some((id, depth)) = stack.pop(){
+        let Some(node) = graph.get_node(id) else {
+            continue;
+        };
+        output.push(vec![
+            graph.get_key(id).unwrap_or_default().to_string(),
+            id.to_string(),
+            depth.to_string(),
+            node.get_status().map(|s| s.to_string()).unwrap_or_default(),
+            node.get_name().to_string(),
+        ]);
+        let mut children = graph.get_children(id);
+        children.sort();
+        children.reverse();
+        stack.extend(children.into_iter().map(|c| (c, depth + 1)));
+    }
+    output
+}
+
+pub fn export(path: &Path, root: Option<&str>, svg: bool, html: bool, dot: bool, out: Option<&Path>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let root = root.map(|r| graph.resolve_id(r).ok_or_else(|| anyhow!("No node '{}'", r))).transpose()?;
+    let scope = root.map_or(Scope::All, Scope::Subtree);
+    // The drawings are documents of their own; the outline is a table for scripts
+    let outline = match (svg, html, dot, format){
+        (true, _, _, _) => network(&graph, &scope).render_svg(),
+        (_, true, _, _) => explorer(&graph, &scope).render_html(),
+        (_, _, true, _) => network(&graph, &scope).render_dot(),
+        (_, _, _, OutputFormat::Table) => export_outline(&graph, root),
+        _ => outline_rows(&graph, root).render(format)?,
+    };
+    match out{
+        Some(out) => fs::write(out, outline).with_context(|| format!("writing {}", out.display()))?,
+        None => print!("{}", outline),
+    }
+    Ok(ExitCode::SUCCESS)
+}
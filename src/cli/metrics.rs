@@ -0,0 +1,44 @@
+// `pm metrics` - the graph's state as Prometheus metrics
+
+use super::output::{Output, OutputFormat};
+use crate::analytics::prometheus_metrics;
+use crate::storage;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+// One row per sample of the exposition text: name, labels and value
+fn samples(text: &str) -> Output{
+    let mut output = Output::new(vec!["metric", "labels", "value"]);
+    for line in text.lines().filter(|l| !l.starts_with('#') && !l.trim().is_empty()){
+        let (series, value) = line.rsplit_once(' ').unwrap_or((line, ""));
+        let (name, labels) = match series.split_once('{'){
+            Some((name, labels)) => (name, labels.trim_end_matches('}')),
+            None => (series, ""),
+        };
+        output.push(vec![name.to_string(), labels.to_string(), value.to_string()]);
+    }
+    output
+}
+
+pub fn metrics(path: &Path, out: Option<&Path>, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let text = prometheus_metrics(&graph, Utc::now());
+    // Prometheus' own format for people and collectors, the samples for scripts
+    let text = match format{
+        OutputFormat::Table => text,
+        _ => samples(&text).render(format)?,
+    };
+    match out{
+        // Written beside the target and renamed, so a collector never reads half a file
+        Some(out) => {
+            let partial = out.with_extension("prom.tmp");
+            fs::write(&partial, text).with_context(|| format!("writing {}", partial.display()))?;
+            fs::rename(&partial, out).with_context(|| format!("writing {}", out.display()))?;
+        }
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -1 +1,658 @@
+// CLI module - the `pm` command line
 
+pub mod actual;
+pub mod approval;
+pub mod audit;
+pub mod backlog;
+pub mod blocked;
+pub mod board;
+pub mod brief;
+pub mod catalog;
+pub mod chain;
+pub mod compare;
+pub mod connect;
+pub mod coordination;
+pub mod cost;
+pub mod create;
+pub mod decision;
+pub mod dod;
+pub mod effort;
+pub mod explain;
+pub mod gantt;
+pub mod github;
+pub mod health;
+pub mod holidays;
+pub mod hook;
+pub mod ics;
+pub mod import;
+pub mod metrics;
+pub mod output;
+pub mod reassign;
+pub mod portfolio;
+pub mod publish;
+pub mod remote;
+pub mod rule;
+pub mod search;
+pub mod settings;
+pub mod standup;
+pub mod template;
+pub mod theme;
+pub mod validate;
+pub mod view;
+
+use crate::core::Actor;
+use crate::storage;
+use approval::ApprovalCommand;
+use audit::AuditCommand;
+use board::GroupBy;
+use catalog::CatalogCommand;
+use cost::CostCommand;
+use create::{NodeArgs, NodeKind};
+use decision::DecisionCommand;
+use dod::DodCommand;
+use github::GithubCommand;
+use holidays::HolidaysCommand;
+use output::{Output, OutputFormat};
+use portfolio::PortfolioCommand;
+use publish::PublishCommand;
+use remote::RemoteCommand;
+use rule::RuleCommand;
+use settings::SettingsArgs;
+use template::{RenderArgs, TemplateCommand};
+use theme::ThemeCommand;
+use view::ViewCommand;
+use anyhow::{anyhow, Result};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Debug, Parser)]
+#[command(name = "pm", about = "DAG-based project manager")]
+pub struct Cli{
+    #[command(subcommand)]
+    pub command: Command,
+    /// How results are printed
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+    /// Who changes are attributed to: a name, token:NAME for an
+    /// integration, or system; $USER by default
+    #[arg(long, global = true)]
+    pub actor: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command{
+    /// Create a new project file
+    Init{
+        #[arg(default_value = "project.json")]
+        path: PathBuf,
+        #[command(flatten)]
+        node: NodeArgs,
+    },
+    /// Add a project, epic, story or task to a project file
+    New{
+        kind: NodeKind,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+        #[command(flatten)]
+        node: NodeArgs,
+    },
+    /// Add a task from one line, e.g. "Fix login bug @alice #backend !p1 3pts due:fri"
+    Add{
+        line: String,
+        /// Key or id of the node that will contain the task
+        #[arg(long)]
+        parent: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Add the items of a Markdown or org-mode outline, nested as in the file
+    Import{
+        outline: PathBuf,
+        /// Key or id of the node to put the outline's top items under
+        #[arg(long)]
+        parent: Option<String>,
+        /// Read the outline as org-mode whatever its extension
+        #[arg(long)]
+        org: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Write nodes as a Markdown checklist outline that `pm import` reads back onto them
+    Export{
+        /// Key or id of the node to export with its contents; all top-level nodes without
+        root: Option<String>,
+        /// Draw the dependencies between the nodes as SVG instead of writing an outline
+        #[arg(long)]
+        svg: bool,
+        /// Write a standalone HTML page to explore the plan in: pan and zoom, fold
+        /// subtrees, highlight the critical path
+        #[arg(long, conflicts_with = "svg")]
+        html: bool,
+        /// Write the dependency diagram as Graphviz DOT
+        #[arg(long, conflicts_with_all = ["svg", "html"])]
+        dot: bool,
+        /// Write to this file instead of standard output
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// A project's or an owner's milestones, sprints and deadlines as an iCalendar feed
+    Ics{
+        /// Key or id of the project; the top-level project when left out
+        #[arg(long, conflicts_with = "owner")]
+        project: Option<String>,
+        /// An owner's deadlines instead of a project's dates
+        #[arg(long)]
+        owner: Option<String>,
+        /// Write to this file instead of standard output
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Apply "closes TASK-42" and "progress TASK-17 50%" from a commit message;
+    /// the last commit's message by default, for a git post-commit hook
+    Hook{
+        message: Option<String>,
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Move a node to Blocked for a reason from the taxonomy (see `pm settings --blocked-reasons`)
+    Block{
+        node: String,
+        reason: String,
+        /// What it waits on: a node key or id, or @person
+        #[arg(long)]
+        by: Option<String>,
+        #[arg(long)]
+        note: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Time spent Blocked by reason, across the project or under a node
+    Blocked{
+        root: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Node counts, overdue work, open blockers and health in Prometheus' text format
+    Metrics{
+        /// Write to this file (e.g. for node_exporter's textfile collector) instead of standard output
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// What an owner has due today and later this week
+    Today{
+        #[arg(long)]
+        owner: String,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// An owner's standup: completed and unblocked since yesterday, due today
+    Standup{
+        #[arg(long)]
+        owner: String,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Work items by status column, optionally in swimlanes
+    Board{
+        /// Key or id of the node whose subtree to show; everything by default
+        #[arg(long)]
+        scope: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        by: GroupBy,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Status report for a project, optionally broken down by swimlane
+    Report{
+        /// Key or id of the project; the top-level one by default
+        project: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        by: GroupBy,
+        #[command(flatten)]
+        render: RenderArgs,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// A project on one page: objective, timeline, milestones, top risks, team
+    /// and where it stands
+    Brief{
+        /// Key or id of the project; the top-level one by default
+        project: Option<String>,
+        #[command(flatten)]
+        render: RenderArgs,
+        /// Write a standalone page sized for printing or saving as PDF
+        #[arg(long, conflicts_with = "context")]
+        page: bool,
+        /// Write to this file instead of standard output
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Open dependencies between a team's (or person's) work and everyone
+    /// else's, by the team on the other side
+    Coordination{
+        #[arg(long, conflicts_with = "owner", required_unless_present = "owner")]
+        team: Option<String>,
+        #[arg(long)]
+        owner: Option<String>,
+        /// Print HTML instead of markdown
+        #[arg(long)]
+        html: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// The schedule as a Gantt chart, as text or with --svg or --mermaid
+    Gantt{
+        /// Key or id of the node whose subtree to show; everything by default
+        #[arg(long)]
+        scope: Option<String>,
+        /// Time per column: day, week, month or quarter; the finest that fits by default
+        #[arg(long, value_enum, default_value_t = gantt::Scale::Auto)]
+        scale: gantt::Scale,
+        /// Draw an SVG chart
+        #[arg(long, conflicts_with = "mermaid")]
+        svg: bool,
+        /// Write a Mermaid gantt diagram
+        #[arg(long)]
+        mermaid: bool,
+        /// Write to this file instead of standard output
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Dependency structure matrix: which nodes of a scope depend on which
+    Dsm{
+        /// Key or id of the node whose subtree to show; everything by default
+        #[arg(long)]
+        scope: Option<String>,
+        /// Print an HTML table instead
+        #[arg(long)]
+        html: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Compare a scenario with a baseline: end dates, cost, peak load and critical path
+    Compare{
+        /// The project file as planned
+        baseline: PathBuf,
+        /// A copy of it with the changes under discussion
+        scenario: PathBuf,
+        #[command(flatten)]
+        render: RenderArgs,
+    },
+    /// Add a dependency: FROM blocks (provides resources for, contains, specifies) TO
+    Connect{
+        /// Key or id of the node depended on
+        from: String,
+        /// Key or id of the dependent node
+        to: String,
+        /// blocks, resources, contains or specifies (a spec to an epic or story)
+        #[arg(long, default_value = "blocks")]
+        kind: String,
+        /// Connect nodes of different projects anyway
+        #[arg(long)]
+        force: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Why a node is scheduled where it is: what set its start and its length
+    Explain{
+        /// Key or id of the node
+        node: String,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// The critical chain once each owner works on one thing at a time, with
+    /// suggested feeding and project buffers
+    Chain{
+        /// cut-and-paste (half the protected length) or root-square
+        #[arg(long, default_value = "cut-and-paste")]
+        method: String,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// 0-100 health score of a project (every project by default), factor by factor
+    Health{
+        /// Key or id of the project
+        project: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Report structure worth a second look; exits non-zero when there is any
+    Validate{
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Specs and the Epics and Stories implementing them
+    Specs{
+        /// Only specs with no implementing work
+        #[arg(long)]
+        unimplemented: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Check that the file's index, keys and references agree with its nodes
+    Check{
+        /// Fix what can be fixed and save the file
+        #[arg(long)]
+        repair: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Move everything a person owns or takes part in to someone else
+    Reassign{
+        from: String,
+        /// Leave the work unassigned if not given
+        #[arg(long)]
+        to: Option<String>,
+        /// Key or id of the node whose subtree to reassign; everything by default
+        #[arg(long)]
+        scope: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Full-text search over names, tags, descriptions and comments
+    Search{
+        /// Words to look for; the last one may be partial
+        #[arg(required = true)]
+        query: Vec<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Add a comment to a node
+    Comment{
+        /// Key or id of the node
+        node: String,
+        text: String,
+        #[arg(long)]
+        author: Option<String>,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Show or set the hours of work on a node; the schedule derives its length from them
+    Effort{
+        /// Key or id of the node
+        node: String,
+        hours: Option<f64>,
+        /// Percent of the owner's working day spent on the node
+        #[arg(long)]
+        allocation: Option<u8>,
+        /// How the hours fall over the node's days: flat, front-loaded or back-loaded
+        #[arg(long)]
+        curve: Option<String>,
+        /// Drop the effort, so the node's timeline sets its length again
+        #[arg(long, conflicts_with_all = ["hours", "allocation", "curve"])]
+        clear: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Scheduled hours per owner and day, or per week, with overloaded days marked
+    Load{
+        /// Key or id of the node whose subtree to count; everything by default
+        #[arg(long)]
+        scope: Option<String>,
+        #[arg(long)]
+        weekly: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Show or set when work on a node actually started and finished; with no node, how far actual dates slipped from plan across the project
+    Actuals{
+        /// Key or id of the node; its subtree is listed unless a date is being set
+        node: Option<String>,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        finish: Option<String>,
+        /// Forget both dates
+        #[arg(long, conflicts_with_all = ["start", "finish"])]
+        clear: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// The ranked backlog, with anything ranked above work it waits on
+    Backlog{
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Move a node to a rank in the backlog, 1 being the top
+    Rank{
+        /// Key or id of the node
+        node: String,
+        /// New rank; the bottom of the backlog when left out
+        rank: Option<usize>,
+        /// Take the node out of the backlog
+        #[arg(long, conflicts_with = "rank")]
+        clear: bool,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Saved views: named filters stored in the project file
+    View{
+        #[command(subcommand)]
+        command: ViewCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Automation rules: tag or notify when work changes
+    Rule{
+        #[command(subcommand)]
+        command: RuleCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Show the project's defaults, or change them with the flags given
+    Settings{
+        #[command(flatten)]
+        args: SettingsArgs,
+        #[arg(short, long, default_value = "project.json")]
+        file: PathBuf,
+    },
+    /// Public holidays, for everyone or per region
+    Holidays{
+        #[command(subcommand)]
+        command: HolidaysCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Approval gates on Done and release cuts: gates, approvers and sign-offs
+    Approval{
+        #[command(subcommand)]
+        command: ApprovalCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Message catalogs for the languages reports are written in
+    Catalog{
+        #[command(subcommand)]
+        command: CatalogCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Report layouts kept in the project: add, list, show, remove, or start from a built-in one
+    Template{
+        #[command(subcommand)]
+        command: TemplateCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Colours and fonts for the Gantt chart, the dependency diagram and HTML reports
+    Theme{
+        #[command(subcommand)]
+        command: ThemeCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Budgets and spend, the currencies they are in and how they are written
+    Cost{
+        #[command(subcommand)]
+        command: CostCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Decisions: the question, options, outcome and the work they affect
+    Decision{
+        #[command(subcommand)]
+        command: DecisionCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Definition-of-done checklists: templates per kind and each node's items
+    Dod{
+        #[command(subcommand)]
+        command: DodCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Pull requests linked to work, moving it to review and Done as they progress
+    Github{
+        #[command(subcommand)]
+        command: GithubCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Push status reports to Confluence pages or Notion databases
+    Publish{
+        #[command(subcommand)]
+        command: PublishCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Dependencies on nodes in other project files
+    Remote{
+        #[command(subcommand)]
+        command: RemoteCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Several project files tracked together, with dependencies between them
+    Portfolio{
+        #[command(subcommand)]
+        command: PortfolioCommand,
+        /// The portfolio manifest
+        #[arg(short, long, default_value = "portfolio.json", global = true)]
+        manifest: PathBuf,
+    },
+    /// Hash-chained change history for compliance reviews
+    Audit{
+        #[command(subcommand)]
+        command: AuditCommand,
+        #[arg(short, long, default_value = "project.json", global = true)]
+        file: PathBuf,
+    },
+    /// Three-way merge of project files (usable as a git merge driver: pm merge %O %A %B)
+    Merge{
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Where to write the result; defaults to overwriting OURS
+        #[arg(short = 'o', long = "out")]
+        out: Option<PathBuf>,
+    },
+    /// Print a shell completion script, e.g. `pm completions bash > /etc/bash_completion.d/pm`
+    Completions{
+        shell: Shell,
+    },
+}
+
+pub fn run(cli: Cli) -> Result<ExitCode>{
+    let format = cli.output;
+    let actor = match cli.actor{
+        Some(actor) => Some(actor.parse::<Actor>().map_err(|e| anyhow!(e))?),
+        None => std::env::var("USER").ok().filter(|u| !u.is_empty()).map(Actor::Person),
+    };
+    Actor::set_current(actor);
+    match cli.command{
+        Command::Init{ path, node } => create::init(&path, node, format),
+        Command::New{ kind, file, node } => create::new_node(&file, kind, node, format),
+        Command::Add{ line, parent, file } => create::quick_add(&file, &line, parent.as_deref(), format),
+        Command::Import{ outline, parent, org, file } => import::import(&file, &outline, parent.as_deref(), org, format),
+        Command::Export{ root, svg, html, dot, out, file } => import::export(&file, root.as_deref(), svg, html, dot, out.as_deref(), format),
+        Command::Ics{ project, owner, out, file } => ics::ics(&file, project.as_deref(), owner, out.as_deref(), format),
+        Command::Hook{ message, dry_run, file } => hook::hook(&file, message, dry_run, format),
+        Command::Block{ node, reason, by, note, file } => blocked::block(&file, &node, &reason, by.as_deref(), note.as_deref(), format),
+        Command::Blocked{ root, file } => blocked::blocked(&file, root.as_deref(), format),
+        Command::Metrics{ out, file } => metrics::metrics(&file, out.as_deref(), format),
+        Command::Today{ owner, file } => standup::today(&file, &owner, format),
+        Command::Standup{ owner, file } => standup::standup_report(&file, &owner, format),
+        Command::Board{ scope, by, file } => board::board(&file, scope.as_deref(), by, format),
+        Command::Report{ project, by, render, file } => board::report(&file, project.as_deref(), by, &render, format),
+        Command::Brief{ project, render, page, out, file } => brief::brief(&file, project.as_deref(), &render, page, out.as_deref(), format),
+        Command::Coordination{ team, owner, html, file } => coordination::coordination(&file, team, owner, html, format),
+        Command::Gantt{ scope, scale, svg, mermaid, out, file } => gantt::gantt(&file, scope.as_deref(), scale, svg, mermaid, out.as_deref(), format),
+        Command::Dsm{ scope, html, file } => board::dsm(&file, scope.as_deref(), html, format),
+        Command::Compare{ baseline, scenario, render } => compare::compare(&baseline, &scenario, &render, format),
+        Command::Connect{ from, to, kind, force, file } => connect::connect(&file, &from, &to, &kind, force, format),
+        Command::Explain{ node, file } => explain::explain(&file, &node, format),
+        Command::Chain{ method, file } => chain::chain(&file, &method, format),
+        Command::Health{ project, file } => health::health(&file, project.as_deref(), format),
+        Command::Validate{ file } => validate::run(&file, format),
+        Command::Specs{ unimplemented, file } => validate::specs(&file, unimplemented, format),
+        Command::Check{ repair, file } => validate::check(&file, repair, format),
+        Command::Reassign{ from, to, scope, file } => reassign::reassign(&file, &from, to.as_deref(), scope.as_deref(), format),
+        Command::Search{ query, limit, file } => search::search(&file, &query.join(" "), limit, format),
+        Command::Comment{ node, text, author, file } => search::comment(&file, &node, text, author, format),
+        Command::Effort{ node, hours, allocation, curve, clear, file } => effort::effort(&file, &node, hours, allocation, curve.as_deref(), clear, format),
+        Command::Load{ scope, weekly, file } => effort::load(&file, scope.as_deref(), weekly, format),
+        Command::Actuals{ node, start, finish, clear, file } => actual::actuals(&file, node.as_deref(), start, finish, clear, format),
+        Command::Backlog{ file } => backlog::backlog(&file, format),
+        Command::Rank{ node, rank, clear, file } => backlog::rank(&file, &node, rank, clear, format),
+        Command::View{ command, file } => view::run(&file, command, format),
+        Command::Rule{ command, file } => rule::run(&file, command, format),
+        Command::Settings{ args, file } => settings::settings(&file, args, format),
+        Command::Holidays{ command, file } => holidays::run(&file, command, format),
+        Command::Approval{ command, file } => approval::run(&file, command, format),
+        Command::Catalog{ command, file } => catalog::run(&file, command, format),
+        Command::Template{ command, file } => template::run(&file, command, format),
+        Command::Theme{ command, file } => theme::run(&file, command, format),
+        Command::Cost{ command, file } => cost::run(&file, command, format),
+        Command::Decision{ command, file } => decision::run(&file, command, format),
+        Command::Dod{ command, file } => dod::run(&file, command, format),
+        Command::Github{ command, file } => github::run(&file, command, format),
+        Command::Publish{ command, file } => publish::run(&file, command, format),
+        Command::Remote{ command, file } => remote::run(&file, command, format),
+        Command::Portfolio{ command, manifest } => portfolio::run(&manifest, command, format),
+        Command::Audit{ command, file } => audit::run(&file, command, format),
+        Command::Merge{ base, ours, theirs, out } => merge(base, ours, theirs, out, format),
+        Command::Completions{ shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "pm", &mut std::io::stdout());
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn merge(base: PathBuf, ours: PathBuf, theirs: PathBuf, out: Option<PathBuf>, format: OutputFormat) -> Result<ExitCode>{
+    let result = storage::merge(&storage::open(&base)?, &storage::open(&ours)?, &storage::open(&theirs)?)?;
+    storage::write(&result.graph, &out.unwrap_or(ours))?;
+
+    if format == OutputFormat::Table{
+        if result.has_conflicts(){
+            eprint!("{}", result.render_conflicts());
+            eprintln!("{} conflict(s); our side was kept for each", result.conflicts.len());
+        }
+    }else{
+        let value = |v: &Option<serde_json::Value>| v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        let mut output = Output::new(vec!["path", "base", "ours", "theirs"]);
+        for conflict in &result.conflicts{
+            output.push(vec![conflict.path.join("/"), value(&conflict.base), value(&conflict.ours), value(&conflict.theirs)]);
+        }
+        output.print(format)?;
+    }
+
+    Ok(if result.has_conflicts() { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
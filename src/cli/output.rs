@@ -0,0 +1,87 @@
+// Output formats shared by every subcommand: aligned text tables for
+// people, JSON and CSV for scripts (`pm ... --output json | jq`)
+
+use crate::views::escape_csv;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat{
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+// A result as named columns, rendered in whichever format was asked for
+#[derive(Debug, Clone, Default)]
+pub struct Output{
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Output{
+    pub fn new(headers: Vec<&'static str>) -> Self{
+        Output{ headers, rows: Vec::new() }
+    }
+
+    pub fn push(&mut self, row: Vec<String>){
+        self.rows.push(row);
+    }
+
+    pub fn render(&self, format: OutputFormat) -> Result<String>{
+        Ok(match format{
+            OutputFormat::Table => self.render_table(),
+            OutputFormat::Json => self.render_json()?,
+            OutputFormat::Csv => self.render_csv(),
+        })
+    }
+
+    pub fn print(&self, format: OutputFormat) -> Result<()>{
+        print!("{}", self.render(format)?);
+        Ok(())
+    }
+
+    fn render_table(&self) -> String{
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for row in &self.rows{
+            for (w, cell) in widths.iter_mut().zip(row){
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+        let line = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells.iter().zip(&widths).map(|(c, w)| format!("{:<w$}", c, w = *w)).collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+
+        let mut out = line(self.headers.iter().map(|h| h.to_uppercase()).collect::<Vec<_>>().iter().map(String::as_str).collect());
+        for row in &self.rows{
+            out.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        out
+    }
+
+    // An array of objects keyed by header
+    fn render_json(&self) -> Result<String>{
+        let records: Vec<Value> = self.rows.iter()
+            .map(|row| {
+                let fields: Map<String,Value> = self.headers.iter()
+                    .zip(row)
+                    .map(|(h, c)| (h.to_string(), Value::String(c.clone())))
+                    .collect();
+                Value::Object(fields)
+            })
+            .collect();
+        Ok(format!("{}\n", serde_json::to_string_pretty(&records)?))
+    }
+
+    fn render_csv(&self) -> String{
+        let mut out = format!("{}\n", self.headers.join(","));
+        for row in &self.rows{
+            out.push_str(&row.iter().map(|c| escape_csv(c)).collect::<Vec<_>>().join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -0,0 +1,119 @@
+// `pm portfolio` - several project files tracked together through a manifest
+
+use super::output::{Output, OutputFormat};
+use crate::portfolio::{dashboard, CrossLink, NodeRef, Portfolio};
+use crate::storage::{self, load_portfolio, PortfolioManifest};
+use anyhow::{anyhow, bail, Result};
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum PortfolioCommand{
+    /// Create an empty portfolio manifest
+    Init{
+        name: String,
+    },
+    /// Add a project file (or directory) to the portfolio
+    Add{
+        name: String,
+        /// Relative to the manifest
+        path: PathBuf,
+    },
+    /// Progress, schedule slip and open cross-project dependencies
+    Show,
+    /// Record that a node in one project blocks a node in another
+    Link{
+        /// project/KEY or project/id of the blocking node
+        from: String,
+        /// project/KEY or project/id of the blocked node
+        to: String,
+    },
+    /// Remove a cross-project link
+    Unlink{
+        from: String,
+        to: String,
+    },
+}
+
+fn node_ref(portfolio: &Portfolio, value: &str) -> Result<NodeRef>{
+    let (project, node) = value.split_once('/').ok_or_else(|| anyhow!("'{}' should look like project/KEY", value))?;
+    let graph = portfolio.project(project).ok_or_else(|| anyhow!("The portfolio has no project '{}'", project))?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}' in {}", node, project))?;
+    Ok(NodeRef::new(project, id))
+}
+
+// Links the manifest holds but that can't be added are left out with a
+// warning, and dropped from the manifest the next time it is written
+fn open(path: &Path) -> Result<Portfolio>{
+    let (portfolio, skipped) = load_portfolio(path)?;
+    for (link, reason) in skipped{
+        eprintln!("warning: skipping link {}/{} -> {}/{}: {}", link.from.project, link.from.node, link.to.project, link.to.node, reason);
+    }
+    Ok(portfolio)
+}
+
+pub fn run(path: &Path, command: PortfolioCommand, format: OutputFormat) -> Result<ExitCode>{
+    match command{
+        PortfolioCommand::Init{ name } => {
+            if path.exists(){
+                bail!("{} already exists", path.display());
+            }
+            PortfolioManifest{ name, ..Default::default() }.write(path)?;
+        }
+        PortfolioCommand::Add{ name, path: project } => {
+            let mut manifest = PortfolioManifest::read(path)?;
+            if manifest.projects.contains_key(&name){
+                bail!("The portfolio already has a project named '{}'", name);
+            }
+            manifest.projects.insert(name.clone(), project);
+            // Load everything once to catch clashing node ids early
+            let file = manifest.project_path(path, &name).expect("just added");
+            let mut portfolio = open(path)?;
+            portfolio.add_project(&name, storage::open(&file)?).map_err(|e| anyhow!(e))?;
+            manifest.write(path)?;
+        }
+        PortfolioCommand::Show => {
+            let portfolio = open(path)?;
+            let dashboard = dashboard(&portfolio).map_err(|e| anyhow!(e))?;
+            if format == OutputFormat::Table{
+                print!("{}", dashboard.render_markdown(&portfolio));
+                return Ok(ExitCode::SUCCESS);
+            }
+            let date = |d: Option<chrono::DateTime<chrono::Utc>>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default();
+            let mut output = Output::new(vec!["project", "completion", "finish", "standalone_finish", "slip_days", "waiting_on", "holding_up", "health"]);
+            for project in &dashboard.projects{
+                output.push(vec![
+                    project.name.clone(),
+                    format!("{:.1}", project.completion),
+                    date(project.finish),
+                    date(project.standalone_finish),
+                    project.slip().num_days().to_string(),
+                    project.waiting_on.to_string(),
+                    project.holding_up.to_string(),
+                    project.health.map(|h| format!("{:.0}", h)).unwrap_or_default(),
+                ]);
+            }
+            output.print(format)?;
+        }
+        PortfolioCommand::Link{ from, to } => {
+            let mut portfolio = open(path)?;
+            let link = CrossLink::new(node_ref(&portfolio, &from)?, node_ref(&portfolio, &to)?);
+            portfolio.link(link).map_err(|e| anyhow!(e))?;
+            let mut manifest = PortfolioManifest::read(path)?;
+            manifest.links = portfolio.links().to_vec();
+            manifest.write(path)?;
+        }
+        PortfolioCommand::Unlink{ from, to } => {
+            let mut portfolio = open(path)?;
+            let (from, to) = (node_ref(&portfolio, &from)?, node_ref(&portfolio, &to)?);
+            if !portfolio.unlink(&from, &to){
+                bail!("There is no such link");
+            }
+            let mut manifest = PortfolioManifest::read(path)?;
+            manifest.links = portfolio.links().to_vec();
+            manifest.write(path)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,180 @@
+// `pm publish` - pushing status reports to Confluence or Notion, on demand or
+// from a scheduler running `pm publish run --due`, and dates to Google Calendar
+
+use super::output::{Output, OutputFormat};
+use crate::core::{CalendarSync, Destination, Node, PublishTarget};
+use crate::publish::{due_targets, env, mark_published, publish, sync_calendar, CurlTransport};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum PublishCommand{
+    /// Add a target: an existing Confluence page or a Notion database
+    Add{
+        name: String,
+        /// Confluence base URL, e.g. https://example.atlassian.net/wiki
+        #[arg(long, requires = "page", conflicts_with = "notion")]
+        confluence: Option<String>,
+        /// Id of the Confluence page to overwrite
+        #[arg(long)]
+        page: Option<String>,
+        /// Id of the Notion database to add report pages to
+        #[arg(long)]
+        notion: Option<String>,
+        /// Key or id of the project to report on; the top-level project when left out
+        #[arg(long)]
+        project: Option<String>,
+        /// Publish from `run --due` once this many days have passed
+        #[arg(long)]
+        every: Option<u32>,
+        /// Report template from `pm template` to publish with
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Publish targets and when each last went out
+    List,
+    Remove{
+        name: String,
+    },
+    /// Publish now: one target, every target, or with --due those whose schedule says so
+    Run{
+        name: Option<String>,
+        #[arg(long, conflicts_with = "name")]
+        due: bool,
+    },
+    /// Sync milestones, sprint boundaries and deadlines to Google Calendar; with
+    /// flags, set up which calendars to use instead
+    Calendar{
+        /// Calendar for milestones, sprints and deadlines of owners without their own
+        #[arg(long)]
+        calendar: Option<String>,
+        /// OWNER=CALENDAR, the calendar an owner's deadlines go to; repeatable
+        #[arg(long = "owner")]
+        owners: Vec<String>,
+    },
+}
+
+fn listing(targets: &[PublishTarget], graph: &crate::core::graph::ProjectGraph) -> Output{
+    let mut output = Output::new(vec!["name", "system", "project", "every", "template", "last_published"]);
+    for t in targets{
+        output.push(vec![
+            t.name.clone(),
+            t.destination.system().to_string(),
+            graph.get_key(t.project).unwrap_or_default().to_string(),
+            t.every_days.map(|d| format!("{}d", d)).unwrap_or_else(|| "-".to_string()),
+            t.template.clone().unwrap_or_else(|| "-".to_string()),
+            t.last_published.map(|at| at.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    output
+}
+
+fn calendars(sync: &CalendarSync) -> Output{
+    let mut output = Output::new(vec!["owner", "calendar"]);
+    output.push(vec!["-".to_string(), sync.calendar_id.clone()]);
+    for (owner, calendar) in &sync.owner_calendars{
+        output.push(vec![owner.clone(), calendar.clone()]);
+    }
+    output
+}
+
+pub fn run(path: &Path, command: PublishCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        PublishCommand::Add{ name, confluence, page, notion, project, every, template } => {
+            let destination = match (confluence, page, notion){
+                (Some(base_url), Some(page_id), None) => Destination::Confluence{ base_url, page_id },
+                (None, None, Some(database_id)) => Destination::Notion{ database_id },
+                _ => bail!("Give either --confluence with --page, or --notion"),
+            };
+            let project = match project{
+                Some(p) => graph.resolve_id(&p).ok_or_else(|| anyhow!("No node '{}'", p))?,
+                None => graph.nodes()
+                    .find(|n| matches!(n, Node::Project{..}) && graph.get_parent(n.get_id()).is_none())
+                    .map(|n| n.get_id())
+                    .ok_or_else(|| anyhow!("The file has no project"))?,
+            };
+            if !matches!(graph.get_node(project), Some(Node::Project{..})){
+                bail!("Reports are made for Projects");
+            }
+            let mut settings = graph.get_settings().clone();
+            if settings.publish_target(&name).is_some(){
+                bail!("There is already a publish target called '{}'", name);
+            }
+            settings.publish_targets.push(PublishTarget{ name, project, destination, every_days: every, last_published: None, template });
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            listing(&graph.get_settings().publish_targets, &graph).print(format)?;
+        }
+        PublishCommand::List => listing(&graph.get_settings().publish_targets, &graph).print(format)?,
+        PublishCommand::Remove{ name } => {
+            let mut settings = graph.get_settings().clone();
+            let before = settings.publish_targets.len();
+            settings.publish_targets.retain(|t| t.name != name);
+            if settings.publish_targets.len() == before{
+                bail!("No publish target '{}'", name);
+            }
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            listing(&graph.get_settings().publish_targets, &graph).print(format)?;
+        }
+        PublishCommand::Run{ name, due } => {
+            let now = Utc::now();
+            let targets: Vec<PublishTarget> = match (&name, due){
+                (Some(name), _) => vec![graph.get_settings().publish_target(name).ok_or_else(|| anyhow!("No publish target '{}'", name))?.clone()],
+                (None, true) => due_targets(&graph, now).into_iter().cloned().collect(),
+                (None, false) => graph.get_settings().publish_targets.clone(),
+            };
+            let mut transport = CurlTransport;
+            let mut output = Output::new(vec!["target", "link"]);
+            let mut failed = false;
+            for target in targets{
+                match publish(&graph, &target, &mut transport, now){
+                    Ok(published) => {
+                        mark_published(&mut graph, &published.target, published.at)?;
+                        output.push(vec![published.target, published.link]);
+                    }
+                    Err(e) => {
+                        eprintln!("{}: {:#}", target.name, e);
+                        failed = true;
+                    }
+                }
+            }
+            storage::write(&graph, path)?;
+            output.print(format)?;
+            if failed{
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        PublishCommand::Calendar{ calendar, owners } if calendar.is_some() || !owners.is_empty() => {
+            let mut settings = graph.get_settings().clone();
+            let mut sync = settings.calendar_sync.take().unwrap_or_default();
+            if let Some(calendar) = calendar{
+                sync.calendar_id = calendar;
+            }
+            for pair in owners{
+                let (owner, id) = pair.split_once('=').ok_or_else(|| anyhow!("'{}' is not OWNER=CALENDAR", pair))?;
+                sync.owner_calendars.insert(owner.trim().to_string(), id.trim().to_string());
+            }
+            settings.calendar_sync = Some(sync.clone());
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            calendars(&sync).print(format)?;
+        }
+        PublishCommand::Calendar{ .. } => {
+            let token = env("PM_GOOGLE_TOKEN")?;
+            let result = sync_calendar(&mut graph, &mut CurlTransport, &token);
+            // What did get through is remembered even when a later event failed
+            storage::write(&graph, path)?;
+            let report = result?;
+            let mut output = Output::new(vec!["written", "unchanged", "deleted"]);
+            output.push(vec![report.written.to_string(), report.unchanged.to_string(), report.deleted.to_string()]);
+            output.print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
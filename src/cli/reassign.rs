@@ -0,0 +1,41 @@
+// `pm reassign` - hand someone's work over when they leave
+
+use super::output::{Output, OutputFormat};
+use crate::core::Scope;
+use crate::storage;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn reassign(path: &Path, from: &str, to: Option<&str>, scope: Option<&str>, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let scope = match scope{
+        Some(root) => Scope::Subtree(graph.resolve_id(root).ok_or_else(|| anyhow!("No node '{}'", root))?),
+        None => Scope::All,
+    };
+    let summary = graph.reassign_owner(from, to, &scope).map_err(|e| anyhow!(e))?;
+    if !summary.is_empty(){
+        storage::write(&graph, path)?;
+    }
+
+    let node = |id| {
+        let key = graph.get_key(id).unwrap_or_default().to_string();
+        let name = graph.get_node(id).map(|n| n.get_name().to_string()).unwrap_or_default();
+        (key, name)
+    };
+    let mut output = Output::new(vec!["role", "key", "name"]);
+    for (role, ids) in [("owner", &summary.owned), ("participant", &summary.participating)]{
+        for id in ids{
+            let (key, name) = node(*id);
+            output.push(vec![role.to_string(), key, name]);
+        }
+    }
+    for risk in graph.risks().filter(|r| summary.risks.contains(&r.get_id())){
+        output.push(vec!["risk owner".to_string(), String::new(), risk.get_title().to_string()]);
+    }
+    for objective in graph.objectives().filter(|o| summary.objectives.contains(&o.id)){
+        output.push(vec!["objective owner".to_string(), String::new(), objective.title.clone()]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
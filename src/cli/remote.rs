@@ -0,0 +1,96 @@
+// `pm remote` - dependencies on nodes in other project files
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::{RemoteDependency, RemoteRef};
+use crate::storage::{self, resolve_remotes};
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum RemoteCommand{
+    /// Make a node wait on a node in another project file
+    Add{
+        /// Key or id of the local node
+        node: String,
+        /// FILE:KEY or FILE:id, the file relative to this one
+        remote: String,
+    },
+    /// Drop a remote dependency
+    Remove{
+        node: String,
+        remote: String,
+    },
+    /// Remote dependencies with what was last known of the remote nodes
+    List,
+    /// Reload the remote files and record the current state of their nodes
+    Refresh,
+}
+
+fn resolve(graph: &ProjectGraph, value: &str) -> Result<uuid::Uuid>{
+    graph.resolve_id(value).ok_or_else(|| anyhow!("No node '{}'", value))
+}
+
+// Opens the remote file to turn a key into an id
+fn remote_ref(path: &Path, value: &str) -> Result<RemoteRef>{
+    let (source, node) = value.rsplit_once(':').ok_or_else(|| anyhow!("'{}' should look like FILE:KEY", value))?;
+    let file = path.parent().unwrap_or(Path::new("")).join(source);
+    let other = storage::open(&file)?;
+    let id = other.resolve_id(node).ok_or_else(|| anyhow!("No node '{}' in {}", node, source))?;
+    Ok(RemoteRef::new(source, id))
+}
+
+pub fn run(path: &Path, command: RemoteCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        RemoteCommand::Add{ node, remote } => {
+            let node = resolve(&graph, &node)?;
+            let remote = remote_ref(path, &remote)?;
+            graph.add_remote_dependency(RemoteDependency::new(remote, node)).map_err(|e| anyhow!(e))?;
+            resolve_remotes(&mut graph, path)?;
+            storage::write(&graph, path)?;
+        }
+        RemoteCommand::Remove{ node, remote } => {
+            let node = resolve(&graph, &node)?;
+            let remote = remote_ref(path, &remote)?;
+            if !graph.remove_remote_dependency(node, &remote){
+                bail!("There is no such remote dependency");
+            }
+            storage::write(&graph, path)?;
+        }
+        RemoteCommand::List => {
+            let mut output = Output::new(vec!["node", "source", "remote", "status", "finish", "resolved"]);
+            for dependency in graph.remote_dependencies(){
+                let known = dependency.last_known.as_ref();
+                output.push(vec![
+                    graph.get_key(dependency.node).unwrap_or_default().to_string(),
+                    dependency.remote.source.clone(),
+                    known.map(|r| format!("{} {}", r.key.as_deref().unwrap_or_default(), r.name).trim().to_string())
+                        .unwrap_or_else(|| dependency.remote.node.to_string()),
+                    known.and_then(|r| r.status).map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+                    known.and_then(|r| r.end).map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                    known.map(|r| r.resolved_at.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+                ]);
+            }
+            output.print(format)?;
+        }
+        RemoteCommand::Refresh => {
+            let unresolved = resolve_remotes(&mut graph, path)?;
+            storage::write(&graph, path)?;
+            for remote in &unresolved{
+                eprintln!("{}: node {} not found", remote.source, remote.node);
+            }
+            let mut output = Output::new(vec!["resolved", "unresolved", "at"]);
+            let total = graph.remote_dependencies().iter().map(|d| &d.remote).collect::<std::collections::HashSet<_>>().len();
+            output.push(vec![(total - unresolved.len()).to_string(), unresolved.len().to_string(), Utc::now().to_rfc3339()]);
+            output.print(format)?;
+            if !unresolved.is_empty(){
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
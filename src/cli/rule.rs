@@ -0,0 +1,130 @@
+// `pm rule` - automation rules stored in the project file
+
+use super::output::{Output, OutputFormat};
+use super::view::{build_filter, FilterArgs};
+use crate::core::{Action, Rule, Trigger};
+use crate::notify::rule_notifications;
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Subcommand};
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Args)]
+pub struct AddArgs{
+    pub name: String,
+    /// A status (done, in-progress, ...), status for any move, state:NAME,
+    /// tagged:TAG or unblocked
+    #[arg(long)]
+    pub when: String,
+    /// Only nodes of this kind: project, epic, story or task
+    #[arg(long)]
+    pub kind: Option<String>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    /// Tag to add; repeatable
+    #[arg(long)]
+    pub add_tag: Vec<String>,
+    /// Tag to remove; repeatable
+    #[arg(long)]
+    pub remove_tag: Vec<String>,
+    /// Channel or person to notify; repeatable
+    #[arg(long)]
+    pub notify: Vec<String>,
+    /// Message for the notification instead of the default
+    #[arg(long, requires = "notify")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RuleCommand{
+    /// List the rules in the order they run
+    List,
+    /// Add (or replace) a rule
+    Add(Box<AddArgs>),
+    /// Delete a rule
+    Remove{
+        name: String,
+    },
+    /// Run the rules over the changes since the last run
+    Run,
+}
+
+// Key prefix of the kind names the command line uses elsewhere
+pub(super) fn kind_prefix(kind: &str) -> Result<&'static str>{
+    match kind.trim().to_ascii_lowercase().as_str(){
+        "project" => Ok("PROJ"),
+        "epic" => Ok("EPIC"),
+        "story" => Ok("STORY"),
+        "task" => Ok("TASK"),
+        "decision" => Ok("DEC"),
+        _ => bail!("Unknown kind '{}'; use project, epic, story, task or decision", kind),
+    }
+}
+
+fn describe(action: &Action) -> String{
+    match action{
+        Action::AddTag(tag) => format!("add tag {}", tag),
+        Action::RemoveTag(tag) => format!("remove tag {}", tag),
+        Action::Notify{ channel, .. } => format!("notify {}", channel),
+    }
+}
+
+pub fn run(path: &Path, command: RuleCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        RuleCommand::List => {
+            let mut output = Output::new(vec!["name", "when", "kind", "actions"]);
+            for rule in graph.rules(){
+                let actions: Vec<String> = rule.actions.iter().map(describe).collect();
+                output.push(vec![rule.name.clone(), rule.trigger.to_string(), rule.kind.clone().unwrap_or_default(), actions.join(", ")]);
+            }
+            output.print(format)?;
+        }
+        RuleCommand::Add(args) => {
+            let AddArgs{ name, when, kind, filter, add_tag, remove_tag, notify, message } = *args;
+            let trigger = when.parse::<Trigger>().map_err(|e| anyhow!(e))?;
+            let mut rule = Rule::new(name, trigger).with_filter(build_filter(&graph, filter)?);
+            if let Some(kind) = kind{
+                rule = rule.with_kind(kind_prefix(&kind)?);
+            }
+            for tag in add_tag{
+                rule = rule.with_action(Action::AddTag(tag));
+            }
+            for tag in remove_tag{
+                rule = rule.with_action(Action::RemoveTag(tag));
+            }
+            for channel in notify{
+                rule = rule.with_action(Action::Notify{ channel, message: message.clone() });
+            }
+            graph.add_rule(rule).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        RuleCommand::Remove{ name } => {
+            if graph.remove_rule(&name).is_none(){
+                bail!("There is no rule named '{}'", name);
+            }
+            storage::write(&graph, path)?;
+        }
+        RuleCommand::Run => {
+            let firings = graph.run_rules().map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+            let notifications = rule_notifications(&graph, &firings);
+            let mut output = Output::new(vec!["rule", "key", "action", "message"]);
+            for firing in &firings{
+                let message = notifications.iter()
+                    .find(|n| n.node == Some(firing.node) && matches!(&firing.action, Action::Notify{ channel, .. } if n.recipient.as_deref() == Some(channel)))
+                    .map(|n| n.title.clone())
+                    .unwrap_or_default();
+                output.push(vec![
+                    firing.rule.clone(),
+                    graph.get_key(firing.node).unwrap_or_default().to_string(),
+                    describe(&firing.action),
+                    message,
+                ]);
+            }
+            output.print(format)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,42 @@
+// `pm search` and `pm comment` - full-text search and the comments it covers
+
+use super::output::{Output, OutputFormat};
+use crate::core::{Actor, Comment};
+use crate::storage;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn search(path: &Path, query: &str, limit: usize, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let hits = graph.search(query, limit);
+    let mut output = Output::new(vec!["key", "name", "field", "score", "snippet"]);
+    for hit in &hits{
+        output.push(vec![
+            graph.get_key(hit.id).unwrap_or_default().to_string(),
+            graph.get_node(hit.id).map(|n| n.get_name().to_string()).unwrap_or_default(),
+            format!("{:?}", hit.field).to_lowercase(),
+            format!("{:.2}", hit.score),
+            hit.snippet.highlighted("**", "**"),
+        ]);
+    }
+    output.print(format)?;
+    Ok(if hits.is_empty() { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+pub fn comment(path: &Path, node: &str, text: String, author: Option<String>, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let id = graph.resolve_id(node).ok_or_else(|| anyhow!("No node '{}'", node))?;
+    let author = author
+        .or_else(|| Actor::current().map(|a| a.to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let comment = Comment::new(author, text, Utc::now()).map_err(|e| anyhow!(e))?;
+    graph.add_comment(id, comment).map_err(|e| anyhow!(e))?;
+    storage::write(&graph, path)?;
+
+    let mut output = Output::new(vec!["key", "comments"]);
+    output.push(vec![graph.get_key(id).unwrap_or_default().to_string(), graph.get_comments(id).len().to_string()]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
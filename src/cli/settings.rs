@@ -0,0 +1,145 @@
+// `pm settings` - the project's defaults, shown or changed
+
+use super::output::{Output, OutputFormat};
+use crate::core::{ActualDates, ConnectionPolicy, ParallelEdges, PointScale};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::Args;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SettingsArgs{
+    /// Length of a working day in hours
+    #[arg(long)]
+    pub hours_per_day: Option<f64>,
+    /// Point scale: any, fibonacci, powers-of-two, t-shirt, values such as
+    /// 1,2,4,8 or sizes such as S=1,M=3,L=8
+    #[arg(long)]
+    pub points: Option<String>,
+    /// Default sprint length in days
+    #[arg(long)]
+    pub sprint_days: Option<u32>,
+    /// strict or relaxed
+    #[arg(long)]
+    pub connections: Option<String>,
+    /// allow or deny edges of different kinds between the same two nodes
+    #[arg(long)]
+    pub parallel_edges: Option<String>,
+    /// What may contain what, as PARENT>CHILD pairs of project, epic, story
+    /// and task, e.g. project>epic,epic>story,epic>task,story>task
+    #[arg(long)]
+    pub hierarchy: Option<String>,
+    /// Most levels of work below a top-level project; 0 removes the limit
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+    /// Most open items one person may hold in a sprint; 0 removes the limit
+    #[arg(long)]
+    pub wip_limit: Option<u32>,
+    /// status to stamp actual start/finish on status changes, manual to set them by hand
+    #[arg(long)]
+    pub actual_dates: Option<String>,
+    /// Comma-separated categories a node may be Blocked for
+    #[arg(long)]
+    pub blocked_reasons: Option<String>,
+    /// Whether moving a node to Blocked needs one of those reasons
+    #[arg(long)]
+    pub require_blocked_reason: Option<bool>,
+    /// Language reports are written in, e.g. de or pt-BR; see pm catalog
+    #[arg(long)]
+    pub language: Option<String>,
+}
+
+fn parse_policy(value: &str) -> Result<ConnectionPolicy>{
+    match value.trim().to_ascii_lowercase().as_str(){
+        "strict" => Ok(ConnectionPolicy::Strict),
+        "relaxed" => Ok(ConnectionPolicy::Relaxed),
+        _ => bail!("Unknown connection policy '{}'; use strict or relaxed", value),
+    }
+}
+
+fn parse_parallel(value: &str) -> Result<ParallelEdges>{
+    match value.trim().to_ascii_lowercase().as_str(){
+        "allow" => Ok(ParallelEdges::Allow),
+        "deny" => Ok(ParallelEdges::Deny),
+        _ => bail!("Unknown parallel edge policy '{}'; use allow or deny", value),
+    }
+}
+
+fn parse_actual_dates(value: &str) -> Result<ActualDates>{
+    match value.trim().to_ascii_lowercase().as_str(){
+        "status" => Ok(ActualDates::FromStatus),
+        "manual" => Ok(ActualDates::Manual),
+        _ => bail!("Unknown actual date mode '{}'; use status or manual", value),
+    }
+}
+
+pub fn settings(path: &Path, args: SettingsArgs, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let mut settings = graph.get_settings().clone();
+    let changed = args.hours_per_day.is_some() || args.points.is_some() || args.sprint_days.is_some() || args.connections.is_some() || args.parallel_edges.is_some() || args.wip_limit.is_some()
+        || args.hierarchy.is_some() || args.max_depth.is_some() || args.actual_dates.is_some()
+        || args.blocked_reasons.is_some() || args.require_blocked_reason.is_some() || args.language.is_some();
+    if let Some(hours) = args.hours_per_day{
+        settings.hours_per_day = hours;
+    }
+    if let Some(points) = &args.points{
+        settings.point_scale = points.parse::<PointScale>().map_err(|e| anyhow!(e))?;
+    }
+    if let Some(days) = args.sprint_days{
+        settings.sprint_days = days;
+    }
+    if let Some(policy) = &args.connections{
+        settings.connections = parse_policy(policy)?;
+    }
+    if let Some(policy) = &args.parallel_edges{
+        settings.parallel_edges = parse_parallel(policy)?;
+    }
+    if let Some(hierarchy) = &args.hierarchy{
+        let max_depth = settings.hierarchy.max_depth;
+        settings.hierarchy = hierarchy.parse().map_err(|e: &str| anyhow!(e))?;
+        settings.hierarchy.max_depth = max_depth;
+    }
+    if let Some(depth) = args.max_depth{
+        settings.hierarchy.max_depth = (depth > 0).then_some(depth);
+    }
+    if let Some(limit) = args.wip_limit{
+        settings.wip_limit = (limit > 0).then_some(limit);
+    }
+    if let Some(mode) = &args.actual_dates{
+        settings.actual_dates = parse_actual_dates(mode)?;
+    }
+    if let Some(reasons) = &args.blocked_reasons{
+        settings.blocked_reasons.categories = reasons.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect();
+    }
+    if let Some(required) = args.require_blocked_reason{
+        settings.blocked_reasons.require_reason = required;
+    }
+    if let Some(language) = &args.language{
+        settings.i18n.language = language.trim().to_string();
+    }
+    if changed{
+        graph.set_settings(settings.clone()).map_err(|e| anyhow!(e))?;
+        storage::write(&graph, path)?;
+    }
+
+    let mut output = Output::new(vec!["setting", "value"]);
+    output.push(vec!["hours_per_day".to_string(), settings.hours_per_day.to_string()]);
+    output.push(vec!["points".to_string(), settings.point_scale.to_string()]);
+    output.push(vec!["sprint_days".to_string(), settings.sprint_days.to_string()]);
+    output.push(vec!["workflow".to_string(), settings.workflow.as_ref().map(|w| w.name.clone()).unwrap_or_else(|| "-".to_string())]);
+    output.push(vec!["connections".to_string(), format!("{:?}", settings.connections).to_lowercase()]);
+    output.push(vec!["parallel_edges".to_string(), format!("{:?}", settings.parallel_edges).to_lowercase()]);
+    output.push(vec!["hierarchy".to_string(), settings.hierarchy.to_string()]);
+    output.push(vec!["max_depth".to_string(), settings.hierarchy.max_depth.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())]);
+    output.push(vec!["wip_limit".to_string(), settings.wip_limit.map(|l| l.to_string()).unwrap_or_else(|| "-".to_string())]);
+    output.push(vec!["actual_dates".to_string(), match settings.actual_dates{
+        ActualDates::FromStatus => "status",
+        ActualDates::Manual => "manual",
+    }.to_string()]);
+    output.push(vec!["blocked_reasons".to_string(), settings.blocked_reasons.categories.join(", ")]);
+    output.push(vec!["require_blocked_reason".to_string(), settings.blocked_reasons.require_reason.to_string()]);
+    output.push(vec!["language".to_string(), settings.i18n.language.clone()]);
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
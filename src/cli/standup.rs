@@ -0,0 +1,50 @@
+// `pm today` and `pm standup` - an owner's day at a glance
+
+use super::output::{Output, OutputFormat};
+use crate::core::timezone::local_date;
+use crate::storage;
+use crate::views::{standup, Standup, StandupItem};
+use anyhow::Result;
+use chrono::Utc;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn push_rows(output: &mut Output, standup: &Standup, section: &str, items: &[StandupItem]){
+    for item in items{
+        output.push(vec![
+            section.to_string(),
+            item.key.clone().unwrap_or_default(),
+            item.name.clone(),
+            item.status.map(|s| s.to_string()).unwrap_or_default(),
+            item.due.map(|d| local_date(d, standup.timezone).to_string()).unwrap_or_default(),
+        ]);
+    }
+}
+
+fn rows(standup: &Standup, sections: &[(&str, &[StandupItem])]) -> Output{
+    let mut output = Output::new(vec!["section", "key", "name", "status", "due"]);
+    for (section, items) in sections{
+        push_rows(&mut output, standup, section, items);
+    }
+    output
+}
+
+pub fn today(path: &Path, owner: &str, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let day = standup(&graph, owner, Utc::now());
+    match format{
+        OutputFormat::Table => print!("{}", day.render_today()),
+        _ => rows(&day, &[("due_today", &day.due_today), ("this_week", &day.due_this_week)]).print(format)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn standup_report(path: &Path, owner: &str, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let day = standup(&graph, owner, Utc::now());
+    match format{
+        OutputFormat::Table => print!("{}", day.render_standup()),
+        _ => rows(&day, &[("completed", &day.completed), ("unblocked", &day.unblocked), ("due_today", &day.due_today)]).print(format)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
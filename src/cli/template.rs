@@ -0,0 +1,131 @@
+// `pm template` - report layouts kept in the project settings, so a team can
+// ship its own status report or comparison without code changes
+//
+// Start from a built-in layout (`pm template builtin status > mine.md`), see
+// what a report offers with `pm report --context`, then `pm template add`.
+// `pm report --template NAME` and `pm publish add --template NAME` use it.
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::{ReportTemplate, TemplateReport};
+use crate::storage;
+use crate::views::{brief, comparison, report, Template};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// How `pm report`, `pm compare` and `pm brief` write what they print
+#[derive(Debug, Clone, Default, Args)]
+pub struct RenderArgs{
+    /// Print an HTML fragment instead of markdown
+    #[arg(long)]
+    pub html: bool,
+    /// Language to write in; the project's report language by default
+    #[arg(long)]
+    pub lang: Option<String>,
+    /// Report template from `pm template` to write with
+    #[arg(long, conflicts_with = "html")]
+    pub template: Option<String>,
+    /// Print the values templates see, as JSON
+    #[arg(long, conflicts_with_all = ["html", "template"])]
+    pub context: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TemplateCommand{
+    /// Add a template from a file, or replace one of the same name
+    Add{
+        name: String,
+        path: PathBuf,
+        /// status, comparison or brief
+        #[arg(long, default_value = "status")]
+        report: String,
+        /// The template writes HTML, so printed values are escaped
+        #[arg(long)]
+        html: bool,
+    },
+    List,
+    /// Print a template's source
+    Show{
+        name: String,
+    },
+    Remove{
+        name: String,
+    },
+    /// Print a built-in layout to start a template from
+    Builtin{
+        /// status, comparison or brief
+        #[arg(default_value = "status")]
+        report: String,
+        #[arg(long)]
+        html: bool,
+    },
+}
+
+// A named template for `report`, parsed, and whether it writes HTML
+pub fn load_template(graph: &ProjectGraph, name: &str, report: TemplateReport) -> Result<(Template, bool)>{
+    let template = graph.get_settings().templates.get(name).ok_or_else(|| anyhow!("No report template '{}'; see pm template list", name))?;
+    if template.report != report{
+        bail!("Template '{}' is for the {} report", name, template.report);
+    }
+    let parsed = Template::parse(&template.body).with_context(|| format!("Report template {}", name))?;
+    Ok((parsed, template.html))
+}
+
+pub fn run(path: &Path, command: TemplateCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        TemplateCommand::Add{ name, path: file, report, html } => {
+            let report: TemplateReport = report.parse().map_err(|e: &str| anyhow!(e))?;
+            let body = std::fs::read_to_string(&file).with_context(|| format!("Could not read {}", file.display()))?;
+            Template::parse(&body).with_context(|| format!("{} is not a valid template", file.display()))?;
+            let mut settings = graph.get_settings().clone();
+            settings.templates.insert(name, ReportTemplate{ report, html, body });
+            graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        TemplateCommand::List => {
+            let settings = graph.get_settings();
+            let mut output = Output::new(vec!["name", "report", "format", "lines", "published_by"]);
+            for (name, template) in &settings.templates{
+                let targets: Vec<&str> = settings.publish_targets.iter()
+                    .filter(|t| t.template.as_ref() == Some(name))
+                    .map(|t| t.name.as_str())
+                    .collect();
+                output.push(vec![
+                    name.clone(),
+                    template.report.to_string(),
+                    if template.html { "html" } else { "markdown" }.to_string(),
+                    template.body.lines().count().to_string(),
+                    targets.join(", "),
+                ]);
+            }
+            output.print(format)?;
+        }
+        TemplateCommand::Show{ name } => {
+            let template = graph.get_settings().templates.get(&name).ok_or_else(|| anyhow!("No report template '{}'", name))?;
+            print!("{}", template.body);
+        }
+        TemplateCommand::Remove{ name } => {
+            let mut settings = graph.get_settings().clone();
+            if settings.templates.remove(&name).is_none(){
+                bail!("No report template '{}'", name);
+            }
+            graph.set_settings(settings).map_err(|e| anyhow!("{}: change the publish target first", e))?;
+            storage::write(&graph, path)?;
+        }
+        TemplateCommand::Builtin{ report: kind, html } => {
+            let body = match (kind.parse().map_err(|e: &str| anyhow!(e))?, html){
+                (TemplateReport::Status, false) => report::MARKDOWN_TEMPLATE,
+                (TemplateReport::Status, true) => report::HTML_TEMPLATE,
+                (TemplateReport::Comparison, false) => comparison::MARKDOWN_TEMPLATE,
+                (TemplateReport::Comparison, true) => comparison::HTML_TEMPLATE,
+                (TemplateReport::Brief, false) => brief::MARKDOWN_TEMPLATE,
+                (TemplateReport::Brief, true) => brief::HTML_TEMPLATE,
+            };
+            print!("{}", body);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
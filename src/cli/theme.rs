@@ -0,0 +1,141 @@
+// `pm theme` - the colours and fonts exports are drawn with
+//
+// Keys are font, font_size, fill_by, neutral, text, muted, line, critical,
+// shade and summary, and status.NAME, kind.NAME and team.NAME for fills.
+
+use super::output::{Output, OutputFormat};
+use crate::core::theme::{status_name, KIND_NAMES, STATUS_NAMES};
+use crate::core::{FillBy, Status, Theme};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::Subcommand;
+use std::path::Path;
+use std::process::ExitCode;
+
+#[derive(Debug, Subcommand)]
+pub enum ThemeCommand{
+    /// Every setting of the theme
+    Show,
+    /// Change one setting, e.g. `critical #c62828` or `team.Platform #b2dfdb`
+    Set{
+        key: String,
+        value: String,
+    },
+    /// Put one setting back as it was built in, or the whole theme without a key
+    Reset{
+        key: Option<String>,
+    },
+}
+
+fn fill_by_name(fill_by: FillBy) -> &'static str{
+    match fill_by{
+        FillBy::Status => "status",
+        FillBy::Kind => "kind",
+        FillBy::Team => "team",
+    }
+}
+
+// The plain colour a key names
+fn color_mut<'a>(theme: &'a mut Theme, key: &str) -> Option<&'a mut String>{
+    match key{
+        "font" => Some(&mut theme.font),
+        "neutral" => Some(&mut theme.neutral),
+        "text" => Some(&mut theme.text),
+        "muted" => Some(&mut theme.muted),
+        "line" => Some(&mut theme.line),
+        "critical" => Some(&mut theme.critical),
+        "shade" => Some(&mut theme.shade),
+        "summary" => Some(&mut theme.summary),
+        _ => None,
+    }
+}
+
+fn set(theme: &mut Theme, key: &str, value: Option<&str>) -> Result<()>{
+    let mut built_in = Theme::default();
+    match key.split_once('.'){
+        Some((map, name)) => {
+            let (fills, known): (_, &[&str]) = match map{
+                "status" => (&mut theme.status, &STATUS_NAMES),
+                "kind" => (&mut theme.kinds, &KIND_NAMES),
+                "team" => (&mut theme.teams, &[]),
+                _ => bail!("Unknown theme key '{}'; see pm theme show", key),
+            };
+            if !known.is_empty() && !known.contains(&name){
+                bail!("No {} called '{}'; use one of {}", map, name, known.join(", "));
+            }
+            match value{
+                Some(value) => {
+                    fills.insert(name.to_string(), value.trim().to_string());
+                }
+                None => {
+                    let default = match map{
+                        "status" => built_in.status.get(name),
+                        "kind" => built_in.kinds.get(name),
+                        _ => None,
+                    };
+                    match default{
+                        Some(color) => fills.insert(name.to_string(), color.clone()),
+                        None => fills.remove(name),
+                    };
+                }
+            }
+        }
+        None => match key{
+            "font_size" => theme.font_size = match value{
+                Some(value) => value.trim().parse().map_err(|_| anyhow!("The font size is a number of pixels"))?,
+                None => built_in.font_size,
+            },
+            "fill_by" => theme.fill_by = match value{
+                Some(value) => value.trim().parse().map_err(|e: &str| anyhow!(e))?,
+                None => built_in.fill_by,
+            },
+            _ => {
+                let default = color_mut(&mut built_in, key).cloned();
+                let (Some(color), Some(default)) = (color_mut(theme, key), default) else {
+                    bail!("Unknown theme key '{}'; see pm theme show", key);
+                };
+                *color = value.map(|v| v.trim().to_string()).unwrap_or(default);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn show(theme: &Theme, format: OutputFormat) -> Result<()>{
+    let mut output = Output::new(vec!["key", "value"]);
+    output.push(vec!["font".to_string(), theme.font.clone()]);
+    output.push(vec!["font_size".to_string(), theme.font_size.to_string()]);
+    output.push(vec!["fill_by".to_string(), fill_by_name(theme.fill_by).to_string()]);
+    let plain = [("neutral", &theme.neutral), ("text", &theme.text), ("muted", &theme.muted), ("line", &theme.line),
+        ("critical", &theme.critical), ("shade", &theme.shade), ("summary", &theme.summary)];
+    for (key, color) in plain{
+        output.push(vec![key.to_string(), color.clone()]);
+    }
+    for status in [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done]{
+        output.push(vec![format!("status.{}", status_name(status)), theme.status_fill(status).to_string()]);
+    }
+    for kind in KIND_NAMES{
+        output.push(vec![format!("kind.{}", kind), theme.kinds.get(kind).unwrap_or(&theme.neutral).clone()]);
+    }
+    for (team, color) in &theme.teams{
+        output.push(vec![format!("team.{}", team), color.clone()]);
+    }
+    output.print(format)?;
+    Ok(())
+}
+
+pub fn run(path: &Path, command: ThemeCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let mut settings = graph.get_settings().clone();
+    match command{
+        ThemeCommand::Show => return show(&settings.theme, format).map(|_| ExitCode::SUCCESS),
+        ThemeCommand::Set{ key, value } => set(&mut settings.theme, &key, Some(&value))?,
+        ThemeCommand::Reset{ key: Some(key) } => set(&mut settings.theme, &key, None)?,
+        ThemeCommand::Reset{ key: None } => settings.theme = Theme::default(),
+    }
+    let theme = settings.theme.clone();
+    graph.set_settings(settings).map_err(|e| anyhow!(e))?;
+    storage::write(&graph, path)?;
+    show(&theme, format)?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,64 @@
+// `pm validate` - the validation report; fails when there is anything to look at
+// `pm check` - the file's internal consistency, optionally repaired
+// `pm specs` - the work implementing each spec
+
+use super::output::{Output, OutputFormat};
+use crate::core::validate;
+use crate::storage;
+use crate::views::spec_coverage;
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::process::ExitCode;
+
+pub fn run(path: &Path, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let report = validate(&graph);
+
+    let mut output = Output::new(vec!["code", "nodes", "message"]);
+    for warning in &report.warnings{
+        let nodes: Vec<String> = warning.nodes().into_iter()
+            .map(|id| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string()))
+            .collect();
+        output.push(vec![warning.code().to_string(), nodes.join(" "), warning.message(&graph)]);
+    }
+    output.print(format)?;
+    Ok(if report.is_clean() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+pub fn specs(path: &Path, unimplemented: bool, format: OutputFormat) -> Result<ExitCode>{
+    let graph = storage::open(path)?;
+    let key = |id: uuid::Uuid| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+
+    let mut output = Output::new(vec!["key", "name", "work", "done"]);
+    for coverage in spec_coverage(&graph).into_iter().filter(|c| !unimplemented || !c.is_implemented()){
+        let work: Vec<String> = coverage.work.iter().map(|id| key(*id)).collect();
+        output.push(vec![key(coverage.spec), coverage.name, work.join(", "), format!("{}/{}", coverage.done, coverage.work.len())]);
+    }
+    output.print(format)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn check(path: &Path, repair: bool, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    let issues = if repair{
+        let fixed = graph.repair().map_err(|e| anyhow!(e))?;
+        if !fixed.is_empty(){
+            storage::write(&graph, path)?;
+        }
+        fixed
+    }else{
+        graph.check_integrity()
+    };
+
+    let mut output = Output::new(vec!["issue", "state"]);
+    for issue in &issues{
+        let state = match (repair, issue.is_repairable()){
+            (true, _) => "repaired",
+            (false, true) => "repairable",
+            (false, false) => "fix by hand",
+        };
+        output.push(vec![issue.to_string(), state.to_string()]);
+    }
+    output.print(format)?;
+    Ok(if issues.is_empty() || repair { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
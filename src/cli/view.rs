@@ -0,0 +1,147 @@
+// `pm view` - named filters saved in the project file
+
+use super::output::{Output, OutputFormat};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Filter, Priority, SavedView, SortKey, Status};
+use crate::storage;
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Subcommand};
+use std::path::Path;
+use std::process::ExitCode;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct FilterArgs{
+    #[arg(long)]
+    pub owner: Option<String>,
+    /// not-started, in-progress, blocked or done
+    #[arg(long)]
+    pub status: Option<String>,
+    /// Repeat to require several tags
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Key or id of the node whose subtree to look in
+    #[arg(long)]
+    pub scope: Option<String>,
+    /// Sprint name or id
+    #[arg(long)]
+    pub sprint: Option<String>,
+    /// Release name or id
+    #[arg(long)]
+    pub release: Option<String>,
+    /// low, medium, high or critical; matches that priority or higher
+    #[arg(long)]
+    pub min_priority: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ViewCommand{
+    /// List saved views
+    List,
+    /// Save (or replace) a view
+    Save{
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Print the items a saved view selects
+    Run{
+        name: String,
+        /// id, key, name, status, owner, priority, points, start or end
+        #[arg(long, default_value = "key")]
+        sort: String,
+        #[arg(long)]
+        desc: bool,
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Delete a saved view
+    Delete{
+        name: String,
+    },
+}
+
+fn by_name_or_id<'a, T: 'a>(items: impl Iterator<Item = &'a T>, value: &str, id: impl Fn(&T) -> Uuid, name: impl Fn(&T) -> &str) -> Option<Uuid>{
+    let parsed = Uuid::parse_str(value.trim()).ok();
+    items.into_iter()
+        .find(|item| Some(id(item)) == parsed || name(item) == value)
+        .map(id)
+}
+
+pub(super) fn build_filter(graph: &ProjectGraph, args: FilterArgs) -> Result<Filter>{
+    let scope = match args.scope{
+        Some(s) => Some(graph.resolve_id(&s).ok_or_else(|| anyhow!("No node '{}'", s))?),
+        None => None,
+    };
+    let sprint = match args.sprint{
+        Some(s) => Some(by_name_or_id(graph.sprints(), &s, |x| x.id, |x| &x.name).ok_or_else(|| anyhow!("No sprint '{}'", s))?),
+        None => None,
+    };
+    let release = match args.release{
+        Some(r) => Some(by_name_or_id(graph.releases(), &r, |x| x.id, |x| &x.name).ok_or_else(|| anyhow!("No release '{}'", r))?),
+        None => None,
+    };
+    Ok(Filter{
+        owner: args.owner,
+        status: args.status.map(|s| s.parse::<Status>()).transpose().map_err(|e| anyhow!(e))?,
+        tags: args.tags,
+        scope,
+        sprint,
+        release,
+        min_priority: args.min_priority.map(|p| p.parse::<Priority>()).transpose().map_err(|e| anyhow!(e))?,
+    })
+}
+
+pub fn run(path: &Path, command: ViewCommand, format: OutputFormat) -> Result<ExitCode>{
+    let mut graph = storage::open(path)?;
+    match command{
+        ViewCommand::List => {
+            let mut output = Output::new(vec!["name", "description", "items"]);
+            for view in graph.views(){
+                let items = view.filter.query(&graph).count();
+                output.push(vec![view.name.clone(), view.description.clone().unwrap_or_default(), items.to_string()]);
+            }
+            output.print(format)?;
+        }
+        ViewCommand::Save{ name, description, filter } => {
+            let mut view = SavedView::new(name, build_filter(&graph, filter)?);
+            if let Some(description) = description{
+                view = view.with_description(description);
+            }
+            graph.save_view(view).map_err(|e| anyhow!(e))?;
+            storage::write(&graph, path)?;
+        }
+        ViewCommand::Run{ name, sort, desc, offset, limit } => {
+            let view = graph.get_view(&name).ok_or_else(|| anyhow!("There is no saved view named '{}'", name))?;
+            let mut query = view.filter.query(&graph).sort_by(sort.parse::<SortKey>().map_err(|e| anyhow!(e))?).offset(offset);
+            if desc{
+                query = query.descending();
+            }
+            if let Some(limit) = limit{
+                query = query.limit(limit);
+            }
+            let nodes = query.run();
+            let mut output = Output::new(vec!["key", "name", "status", "owner"]);
+            for node in nodes{
+                output.push(vec![
+                    graph.get_key(node.get_id()).unwrap_or_default().to_string(),
+                    node.get_name().to_string(),
+                    node.get_status().map(|s| s.to_string()).unwrap_or_default(),
+                    node.get_owner().unwrap_or_default().to_string(),
+                ]);
+            }
+            output.print(format)?;
+        }
+        ViewCommand::Delete{ name } => {
+            if graph.remove_view(&name).is_none(){
+                bail!("There is no saved view named '{}'", name);
+            }
+            storage::write(&graph, path)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,94 @@
+// Actor - who made a change: a person, an integration acting through its
+// token, or pm itself
+//
+// Mutators don't take the actor as a parameter. It is set per thread, once
+// by the CLI (from --actor or $USER) or around a block of changes, and
+// picked up wherever changes are recorded:
+//
+//   let _as_alice = Actor::person("alice").enter();
+//   graph.set_status(id, Status::Done)?;   // the event names alice
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Actor{
+    Person(String),
+    // A sync source or other integration, by the name its token was issued to
+    Integration(String),
+    // Automatic changes pm makes on its own
+    System,
+}
+
+thread_local!{
+    static CURRENT: RefCell<Option<Actor>> = const { RefCell::new(None) };
+}
+
+// Restores the previous actor when dropped
+#[must_use = "the actor is reset as soon as the guard is dropped"]
+pub struct ActorGuard{
+    previous: Option<Actor>,
+}
+
+impl Drop for ActorGuard{
+    fn drop(&mut self){
+        let previous = self.previous.take();
+        CURRENT.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+impl Actor{
+    pub fn person(name: &str) -> Self{
+        Actor::Person(name.to_string())
+    }
+
+    pub fn integration(name: &str) -> Self{
+        Actor::Integration(name.to_string())
+    }
+
+    // The actor changes on this thread are attributed to, if any
+    pub fn current() -> Option<Actor>{
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    // Makes this the current actor until the guard is dropped
+    pub fn enter(self) -> ActorGuard{
+        let previous = CURRENT.with(|c| c.borrow_mut().replace(self));
+        ActorGuard{ previous }
+    }
+
+    // Makes this the current actor for the rest of the thread
+    pub fn set_current(actor: Option<Actor>){
+        CURRENT.with(|c| *c.borrow_mut() = actor);
+    }
+}
+
+// "alice", "token:github", "system"
+impl fmt::Display for Actor{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            Actor::Person(name) => write!(f, "{}", name),
+            Actor::Integration(name) => write!(f, "token:{}", name),
+            Actor::System => write!(f, "system"),
+        }
+    }
+}
+
+impl FromStr for Actor{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        let s = s.trim();
+        if s.is_empty(){
+            return Err("An actor needs a name");
+        }
+        Ok(match s.strip_prefix("token:"){
+            Some("") => return Err("An integration needs a name"),
+            Some(name) => Actor::Integration(name.to_string()),
+            None if s == "system" => Actor::System,
+            None => Actor::Person(s.to_string()),
+        })
+    }
+}
@@ -0,0 +1,42 @@
+// Actual dates - when work really started and finished, next to the planned
+// Timeline
+//
+// By default the graph stamps them as statuses change: the start when a node
+// first leaves Not Started, the finish when it is marked Done (cleared again
+// if it is reopened). Projects that record them by hand switch this off in
+// the settings.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actuals{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<DT>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish: Option<DT>,
+}
+
+impl Actuals{
+    pub fn is_empty(&self) -> bool{
+        self.start.is_none() && self.finish.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        match (self.start, self.finish){
+            (Some(start), Some(finish)) if finish < start => Err("The actual finish must not be before the actual start"),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Who sets the actual dates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActualDates{
+    // Stamped on status changes; still editable by hand
+    #[default]
+    FromStatus,
+    Manual,
+}
@@ -0,0 +1,62 @@
+// Approval gates - transitions that need sign-off before they happen
+//
+// A gate names a transition (nodes of a kind moving to Done, or a release
+// being cut) and the roles that must approve it. Approvals are made by
+// people holding the role (see Person::roles), as the current actor, and
+// are logged against the node or release like any other change, so they
+// show up in the audit trail. The transition is refused until every role
+// of every gate on it has approved.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GateTarget{
+    // Nodes with this key prefix (EPIC, STORY, ...) moving to Done
+    Done(String),
+    ReleaseCut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalGate{
+    pub name: String,
+    pub target: GateTarget,
+    // One approval from someone holding each of these
+    pub roles: Vec<String>,
+}
+
+impl ApprovalGate{
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if self.name.trim().is_empty(){
+            return Err("An approval gate needs a name");
+        }
+        if self.roles.is_empty() || self.roles.iter().any(|r| r.trim().is_empty()){
+            return Err("An approval gate needs the roles that approve it");
+        }
+        if let GateTarget::Done(prefix) = &self.target{
+            if !["PROJ", "SPEC", "EPIC", "STORY", "TASK"].contains(&prefix.as_str()){
+                return Err("Approval gates on Done are per kind: PROJ, SPEC, EPIC, STORY or TASK");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval{
+    // The node or release approved
+    pub subject: Uuid,
+    pub gate: String,
+    pub role: String,
+    pub by: String,
+    pub at: DateTime<Utc>,
+}
+
+// A gated transition still waiting on roles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingApproval{
+    pub subject: Uuid,
+    pub gate: String,
+    pub missing: Vec<String>,
+}
@@ -0,0 +1,223 @@
+// Automation rules - "when a Task under Epic X is done, tag it ready-for-qa
+// and tell #qa"
+//
+// Rules are stored with the project and run over the event log in the order
+// events were recorded: each run looks at the events recorded since the
+// previous one, so a change fires a rule once, even one backdated to before
+// the last run, and a rule only sees changes recorded after it was added. A
+// rule's filter is checked against the node as it was right after the
+// change, not as it is now. Tags set by rules are logged like any other
+// change but not fed back into the rules, so rules cannot set each other off
+// in a loop.
+//
+// Notify actions are returned to the caller as firings to deliver; see
+// notify::rule_notifications.
+
+use super::event::{Event, EventKind};
+use super::graph::ProjectGraph;
+use super::view::Filter;
+use super::{Node, Priority, Status};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger{
+    // Moved into the status, or into any status when None
+    Status(Option<Status>),
+    // Moved into the workflow state
+    State(String),
+    // Given the tag
+    Tagged(String),
+    // Its last open blocker was finished
+    Unblocked,
+}
+
+impl Trigger{
+    pub fn matches(&self, kind: &EventKind) -> bool{
+        let has = |tags: &Value, tag: &str| tags.as_array().is_some_and(|t| t.iter().any(|v| v.as_str() == Some(tag)));
+        match (self, kind){
+            (Trigger::Status(wanted), EventKind::StatusChanged{ to, .. }) => wanted.is_none_or(|w| w == *to),
+            (Trigger::State(wanted), EventKind::StateChanged{ to, .. }) => wanted.eq_ignore_ascii_case(to),
+            (Trigger::Tagged(tag), EventKind::FieldChanged{ field, from, to }) => field == "tags" && has(to, tag) && !has(from, tag),
+            (Trigger::Unblocked, EventKind::Unblocked{..}) => true,
+            _ => false,
+        }
+    }
+}
+
+// done, status, status:in-progress, state:In QA, tagged:urgent, unblocked
+impl FromStr for Trigger{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        let s = s.trim();
+        let (head, rest) = s.split_once(':').map_or((s, None), |(h, r)| (h, Some(r.trim())));
+        match (head.to_ascii_lowercase().as_str(), rest){
+            ("status", None) => Ok(Trigger::Status(None)),
+            ("status", Some(status)) => Ok(Trigger::Status(Some(status.parse()?))),
+            ("state", Some(state)) if !state.is_empty() => Ok(Trigger::State(state.to_string())),
+            ("tagged", Some(tag)) if !tag.is_empty() => Ok(Trigger::Tagged(tag.to_string())),
+            ("unblocked", None) => Ok(Trigger::Unblocked),
+            (_, None) => Ok(Trigger::Status(Some(s.parse().map_err(|_| "Unknown trigger; expected a status, status:STATUS, state:NAME, tagged:TAG or unblocked")?))),
+            _ => Err("Unknown trigger; expected a status, status:STATUS, state:NAME, tagged:TAG or unblocked"),
+        }
+    }
+}
+
+impl fmt::Display for Trigger{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            Trigger::Status(None) => write!(f, "status"),
+            Trigger::Status(Some(status)) => write!(f, "status:{}", status),
+            Trigger::State(state) => write!(f, "state:{}", state),
+            Trigger::Tagged(tag) => write!(f, "tagged:{}", tag),
+            Trigger::Unblocked => write!(f, "unblocked"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action{
+    AddTag(String),
+    RemoveTag(String),
+    // Message a channel or person; the default message names the rule and the node
+    Notify{ channel: String, message: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule{
+    pub name: String,
+    pub trigger: Trigger,
+    // Key prefix of the nodes it applies to (TASK, STORY, EPIC, ...); any when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    // What the node must look like after the change
+    #[serde(default)]
+    pub filter: Filter,
+    pub actions: Vec<Action>,
+    // Last event recorded before the rule was added; it ignores that one
+    // and anything earlier
+    #[serde(default)]
+    pub after: Option<u64>,
+    // When the rule was added, for rules saved before `after` existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DT>,
+}
+
+impl Rule{
+    pub fn new(name: String, trigger: Trigger) -> Self{
+        Rule{ name, trigger, kind: None, filter: Filter::default(), actions: Vec::new(), after: None, since: None }
+    }
+
+    pub fn with_kind(mut self, kind: &str) -> Self{
+        self.kind = Some(kind.to_ascii_uppercase());
+        self
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self{
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_action(mut self, action: Action) -> Self{
+        self.actions.push(action);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if self.name.trim().is_empty(){
+            return Err("A rule needs a name");
+        }
+        if self.actions.is_empty(){
+            return Err("A rule needs at least one action");
+        }
+        let blank = |s: &str| s.trim().is_empty();
+        if self.actions.iter().any(|a| match a{
+            Action::AddTag(tag) | Action::RemoveTag(tag) => blank(tag),
+            Action::Notify{ channel, .. } => blank(channel),
+        }){
+            return Err("Rule actions need a tag or channel");
+        }
+        Ok(())
+    }
+
+    pub fn applies_to(&self, graph: &ProjectGraph, node: &Node) -> bool{
+        self.applies_with(graph, node, graph.get_priority(node.get_id()))
+    }
+
+    fn applies_with(&self, graph: &ProjectGraph, node: &Node, priority: Option<Priority>) -> bool{
+        self.kind.as_deref().is_none_or(|k| node.get_key_prefix().eq_ignore_ascii_case(k))
+            && self.filter.matches_with(graph, node, priority)
+    }
+
+    pub fn fires_on(&self, graph: &ProjectGraph, event: &Event) -> bool{
+        let recorded_after = match self.after{
+            Some(after) => event.seq > after,
+            None => self.since.is_none_or(|since| event.at > since),
+        };
+        recorded_after
+            && self.trigger.matches(&event.kind)
+            && node_after(graph, event).is_some_and(|(node, priority)| self.applies_with(graph, &node, priority))
+    }
+}
+
+// The node and its priority as they were right after `event`, found by
+// undoing the changes recorded for it since, newest first. Status, owner,
+// tags and priority are wound back; scope, sprint and release are as now.
+fn node_after(graph: &ProjectGraph, event: &Event) -> Option<(Node,Option<Priority>)>{
+    let mut node = graph.get_node(event.node)?.clone();
+    let mut priority = graph.get_priority(event.node);
+    let mut later: Vec<&Event> = graph.get_events().for_node(event.node).filter(|e| e.seq > event.seq).collect();
+    later.sort_by_key(|e| std::cmp::Reverse(e.seq));
+    for change in later{
+        match &change.kind{
+            EventKind::StatusChanged{ from, .. } => { let _ = node.set_status(*from); }
+            EventKind::FieldChanged{ field, from, .. } => match field.as_str(){
+                "owner" => match from.as_str(){
+                    Some(owner) => node.set_owner(owner.to_string()),
+                    None => node.clear_owner(),
+                },
+                "tags" => {
+                    let now: Vec<String> = node.get_tags().iter().map(|t| t.to_string()).collect();
+                    for tag in now{
+                        node.remove_tag(&tag);
+                    }
+                    for tag in from.as_array().into_iter().flatten().filter_map(|t| t.as_str()){
+                        node.add_tag(tag.into());
+                    }
+                }
+                "priority" => priority = serde_json::from_value(from.clone()).unwrap_or(None),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    Some((node, priority))
+}
+
+// One action of one rule, run for one node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Firing{
+    pub rule: String,
+    pub node: Uuid,
+    // When the change that fired it happened
+    pub at: DT,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Automation{
+    pub(crate) rules: Vec<Rule>,
+    // Events recorded up to this sequence number have been run through the rules
+    #[serde(default)]
+    pub(crate) checked: Option<u64>,
+    // The time-based mark files saved before `checked` existed carry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) checked_until: Option<DT>,
+}
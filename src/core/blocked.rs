@@ -0,0 +1,104 @@
+// Blocked reasons - why work is stuck, from a taxonomy kept in the settings,
+// and who or what it waits on
+//
+// Every stretch a node spends Blocked is kept as a BlockedPeriod, so blocked
+// time can be reported by reason. Stretches begun without a reason (by a
+// sync or a plain status change) count as "unspecified"; with
+// `require_reason` set, a person blocking a node without one is refused,
+// while integrations and pm itself still block as "unspecified".
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const UNSPECIFIED: &str = "unspecified";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Blocker{
+    Node(Uuid),
+    Person(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockReason{
+    // One of the settings' categories
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocker: Option<Blocker>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl BlockReason{
+    pub fn new(category: &str) -> Self{
+        BlockReason{ category: category.trim().to_string(), blocker: None, note: None }
+    }
+
+    pub fn with_blocker(mut self, blocker: Blocker) -> Self{
+        self.blocker = Some(blocker);
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self{
+        self.note = Some(note.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedPeriod{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<BlockReason>,
+    pub start: DateTime<Utc>,
+    // None while the node is still Blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl BlockedPeriod{
+    pub fn category(&self) -> &str{
+        self.reason.as_ref().map(|r| r.category.as_str()).unwrap_or(UNSPECIFIED)
+    }
+
+    // Open periods run until `now`
+    pub fn duration(&self, now: DateTime<Utc>) -> TimeDelta{
+        (self.end.unwrap_or(now) - self.start).max(TimeDelta::zero())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedReasons{
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub require_reason: bool,
+}
+
+impl Default for BlockedReasons{
+    fn default() -> Self{
+        BlockedReasons{
+            categories: ["waiting on dependency", "external vendor", "decision needed"].map(str::to_string).to_vec(),
+            require_reason: false,
+        }
+    }
+}
+
+impl BlockedReasons{
+    pub fn contains(&self, category: &str) -> bool{
+        self.categories.iter().any(|c| c == category)
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if self.categories.is_empty(){
+            return Err("The blocked reason taxonomy needs at least one category");
+        }
+        for (i, category) in self.categories.iter().enumerate(){
+            if category.trim().is_empty() || category == UNSPECIFIED{
+                return Err("Blocked reason categories need a name other than 'unspecified'");
+            }
+            if self.categories[..i].contains(category){
+                return Err("Blocked reason categories must be unique");
+            }
+        }
+        Ok(())
+    }
+}
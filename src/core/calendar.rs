@@ -0,0 +1,102 @@
+// Calendar - which days count as working days
+//
+// Weekends are never working days; holidays are added explicitly, either
+// for everyone or for one region (e.g. "de-by", "us-ca"). People in a
+// region (see Person::region) also get that region's holidays off.
+
+use super::holidays::Holiday;
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Calendar{
+    holidays: BTreeSet<NaiveDate>,
+    #[serde(default)]
+    regions: BTreeMap<String,BTreeSet<NaiveDate>>,
+}
+
+impl Calendar{
+    pub fn new() -> Self{
+        Calendar::default()
+    }
+
+    pub fn add_holiday(&mut self, date: NaiveDate) -> bool{
+        self.holidays.insert(date)
+    }
+
+    pub fn remove_holiday(&mut self, date: NaiveDate) -> bool{
+        self.holidays.remove(&date)
+    }
+
+    pub fn get_holidays(&self) -> &BTreeSet<NaiveDate>{
+        &self.holidays
+    }
+
+    pub fn add_regional_holiday(&mut self, region: &str, date: NaiveDate) -> bool{
+        self.regions.entry(region.to_string()).or_default().insert(date)
+    }
+
+    pub fn remove_regional_holiday(&mut self, region: &str, date: NaiveDate) -> bool{
+        let Some(dates) = self.regions.get_mut(region) else {
+            return false;
+        };
+        let removed = dates.remove(&date);
+        if dates.is_empty(){
+            self.regions.remove(region);
+        }
+        removed
+    }
+
+    pub fn get_regional_holidays(&self, region: &str) -> Option<&BTreeSet<NaiveDate>>{
+        self.regions.get(region)
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = &str>{
+        self.regions.keys().map(|r| r.as_str())
+    }
+
+    // Adds imported holidays for everyone, or for `region`; returns how many were new
+    pub fn import(&mut self, region: Option<&str>, holidays: &[Holiday]) -> usize{
+        holidays.iter()
+            .filter(|h| match region{
+                Some(region) => self.add_regional_holiday(region, h.date),
+                None => self.add_holiday(h.date),
+            })
+            .count()
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool{
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    // A working day for everyone and, if given, in `region` as well
+    pub fn is_working_day_in(&self, date: NaiveDate, region: Option<&str>) -> bool{
+        self.is_working_day(date) && !region.and_then(|r| self.regions.get(r)).is_some_and(|dates| dates.contains(&date))
+    }
+
+    // Working days in the inclusive range [start, end]
+    pub fn working_days(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate>{
+        self.working_days_in(start, end, None)
+    }
+
+    pub fn working_days_in(&self, start: NaiveDate, end: NaiveDate, region: Option<&str>) -> Vec<NaiveDate>{
+        start.iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_working_day_in(*d, region))
+            .collect()
+    }
+
+    // The `count`-th working day after `from`; `from` itself when count is 0
+    pub fn add_working_days(&self, from: NaiveDate, count: u64) -> Option<NaiveDate>{
+        let mut date = from;
+        let mut left = count;
+        while left > 0{
+            date = date.succ_opt()?;
+            if self.is_working_day(date){
+                left -= 1;
+            }
+        }
+        Some(date)
+    }
+}
@@ -0,0 +1,70 @@
+// Definition of done - checklists a node must work through before it counts
+// as finished
+//
+// The settings hold one template per node kind (by key prefix: TASK, STORY,
+// ...), e.g. TASK: code review, tests, docs. New nodes of that kind get a
+// copy of it as their checklist, which can then be ticked off or extended
+// per node. Moving a node to Done with unticked items is refused under
+// DodPolicy::Enforce, and reported by validation under DodPolicy::Warn.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItem{
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl ChecklistItem{
+    pub fn new(text: &str) -> Self{
+        ChecklistItem{ text: text.trim().to_string(), done: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DodPolicy{
+    // Done is allowed; validation lists what was left unticked
+    #[default]
+    Warn,
+    // Done is refused until every item is ticked
+    Enforce,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefinitionOfDone{
+    // Key prefix -> items every new node of that kind starts with
+    #[serde(default)]
+    pub templates: BTreeMap<String,Vec<String>>,
+    #[serde(default)]
+    pub policy: DodPolicy,
+}
+
+impl DefinitionOfDone{
+    pub fn is_empty(&self) -> bool{
+        self.templates.is_empty() && self.policy == DodPolicy::default()
+    }
+
+    // The checklist a new node with this key prefix starts with
+    pub fn checklist_for(&self, prefix: &str) -> Vec<ChecklistItem>{
+        self.templates.get(prefix).map(|items| items.iter().map(|t| ChecklistItem::new(t)).collect()).unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        for (prefix, items) in &self.templates{
+            if !["PROJ", "SPEC", "EPIC", "STORY", "TASK"].contains(&prefix.as_str()){
+                return Err("Definition of done templates are per kind: PROJ, SPEC, EPIC, STORY or TASK");
+            }
+            for (i, item) in items.iter().enumerate(){
+                if item.trim().is_empty(){
+                    return Err("Definition of done items need some text");
+                }
+                if items[..i].iter().any(|other| other.trim() == item.trim()){
+                    return Err("Definition of done items must be unique within a kind");
+                }
+            }
+        }
+        Ok(())
+    }
+}
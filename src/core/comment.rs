@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment{
+    pub author: String,
+    pub text: String,
+    pub at: DateTime<Utc>,
+}
+
+impl Comment{
+    pub fn new(author: String, text: String, at: DateTime<Utc>) -> Result<Self,&'static str>{
+        if text.trim().is_empty(){
+            return Err("A comment cannot be empty");
+        }
+        Ok(Comment{ author, text, at })
+    }
+}
@@ -0,0 +1,126 @@
+// Commit messages - status changes named in a commit message, for a git
+// post-commit hook or a CI step, e.g.
+//
+//   Fix token refresh, closes TASK-42 and TASK-43; progress STORY-17 50%
+//
+// Recognised phrases, anywhere in the message and in any case:
+//   close(s|d), fix(es|ed), resolve(s|d) KEY...    marks the nodes Done
+//   start(s|ed) KEY...                              moves them to In Progress
+//   progress KEY N%                                 records progress, see
+//                                                   ProjectGraph::set_progress_at
+// A keyword applies to every key after it up to the next word that is not a
+// key or "and". Keys are node keys as pm shows them, in any case, e.g.
+// TASK-42 or task-42.
+
+use super::graph::ProjectGraph;
+use super::status::Status;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitChange{
+    Close,
+    Start,
+    Progress(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitAction{
+    pub key: String,
+    pub change: CommitChange,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOutcome{
+    pub applied: Vec<(Uuid, CommitChange)>,
+    // Keys that name no node in the graph
+    pub unknown: Vec<String>,
+    // Keys whose change the graph refused, with why
+    pub refused: Vec<(String, &'static str)>,
+}
+
+fn is_key(word: &str) -> bool{
+    let Some((prefix, number)) = word.split_once('-') else {
+        return false;
+    };
+    !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphabetic())
+        && !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+}
+
+fn keyword(word: &str) -> Option<CommitChange>{
+    match word.to_lowercase().as_str(){
+        "close" | "closes" | "closed" | "fix" | "fixes" | "fixed" | "resolve" | "resolves" | "resolved" => Some(CommitChange::Close),
+        "start" | "starts" | "started" => Some(CommitChange::Start),
+        "progress" => Some(CommitChange::Progress(0)),
+        _ => None,
+    }
+}
+
+pub fn parse_commit_message(message: &str) -> Vec<CommitAction>{
+    let words: Vec<&str> = message.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| matches!(c, ',' | '.' | ';' | ':' | '(' | ')' | '[' | ']' | '!' | '"' | '\'')))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut actions = Vec::new();
+    let mut current: Option<CommitChange> = None;
+    let mut i = 0;
+    while i < words.len(){
+        let word = words[i];
+        i += 1;
+        if let Some(change) = keyword(word){
+            current = Some(change);
+            continue;
+        }
+        let Some(change) = current else {
+            continue;
+        };
+        if word.eq_ignore_ascii_case("and"){
+            continue;
+        }
+        if !is_key(word){
+            current = None;
+            continue;
+        }
+        let change = match change{
+            // The percentage follows the key; without one the key is skipped
+            CommitChange::Progress(_) => {
+                let Some(percent) = words.get(i).and_then(|w| w.strip_suffix('%')).and_then(|n| n.parse::<u8>().ok()).filter(|p| *p <= 100) else {
+                    continue;
+                };
+                i += 1;
+                CommitChange::Progress(percent)
+            }
+            other => other,
+        };
+        actions.push(CommitAction{ key: word.to_string(), change });
+    }
+    actions
+}
+
+// Applies what the message asks for, as of `at`; keys that are unknown or
+// refused are reported rather than stopping the rest
+pub fn apply_commit_message(graph: &mut ProjectGraph, message: &str, at: DateTime<Utc>) -> CommitOutcome{
+    let mut outcome = CommitOutcome::default();
+    for action in parse_commit_message(message){
+        let Some(id) = graph.resolve_id(&action.key) else {
+            outcome.unknown.push(action.key);
+            continue;
+        };
+        let result = match action.change{
+            CommitChange::Close => graph.set_status_at(id, Status::Done, at),
+            // Never moves finished or blocked work back
+            CommitChange::Start => match graph.get_node(id).and_then(|n| n.get_status()){
+                Some(Status::NotStarted) => graph.set_status_at(id, Status::InProgress, at),
+                Some(_) => Ok(()),
+                None => Err("The node does not carry a status"),
+            },
+            CommitChange::Progress(percent) => graph.set_progress_at(id, percent, at),
+        };
+        match result{
+            Ok(()) => outcome.applied.push((id, action.change)),
+            Err(e) => outcome.refused.push((action.key, e)),
+        }
+    }
+    outcome
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+type DT = DateTime<Utc>;
+
+// Scheduling constraints honored by the auto-scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Constraint{
+    // MSO: the node starts exactly on this date
+    MustStartOn(DT),
+    // SNET: the node may not start before this date
+    StartNoEarlierThan(DT),
+    // FNLT: the node must finish on or before this date
+    FinishNoLaterThan(DT),
+}
+
+impl Constraint{
+    // The earliest start this constraint imposes, if any
+    pub fn earliest_start(&self) -> Option<DT>{
+        match self{
+            Constraint::MustStartOn(d) | Constraint::StartNoEarlierThan(d) => Some(*d),
+            Constraint::FinishNoLaterThan(_) => None,
+        }
+    }
+
+    // Whether a node scheduled over [start, end] satisfies the constraint
+    pub fn is_satisfied(&self, start: DT, end: DT) -> bool{
+        match self{
+            Constraint::MustStartOn(d) => start == *d,
+            Constraint::StartNoEarlierThan(d) => start >= *d,
+            Constraint::FinishNoLaterThan(d) => end <= *d,
+        }
+    }
+}
@@ -0,0 +1,221 @@
+// Human-friendly date parsing for the CLI and importers
+//
+// Understands ISO dates plus phrases like "today", "next monday",
+// "in 3 weeks", "in 5 working days", "2 days ago", "end of Q2" or
+// "start of next month". Relative phrases count from `now`; "end of"/"start
+// of" snap to the nearest working day inside the period, per `calendar`.
+
+use super::Calendar;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
+
+const ERR: &str = "Unrecognized date; try YYYY-MM-DD, 'next friday', 'in 2 weeks' or 'end of Q3'";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit{
+    Days,
+    WorkingDays,
+    Weeks,
+    Months,
+    Years,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Period{
+    Week,
+    Month,
+    Quarter(u32),
+    Year,
+}
+
+fn weekday(word: &str) -> Option<Weekday>{
+    Some(match word{
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn number(word: &str) -> Option<u64>{
+    Some(match word{
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        _ => return word.parse().ok(),
+    })
+}
+
+fn unit(words: &[&str]) -> Option<Unit>{
+    Some(match words{
+        ["day" | "days"] => Unit::Days,
+        ["working" | "business" | "work", "day" | "days"] => Unit::WorkingDays,
+        ["workday" | "workdays" | "weekday" | "weekdays"] => Unit::WorkingDays,
+        ["week" | "weeks"] => Unit::Weeks,
+        ["month" | "months"] => Unit::Months,
+        ["year" | "years"] => Unit::Years,
+        _ => return None,
+    })
+}
+
+// The first `day` strictly after `from`
+fn next_weekday(from: NaiveDate, day: Weekday) -> NaiveDate{
+    let ahead = (day.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    from + Days::new(if ahead == 0 { 7 } else { ahead as u64 })
+}
+
+fn shift_working_days(calendar: &Calendar, from: NaiveDate, count: u64, forward: bool) -> Option<NaiveDate>{
+    // Out of range if that many calendar days already are
+    let reachable = if forward { from.checked_add_days(Days::new(count)) } else { from.checked_sub_days(Days::new(count)) };
+    reachable?;
+    if forward{
+        return calendar.add_working_days(from, count);
+    }
+    let mut date = from;
+    let mut left = count;
+    while left > 0{
+        date = date.pred_opt()?;
+        if calendar.is_working_day(date){
+            left -= 1;
+        }
+    }
+    Some(date)
+}
+
+// None when the count is too large for a date, rather than wrapping around
+fn shift(calendar: &Calendar, from: NaiveDate, n: u64, unit: Unit, forward: bool) -> Option<NaiveDate>{
+    let months = |m: u64| u32::try_from(m).ok().map(Months::new);
+    match (unit, forward){
+        (Unit::Days, true) => from.checked_add_days(Days::new(n)),
+        (Unit::Days, false) => from.checked_sub_days(Days::new(n)),
+        (Unit::Weeks, true) => from.checked_add_days(Days::new(n.checked_mul(7)?)),
+        (Unit::Weeks, false) => from.checked_sub_days(Days::new(n.checked_mul(7)?)),
+        (Unit::Months, true) => from.checked_add_months(months(n)?),
+        (Unit::Months, false) => from.checked_sub_months(months(n)?),
+        (Unit::Years, true) => from.checked_add_months(months(n.checked_mul(12)?)?),
+        (Unit::Years, false) => from.checked_sub_months(months(n.checked_mul(12)?)?),
+        (Unit::WorkingDays, forward) => shift_working_days(calendar, from, n, forward),
+    }
+}
+
+// First and last day of the period containing `date`
+fn bounds(date: NaiveDate, period: Period) -> Option<(NaiveDate, NaiveDate)>{
+    let (first, months) = match period{
+        Period::Week => {
+            let monday = date - Days::new(date.weekday().num_days_from_monday() as u64);
+            return Some((monday, monday + Days::new(6)));
+        }
+        Period::Month => (NaiveDate::from_ymd_opt(date.year(), date.month(), 1)?, 1),
+        Period::Quarter(q) => (NaiveDate::from_ymd_opt(date.year(), (q - 1) * 3 + 1, 1)?, 3),
+        Period::Year => (NaiveDate::from_ymd_opt(date.year(), 1, 1)?, 12),
+    };
+    Some((first, first.checked_add_months(Months::new(months))?.pred_opt()?))
+}
+
+fn period(words: &[&str], today: NaiveDate) -> Option<(Period, NaiveDate)>{
+    let words = match words{
+        ["the", rest @ ..] => rest,
+        rest => rest,
+    };
+    let (anchor, name) = match words{
+        ["next", name] => (true, *name),
+        ["this", name] => (false, *name),
+        [name] => (false, *name),
+        _ => return None,
+    };
+
+    let quarter_of = |d: NaiveDate| (d.month() - 1) / 3 + 1;
+    let (period, step) = match name{
+        "week" => (Period::Week, Some((7, Unit::Days))),
+        "month" => (Period::Month, Some((1, Unit::Months))),
+        "quarter" => (Period::Quarter(quarter_of(today)), Some((3, Unit::Months))),
+        "year" => (Period::Year, Some((1, Unit::Years))),
+        "q1" | "q2" | "q3" | "q4" if !anchor => {
+            let q = name[1..].parse().ok()?;
+            // A quarter that is already over means next year's
+            let year = if q < quarter_of(today) { today.year() + 1 } else { today.year() };
+            return Some((Period::Quarter(q), NaiveDate::from_ymd_opt(year, (q - 1) * 3 + 1, 1)?));
+        }
+        _ => return None,
+    };
+
+    let reference = match (anchor, step){
+        (true, Some((n, Unit::Days))) => today.checked_add_days(Days::new(n))?,
+        (true, Some((n, Unit::Months))) => today.checked_add_months(Months::new(n as u32))?,
+        (true, Some((n, _))) => today.checked_add_months(Months::new(n as u32 * 12))?,
+        _ => today,
+    };
+    let period = match period{
+        Period::Quarter(_) => Period::Quarter(quarter_of(reference)),
+        other => other,
+    };
+    Some((period, reference))
+}
+
+// Last working day on or before `date`, staying on or after `floor`
+fn working_on_or_before(calendar: &Calendar, date: NaiveDate, floor: NaiveDate) -> NaiveDate{
+    let mut d = date;
+    while d > floor && !calendar.is_working_day(d){
+        d = d.pred_opt().unwrap_or(floor);
+    }
+    d
+}
+
+fn working_on_or_after(calendar: &Calendar, date: NaiveDate, ceiling: NaiveDate) -> NaiveDate{
+    let mut d = date;
+    while d < ceiling && !calendar.is_working_day(d){
+        d = d.succ_opt().unwrap_or(ceiling);
+    }
+    d
+}
+
+pub fn parse_human(input: &str, now: DateTime<Utc>, calendar: &Calendar) -> Result<NaiveDate,&'static str>{
+    let trimmed = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d"){
+        return Ok(date);
+    }
+
+    let today = now.date_naive();
+    let lower = trimmed.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+
+    let parsed = match words.as_slice(){
+        ["today" | "now"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        ["next", "working" | "business", "day"] | ["next", "workday"] => shift_working_days(calendar, today, 1, true),
+        ["next", "week"] => Some(next_weekday(today, Weekday::Mon)),
+        ["next", "month"] => bounds(today.checked_add_months(Months::new(1)).ok_or(ERR)?, Period::Month).map(|b| b.0),
+        ["next", "year"] => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1),
+        ["next", day] => weekday(day).map(|d| next_weekday(today, d)),
+        ["this", day] | [day] => weekday(day).map(|d| {
+            if today.weekday() == d { today } else { next_weekday(today, d) }
+        }),
+        ["in", n, rest @ ..] => match (number(n), unit(rest)){
+            (Some(n), Some(u)) => shift(calendar, today, n, u, true),
+            _ => None,
+        },
+        [n, rest @ .., "ago"] => match (number(n), unit(rest)){
+            (Some(n), Some(u)) => shift(calendar, today, n, u, false),
+            _ => None,
+        },
+        ["end", "of", rest @ ..] => period(rest, today)
+            .and_then(|(p, reference)| bounds(reference, p))
+            .map(|(first, last)| working_on_or_before(calendar, last, first)),
+        ["start" | "beginning", "of", rest @ ..] => period(rest, today)
+            .and_then(|(p, reference)| bounds(reference, p))
+            .map(|(first, last)| working_on_or_after(calendar, first, last)),
+        _ => None,
+    };
+    parsed.ok_or(ERR)
+}
@@ -0,0 +1,110 @@
+// Effort - how much work a node is, as opposed to how long it takes
+//
+// Points size work relative to other work and the timeline says when it
+// happens; effort is the hours of work itself. The assignee's allocation
+// (the share of their working day spent on the node) turns it into working
+// days: 20 hours at 50% of an 8 hour day take five working days.
+//
+// The curve says how those hours fall over the working days. The length
+// stays the same whatever the curve; only the load per day changes, so
+// workload reports see a front-loaded task take most of its hours early.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffortCurve{
+    // The same load every day
+    #[default]
+    Flat,
+    // Starts at 150% of the average day and tapers to 50%
+    FrontLoaded,
+    // Starts at 50% of the average day and builds to 150%
+    BackLoaded,
+}
+
+impl EffortCurve{
+    // Share of the work done over [from, to], as fractions of the way through
+    fn share(&self, from: f64, to: f64) -> f64{
+        // Integral of the load 1 + slope * (0.5 - x), which averages 1
+        let slope = match self{
+            EffortCurve::Flat => 0.0,
+            EffortCurve::FrontLoaded => 1.0,
+            EffortCurve::BackLoaded => -1.0,
+        };
+        let integral = |x: f64| x + slope * (x - x * x) / 2.0;
+        integral(to) - integral(from)
+    }
+
+    // Share of the effort falling on each of `days` working days; sums to 1
+    pub fn daily_shares(&self, days: usize) -> Vec<f64>{
+        let n = days as f64;
+        (0..days).map(|i| self.share(i as f64 / n, (i + 1) as f64 / n)).collect()
+    }
+}
+
+// "flat", "front-loaded", "back-loaded"
+impl fmt::Display for EffortCurve{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            EffortCurve::Flat => write!(f, "flat"),
+            EffortCurve::FrontLoaded => write!(f, "front-loaded"),
+            EffortCurve::BackLoaded => write!(f, "back-loaded"),
+        }
+    }
+}
+
+impl FromStr for EffortCurve{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        match s.trim().to_ascii_lowercase().as_str(){
+            "flat" => Ok(EffortCurve::Flat),
+            "front" | "front-loaded" => Ok(EffortCurve::FrontLoaded),
+            "back" | "back-loaded" => Ok(EffortCurve::BackLoaded),
+            _ => Err("Effort curves are flat, front-loaded or back-loaded"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Effort{
+    pub hours: f64,
+    // Percent of the assignee's working day, 1 to 100
+    pub allocation: u8,
+    #[serde(default)]
+    pub curve: EffortCurve,
+}
+
+impl Effort{
+    // Full-time until an allocation is given
+    pub fn new(hours: f64) -> Result<Self,&'static str>{
+        if !(hours.is_finite() && hours > 0.0){
+            return Err("Effort must be a positive number of hours");
+        }
+        Ok(Effort{ hours, allocation: 100, curve: EffortCurve::Flat })
+    }
+
+    pub fn with_allocation(mut self, percent: u8) -> Result<Self,&'static str>{
+        if percent == 0 || percent > 100{
+            return Err("Allocation must be between 1 and 100 percent");
+        }
+        self.allocation = percent;
+        Ok(self)
+    }
+
+    pub fn with_curve(mut self, curve: EffortCurve) -> Self{
+        self.curve = curve;
+        self
+    }
+
+    // Hours of the node done per working day, on average over its curve
+    pub fn hours_per_day(&self, working_hours: f64) -> f64{
+        working_hours * self.allocation as f64 / 100.0
+    }
+
+    pub fn working_days(&self, working_hours: f64) -> f64{
+        self.hours / self.hours_per_day(working_hours)
+    }
+}
@@ -0,0 +1,70 @@
+// Planning-poker estimates - everyone's vote on a backlog item, from which
+// the item's points are derived instead of being typed in directly
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Estimate{
+    pub person: String,
+    pub points: u32,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Estimate{
+    pub fn new(person: String, points: u32, timestamp: DateTime<Utc>) -> Result<Self,&'static str>{
+        if person.trim().is_empty(){
+            return Err("An estimate needs the name of the person giving it");
+        }
+        Ok(Estimate{ person, points, timestamp })
+    }
+}
+
+// How the votes are turned into a single number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Consensus{
+    // Middle vote; with an even count, the mean of the two middle votes rounded up
+    #[default]
+    Median,
+    // Most common vote; ties go to the larger estimate
+    Mode,
+}
+
+// Only each person's most recent vote counts, so re-estimating replaces it
+pub fn current_votes(estimates: &[Estimate]) -> Vec<u32>{
+    let mut latest: HashMap<&str,&Estimate> = HashMap::new();
+    for e in estimates{
+        match latest.get(e.person.as_str()){
+            Some(prev) if prev.timestamp > e.timestamp => {}
+            _ => { latest.insert(&e.person, e); }
+        }
+    }
+    let mut votes: Vec<u32> = latest.values().map(|e| e.points).collect();
+    votes.sort_unstable();
+    votes
+}
+
+pub fn consensus(estimates: &[Estimate], method: Consensus) -> Option<u32>{
+    let votes = current_votes(estimates);
+    if votes.is_empty(){
+        return None;
+    }
+    match method{
+        Consensus::Median => {
+            let mid = votes.len() / 2;
+            if votes.len() % 2 == 1{
+                Some(votes[mid])
+            }else{
+                Some((votes[mid - 1] + votes[mid]).div_ceil(2))
+            }
+        }
+        Consensus::Mode => {
+            let mut counts: HashMap<u32,usize> = HashMap::new();
+            for v in &votes{
+                *counts.entry(*v).or_default() += 1;
+            }
+            counts.into_iter().max_by_key(|(points, count)| (*count, *points)).map(|(points, _)| points)
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Event log - a record of changes to the graph over time, saved with the
+// project so reports can look at history (what finished, what got unblocked)
+
+use super::{Actor, Status};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EventKind{
+    StatusChanged{ from: Status, to: Status },
+    // A move within a custom workflow, see Workflow
+    StateChanged{ from: String, to: String },
+    // The last open blocker of the node, `by`, was finished
+    Unblocked{ by: Uuid },
+    // Any other edit to a node, with the field's old and new value (null
+    // when unset); tags are logged as the whole sorted set
+    FieldChanged{ field: String, from: Value, to: Value },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event{
+    // Order the event was recorded in, which backdated events don't follow;
+    // 0 only in files written before events were numbered
+    #[serde(default)]
+    pub seq: u64,
+    pub at: DT,
+    pub node: Uuid,
+    pub kind: EventKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Actor>,
+}
+
+impl Event{
+    // Attributed to the current actor, see Actor::enter
+    pub fn new(at: DT, node: Uuid, kind: EventKind) -> Self{
+        Event{ seq: 0, at, node, kind, actor: Actor::current() }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "StoredLog")]
+pub struct EventLog{
+    events: Vec<Event>,
+    // Last sequence number handed out
+    #[serde(skip)]
+    last_seq: u64,
+}
+
+#[derive(Deserialize)]
+struct StoredLog{
+    events: Vec<Event>,
+}
+
+// Events from before numbering get numbers in log order, after any that
+// already have one
+impl From<StoredLog> for EventLog{
+    fn from(stored: StoredLog) -> Self{
+        let mut log = EventLog{ events: stored.events, last_seq: 0 };
+        log.last_seq = log.events.iter().map(|e| e.seq).max().unwrap_or(0);
+        for event in log.events.iter_mut().filter(|e| e.seq == 0){
+            log.last_seq += 1;
+            event.seq = log.last_seq;
+        }
+        log
+    }
+}
+
+impl EventLog{
+    pub fn new() -> Self{
+        EventLog::default()
+    }
+
+    // Keeps the log ordered by time even if events arrive out of order,
+    // and numbers each event in the order it arrived
+    pub fn record(&mut self, mut event: Event){
+        self.last_seq += 1;
+        event.seq = self.last_seq;
+        let pos = self.events.partition_point(|e| e.at <= event.at);
+        self.events.insert(pos, event);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Event>{
+        self.events.iter()
+    }
+
+    pub fn last_seq(&self) -> u64{
+        self.last_seq
+    }
+
+    // Events recorded after sequence number `seq`, in the order recorded
+    pub fn recorded_after(&self, seq: u64) -> Vec<&Event>{
+        let mut events: Vec<&Event> = self.events.iter().filter(|e| e.seq > seq).collect();
+        events.sort_by_key(|e| e.seq);
+        events
+    }
+
+    pub fn for_node(&self, id: Uuid) -> impl Iterator<Item = &Event>{
+        self.events.iter().filter(move |e| e.node == id)
+    }
+
+    pub fn since(&self, at: DT) -> impl Iterator<Item = &Event>{
+        let start = self.events.partition_point(|e| e.at < at);
+        self.events[start..].iter()
+    }
+
+    // When the node last moved into `status`, if the log saw it happen
+    pub fn last_transition_to(&self, id: Uuid, status: Status) -> Option<DT>{
+        self.for_node(id)
+            .filter(|e| matches!(e.kind, EventKind::StatusChanged{ to, .. } if to == status))
+            .map(|e| e.at)
+            .last()
+    }
+
+    // When the node first left `status`, e.g. when work on it really started
+    pub fn first_transition_from(&self, id: Uuid, status: Status) -> Option<DT>{
+        self.for_node(id)
+            .find(|e| matches!(e.kind, EventKind::StatusChanged{ from, .. } if from == status))
+            .map(|e| e.at)
+    }
+
+    // When the node last left `status`
+    pub fn last_transition_from(&self, id: Uuid, status: Status) -> Option<DT>{
+        self.for_node(id)
+            .filter(|e| matches!(e.kind, EventKind::StatusChanged{ from, .. } if from == status))
+            .map(|e| e.at)
+            .last()
+    }
+
+    // Edits to one field of the node, oldest first
+    pub fn field_changes<'a>(&'a self, id: Uuid, field: &'a str) -> impl Iterator<Item = &'a Event>{
+        self.for_node(id).filter(move |e| matches!(&e.kind, EventKind::FieldChanged{ field: f, .. } if f == field))
+    }
+
+    // When the node's last blocker was most recently finished
+    pub fn last_unblocked(&self, id: Uuid) -> Option<DT>{
+        self.for_node(id)
+            .filter(|e| matches!(e.kind, EventKind::Unblocked{..}))
+            .map(|e| e.at)
+            .last()
+    }
+
+    pub fn len(&self) -> usize{
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.events.is_empty()
+    }
+}
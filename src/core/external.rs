@@ -0,0 +1,24 @@
+// Links from a node to the same item in external trackers
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ExternalRef{
+    // Tracker name, e.g. "jira", "github", "linear"
+    pub system: String,
+    // The tracker's own id, e.g. "PROJ-123" or "owner/repo#42"
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ExternalRef{
+    pub fn new(system: &str, key: &str) -> Self{
+        ExternalRef{ system: system.to_string(), key: key.to_string(), url: None }
+    }
+
+    pub fn with_url(mut self, url: String) -> Self{
+        self.url = Some(url);
+        self
+    }
+}
@@ -0,0 +1,112 @@
+// FiscalCalendar - fiscal-year/quarter boundaries and custom named periods
+//
+// The default is the calendar year. Organizations whose year starts in another
+// month set `first_month`; those with irregular periods (4-4-5, planning
+// increments, ...) define them explicitly.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedPeriod{
+    pub name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiscalCalendar{
+    // Month (1-12) in which the fiscal year starts
+    pub first_month: u32,
+    // FY2026 starting in Oct 2025 is labelled by its end year (the common convention)
+    pub label_by_end_year: bool,
+    periods: Vec<NamedPeriod>,
+}
+
+impl Default for FiscalCalendar{
+    fn default() -> Self{
+        FiscalCalendar{ first_month: 1, label_by_end_year: true, periods: Vec::new() }
+    }
+}
+
+impl NamedPeriod{
+    pub fn new(name: String, start: NaiveDate, end: NaiveDate) -> Result<Self,&'static str>{
+        if end < start{
+            return Err("A period must not end before it starts");
+        }
+        Ok(NamedPeriod{ name, start, end })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool{
+        self.start <= date && date <= self.end
+    }
+}
+
+impl FiscalCalendar{
+    pub fn new(first_month: u32) -> Result<Self,&'static str>{
+        if !(1..=12).contains(&first_month){
+            return Err("The fiscal year must start in a month between 1 and 12");
+        }
+        Ok(FiscalCalendar{ first_month, ..FiscalCalendar::default() })
+    }
+
+    pub fn is_calendar_year(&self) -> bool{
+        self.first_month == 1
+    }
+
+    pub fn fiscal_year(&self, date: NaiveDate) -> i32{
+        if self.is_calendar_year(){
+            return date.year();
+        }
+
+        let in_next_year = date.month() >= self.first_month;
+        match (self.label_by_end_year, in_next_year){
+            (true, true) => date.year() + 1,
+            (true, false) => date.year(),
+            (false, true) => date.year(),
+            (false, false) => date.year() - 1,
+        }
+    }
+
+    // Fiscal quarter, 1-4
+    pub fn quarter(&self, date: NaiveDate) -> u32{
+        let offset = (date.month() + 12 - self.first_month) % 12;
+        offset / 3 + 1
+    }
+
+    // First to last day of a fiscal quarter, labelled the way roadmaps label it
+    pub fn quarter_period(&self, fiscal_year: i32, quarter: u32) -> Result<NamedPeriod,&'static str>{
+        if !(1..=4).contains(&quarter){
+            return Err("A quarter must be between 1 and 4");
+        }
+        // Year in which the fiscal year's first month falls
+        let first_year = if !self.is_calendar_year() && self.label_by_end_year { fiscal_year - 1 } else { fiscal_year };
+        let month0 = self.first_month - 1 + 3 * (quarter - 1);
+        let start = NaiveDate::from_ymd_opt(first_year + (month0 / 12) as i32, month0 % 12 + 1, 1).ok_or("The quarter is out of range")?;
+        let next = start.checked_add_months(chrono::Months::new(3)).ok_or("The quarter is out of range")?;
+        let name = if self.is_calendar_year(){
+            format!("{} Q{}", fiscal_year, quarter)
+        }else{
+            format!("FY{} Q{}", fiscal_year, quarter)
+        };
+        NamedPeriod::new(name, start, next.pred_opt().ok_or("The quarter is out of range")?)
+    }
+
+    pub fn add_period(&mut self, period: NamedPeriod) -> Result<(),&'static str>{
+        if self.periods.iter().any(|p| p.start <= period.end && period.start <= p.end){
+            return Err("The period overlaps an existing period");
+        }
+        self.periods.push(period);
+        self.periods.sort_by_key(|p| p.start);
+        Ok(())
+    }
+
+    // Custom periods, ordered by start date
+    pub fn get_periods(&self) -> &[NamedPeriod]{
+        &self.periods
+    }
+
+    pub fn period_for(&self, date: NaiveDate) -> Option<&NamedPeriod>{
+        self.periods.iter().find(|p| p.contains(date))
+    }
+}
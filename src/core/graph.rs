@@ -4,25 +4,259 @@
 // For now, it's just a placeholder
 
 use super::Node;
-use petgraph::visit::{EdgeRef, Visitable};
+use crate::trace::{self, Level};
+use super::keys::NodeKeys;
+use super::interner::Interner;
+use super::index::NodeIndexes;
+use super::query::Query;
+use super::rollup::{Rollup, RollupCache};
+use super::scope::Scope;
+use super::release::Release;
+use super::risk::Risk;
+use super::okr::{KeyResult, Objective};
+use super::person::{Person, Unavailability};
+use super::worklog::Worklog;
+use super::comment::Comment;
+use super::effort::Effort;
+use super::actor::Actor;
+use super::actual::{ActualDates, Actuals};
+use super::approval::{Approval, ApprovalGate, GateTarget, PendingApproval};
+use super::blocked::{BlockReason, BlockedPeriod, Blocker};
+use super::checklist::{ChecklistItem, DodPolicy};
+use super::hierarchy::level;
+use super::search::{Field, SearchHit, SearchIndex, Snippet};
+use super::estimate::{self, Consensus, Estimate};
+use super::calendar::Calendar;
+use super::constraint::Constraint;
+use super::priority::Priority;
+use super::fiscal::FiscalCalendar;
+use super::sprint::Sprint;
+use super::team::Team;
+use super::sync_state::SyncState;
+use super::external::ExternalRef;
+use super::remote::{RemoteDependency, RemoteNode, RemoteRef};
+use super::event::{Event, EventKind, EventLog};
+use super::workflow::{Workflow, WorkflowState};
+use super::view::SavedView;
+use super::validation::Warning;
+use super::integrity::IntegrityIssue;
+use super::settings::{ConnectionPolicy, ParallelEdges, ProjectSettings};
+use super::automation::{Action, Automation, Firing, Rule};
+use super::plugin::{GraphPlugin, Metric, Plugins};
+use petgraph::visit::{Bfs, EdgeFiltered, EdgeRef};
 use petgraph::{Graph, Directed};
-use petgraph::graph::NodeIndex;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::algo::is_cyclic_directed;
 use uuid::Uuid;
-use std::collections::HashMap;
+use super::status::Status;
+use super::timeline::{Duration, Timeline, ToTimeDelta};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
 use serde::{Serialize,Deserialize};
+use serde_json::{json, Value};
 
-#[derive(Debug, Clone,Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone,Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DependencyType{
     Blocks,
     ResourcesRequiredFor,
     Contains,
+    // Spec -> the Epic or Story it specifies
+    SpecifiedBy,
 }
 
+impl DependencyType{
+    // Whether the edge orders work; Contains and SpecifiedBy only relate it
+    pub fn is_scheduling(self) -> bool{
+        matches!(self, DependencyType::Blocks | DependencyType::ResourcesRequiredFor)
+    }
+}
+
+// Where split_task cuts a task in two
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitAt{
+    // The first part keeps this many points, the second gets the rest
+    Points(u32),
+    // The first part ends and the second starts at this instant
+    Date(DateTime<Utc>),
+}
+
+// Node id suffix for the second half of a split task
+const SPLIT_ID: &[u8; 6] = b"pmsplt";
+// Id suffix for sprints added by plan_next_sprint
+const SPRINT_ID: &[u8; 6] = b"pmsprt";
+
+// Errors for edges that are never allowed, for callers to match on
+pub const SELF_DEPENDENCY: &str = "A node cannot depend on itself";
+pub const DUPLICATE_DEPENDENCY: &str = "The nodes are already connected by this kind of dependency";
+pub const PARALLEL_DEPENDENCY: &str = "The nodes are already connected by another kind of dependency";
+
+// What reassign_owner moved, by id
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reassignment{
+    pub owned: Vec<Uuid>,
+    pub participating: Vec<Uuid>,
+    pub risks: Vec<Uuid>,
+    pub objectives: Vec<Uuid>,
+}
+
+impl Reassignment{
+    pub fn len(&self) -> usize{
+        self.owned.len() + self.participating.len() + self.risks.len() + self.objectives.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+// Edge weight: the dependency kind plus an optional lag (negative for lead)
+// between the predecessor finishing and the successor starting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency{
+    pub kind: DependencyType,
+    #[serde(default)]
+    pub lag: Option<Duration>,
+}
+
+impl Dependency{
+    pub fn new(kind: DependencyType) -> Self{
+        Dependency{ kind, lag: None }
+    }
+
+    pub fn lag_delta(&self) -> TimeDelta{
+        self.lag.as_ref().map(|l| l.to_time_delta()).unwrap_or_else(TimeDelta::zero)
+    }
+}
+
+// The derived serde code is reached through the impls below, which also
+// rebuild what is not saved once a graph is read
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct ProjectGraph{
-    graph: Graph<Node,DependencyType,Directed>,
+    // Saved with edges by Uuid, see stored.rs
+    #[serde(with = "super::stored")]
+    graph: Graph<Node,Dependency,Directed>,
+    // Not saved; rebuilt from the nodes on load, see rebuild_index
+    #[serde(skip)]
     uid_to_index : HashMap<Uuid,NodeIndex>,
+    #[serde(default)]
+    keys: NodeKeys,
+    #[serde(default)]
+    releases: HashMap<Uuid,Release>,
+    #[serde(default)]
+    risks: HashMap<Uuid,Risk>,
+    #[serde(default)]
+    objectives: HashMap<Uuid,Objective>,
+    #[serde(default)]
+    people: HashMap<String,Person>,
+    #[serde(default)]
+    worklogs: HashMap<Uuid,Vec<Worklog>>,
+    #[serde(default)]
+    estimates: HashMap<Uuid,Vec<Estimate>>,
+    #[serde(default)]
+    descriptions: HashMap<Uuid,String>,
+    #[serde(default)]
+    comments: HashMap<Uuid,Vec<Comment>>,
+    // Hours of work by node, separate from how long the timeline says it takes
+    #[serde(default)]
+    efforts: HashMap<Uuid,Effort>,
+    // When work really started and finished, see actual.rs
+    #[serde(default)]
+    actuals: HashMap<Uuid,Actuals>,
+    // Percent done as last reported for work under way; rollups still count
+    // a node as done or not
+    #[serde(default)]
+    progress: HashMap<Uuid,u8>,
+    // Definition-of-done checklists, see checklist.rs
+    #[serde(default)]
+    checklists: HashMap<Uuid,Vec<ChecklistItem>>,
+    // Sign-offs on gated transitions, see approval.rs
+    #[serde(default)]
+    approvals: Vec<Approval>,
+    // Every stretch each node spent Blocked, see blocked.rs
+    #[serde(default)]
+    blocked_periods: HashMap<Uuid,Vec<BlockedPeriod>>,
+    // Decision node -> the work it affects
+    #[serde(default)]
+    decision_links: HashMap<Uuid,BTreeSet<Uuid>>,
+    // Currency of each estimated cost not in the base currency, see money.rs
+    #[serde(default)]
+    cost_currencies: HashMap<Uuid,String>,
+    #[serde(default)]
+    consensus: Consensus,
+    #[serde(default)]
+    calendar: Calendar,
+    #[serde(default)]
+    fiscal_calendar: FiscalCalendar,
+    #[serde(default)]
+    sprints: HashMap<Uuid,Sprint>,
+    #[serde(default)]
+    teams: HashMap<String,Team>,
+    #[serde(default)]
+    constraints: HashMap<Uuid,Constraint>,
+    #[serde(default)]
+    priorities: HashMap<Uuid,Priority>,
+    // Ranked backlog, top first; unranked work comes after all of it
+    #[serde(default)]
+    backlog: Vec<Uuid>,
+    #[serde(default)]
+    sync_state: SyncState,
+    // Local nodes waiting on nodes in other project files
+    #[serde(default)]
+    remote_dependencies: Vec<RemoteDependency>,
+    #[serde(default)]
+    events: EventLog,
+    // Status that Blocked nodes move to once their last blocker is done;
+    // None leaves them for someone to pick up by hand
+    #[serde(default)]
+    auto_unblock: Option<Status>,
+    // Custom status models by Project id, and each node's state within its
+    // project's model when it is not just the default for its status
+    #[serde(default)]
+    workflows: HashMap<Uuid,Workflow>,
+    #[serde(default)]
+    states: HashMap<Uuid,String>,
+    #[serde(default)]
+    views: BTreeMap<String,SavedView>,
+    #[serde(default)]
+    settings: ProjectSettings,
+    #[serde(default)]
+    automation: Automation,
+    // Derived from the nodes and rebuilt after loading, see rebuild_caches
+    #[serde(skip)]
+    interner: Interner,
+    #[serde(skip)]
+    indexes: NodeIndexes,
+    #[serde(skip)]
+    rollups: RollupCache,
+    #[serde(skip)]
+    search: SearchIndex,
+    // Registered at runtime by whoever embeds the crate
+    #[serde(skip)]
+    plugins: Plugins,
+}
+
+impl Default for ProjectGraph{
+    fn default() -> Self{
+        Self::new()
+    }
+}
+
+impl Serialize for ProjectGraph{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok,S::Error>{
+        ProjectGraph::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProjectGraph{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self,D::Error>{
+        let mut graph = ProjectGraph::deserialize(deserializer)?;
+        graph.rebuild_index().map_err(serde::de::Error::custom)?;
+        graph.rebuild_caches();
+        Ok(graph)
+    }
 }
 
 impl ProjectGraph{
@@ -30,22 +264,111 @@ impl ProjectGraph{
     pub fn new() -> Self{
         ProjectGraph{
             graph: Graph::new(),
-            uid_to_index: HashMap::new()
+            uid_to_index: HashMap::new(),
+            keys: NodeKeys::new(),
+            releases: HashMap::new(),
+            risks: HashMap::new(),
+            objectives: HashMap::new(),
+            people: HashMap::new(),
+            worklogs: HashMap::new(),
+            estimates: HashMap::new(),
+            descriptions: HashMap::new(),
+            comments: HashMap::new(),
+            efforts: HashMap::new(),
+            actuals: HashMap::new(),
+            progress: HashMap::new(),
+            checklists: HashMap::new(),
+            approvals: Vec::new(),
+            blocked_periods: HashMap::new(),
+            decision_links: HashMap::new(),
+            cost_currencies: HashMap::new(),
+            consensus: Consensus::default(),
+            calendar: Calendar::new(),
+            fiscal_calendar: FiscalCalendar::default(),
+            sprints: HashMap::new(),
+            teams: HashMap::new(),
+            constraints: HashMap::new(),
+            priorities: HashMap::new(),
+            backlog: Vec::new(),
+            sync_state: SyncState::default(),
+            remote_dependencies: Vec::new(),
+            events: EventLog::new(),
+            auto_unblock: None,
+            workflows: HashMap::new(),
+            states: HashMap::new(),
+            views: BTreeMap::new(),
+            settings: ProjectSettings::default(),
+            automation: Automation::default(),
+            interner: Interner::new(),
+            indexes: NodeIndexes::new(),
+            rollups: RollupCache::new(),
+            search: SearchIndex::new(),
+            plugins: Plugins::default(),
+        }
+    }
+
+    // Everything except the nodes and edges; cheap to serialize on its own
+    pub fn metadata(&self) -> ProjectGraph{
+        ProjectGraph{
+            graph: Graph::new(),
+            uid_to_index: HashMap::new(),
+            keys: self.keys.clone(),
+            releases: self.releases.clone(),
+            risks: self.risks.clone(),
+            objectives: self.objectives.clone(),
+            people: self.people.clone(),
+            worklogs: self.worklogs.clone(),
+            estimates: self.estimates.clone(),
+            descriptions: self.descriptions.clone(),
+            comments: self.comments.clone(),
+            efforts: self.efforts.clone(),
+            actuals: self.actuals.clone(),
+            progress: self.progress.clone(),
+            checklists: self.checklists.clone(),
+            approvals: self.approvals.clone(),
+            blocked_periods: self.blocked_periods.clone(),
+            decision_links: self.decision_links.clone(),
+            cost_currencies: self.cost_currencies.clone(),
+            consensus: self.consensus,
+            calendar: self.calendar.clone(),
+            fiscal_calendar: self.fiscal_calendar.clone(),
+            sprints: self.sprints.clone(),
+            teams: self.teams.clone(),
+            constraints: self.constraints.clone(),
+            priorities: self.priorities.clone(),
+            backlog: self.backlog.clone(),
+            sync_state: self.sync_state.clone(),
+            remote_dependencies: self.remote_dependencies.clone(),
+            events: self.events.clone(),
+            auto_unblock: self.auto_unblock,
+            workflows: self.workflows.clone(),
+            states: self.states.clone(),
+            views: self.views.clone(),
+            settings: self.settings.clone(),
+            automation: self.automation.clone(),
+            interner: Interner::new(),
+            indexes: NodeIndexes::new(),
+            rollups: RollupCache::new(),
+            search: SearchIndex::new(),
+            plugins: self.plugins.clone(),
         }
     }
 
-    fn is_valid_connection(from: &Node, to: &Node, dep_type: &DependencyType)-> bool{
+    fn is_valid_connection(&self, from: &Node, to: &Node, dep_type: &DependencyType)-> bool{
         use Node::*;
         use DependencyType::*;
 
+        let relaxed = self.settings.connections == ConnectionPolicy::Relaxed;
         match (from, to, dep_type) {
             (Spec{..}, Spec{..},Contains) => true,
             (Spec{..}, Project{..}, Contains) => true,
-            (Project{..}, Project{..},Contains) => true,
-            (Project{..}, Epic{..}, Contains) => true,
-            (Project{..}, UserStory{..}, Contains) => true,
-            (Epic{..}, UserStory{..}, Contains) => true,
-            (UserStory{..}, Tasks{..}, Contains) => true,
+            (Spec{..}, Epic{..}, SpecifiedBy) => true,
+            (Spec{..}, UserStory{..}, SpecifiedBy) => true,
+            (Project{..}, Decision{..}, Contains) => true,
+            (Epic{..}, Decision{..}, Contains) => true,
+            // Work contains work at its own level or below; which of those
+            // pairs are allowed is up to the hierarchy rules, see connect_nodes
+            (_, _, Contains) => matches!((level(from.get_key_prefix()), level(to.get_key_prefix())), (Some(p), Some(c)) if p <= c),
 
             // Blocks relationships (same or compatible levels)
             (Project{..},Project{..},Blocks) => true,
@@ -61,73 +384,2732 @@ impl ProjectGraph{
             (Tasks{..}, Tasks{..}, ResourcesRequiredFor) => true,
             (Tasks{..}, UserStory{..}, ResourcesRequiredFor) => true,
 
+            // The relaxed policy lets any two work items depend on each other
+            (Spec{..} | Decision{..}, _, _) | (_, Spec{..} | Decision{..}, _) => false,
+            (_, _, Blocks | ResourcesRequiredFor) => relaxed,
+
             //everything else is invalid
             _ => false
         }
 
     }
 
-    fn try_connect(self :&mut Self, node1: &Node, node2: &Node, dep_type :DependencyType)-> Result<(),&'static str>{
+    // A new Contains edge against the hierarchy rules; edges already in the
+    // graph are left to validation
+    fn check_hierarchy(&self, parent: &Node, child: &Node) -> Result<(),&'static str>{
+        let rules = &self.settings.hierarchy;
+        let (p, c) = (parent.get_key_prefix(), child.get_key_prefix());
+        if level(p).is_none() || level(c).is_none(){
+            return Ok(());
+        }
+        if !rules.allows(p, c){
+            return Err("The hierarchy rules do not let this kind of work contain that one");
+        }
+        if rules.max_depth.is_some_and(|max| self.work_depth(parent.get_id()) + 1 + self.work_height(child.get_id()) > max){
+            return Err("The hierarchy would go deeper than its depth limit");
+        }
+        Ok(())
+    }
+
+    // Levels of work above the node: 0 for a top-level project
+    pub fn work_depth(&self, id: Uuid) -> usize{
+        self.get_ancestors(id).into_iter()
+            .filter(|a| self.get_node(*a).is_some_and(|n| level(n.get_key_prefix()).is_some()))
+            .count()
+    }
+
+    // Levels of work below the node: 0 for a leaf
+    fn work_height(&self, id: Uuid) -> usize{
+        self.get_children(id).into_iter()
+            .filter(|c| self.get_node(*c).is_some_and(|n| level(n.get_key_prefix()).is_some()))
+            .map(|c| 1 + self.work_height(c))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // An edge of the same kind between the same nodes is always a mistake;
+    // one of another kind only when the settings forbid parallel edges
+    fn check_parallel_edge(&self, from: NodeIndex, to: NodeIndex, kind: DependencyType) -> Result<(),&'static str>{
+        for edge in self.graph.edges_connecting(from, to){
+            if edge.weight().kind == kind{
+                return Err(DUPLICATE_DEPENDENCY);
+            }
+            if self.settings.parallel_edges == ParallelEdges::Deny{
+                return Err(PARALLEL_DEPENDENCY);
+            }
+        }
+        Ok(())
+    }
+
+    fn try_connect(&mut self, node1: &Node, node2: &Node, dep_type :DependencyType, moved_from: Option<Uuid>)-> Result<(),&'static str>{
         let u1 = node1.get_id();
         let u2 = node2.get_id();
         let from_idx = *self.uid_to_index.get(&u1).expect("Bug: node existence was already verified");
         let to_idx = *self.uid_to_index.get(&u2).expect("Bug: node existence was already verified");
 
-        self.graph.add_edge(from_idx,to_idx,dep_type);
+        let edge_idx = self.graph.add_edge(from_idx,to_idx,Dependency::new(dep_type));
 
         if is_cyclic_directed(&self.graph){
-            if let Some(edge_idx) = self.graph.find_edge(from_idx,to_idx){
-                self.graph.remove_edge(edge_idx);
-            }
-
+            self.graph.remove_edge(edge_idx);
             return Err("Connection would create a cycle");
         }
 
+        if dep_type == DependencyType::Contains{
+            self.invalidate_rollups(u1);
+            // Logged so scope churn can tell when work joined a container
+            self.record_change(u2, "parent", json!(moved_from), json!(u1));
+        }
+        Ok(())
+    }
+
+    // Adds a node the user is creating now; add_node alone is for nodes that
+    // already exist elsewhere (loading, merging), which may predate the settings
+    pub fn create_node(&mut self, node: &Node)->Result<(),&'static str>{
+        if node.get_points().is_some_and(|p| !self.settings.accepts_points(p)){
+            return Err("The points are not on the project's point scale");
+        }
+        self.add_node(node)?;
+        // New work starts from its kind's definition of done
+        let checklist = self.settings.definition_of_done.checklist_for(node.get_key_prefix());
+        if !checklist.is_empty() && !node.is_done(){
+            self.checklists.insert(node.get_id(), checklist);
+        }
         Ok(())
     }
 
-    pub fn add_node(self: &mut Self, node: &Node)->Result<(),&'static str>{
+    pub fn add_node(&mut self, node: &Node)->Result<(),&'static str>{
         let node_id = node.get_id();
+        let _span = trace::span(module_path!(), "add_node", &[("node", &node_id)]);
         
         // check that the node_id is not already associated with another node_idx
-        if self.uid_to_index.keys().any(|k| *k == node_id){
+        if self.uid_to_index.contains_key(&node_id){
             return Err("The node has already been inserted into the graph");
         }
+        if node.get_external_refs().iter().any(|r| self.indexes.by_external(&r.system, &r.key).is_some()){
+            return Err("An external ref of the node is already linked to another node");
+        }
 
-        let node_idx: NodeIndex = self.graph.add_node(node.clone());
+        let mut shared = node.clone();
+        shared.intern_strings(&mut self.interner);
+        self.indexes.insert(&shared);
+        let node_idx: NodeIndex = self.graph.add_node(shared);
         self.uid_to_index.insert(node_id,node_idx);
+        self.keys.assign(node.get_key_prefix(), node_id);
+        self.reindex_text(node_id);
+        for plugin in self.plugins.clone().iter(){
+            plugin.on_node_created(self, node_id);
+        }
         Ok(())
     }
 
-    pub fn connect_nodes(self: &mut Self, node1: &Node, node2: &Node, dep_type: DependencyType)->Result<(),&'static str>{
+    pub fn connect_nodes(&mut self, node1: &Node, node2: &Node, dep_type: DependencyType)->Result<(),&'static str>{
+        self.connect_moving(node1, node2, dep_type, None)
+    }
+
+    // Same as connect_nodes; a Contains edge that moves node2 out of
+    // `moved_from` is logged as one move rather than a leave and a join
+    fn connect_moving(&mut self, node1: &Node, node2: &Node, dep_type: DependencyType, moved_from: Option<Uuid>)->Result<(),&'static str>{
         let u1: Uuid = node1.get_id();
         let u2: Uuid = node2.get_id();
+        let _span = trace::span(module_path!(), "connect", &[("from", &u1), ("to", &u2), ("kind", &format_args!("{:?}", dep_type))]);
 
-        if !Self::is_valid_connection(node1,node2,&dep_type){
+        if u1 == u2{
+            return Err(SELF_DEPENDENCY);
+        }
+        if !self.is_valid_connection(node1,node2,&dep_type){
             return Err("Invalid connection between the two nodes");
         }
 
         if !self.uid_to_index.contains_key(&u1) || !self.uid_to_index.contains_key(&u2){
             return Err("One or more of the nodes does not exist in the graph");
         }
+        self.check_parallel_edge(self.uid_to_index[&u1], self.uid_to_index[&u2], dep_type)?;
+        if dep_type == DependencyType::Contains{
+            self.check_hierarchy(node1, node2)?;
+        }
+        for plugin in self.plugins.iter(){
+            plugin.validate_connection(self, u1, u2, dep_type)?;
+        }
+
+        self.try_connect(node1,node2,dep_type,moved_from)
+    }
+
+    // Adds a validated edge without the cycle check, for bulk loading;
+    // callers must check is_acyclic() once all edges are in. A file may hold
+    // an edge twice, or parallel edges the settings now forbid: the repeat is
+    // skipped with a warning and parallel edges are kept, so such a file
+    // still opens.
+    pub fn connect_unchecked(&mut self, from: Uuid, to: Uuid, dependency: Dependency)->Result<(),&'static str>{
+        let from_idx = *self.uid_to_index.get(&from).ok_or("One or more of the nodes does not exist in the graph")?;
+        let to_idx = *self.uid_to_index.get(&to).ok_or("One or more of the nodes does not exist in the graph")?;
+
+        if from == to{
+            return Err(SELF_DEPENDENCY);
+        }
+        if !self.is_valid_connection(&self.graph[from_idx],&self.graph[to_idx],&dependency.kind){
+            return Err("Invalid connection between the two nodes");
+        }
+        if self.graph.edges_connecting(from_idx, to_idx).any(|e| e.weight().kind == dependency.kind){
+            trace::event(Level::Warn, module_path!(), "duplicate edge skipped", &[("from", &from), ("to", &to)]);
+            return Ok(());
+        }
+
+        if dependency.kind == DependencyType::Contains{
+            self.invalidate_rollups(from);
+        }
+        self.graph.add_edge(from_idx,to_idx,dependency);
+        Ok(())
+    }
+
+    pub fn is_acyclic(&self) -> bool{
+        !is_cyclic_directed(&self.graph)
+    }
+
+    // Same as connect_nodes, for callers that only hold ids
+    pub fn connect(&mut self, from: Uuid, to: Uuid, dep_type: DependencyType)->Result<(),&'static str>{
+        let node1 = self.get_node(from).ok_or("One or more of the nodes does not exist in the graph")?.clone();
+        let node2 = self.get_node(to).ok_or("One or more of the nodes does not exist in the graph")?.clone();
+        self.connect_nodes(&node1,&node2,dep_type)
+    }
+
+    // Same as connect, but refuses edges that would need coordination
+    // between projects unless `force` is set; forced edges come back with
+    // the warning so callers can pass it on
+    pub fn connect_checked(&mut self, from: Uuid, to: Uuid, dep_type: DependencyType, force: bool)->Result<Vec<Warning>,&'static str>{
+        let warnings: Vec<Warning> = self.connection_warning(from, to, dep_type).into_iter().collect();
+        if !warnings.is_empty() && !force{
+            return Err("The nodes belong to different projects; force the dependency once both sides have agreed on it");
+        }
+        self.connect(from, to, dep_type)?;
+        Ok(warnings)
+    }
+
+    // The warning an edge from `from` to `to` would raise, if any
+    pub fn connection_warning(&self, from: Uuid, to: Uuid, kind: DependencyType) -> Option<Warning>{
+        if !kind.is_scheduling(){
+            return None;
+        }
+        let from_project = self.root_project(from)?;
+        let to_project = self.root_project(to)?;
+        (from_project != to_project).then_some(Warning::CrossProject{ from, to, kind, from_project, to_project })
+    }
+
+    // The outermost Project containing the node (the node itself if it is one)
+    pub fn root_project(&self, id: Uuid) -> Option<Uuid>{
+        std::iter::once(id)
+            .chain(self.get_ancestors(id))
+            .filter(|a| matches!(self.get_node(*a), Some(Node::Project{..})))
+            .find(|a| self.get_ancestors(*a).iter().all(|p| !matches!(self.get_node(*p), Some(Node::Project{..}))))
+    }
 
-        self.try_connect(node1,node2,dep_type)
+    pub fn edges(&self) -> impl Iterator<Item = (Uuid,Uuid,&Dependency)>{
+        self.graph.edge_references().map(|e| {
+            (self.graph[e.source()].get_id(), self.graph[e.target()].get_id(), e.weight())
+        })
     }
 
-    pub fn get_node(self : &Self, id : Uuid)->Option<&Node>{
+    pub fn get_node(&self, id : Uuid)->Option<&Node>{
         self.uid_to_index.get(&id).and_then(|idx| self.graph.node_weight(*idx))
     }
 
-    pub fn get_dependencies(self : &Self, uuid: Uuid) -> Option<Vec<(Uuid,DependencyType)>>{
+    pub fn get_dependencies(&self, uuid: Uuid) -> Option<Vec<(Uuid,DependencyType)>>{
             self.uid_to_index.get(&uuid).map(|idx|{
                 self.graph.edges(*idx)
                     .filter_map(|e|{
                         self.graph.node_weight(e.target())
-                            .map(|target| (target.get_id(),*e.weight()))
+                            .map(|target| (target.get_id(),e.weight().kind))
                     })
                     .collect()
             })
     }
 
+    pub fn get_key(&self, id: Uuid) -> Option<&str>{
+        self.keys.get_key(id)
+    }
+
+    pub fn get_node_by_key(&self, key: &str) -> Option<&Node>{
+        self.keys.get_uid(key).and_then(|id| self.get_node(id))
+    }
+
+    // Accepts either a Uuid or a short key (e.g. "EPIC-3")
+    pub fn resolve_id(&self, id_or_key: &str) -> Option<Uuid>{
+        match Uuid::parse_str(id_or_key.trim()){
+            Ok(uid) if self.uid_to_index.contains_key(&uid) => Some(uid),
+            Ok(_) => None,
+            Err(_) => self.keys.get_uid(id_or_key.trim()),
+        }
+    }
+
+    // Forgets keys of nodes that are no longer in the graph
+    pub fn prune_keys(&mut self){
+        let uid_to_index = &self.uid_to_index;
+        self.keys.retain(|uid| uid_to_index.contains_key(&uid));
+    }
+
+    // Reassigns keys per prefix in insertion order, closing any gaps
+    pub fn renumber_keys(&mut self){
+        self.keys.clear();
+        for idx in self.graph.node_indices(){
+            let node = &self.graph[idx];
+            self.keys.assign(node.get_key_prefix(), node.get_id());
+        }
+    }
+
+    // Sets the lag (or lead, when negative) on an existing Blocks or
+    // ResourcesRequiredFor edge
+    pub fn set_lag(&mut self, from: Uuid, to: Uuid, lag: Duration) -> Result<(),&'static str>{
+        let from_idx = *self.uid_to_index.get(&from).ok_or("One or more of the nodes does not exist in the graph")?;
+        let to_idx = *self.uid_to_index.get(&to).ok_or("One or more of the nodes does not exist in the graph")?;
+
+        let edge_idx = self.graph.edges_connecting(from_idx, to_idx)
+            .find(|e| e.weight().kind.is_scheduling())
+            .map(|e| e.id())
+            .ok_or("There is no scheduling dependency between the two nodes")?;
+        self.graph[edge_idx].lag = Some(lag);
+        Ok(())
+    }
+
+    pub fn set_constraint(&mut self, id: Uuid, constraint: Constraint) -> Result<(),&'static str>{
+        match self.get_node(id){
+            Some(n) if n.get_timeline().is_some() || !self.get_children(id).is_empty() => {}
+            Some(_) => return Err("Only scheduled nodes can carry a constraint"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        self.constraints.insert(id, constraint);
+        Ok(())
+    }
+
+    pub fn clear_constraint(&mut self, id: Uuid) -> Option<Constraint>{
+        self.constraints.remove(&id)
+    }
+
+    pub fn get_constraint(&self, id: Uuid) -> Option<Constraint>{
+        self.constraints.get(&id).copied()
+    }
+
+    pub fn set_priority(&mut self, id: Uuid, priority: Priority) -> Result<(),&'static str>{
+        match self.get_node(id){
+            Some(n) if n.get_status().is_some() => {}
+            Some(_) => return Err("Only nodes that track a status can be prioritized"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        let before = self.priorities.insert(id, priority);
+        self.record_change(id, "priority", json!(before), json!(priority));
+        Ok(())
+    }
+
+    pub fn clear_priority(&mut self, id: Uuid) -> Option<Priority>{
+        let before = self.priorities.remove(&id);
+        self.record_change(id, "priority", json!(before), Value::Null);
+        before
+    }
+
+    pub fn get_priority(&self, id: Uuid) -> Option<Priority>{
+        self.priorities.get(&id).copied()
+    }
+
+    // Moves `id` to `rank` in the backlog, 1 being the top; past the end puts
+    // it last
+    pub fn set_rank(&mut self, id: Uuid, rank: usize) -> Result<usize,&'static str>{
+        match self.get_node(id){
+            Some(Node::Epic{..} | Node::UserStory{..} | Node::Tasks{..}) => {}
+            Some(_) => return Err("Only Epics, User Stories and Tasks can be ranked in the backlog"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        if rank == 0{
+            return Err("Ranks start at 1");
+        }
+        let before = self.get_rank(id);
+        self.backlog.retain(|b| *b != id);
+        let position = (rank - 1).min(self.backlog.len());
+        self.backlog.insert(position, id);
+        self.record_change(id, "rank", json!(before), json!(position + 1));
+        Ok(position + 1)
+    }
+
+    pub fn clear_rank(&mut self, id: Uuid) -> Option<usize>{
+        let before = self.get_rank(id)?;
+        self.backlog.remove(before - 1);
+        self.record_change(id, "rank", json!(before), Value::Null);
+        Some(before)
+    }
+
+    // Position in the backlog, 1 being the top
+    pub fn get_rank(&self, id: Uuid) -> Option<usize>{
+        self.backlog.iter().position(|b| *b == id).map(|p| p + 1)
+    }
+
+    pub fn backlog(&self) -> &[Uuid]{
+        &self.backlog
+    }
+
+    // Nodes that must finish before `id` can start, with the lag after each
+    pub fn get_predecessors(&self, id: Uuid) -> Vec<(Uuid,TimeDelta)>{
+        match self.uid_to_index.get(&id){
+            Some(idx) => self.graph.edges_directed(*idx, petgraph::Direction::Incoming)
+                .filter(|e| e.weight().kind.is_scheduling())
+                .map(|e| (self.graph[e.source()].get_id(), e.weight().lag_delta()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &Node>{
+        self.graph.node_weights()
+    }
+
+    pub fn get_children(&self, id: Uuid) -> Vec<Uuid>{
+        match self.uid_to_index.get(&id){
+            Some(idx) => self.graph.edges(*idx)
+                .filter(|e| e.weight().kind == DependencyType::Contains)
+                .map(|e| self.graph[e.target()].get_id())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_parent(&self, id: Uuid) -> Option<Uuid>{
+        let idx = self.uid_to_index.get(&id)?;
+        self.graph.edges_directed(*idx, petgraph::Direction::Incoming)
+            .find(|e| e.weight().kind == DependencyType::Contains)
+            .map(|e| self.graph[e.source()].get_id())
+    }
+
+    // Moves `id` under `parent`, or out of its container with None; on
+    // failure the node stays where it was
+    pub fn set_parent(&mut self, id: Uuid, parent: Option<Uuid>) -> Result<(),&'static str>{
+        let _span = trace::span(module_path!(), "set_parent", &[("node", &id)]);
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.get_parent(id);
+        if before == parent{
+            return Ok(());
+        }
+        let old_edge = self.graph.edges_directed(idx, petgraph::Direction::Incoming)
+            .find(|e| e.weight().kind == DependencyType::Contains)
+            .map(|e| e.id());
+        let removed = old_edge.and_then(|e| {
+            let source = self.graph.edge_endpoints(e).map(|(s, _)| s);
+            self.graph.remove_edge(e).zip(source)
+        });
+        if let Some(parent) = parent{
+            let connected = match self.get_node(parent).cloned(){
+                Some(container) => {
+                    let node = self.graph[idx].clone();
+                    self.connect_moving(&container, &node, DependencyType::Contains, before)
+                }
+                None => Err("One or more of the nodes does not exist in the graph"),
+            };
+            if let Err(e) = connected{
+                if let Some((dependency, source)) = removed{
+                    self.graph.add_edge(source, idx, dependency);
+                }
+                return Err(e);
+            }
+        }
+        if let Some(before) = before{
+            self.invalidate_rollups(before);
+            if parent.is_none(){
+                self.record_change(id, "parent", json!(before), Value::Null);
+            }
+        }
+        Ok(())
+    }
+
+    // Specs linked to an Epic or Story by SpecifiedBy
+    pub fn specs_for(&self, id: Uuid) -> Vec<Uuid>{
+        match self.uid_to_index.get(&id){
+            Some(idx) => self.graph.edges_directed(*idx, petgraph::Direction::Incoming)
+                .filter(|e| e.weight().kind == DependencyType::SpecifiedBy)
+                .map(|e| self.graph[e.source()].get_id())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // The Epics and Stories a Spec is linked to
+    pub fn specified_work(&self, spec: Uuid) -> Vec<Uuid>{
+        match self.uid_to_index.get(&spec){
+            Some(idx) => self.graph.edges(*idx)
+                .filter(|e| e.weight().kind == DependencyType::SpecifiedBy)
+                .map(|e| self.graph[e.target()].get_id())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Every node that transitively Contains `id`
+    pub fn get_ancestors(&self, id: Uuid) -> Vec<Uuid>{
+        let mut ancestors = Vec::new();
+        let mut stack = vec![id];
+        while let Some(cur) = stack.pop(){
+            let Some(idx) = self.uid_to_index.get(&cur) else {
+                continue;
+            };
+            for e in self.graph.edges_directed(*idx, petgraph::Direction::Incoming){
+                let parent = self.graph[e.source()].get_id();
+                if e.weight().kind == DependencyType::Contains && !ancestors.contains(&parent){
+                    ancestors.push(parent);
+                    stack.push(parent);
+                }
+            }
+        }
+        ancestors
+    }
+
+    // The node itself followed by everything it transitively Contains
+    pub fn get_subtree(&self, id: Uuid) -> Vec<Uuid>{
+        let Some(start) = self.uid_to_index.get(&id) else {
+            return Vec::new();
+        };
+
+        let contains = EdgeFiltered::from_fn(&self.graph, |e| e.weight().kind == DependencyType::Contains);
+        let mut bfs = Bfs::new(&contains, *start);
+        let mut ids = Vec::new();
+        while let Some(idx) = bfs.next(&contains){
+            ids.push(self.graph[idx].get_id());
+        }
+        ids
+    }
+
+    pub fn nodes_in_scope(&self, scope: &Scope) -> Vec<&Node>{
+        match scope{
+            Scope::All => self.nodes().collect(),
+            Scope::Subtree(root) => self.get_subtree(*root)
+                .into_iter()
+                .filter_map(|id| self.get_node(id))
+                .collect(),
+        }
+    }
+
+    pub fn set_status(&mut self, id: Uuid, status: super::Status) -> Result<(),&'static str>{
+        self.set_status_at(id, status, Utc::now())
+    }
+
+    // Same as set_status, logging the change as having happened at `at`
+    pub fn set_status_at(&mut self, id: Uuid, status: super::Status, at: DateTime<Utc>) -> Result<(),&'static str>{
+        self.change_status(id, status, at, None)
+    }
+
+    // Moves a node to Blocked for `reason`, or gives the reason for a node
+    // that is already Blocked
+    pub fn block_at(&mut self, id: Uuid, reason: BlockReason, at: DateTime<Utc>) -> Result<(),&'static str>{
+        if !self.settings.blocked_reasons.contains(&reason.category){
+            return Err("The reason is not one of the blocked reason categories");
+        }
+        match &reason.blocker{
+            Some(Blocker::Node(blocker)) if *blocker == id => return Err("A node cannot block itself"),
+            Some(Blocker::Node(blocker)) if !self.uid_to_index.contains_key(blocker) => return Err("The blocking node does not exist in the graph"),
+            Some(Blocker::Person(name)) if name.trim().is_empty() => return Err("The blocking person needs a name"),
+            _ => {}
+        }
+        let status = self.get_node(id).ok_or("The node does not exist in the graph")?.get_status().ok_or("The node does not carry a status")?;
+        if status != Status::Blocked{
+            return self.change_status(id, Status::Blocked, at, Some(reason));
+        }
+        let periods = self.blocked_periods.entry(id).or_default();
+        let before = match periods.last_mut().filter(|p| p.end.is_none()){
+            Some(open) => open.reason.replace(reason.clone()).map(|r| r.category),
+            // Blocked since before periods were kept
+            None => {
+                periods.push(BlockedPeriod{ reason: Some(reason.clone()), start: at, end: None });
+                None
+            }
+        };
+        self.record_change(id, "blocked_reason", json!(before), json!(reason.category));
+        Ok(())
+    }
+
+    // The stretches the node spent Blocked, oldest first
+    pub fn blocked_periods(&self, id: Uuid) -> &[BlockedPeriod]{
+        self.blocked_periods.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn change_status(&mut self, id: Uuid, status: Status, at: DateTime<Utc>, reason: Option<BlockReason>) -> Result<(),&'static str>{
+        let _span = trace::span(module_path!(), "set_status", &[("node", &id), ("status", &format_args!("{:?}", status))]);
+        let previous = self.get_node(id).ok_or("The node does not exist in the graph")?.get_status();
+        if status.is_done() && previous.is_some_and(|p| !p.is_done())
+            && self.settings.definition_of_done.policy == DodPolicy::Enforce && self.open_checklist_items(id) > 0{
+            return Err("The definition of done has unticked items");
+        }
+        if status.is_done() && previous.is_some_and(|p| !p.is_done()) && !self.missing_approvals(id).is_empty(){
+            return Err("Moving the node to Done needs approval first");
+        }
+        // Syncs and pm itself have no reason to give; their blocks stay unspecified
+        let from_person = !matches!(Actor::current(), Some(Actor::Integration(_) | Actor::System));
+        if status == Status::Blocked && previous.is_some_and(|p| p != Status::Blocked) && reason.is_none()
+            && self.settings.blocked_reasons.require_reason && from_person{
+            return Err("Blocking a node needs a reason");
+        }
+        let state = self.workflow_target(id, status)?;
+        self.update_indexed(id, |node, _| node.set_status(status))?;
+        match state{
+            Some(name) if self.workflow_for(id).and_then(|w| w.default_state_for(status)).is_none_or(|d| d.name != name) => {
+                self.states.insert(id, name);
+            }
+            Some(_) => { self.states.remove(&id); }
+            None => {}
+        }
+        self.invalidate_rollups(id);
+        if let Some(from) = previous.filter(|from| *from != status){
+            self.events.record(Event::new(at, id, EventKind::StatusChanged{ from, to: status }));
+            if self.settings.actual_dates == ActualDates::FromStatus{
+                self.stamp_actuals(id, from, status, at);
+            }
+            if status == Status::Blocked{
+                self.blocked_periods.entry(id).or_default().push(BlockedPeriod{ reason, start: at, end: None });
+            }else if from == Status::Blocked{
+                if let Some(open) = self.blocked_periods.get_mut(&id).and_then(|p| p.last_mut()).filter(|p| p.end.is_none()){
+                    open.end = Some(at.max(open.start));
+                }
+            }
+            if status.is_done(){
+                self.propagate_unblocking(id, at)?;
+            }
+            for plugin in self.plugins.clone().iter(){
+                plugin.on_status_change(self, id, from, status);
+            }
+        }
+        Ok(())
+    }
+
+    fn stamp_actuals(&mut self, id: Uuid, from: Status, to: Status, at: DateTime<Utc>){
+        let actuals = self.actuals.entry(id).or_default();
+        if from == Status::NotStarted && actuals.start.is_none(){
+            actuals.start = Some(at);
+        }
+        if to.is_done(){
+            actuals.start.get_or_insert(at);
+            actuals.finish = Some(at);
+        }else if from.is_done(){
+            actuals.finish = None;
+        }
+        if actuals.is_empty(){
+            self.actuals.remove(&id);
+        }
+    }
+
+    // Dependents whose last open blocker is `id` get an Unblocked event and,
+    // with auto_unblock set, leave Blocked
+    fn propagate_unblocking(&mut self, id: Uuid, at: DateTime<Utc>) -> Result<(),&'static str>{
+        let idx = self.uid_to_index[&id];
+        let mut dependents: Vec<Uuid> = self.graph.edges(idx)
+            .filter(|e| e.weight().kind.is_scheduling())
+            .map(|e| self.graph[e.target()].get_id())
+            .collect();
+        dependents.sort();
+        dependents.dedup();
+
+        for dependent in dependents{
+            let Some(status) = self.get_node(dependent).and_then(|n| n.get_status()) else {
+                continue;
+            };
+            let cleared = self.get_predecessors(dependent).iter()
+                .all(|(pred, _)| self.get_node(*pred).is_some_and(|n| n.is_done()));
+            if status.is_done() || !cleared{
+                continue;
+            }
+            self.events.record(Event::new(at, dependent, EventKind::Unblocked{ by: id }));
+            // Left Blocked when its workflow has no way to `to` from here
+            if let (Status::Blocked, Some(to)) = (status, self.auto_unblock){
+                if self.workflow_target(dependent, to).is_ok(){
+                    self.set_status_at(dependent, to, at)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Installs a custom status model for everything under the project that
+    // has no nearer model of its own
+    pub fn set_workflow(&mut self, project_id: Uuid, workflow: Workflow) -> Result<(),&'static str>{
+        match self.get_node(project_id){
+            Some(Node::Project{..}) => {}
+            Some(_) => return Err("Workflows can only be set on Projects"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        workflow.validate()?;
+        self.workflows.insert(project_id, workflow);
+        self.prune_states();
+        Ok(())
+    }
+
+    pub fn remove_workflow(&mut self, project_id: Uuid) -> Option<Workflow>{
+        let removed = self.workflows.remove(&project_id);
+        self.prune_states();
+        removed
+    }
+
+    // The model of the nearest enclosing Project that has one, else the
+    // project-wide default from the settings
+    pub fn workflow_for(&self, id: Uuid) -> Option<&Workflow>{
+        let mut current = Some(id);
+        while let Some(cur) = current{
+            if let Some(workflow) = self.workflows.get(&cur){
+                return Some(workflow);
+            }
+            current = self.get_parent(cur);
+        }
+        self.settings.workflow.as_ref()
+    }
+
+    // The node's state in its workflow: the one it was moved to, else the
+    // first state of its status category
+    pub fn get_state(&self, id: Uuid) -> Option<&WorkflowState>{
+        let workflow = self.workflow_for(id)?;
+        match self.states.get(&id){
+            Some(name) => workflow.get_state(name),
+            None => workflow.default_state_for(self.get_node(id)?.get_status()?),
+        }
+    }
+
+    pub fn set_state(&mut self, id: Uuid, state: &str) -> Result<(),&'static str>{
+        self.set_state_at(id, state, Utc::now())
+    }
+
+    // The state a node enters when its status moves to `status`: the first
+    // one of that category its workflow allows from where it is. None when no
+    // workflow covers it or the category stays the same.
+    fn workflow_target(&self, id: Uuid, status: Status) -> Result<Option<String>,&'static str>{
+        let (Some(workflow), Some(current)) = (self.workflow_for(id), self.get_state(id)) else {
+            return Ok(None);
+        };
+        if current.category == status{
+            return Ok(None);
+        }
+        workflow.next_states(&current.name).into_iter()
+            .find(|s| s.category == status)
+            .map(|s| Some(s.name.clone()))
+            .ok_or("The workflow does not allow this transition")
+    }
 
-}
\ No newline at end of file
+    // Moves a node along its workflow; the status follows the new state's category
+    pub fn set_state_at(&mut self, id: Uuid, state: &str, at: DateTime<Utc>) -> Result<(),&'static str>{
+        let workflow = self.workflow_for(id).ok_or("The node is not covered by a custom workflow")?;
+        let target = workflow.get_state(state).ok_or("The workflow has no such state")?.clone();
+        let current = self.get_state(id).ok_or("The node does not carry a status")?.name.clone();
+        if current == target.name{
+            return Ok(());
+        }
+        if !workflow.can_transition(&current, &target.name){
+            return Err("The workflow does not allow this transition");
+        }
+
+        self.set_status_at(id, target.category, at)?;
+        self.states.insert(id, target.name.clone());
+        self.events.record(Event::new(at, id, EventKind::StateChanged{ from: current, to: target.name }));
+        Ok(())
+    }
+
+    // Drops states that the node's current workflow does not know
+    fn prune_states(&mut self){
+        let stale: Vec<Uuid> = self.states.iter()
+            .filter(|(id, name)| self.workflow_for(**id).is_none_or(|w| w.get_state(name).is_none()))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in stale{
+            self.states.remove(&id);
+        }
+    }
+
+    // Consistency of the custom workflows, for freshly loaded graphs
+    pub fn check_workflows(&self) -> Result<(),&'static str>{
+        self.settings.validate()?;
+        for (project, workflow) in &self.workflows{
+            if !matches!(self.get_node(*project), Some(Node::Project{..})){
+                return Err("A workflow is attached to something that is not a Project");
+            }
+            workflow.validate()?;
+        }
+        for (id, name) in &self.states{
+            let node = self.get_node(*id).ok_or("A workflow state refers to a missing node")?;
+            let state = self.workflow_for(*id).and_then(|w| w.get_state(name))
+                .ok_or("A node is in a state its workflow does not define")?;
+            if node.get_status() != Some(state.category){
+                return Err("A node's workflow state does not match its status");
+            }
+        }
+        Ok(())
+    }
+
+    // Stores a named filter, replacing any view with the same name
+    pub fn save_view(&mut self, view: SavedView) -> Result<Option<SavedView>,&'static str>{
+        if view.name.trim().is_empty(){
+            return Err("A saved view needs a name");
+        }
+        if view.filter.dangling(self){
+            return Err("The view refers to a node, sprint or release that does not exist");
+        }
+        Ok(self.views.insert(view.name.clone(), view))
+    }
+
+    pub fn get_view(&self, name: &str) -> Option<&SavedView>{
+        self.views.get(name)
+    }
+
+    // Sorted by name
+    pub fn views(&self) -> impl Iterator<Item = &SavedView>{
+        self.views.values()
+    }
+
+    pub fn remove_view(&mut self, name: &str) -> Option<SavedView>{
+        self.views.remove(name)
+    }
+
+    pub fn run_view(&self, name: &str) -> Result<Vec<&Node>,&'static str>{
+        let view = self.views.get(name).ok_or("There is no saved view with this name")?;
+        Ok(view.filter.query(self).run())
+    }
+
+    // Adds a rule, replacing any rule with the same name; it fires on
+    // changes from now on
+    pub fn add_rule(&mut self, mut rule: Rule) -> Result<Option<Rule>,&'static str>{
+        rule.validate()?;
+        if rule.filter.dangling(self){
+            return Err("The rule refers to a node, sprint or release that does not exist");
+        }
+        rule.after = Some(self.events.last_seq());
+        rule.since = None;
+        let replaced = self.remove_rule(&rule.name);
+        self.automation.rules.push(rule);
+        Ok(replaced)
+    }
+
+    pub fn remove_rule(&mut self, name: &str) -> Option<Rule>{
+        let position = self.automation.rules.iter().position(|r| r.name == name)?;
+        Some(self.automation.rules.remove(position))
+    }
+
+    pub fn get_rule(&self, name: &str) -> Option<&Rule>{
+        self.automation.rules.iter().find(|r| r.name == name)
+    }
+
+    // In the order they were added, which is the order they run in
+    pub fn rules(&self) -> &[Rule]{
+        &self.automation.rules
+    }
+
+    // Runs the rules over the events recorded since the last run, applies
+    // their tag actions and returns every action taken, notifications
+    // included for the caller to deliver
+    pub fn run_rules(&mut self) -> Result<Vec<Firing>,&'static str>{
+        let until = self.automation.checked_until;
+        let events: Vec<Event> = self.events.recorded_after(self.automation.checked.unwrap_or(0)).into_iter()
+            .filter(|e| self.automation.checked.is_some() || until.is_none_or(|u| e.at > u))
+            .cloned()
+            .collect();
+        let mut firings = Vec::new();
+        for event in &events{
+            let rules: Vec<Rule> = self.automation.rules.iter().filter(|r| r.fires_on(self, event)).cloned().collect();
+            for rule in rules{
+                for action in rule.actions{
+                    match &action{
+                        Action::AddTag(tag) => { self.add_tag(event.node, tag)?; }
+                        Action::RemoveTag(tag) => { self.remove_tag(event.node, tag)?; }
+                        Action::Notify{..} => {}
+                    }
+                    firings.push(Firing{ rule: rule.name.clone(), node: event.node, at: event.at, action });
+                }
+            }
+        }
+        // Past the rules' own changes too, so they do not fire anything
+        self.automation.checked = Some(self.events.last_seq());
+        self.automation.checked_until = None;
+        Ok(firings)
+    }
+
+    pub fn register_plugin(&mut self, plugin: Arc<dyn GraphPlugin>) -> Result<(),&'static str>{
+        self.plugins.add(plugin.clone())?;
+        plugin.on_register(self);
+        Ok(())
+    }
+
+    pub fn unregister_plugin(&mut self, name: &str) -> Option<Arc<dyn GraphPlugin>>{
+        let plugin = self.plugins.remove(name)?;
+        plugin.on_unregister(self);
+        Some(plugin)
+    }
+
+    pub fn plugins(&self) -> &Plugins{
+        &self.plugins
+    }
+
+    // Every plugin's metrics, by plugin name
+    pub fn plugin_metrics(&self) -> Vec<(String, Metric)>{
+        self.plugins.iter()
+            .flat_map(|p| p.metrics(self).into_iter().map(|m| (p.name().to_string(), m)))
+            .collect()
+    }
+
+    // The graph exported by the first plugin that knows `format`
+    pub fn export_with(&self, format: &str) -> Option<String>{
+        self.plugins.iter().find_map(|p| p.export(self, format))
+    }
+
+    pub fn get_settings(&self) -> &ProjectSettings{
+        &self.settings
+    }
+
+    // Applies to changes from now on; existing points and edges are left
+    // as they are
+    pub fn set_settings(&mut self, settings: ProjectSettings) -> Result<(),&'static str>{
+        let _span = trace::span(module_path!(), "set_settings", &[]);
+        settings.validate()?;
+        // Amounts without a currency were in the old base; keep them there
+        let old_base = self.settings.money.currency.clone();
+        let mut cost_currencies = self.cost_currencies.clone();
+        let mut people = self.people.clone();
+        if settings.money.currency != old_base{
+            for node in self.graph.node_weights().filter(|n| n.get_estimated_cost().is_some()){
+                cost_currencies.entry(node.get_id()).or_insert_with(|| old_base.clone());
+            }
+            for person in people.values_mut().filter(|p| p.hourly_rate.is_some()){
+                person.rate_currency.get_or_insert_with(|| old_base.clone());
+            }
+            cost_currencies.retain(|_, c| *c != settings.money.currency);
+            for person in people.values_mut(){
+                if person.rate_currency.as_ref() == Some(&settings.money.currency){
+                    person.rate_currency = None;
+                }
+            }
+        }
+        let in_use = cost_currencies.values().chain(people.values().filter_map(|p| p.rate_currency.as_ref()));
+        if in_use.into_iter().any(|c| !settings.money.converts(c)){
+            return Err("Every currency costs or rates are in needs an exchange rate");
+        }
+        self.cost_currencies = cost_currencies;
+        self.people = people;
+        self.settings = settings;
+        self.prune_states();
+        Ok(())
+    }
+
+    pub fn get_auto_unblock(&self) -> Option<Status>{
+        self.auto_unblock
+    }
+
+    pub fn set_auto_unblock(&mut self, to: Option<Status>) -> Result<(),&'static str>{
+        if matches!(to, Some(Status::Blocked | Status::Done)){
+            return Err("Unblocked nodes can only move to an open, unblocked status");
+        }
+        self.auto_unblock = to;
+        Ok(())
+    }
+
+    pub fn get_events(&self) -> &EventLog{
+        &self.events
+    }
+
+    // Logs a field-level edit as happening now; nothing when the value is unchanged
+    fn record_change(&mut self, id: Uuid, field: &str, from: Value, to: Value){
+        if from != to{
+            trace::event(Level::Debug, module_path!(), "field changed", &[("node", &id), ("field", &field)]);
+            self.events.record(Event::new(Utc::now(), id, EventKind::FieldChanged{ field: field.to_string(), from, to }));
+        }
+    }
+
+    fn sorted_tags(node: &Node) -> Value{
+        let mut tags: Vec<&str> = node.get_tags().iter().map(|t| t.as_ref()).collect();
+        tags.sort();
+        json!(tags)
+    }
+
+    pub fn set_name(&mut self, id: Uuid, name: &str) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_name().to_string();
+        self.graph[idx].set_name(name.to_string());
+        self.reindex_text(id);
+        self.record_change(id, "name", json!(before), json!(name));
+        Ok(())
+    }
+
+    pub fn set_points(&mut self, id: Uuid, points: u32) -> Result<(),&'static str>{
+        if !self.settings.accepts_points(points){
+            return Err("The points are not on the project's point scale");
+        }
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_points();
+        self.graph[idx].set_points(points)?;
+        self.invalidate_rollups(id);
+        self.record_change(id, "points", json!(before), json!(points));
+        Ok(())
+    }
+
+    pub fn clear_points(&mut self, id: Uuid) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_points();
+        self.graph[idx].clear_points()?;
+        self.invalidate_rollups(id);
+        self.record_change(id, "points", json!(before), Value::Null);
+        Ok(())
+    }
+
+    pub fn set_timeline(&mut self, id: Uuid, timeline: super::Timeline) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = json!(self.graph[idx].get_timeline());
+        let after = json!(timeline);
+        self.graph[idx].set_timeline(timeline);
+        self.invalidate_rollups(id);
+        self.record_change(id, "timeline", before, after);
+        Ok(())
+    }
+
+    pub fn set_estimated_cost(&mut self, id: Uuid, cost: f64) -> Result<(),&'static str>{
+        if !(cost.is_finite() && cost >= 0.0){
+            return Err("An estimated cost must be zero or more");
+        }
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_estimated_cost();
+        self.graph[idx].set_estimated_cost(cost)?;
+        self.record_change(id, "estimated_cost", json!(before), json!(cost));
+        Ok(())
+    }
+
+    // The cost in another currency; None puts it in the base currency
+    pub fn set_estimated_cost_in(&mut self, id: Uuid, cost: f64, currency: Option<&str>) -> Result<(),&'static str>{
+        let currency = currency.filter(|c| *c != self.settings.money.currency);
+        if currency.is_some_and(|c| !self.settings.money.converts(c)){
+            return Err("The currency needs an exchange rate in the project's money settings");
+        }
+        self.set_estimated_cost(id, cost)?;
+        let before = self.cost_currencies.get(&id).cloned();
+        match currency{
+            Some(c) => self.cost_currencies.insert(id, c.to_string()),
+            None => self.cost_currencies.remove(&id),
+        };
+        if before.as_deref() != currency{
+            self.record_change(id, "cost_currency", json!(before), json!(currency));
+        }
+        Ok(())
+    }
+
+    pub fn get_cost_currency(&self, id: Uuid) -> &str{
+        self.cost_currencies.get(&id).unwrap_or(&self.settings.money.currency)
+    }
+
+    // The estimated cost converted into the base currency
+    pub fn cost_in_base(&self, id: Uuid) -> Option<f64>{
+        let cost = self.get_node(id)?.get_estimated_cost()?;
+        self.settings.money.to_base(cost, self.get_cost_currency(id))
+    }
+
+    // Work items only; containers take as long as their children
+    pub fn set_effort(&mut self, id: Uuid, effort: Effort) -> Result<(),&'static str>{
+        match self.get_node(id){
+            Some(Node::Epic{..} | Node::UserStory{..} | Node::Tasks{..}) => {}
+            Some(_) => return Err("Only Epics, User Stories and Tasks carry effort"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        Effort::new(effort.hours)?.with_allocation(effort.allocation)?;
+        let before = self.efforts.insert(id, effort);
+        self.record_change(id, "effort_hours", json!(before.map(|e| e.hours)), json!(effort.hours));
+        self.record_change(id, "allocation", json!(before.map(|e| e.allocation)), json!(effort.allocation));
+        Ok(())
+    }
+
+    pub fn get_actuals(&self, id: Uuid) -> Actuals{
+        self.actuals.get(&id).copied().unwrap_or_default()
+    }
+
+    pub fn actual_start(&self, id: Uuid) -> Option<DateTime<Utc>>{
+        self.actuals.get(&id).and_then(|a| a.start)
+    }
+
+    pub fn actual_finish(&self, id: Uuid) -> Option<DateTime<Utc>>{
+        self.actuals.get(&id).and_then(|a| a.finish)
+    }
+
+    // Overrides whatever the status changes recorded
+    pub fn set_actuals(&mut self, id: Uuid, actuals: Actuals) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&id){
+            return Err("The node does not exist in the graph");
+        }
+        actuals.validate()?;
+        let before = self.get_actuals(id);
+        if actuals.is_empty(){
+            self.actuals.remove(&id);
+        }else{
+            self.actuals.insert(id, actuals);
+        }
+        self.record_change(id, "actual_start", json!(before.start), json!(actuals.start));
+        self.record_change(id, "actual_finish", json!(before.finish), json!(actuals.finish));
+        Ok(())
+    }
+
+    pub fn get_progress(&self, id: Uuid) -> Option<u8>{
+        self.progress.get(&id).copied()
+    }
+
+    // Records how far along a node is; work that was not started moves to
+    // In Progress, and 100% marks it Done
+    pub fn set_progress_at(&mut self, id: Uuid, percent: u8, at: DateTime<Utc>) -> Result<(),&'static str>{
+        let status = self.get_node(id).ok_or("The node does not exist in the graph")?.get_status().ok_or("The node does not carry a status")?;
+        if percent > 100{
+            return Err("Progress must be between 0 and 100 percent");
+        }
+        // The status moves first, so progress is only stored once it could
+        if percent == 100{
+            self.set_status_at(id, Status::Done, at)?;
+        }else if percent > 0 && status == Status::NotStarted{
+            self.set_status_at(id, Status::InProgress, at)?;
+        }
+        let before = self.progress.insert(id, percent);
+        self.record_change(id, "progress", json!(before), json!(percent));
+        Ok(())
+    }
+
+    // Gates on the transition ahead of a node (to Done) or a release (its cut)
+    fn gates_for(&self, subject: Uuid) -> Vec<&ApprovalGate>{
+        let target = match (self.releases.contains_key(&subject), self.get_node(subject)){
+            (true, _) => GateTarget::ReleaseCut,
+            (false, Some(node)) => GateTarget::Done(node.get_key_prefix().to_string()),
+            (false, None) => return Vec::new(),
+        };
+        self.settings.approval_gates.iter().filter(|g| g.target == target).collect()
+    }
+
+    pub fn approvals_for(&self, subject: Uuid) -> impl Iterator<Item = &Approval>{
+        self.approvals.iter().filter(move |a| a.subject == subject)
+    }
+
+    // (gate, role) pairs the subject's next gated transition still waits on
+    pub fn missing_approvals(&self, subject: Uuid) -> Vec<(String,String)>{
+        let mut missing = Vec::new();
+        for gate in self.gates_for(subject){
+            for role in &gate.roles{
+                if !self.approvals_for(subject).any(|a| a.gate == gate.name && a.role == *role){
+                    missing.push((gate.name.clone(), role.clone()));
+                }
+            }
+        }
+        missing
+    }
+
+    // Records the current actor's approval of `subject` for `role` of `gate`;
+    // the actor must be a person holding the role
+    pub fn approve_at(&mut self, subject: Uuid, gate: &str, role: &str, at: DateTime<Utc>) -> Result<(),&'static str>{
+        let Some(Actor::Person(by)) = Actor::current() else {
+            return Err("Approvals are made by people");
+        };
+        if !self.people.get(&by).is_some_and(|p| p.roles.contains(role)){
+            return Err("The approver does not hold the role");
+        }
+        let gate = self.gates_for(subject).into_iter().find(|g| g.name == gate).ok_or("No such gate on this node or release")?;
+        if !gate.roles.iter().any(|r| r == role){
+            return Err("The gate is not approved by this role");
+        }
+        let gate = gate.name.clone();
+        if self.approvals_for(subject).any(|a| a.gate == gate && a.role == role){
+            return Err("This role has already approved");
+        }
+        self.events.record(Event::new(at, subject, EventKind::FieldChanged{
+            field: "approval".to_string(),
+            from: Value::Null,
+            to: json!({ "gate": gate, "role": role, "by": by }),
+        }));
+        self.approvals.push(Approval{ subject, gate, role: role.to_string(), by, at });
+        Ok(())
+    }
+
+    // Gated transitions that wait on little more than approval: leaves in
+    // progress, containers whose contained work is done and uncut releases
+    // whose scope is; plus anything already partly approved
+    pub fn pending_approvals(&self) -> Vec<PendingApproval>{
+        let finished = |id: Uuid| self.get_node(id).is_none_or(|n| n.get_status().is_none_or(|s| s.is_done()));
+        let mut subjects: Vec<Uuid> = self.nodes()
+            .filter(|n| n.get_status().is_some_and(|s| !s.is_done()))
+            .map(|n| n.get_id())
+            .filter(|id| {
+                let ready = match self.get_children(*id).is_empty(){
+                    true => self.get_node(*id).and_then(|n| n.get_status()) == Some(Status::InProgress),
+                    false => self.get_subtree(*id).into_iter().filter(|d| d != id).all(finished),
+                };
+                ready || self.approvals_for(*id).next().is_some()
+            })
+            .collect();
+        subjects.extend(self.releases.values()
+            .filter(|r| !r.is_cut())
+            .filter(|r| r.get_scope().iter().all(|id| finished(*id)) || self.approvals_for(r.id).next().is_some())
+            .map(|r| r.id));
+        subjects.sort();
+
+        let mut pending = Vec::new();
+        for subject in subjects{
+            let mut by_gate: Vec<PendingApproval> = Vec::new();
+            for (gate, role) in self.missing_approvals(subject){
+                match by_gate.iter_mut().find(|p| p.gate == gate){
+                    Some(p) => p.missing.push(role),
+                    None => by_gate.push(PendingApproval{ subject, gate, missing: vec![role] }),
+                }
+            }
+            pending.extend(by_gate);
+        }
+        pending
+    }
+
+    pub fn get_checklist(&self, id: Uuid) -> &[ChecklistItem]{
+        self.checklists.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn open_checklist_items(&self, id: Uuid) -> usize{
+        self.get_checklist(id).iter().filter(|i| !i.done).count()
+    }
+
+    fn change_checklist(&mut self, id: Uuid, change: impl FnOnce(&mut Vec<ChecklistItem>) -> Result<(),&'static str>) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&id){
+            return Err("The node does not exist in the graph");
+        }
+        let before = self.get_checklist(id).to_vec();
+        let mut items = before.clone();
+        change(&mut items)?;
+        self.record_change(id, "checklist", json!(before), json!(items));
+        self.checklists.insert(id, items);
+        Ok(())
+    }
+
+    pub fn add_checklist_item(&mut self, id: Uuid, text: &str) -> Result<(),&'static str>{
+        let item = ChecklistItem::new(text);
+        self.change_checklist(id, |items| {
+            if item.text.is_empty(){
+                return Err("Checklist items need some text");
+            }
+            if items.iter().any(|i| i.text == item.text){
+                return Err("The checklist already has this item");
+            }
+            items.push(item);
+            Ok(())
+        })
+    }
+
+    // `index` counts from 0
+    pub fn set_checklist_item_done(&mut self, id: Uuid, index: usize, done: bool) -> Result<(),&'static str>{
+        self.change_checklist(id, |items| {
+            items.get_mut(index).ok_or("The checklist has no such item")?.done = done;
+            Ok(())
+        })
+    }
+
+    pub fn remove_checklist_item(&mut self, id: Uuid, index: usize) -> Result<(),&'static str>{
+        self.change_checklist(id, |items| {
+            if index >= items.len(){
+                return Err("The checklist has no such item");
+            }
+            items.remove(index);
+            Ok(())
+        })
+    }
+
+    pub fn get_effort(&self, id: Uuid) -> Option<Effort>{
+        self.efforts.get(&id).copied()
+    }
+
+    pub fn clear_effort(&mut self, id: Uuid) -> Option<Effort>{
+        let removed = self.efforts.remove(&id);
+        if let Some(effort) = removed{
+            self.record_change(id, "effort_hours", json!(effort.hours), Value::Null);
+            self.record_change(id, "allocation", json!(effort.allocation), Value::Null);
+        }
+        removed
+    }
+
+    // Length of the working day of the person's team, or the project's for
+    // people on no team
+    pub fn hours_per_day_of(&self, person: Option<&str>) -> f64{
+        person.and_then(|p| self.team_of(p)).map_or(self.settings.hours_per_day, |t| t.hours_per_day)
+    }
+
+    // How long the node's effort takes from `start`, counting only the days
+    // its owner works, as long as their team's day; None without effort, or
+    // when the owner has no working day left within ten years
+    pub fn effort_span(&self, id: Uuid, start: DateTime<Utc>) -> Option<TimeDelta>{
+        let effort = self.efforts.get(&id)?;
+        let owner = self.get_node(id)?.get_owner();
+        let mut remaining = effort.working_days(self.hours_per_day_of(owner));
+        let first = start.date_naive();
+        for (offset, date) in first.iter_days().take(3660).enumerate(){
+            if !self.is_available_on(owner, date){
+                continue;
+            }
+            if remaining <= 1.0{
+                return Some(TimeDelta::days(offset as i64) + TimeDelta::seconds((remaining * 86_400.0).round() as i64));
+            }
+            remaining -= 1.0;
+        }
+        None
+    }
+
+    // Where work planned by its dates rather than effort lands around its
+    // owner's time off: on their first day back unless `pinned`, and long
+    // enough to hold as many of their working days as were planned
+    pub fn absence_span(&self, id: Uuid, start: DateTime<Utc>, length: TimeDelta, pinned: bool) -> (DateTime<Utc>, TimeDelta){
+        let owner = self.get_node(id).and_then(|n| n.get_owner());
+        let Some(person) = owner.and_then(|o| self.people.get(o)).filter(|p| !p.unavailability.is_empty()) else {
+            return (start, length);
+        };
+        let region = person.region.as_deref();
+        let planned = start.date_naive().iter_days()
+            .take_while(|d| *d < (start + length).date_naive())
+            .filter(|d| self.calendar.is_working_day_in(*d, region))
+            .count();
+        let mut start = start;
+        if !pinned{
+            for _ in 0..3660{
+                if person.is_available(start.date_naive()){
+                    break;
+                }
+                start += TimeDelta::days(1);
+            }
+        }
+        let Some(last) = start.date_naive().iter_days().take(3660)
+            .filter(|d| self.is_available_on(Some(&person.name), *d))
+            .nth(planned.saturating_sub(1)).filter(|_| planned > 0) else {
+            return (start, length);
+        };
+        (start, TimeDelta::days((last - start.date_naive()).num_days() + 1).max(length))
+    }
+
+    pub fn set_owner(&mut self, id: Uuid, owner: &str) -> Result<(),&'static str>{
+        let before = json!(self.get_node(id).and_then(|n| n.get_owner()));
+        self.update_indexed(id, |node, interner| {
+            node.set_owner(owner.to_string());
+            node.intern_strings(interner);
+            Ok(())
+        })?;
+        self.record_change(id, "owner", before, json!(owner));
+        Ok(())
+    }
+
+    pub fn clear_owner(&mut self, id: Uuid) -> Result<(),&'static str>{
+        let before = json!(self.get_node(id).and_then(|n| n.get_owner()));
+        self.update_indexed(id, |node, _| {
+            node.clear_owner();
+            Ok(())
+        })?;
+        self.record_change(id, "owner", before, Value::Null);
+        Ok(())
+    }
+
+    // Hands everything `from` owns or takes part in within `scope` to `to`,
+    // or leaves it unassigned: node owners, participants, and the owners of
+    // the risks linked into the scope (and of objectives, when the scope is
+    // everything). Each change is logged like any other edit, risk and
+    // objective owners under the risk's or objective's id. Works on a copy,
+    // so a failure part way leaves the graph as it was.
+    pub fn reassign_owner(&mut self, from: &str, to: Option<&str>, scope: &Scope) -> Result<Reassignment,&'static str>{
+        if from.trim().is_empty(){
+            return Err("The person to reassign from needs a name");
+        }
+        if let Some(to) = to{
+            if to.trim().is_empty(){
+                return Err("The person to reassign to needs a name");
+            }
+            if to == from{
+                return Err("Cannot reassign work to the same person");
+            }
+        }
+        if let Scope::Subtree(root) = scope{
+            if !self.uid_to_index.contains_key(root){
+                return Err("The node does not exist in the graph");
+            }
+        }
+
+        let mut next = self.clone();
+        let summary = next.reassign_in(from, to, scope)?;
+        *self = next;
+        Ok(summary)
+    }
+
+    fn reassign_in(&mut self, from: &str, to: Option<&str>, scope: &Scope) -> Result<Reassignment,&'static str>{
+        let mut summary = Reassignment::default();
+        let nodes: Vec<(Uuid,bool,bool)> = self.nodes_in_scope(scope).iter()
+            .map(|n| (n.get_id(), n.get_owner() == Some(from), n.get_participants().contains(&from)))
+            .collect();
+        for (id, owned, participating) in nodes{
+            if owned{
+                match to{
+                    Some(to) => self.set_owner(id, to)?,
+                    None => self.clear_owner(id)?,
+                }
+                summary.owned.push(id);
+            }
+            if participating{
+                let before = json!(self.get_node(id).map(|n| n.get_participants()));
+                let idx = self.uid_to_index[&id];
+                let node = &mut self.graph[idx];
+                node.remove_participant(from)?;
+                if let Some(to) = to{
+                    node.add_participant(to.to_string())?;
+                }
+                node.intern_strings(&mut self.interner);
+                let after = json!(self.get_node(id).map(|n| n.get_participants()));
+                self.record_change(id, "participants", before, after);
+                summary.participating.push(id);
+            }
+        }
+
+        let risks: Vec<Uuid> = self.risks_in_scope(scope).iter()
+            .filter(|r| r.get_owner() == Some(from))
+            .map(|r| r.get_id())
+            .collect();
+        for id in &risks{
+            if let Some(risk) = self.risks.get_mut(id){
+                risk.set_owner(to.map(str::to_string));
+            }
+            self.record_change(*id, "owner", json!(from), json!(to));
+        }
+        summary.risks = risks;
+        if *scope == Scope::All{
+            let objectives: Vec<Uuid> = self.objectives.values()
+                .filter(|o| o.owner.as_deref() == Some(from))
+                .map(|o| o.id)
+                .collect();
+            for id in &objectives{
+                if let Some(objective) = self.objectives.get_mut(id){
+                    objective.owner = to.map(str::to_string);
+                }
+                self.record_change(*id, "owner", json!(from), json!(to));
+            }
+            summary.objectives = objectives;
+        }
+
+        summary.owned.sort();
+        summary.participating.sort();
+        summary.risks.sort();
+        summary.objectives.sort();
+        Ok(summary)
+    }
+
+    // Returns false if the node already carried the tag
+    pub fn add_tag(&mut self, id: Uuid, tag: &str) -> Result<bool,&'static str>{
+        let before = self.get_node(id).map(Self::sorted_tags).unwrap_or_default();
+        let added = self.update_indexed(id, |node, interner| Ok(node.add_tag(interner.intern(tag))))?;
+        let after = self.get_node(id).map(Self::sorted_tags).unwrap_or_default();
+        self.record_change(id, "tags", before, after);
+        Ok(added)
+    }
+
+    // Links a node to an external item; each item maps to at most one node
+    pub fn add_external_ref(&mut self, id: Uuid, external: ExternalRef) -> Result<(),&'static str>{
+        if self.indexes.by_external(&external.system, &external.key).is_some_and(|other| other != id){
+            return Err("The external ref is already linked to another node");
+        }
+        self.update_indexed(id, |node, _| {
+            node.add_external_ref(external);
+            Ok(())
+        })
+    }
+
+    pub fn remove_external_ref(&mut self, id: Uuid, system: &str, key: &str) -> Result<bool,&'static str>{
+        self.update_indexed(id, |node, _| Ok(node.remove_external_ref(system, key)))
+    }
+
+    // The node linked to `key` in `system`, for deduplicating imports
+    pub fn find_by_external(&self, system: &str, key: &str) -> Option<Uuid>{
+        self.indexes.by_external(system, key)
+    }
+
+    pub fn remove_tag(&mut self, id: Uuid, tag: &str) -> Result<bool,&'static str>{
+        let before = self.get_node(id).map(Self::sorted_tags).unwrap_or_default();
+        let removed = self.update_indexed(id, |node, _| Ok(node.remove_tag(tag)))?;
+        let after = self.get_node(id).map(Self::sorted_tags).unwrap_or_default();
+        self.record_change(id, "tags", before, after);
+        Ok(removed)
+    }
+
+    // Applies a change to an indexed field (owner, status, tags) and
+    // re-indexes the node around it
+    fn update_indexed<T>(&mut self, id: Uuid, change: impl FnOnce(&mut Node, &mut Interner) -> Result<T,&'static str>) -> Result<T,&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let node = &mut self.graph[idx];
+        self.indexes.remove(node);
+        let result = change(node, &mut self.interner);
+        self.indexes.insert(node);
+        self.reindex_text(id);
+        result
+    }
+
+    // Points each id at the node carrying it; fails when two nodes share an
+    // id, as there is no telling which one the rest of the file means
+    pub fn rebuild_index(&mut self) -> Result<(),&'static str>{
+        let mut uid_to_index = HashMap::with_capacity(self.graph.node_count());
+        for idx in self.graph.node_indices(){
+            if uid_to_index.insert(self.graph[idx].get_id(), idx).is_some(){
+                return Err("Several nodes share an id; remove or renumber one of them by hand");
+            }
+        }
+        self.uid_to_index = uid_to_index;
+        Ok(())
+    }
+
+    // Rebuilds the interned strings and secondary indexes from the nodes;
+    // needed after deserializing, which skips both
+    pub fn rebuild_caches(&mut self){
+        let mut interner = Interner::new();
+        let mut indexes = NodeIndexes::new();
+        for node in self.graph.node_weights_mut(){
+            node.intern_strings(&mut interner);
+            indexes.insert(node);
+        }
+        self.interner = interner;
+        self.indexes = indexes;
+        self.rollups.clear();
+        self.search = SearchIndex::new();
+        let ids: Vec<Uuid> = self.uid_to_index.keys().copied().collect();
+        for id in ids{
+            self.reindex_text(id);
+        }
+    }
+
+    pub fn interner(&self) -> &Interner{
+        &self.interner
+    }
+
+    pub fn indexes(&self) -> &NodeIndexes{
+        &self.indexes
+    }
+
+    pub fn query(&self) -> Query<'_>{
+        Query::new(self)
+    }
+
+    pub fn add_release(&mut self, release: Release) -> Result<(),&'static str>{
+        if self.releases.contains_key(&release.id){
+            return Err("The release has already been added to the graph");
+        }
+        self.releases.insert(release.id, release);
+        Ok(())
+    }
+
+    pub fn get_release(&self, id: Uuid) -> Option<&Release>{
+        self.releases.get(&id)
+    }
+
+    pub fn releases(&self) -> impl Iterator<Item = &Release>{
+        self.releases.values()
+    }
+
+    pub fn cut_release(&mut self, release_id: Uuid) -> Result<(),&'static str>{
+        if !self.missing_approvals(release_id).is_empty(){
+            return Err("Cutting the release needs approval first");
+        }
+        self.releases.get_mut(&release_id)
+            .ok_or("The release does not exist")?
+            .cut(Utc::now())
+    }
+
+    pub fn target_release(&mut self, release_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        match self.get_node(node_id){
+            Some(Node::Epic{..}) | Some(Node::UserStory{..}) => {}
+            Some(_) => return Err("Only Epics and User Stories can be targeted to a release"),
+            None => return Err("The node does not exist in the graph"),
+        }
+
+        let release = self.releases.get_mut(&release_id).ok_or("The release does not exist")?;
+        if !release.add_to_scope(node_id, Utc::now()){
+            return Err("The node is already targeted to this release");
+        }
+        Ok(())
+    }
+
+    pub fn untarget_release(&mut self, release_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        let release = self.releases.get_mut(&release_id).ok_or("The release does not exist")?;
+        if !release.remove_from_scope(node_id, Utc::now()){
+            return Err("The node is not targeted to this release");
+        }
+        Ok(())
+    }
+
+    pub fn release_scope(&self, release_id: Uuid) -> Option<Vec<&Node>>{
+        self.releases.get(&release_id).map(|r| {
+            r.get_scope().iter().filter_map(|id| self.get_node(*id)).collect()
+        })
+    }
+
+    pub fn release_completion(&self, release_id: Uuid) -> Option<f64>{
+        self.release_scope(release_id).map(|scope| Self::completion_of(&scope))
+    }
+
+    // Percentage of done work, weighted by points when any item is estimated
+    pub fn completion_of(nodes: &[&Node]) -> f64{
+        if nodes.is_empty(){
+            return 0.0;
+        }
+
+        let total_points: u32 = nodes.iter().filter_map(|n| n.get_points()).sum();
+
+        if total_points > 0{
+            let done_points: u32 = nodes.iter().filter(|n| n.is_done()).filter_map(|n| n.get_points()).sum();
+            100.0 * done_points as f64 / total_points as f64
+        }else{
+            let done = nodes.iter().filter(|n| n.is_done()).count();
+            100.0 * done as f64 / nodes.len() as f64
+        }
+    }
+
+    // Completion of a node's contained work; a leaf is either 0% or 100%
+    pub fn subtree_completion(&self, id: Uuid) -> Option<f64>{
+        self.rollup(id).map(|r| r.completion())
+    }
+
+    // Points, progress and planned span of a node's subtree, cached until
+    // something inside it changes
+    pub fn rollup(&self, id: Uuid) -> Option<Rollup>{
+        if let Some(cached) = self.rollups.get(id){
+            return Some(cached);
+        }
+        let node = self.get_node(id)?;
+        let subtree = self.nodes_in_scope(&Scope::Subtree(id));
+
+        let mut rollup = Rollup{ self_done: node.is_done(), ..Rollup::default() };
+        for n in &subtree{
+            if let Some(tl) = n.get_timeline(){
+                rollup.start = Some(rollup.start.map_or(tl.start, |s| s.min(tl.start)));
+                if let Some(end) = tl.end{
+                    rollup.end = Some(rollup.end.map_or(end, |e| e.max(end)));
+                }
+            }
+            if n.get_id() == id || n.get_status().is_none(){
+                continue;
+            }
+            let points = n.get_points().unwrap_or(0);
+            rollup.items += 1;
+            rollup.total_points += points;
+            if n.is_done(){
+                rollup.done_items += 1;
+                rollup.done_points += points;
+            }
+        }
+
+        self.rollups.insert(id, rollup);
+        Some(rollup)
+    }
+
+    // Drops cached rollups of `id` and everything containing it
+    fn invalidate_rollups(&mut self, id: Uuid){
+        let ancestors = self.get_ancestors(id);
+        self.rollups.invalidate(std::iter::once(id).chain(ancestors));
+    }
+
+    pub fn add_risk(&mut self, risk: Risk) -> Result<(),&'static str>{
+        if self.risks.contains_key(&risk.get_id()){
+            return Err("The risk has already been added to the graph");
+        }
+        if risk.get_linked().iter().any(|id| !self.uid_to_index.contains_key(id)){
+            return Err("The risk is linked to a node that does not exist in the graph");
+        }
+        self.risks.insert(risk.get_id(), risk);
+        Ok(())
+    }
+
+    pub fn get_risk(&self, id: Uuid) -> Option<&Risk>{
+        self.risks.get(&id)
+    }
+
+    pub fn risks(&self) -> impl Iterator<Item = &Risk>{
+        self.risks.values()
+    }
+
+    pub fn link_risk(&mut self, risk_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&node_id){
+            return Err("The node does not exist in the graph");
+        }
+        let risk = self.risks.get_mut(&risk_id).ok_or("The risk does not exist")?;
+        if !risk.link(node_id){
+            return Err("The risk is already linked to this node");
+        }
+        Ok(())
+    }
+
+    pub fn unlink_risk(&mut self, risk_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        let risk = self.risks.get_mut(&risk_id).ok_or("The risk does not exist")?;
+        if !risk.unlink(node_id){
+            return Err("The risk is not linked to this node");
+        }
+        Ok(())
+    }
+
+    pub fn add_decision_option(&mut self, id: Uuid, option: &str) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_options().to_vec();
+        self.graph[idx].add_option(option)?;
+        let after = self.graph[idx].get_options().to_vec();
+        self.record_change(id, "options", json!(before), json!(after));
+        Ok(())
+    }
+
+    // Records the choice as made on the day of `at`
+    pub fn decide_at(&mut self, id: Uuid, option: &str, by: &[String], at: DateTime<Utc>) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&id).ok_or("The node does not exist in the graph")?;
+        let before = self.graph[idx].get_decision().map(|(chosen, _)| chosen.to_string());
+        self.graph[idx].decide(option, at.date_naive(), by)?;
+        self.record_change(id, "decision", json!(before), json!(option.trim()));
+        Ok(())
+    }
+
+    pub fn link_decision(&mut self, decision_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        if !matches!(self.get_node(decision_id), Some(Node::Decision{..})){
+            return Err("The decision does not exist");
+        }
+        match self.get_node(node_id){
+            None => return Err("The node does not exist in the graph"),
+            Some(Node::Decision{..}) => return Err("A decision affects work, not other decisions"),
+            Some(_) => {}
+        }
+        let before = self.affected_by(decision_id);
+        if !self.decision_links.entry(decision_id).or_default().insert(node_id){
+            return Err("The decision is already linked to this node");
+        }
+        let after = self.affected_by(decision_id);
+        self.record_change(decision_id, "affects", json!(before), json!(after));
+        Ok(())
+    }
+
+    pub fn unlink_decision(&mut self, decision_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        let before = self.affected_by(decision_id);
+        let linked = self.decision_links.get_mut(&decision_id).ok_or("The decision is not linked to this node")?;
+        if !linked.remove(&node_id){
+            return Err("The decision is not linked to this node");
+        }
+        if linked.is_empty(){
+            self.decision_links.remove(&decision_id);
+        }
+        let after = self.affected_by(decision_id);
+        self.record_change(decision_id, "affects", json!(before), json!(after));
+        Ok(())
+    }
+
+    // The work a decision affects
+    pub fn affected_by(&self, decision_id: Uuid) -> Vec<Uuid>{
+        self.decision_links.get(&decision_id).map(|l| l.iter().copied().collect()).unwrap_or_default()
+    }
+
+    pub fn decisions_affecting(&self, node_id: Uuid) -> Vec<Uuid>{
+        let mut ids: Vec<Uuid> = self.decision_links.iter().filter(|(_, l)| l.contains(&node_id)).map(|(d, _)| *d).collect();
+        ids.sort();
+        ids
+    }
+
+    // Decisions in scope or affecting anything in it; open ones first, then
+    // the most recently decided
+    pub fn decisions_in_scope(&self, scope: &Scope) -> Vec<&Node>{
+        let nodes = self.nodes_in_scope(scope);
+        let ids: HashSet<Uuid> = nodes.iter().map(|n| n.get_id()).collect();
+        let mut decisions: Vec<&Node> = self.nodes()
+            .filter(|n| matches!(n, Node::Decision{..}))
+            .filter(|n| ids.contains(&n.get_id()) || self.affected_by(n.get_id()).iter().any(|a| ids.contains(a)))
+            .collect();
+        decisions.sort_by(|a, b| {
+            let on = |n: &Node| n.get_decision().map(|(_, on)| on);
+            on(a).is_some().cmp(&on(b).is_some())
+                .then_with(|| on(b).cmp(&on(a)))
+                .then_with(|| a.get_name().cmp(b.get_name()))
+        });
+        decisions
+    }
+
+    // Risks linked to any node in scope, highest score first
+    pub fn risks_in_scope(&self, scope: &Scope) -> Vec<&Risk>{
+        let ids: Vec<Uuid> = self.nodes_in_scope(scope).iter().map(|n| n.get_id()).collect();
+        let mut risks: Vec<&Risk> = self.risks.values()
+            .filter(|r| r.get_linked().iter().any(|id| ids.contains(id)))
+            .collect();
+        risks.sort_by(|a, b| b.score().total_cmp(&a.score()).then_with(|| a.get_title().cmp(b.get_title())));
+        risks
+    }
+
+    // Sum of the scores of every risk attached to the project's subtree
+    pub fn project_risk_score(&self, project_id: Uuid) -> Option<f64>{
+        match self.get_node(project_id){
+            Some(Node::Project{..}) => Some(
+                self.risks_in_scope(&Scope::Subtree(project_id)).iter().fold(0.0, |sum, r| sum + r.score())
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn add_objective(&mut self, objective: Objective) -> Result<(),&'static str>{
+        if self.objectives.contains_key(&objective.id){
+            return Err("The objective has already been added to the graph");
+        }
+        self.objectives.insert(objective.id, objective);
+        Ok(())
+    }
+
+    pub fn get_objective(&self, id: Uuid) -> Option<&Objective>{
+        self.objectives.get(&id)
+    }
+
+    pub fn objectives(&self) -> impl Iterator<Item = &Objective>{
+        self.objectives.values()
+    }
+
+    pub fn add_key_result(&mut self, objective_id: Uuid, key_result: KeyResult) -> Result<(),&'static str>{
+        if self.find_key_result(key_result.id).is_some(){
+            return Err("The key result has already been added to the graph");
+        }
+        self.objectives.get_mut(&objective_id)
+            .ok_or("The objective does not exist")?
+            .add_key_result(key_result);
+        Ok(())
+    }
+
+    pub fn find_key_result(&self, id: Uuid) -> Option<&KeyResult>{
+        self.objectives.values().find_map(|o| o.get_key_result(id))
+    }
+
+    pub fn link_key_result(&mut self, key_result_id: Uuid, node_id: Uuid, weight: f64) -> Result<(),&'static str>{
+        match self.get_node(node_id){
+            Some(Node::Epic{..}) | Some(Node::Project{..}) => {}
+            Some(_) => return Err("Only Epics and Projects can contribute to key results"),
+            None => return Err("The node does not exist in the graph"),
+        }
+
+        self.objectives.values_mut()
+            .find_map(|o| o.get_key_result_mut(key_result_id))
+            .ok_or("The key result does not exist")?
+            .set_contribution(node_id, weight)
+    }
+
+    // Weighted average of the completion of the linked work
+    pub fn key_result_progress(&self, key_result_id: Uuid) -> Option<f64>{
+        let kr = self.find_key_result(key_result_id)?;
+
+        let (weighted, total) = kr.get_contributions().iter()
+            .filter_map(|(id, weight)| self.subtree_completion(*id).map(|c| (c * weight, *weight)))
+            .fold((0.0, 0.0), |(w, t), (c, weight)| (w + c, t + weight));
+
+        if total == 0.0{
+            Some(0.0)
+        }else{
+            Some(weighted / total)
+        }
+    }
+
+    // Mean progress of the objective's key results
+    pub fn objective_progress(&self, objective_id: Uuid) -> Option<f64>{
+        let objective = self.objectives.get(&objective_id)?;
+        if objective.get_key_results().is_empty(){
+            return Some(0.0);
+        }
+
+        let sum: f64 = objective.get_key_results().iter()
+            .filter_map(|kr| self.key_result_progress(kr.id))
+            .sum();
+        Some(sum / objective.get_key_results().len() as f64)
+    }
+
+    pub fn add_person(&mut self, person: Person) -> Result<(),&'static str>{
+        if self.people.contains_key(&person.name){
+            return Err("A person with this name already exists");
+        }
+        self.people.insert(person.name.clone(), person);
+        Ok(())
+    }
+
+    pub fn get_person(&self, name: &str) -> Option<&Person>{
+        self.people.get(name)
+    }
+
+    pub fn set_person_region(&mut self, name: &str, region: Option<&str>) -> Result<(),&'static str>{
+        self.people.get_mut(name).ok_or("The person does not exist")?.region = region.map(str::to_string);
+        Ok(())
+    }
+
+    pub fn add_person_role(&mut self, name: &str, role: &str) -> Result<(),&'static str>{
+        if role.trim().is_empty(){
+            return Err("A role needs a name");
+        }
+        self.people.get_mut(name).ok_or("The person does not exist")?.roles.insert(role.trim().to_string());
+        Ok(())
+    }
+
+    pub fn add_unavailability(&mut self, name: &str, range: Unavailability) -> Result<(),&'static str>{
+        self.people.get_mut(name)
+            .ok_or("The person does not exist")?
+            .unavailability.push(range);
+        Ok(())
+    }
+
+    // Working days in [start, end] on which the person is available, their
+    // region's holidays excluded; people without a record are assumed to be
+    // available every working day
+    pub fn available_days(&self, name: &str, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate>{
+        start.iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_available_on(Some(name), *d))
+            .collect()
+    }
+
+    // A working day for the person, or for everyone when there is nobody
+    pub fn is_available_on(&self, name: Option<&str>, date: NaiveDate) -> bool{
+        let person = name.and_then(|n| self.people.get(n));
+        self.calendar.is_working_day_in(date, person.and_then(|p| p.region.as_deref()))
+            && person.is_none_or(|p| p.is_available(date))
+    }
+
+    pub fn people(&self) -> impl Iterator<Item = &Person>{
+        self.people.values()
+    }
+
+    pub fn log_work(&mut self, node_id: Uuid, worklog: Worklog) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&node_id){
+            return Err("The node does not exist in the graph");
+        }
+        self.worklogs.entry(node_id).or_default().push(worklog);
+        Ok(())
+    }
+
+    pub fn get_worklogs(&self, node_id: Uuid) -> &[Worklog]{
+        self.worklogs.get(&node_id).map(|w| w.as_slice()).unwrap_or(&[])
+    }
+
+    // Logged hours priced at each person's hourly rate, in the base currency;
+    // unknown rates cost nothing
+    pub fn actual_cost(&self, node_id: Uuid) -> f64{
+        self.get_worklogs(node_id).iter()
+            .map(|w| {
+                let rate = self.people.get(&w.person)
+                    .and_then(|p| {
+                        let currency = p.rate_currency.as_deref().unwrap_or(&self.settings.money.currency);
+                        self.settings.money.to_base(p.hourly_rate?, currency)
+                    })
+                    .unwrap_or(0.0);
+                w.hours * rate
+            })
+            .sum()
+    }
+
+    // None clears the rate; a rate without a currency is in the base currency
+    pub fn set_person_rate(&mut self, name: &str, rate: Option<f64>, currency: Option<&str>) -> Result<(),&'static str>{
+        if rate.is_some_and(|r| !(r.is_finite() && r >= 0.0)){
+            return Err("An hourly rate must be zero or more");
+        }
+        let currency = currency.filter(|c| *c != self.settings.money.currency);
+        if currency.is_some_and(|c| !self.settings.money.converts(c)){
+            return Err("The currency needs an exchange rate in the project's money settings");
+        }
+        let person = self.people.get_mut(name).ok_or("The person does not exist")?;
+        person.hourly_rate = rate;
+        person.rate_currency = rate.and(currency.map(str::to_string));
+        Ok(())
+    }
+
+    // An empty description clears it
+    pub fn set_description(&mut self, id: Uuid, description: &str) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&id){
+            return Err("The node does not exist in the graph");
+        }
+        let before = if description.trim().is_empty(){
+            self.descriptions.remove(&id)
+        }else{
+            self.descriptions.insert(id, description.to_string())
+        };
+        let after = json!(self.get_description(id));
+        self.reindex_text(id);
+        self.record_change(id, "description", json!(before), after);
+        Ok(())
+    }
+
+    pub fn get_description(&self, id: Uuid) -> Option<&str>{
+        self.descriptions.get(&id).map(|d| d.as_str())
+    }
+
+    pub fn add_comment(&mut self, id: Uuid, comment: Comment) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&id){
+            return Err("The node does not exist in the graph");
+        }
+        self.comments.entry(id).or_default().push(comment);
+        self.reindex_text(id);
+        Ok(())
+    }
+
+    pub fn get_comments(&self, id: Uuid) -> &[Comment]{
+        self.comments.get(&id).map(|c| c.as_slice()).unwrap_or(&[])
+    }
+
+    // The searchable text of a node, most significant field first
+    fn text_fields(&self, id: Uuid) -> Vec<(Field,&str)>{
+        let Some(node) = self.get_node(id) else {
+            return Vec::new();
+        };
+        let mut tags: Vec<&str> = node.get_tags().iter().map(|t| t.as_ref()).collect();
+        tags.sort();
+        let mut fields = vec![(Field::Name, node.get_name())];
+        fields.extend(tags.into_iter().map(|t| (Field::Tag, t)));
+        if let Some(description) = self.get_description(id){
+            fields.push((Field::Description, description));
+        }
+        fields.extend(self.get_comments(id).iter().map(|c| (Field::Comment, c.text.as_str())));
+        fields
+    }
+
+    fn reindex_text(&mut self, id: Uuid){
+        let fields: Vec<(Field,String)> = self.text_fields(id).into_iter().map(|(f, t)| (f, t.to_string())).collect();
+        let fields: Vec<(Field,&str)> = fields.iter().map(|(f, t)| (*f, t.as_str())).collect();
+        self.search.insert(id, &fields);
+    }
+
+    // Ranked full-text matches for `query`, at most `limit` of them, each
+    // with an excerpt of the most significant field that matched
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit>{
+        self.search.search(query).into_iter()
+            .take(limit)
+            .filter_map(|(id, score)| {
+                self.text_fields(id).into_iter()
+                    .find_map(|(field, text)| Snippet::find(text, query, 120).map(|snippet| (field, snippet)))
+                    .map(|(field, snippet)| SearchHit{ id, score, field, snippet })
+            })
+            .collect()
+    }
+
+    // Records a vote and re-derives the node's points from the consensus
+    pub fn add_estimate(&mut self, node_id: Uuid, estimate: Estimate) -> Result<(),&'static str>{
+        match self.get_node(node_id){
+            Some(n) if n.get_status().is_some() && !matches!(n, Node::Project{..}) => {}
+            Some(_) => return Err("Only Epics, User Stories and Tasks can be estimated"),
+            None => return Err("The node does not exist in the graph"),
+        }
+        self.estimates.entry(node_id).or_default().push(estimate);
+        self.derive_points(node_id)
+    }
+
+    pub fn get_estimates(&self, node_id: Uuid) -> &[Estimate]{
+        self.estimates.get(&node_id).map(|e| e.as_slice()).unwrap_or(&[])
+    }
+
+    // Forgets every vote on the node; its current points stay as they are
+    pub fn clear_estimates(&mut self, node_id: Uuid) -> Vec<Estimate>{
+        self.estimates.remove(&node_id).unwrap_or_default()
+    }
+
+    pub fn estimate_consensus(&self, node_id: Uuid) -> Option<u32>{
+        estimate::consensus(self.get_estimates(node_id), self.consensus)
+    }
+
+    pub fn get_consensus_method(&self) -> Consensus{
+        self.consensus
+    }
+
+    // Switches how votes are combined and re-derives every estimated node
+    pub fn set_consensus_method(&mut self, method: Consensus) -> Result<(),&'static str>{
+        self.consensus = method;
+        let estimated: Vec<Uuid> = self.estimates.keys().copied().collect();
+        for id in estimated{
+            self.derive_points(id)?;
+        }
+        Ok(())
+    }
+
+    // The consensus rounded up to the project's point scale, since a median
+    // can fall between two allowed values
+    fn derive_points(&mut self, node_id: Uuid) -> Result<(),&'static str>{
+        match self.estimate_consensus(node_id).map(|p| self.settings.point_scale.snap(p)){
+            Some(points) => self.set_points(node_id, points),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_calendar(&self) -> &Calendar{
+        &self.calendar
+    }
+
+    pub fn get_calendar_mut(&mut self) -> &mut Calendar{
+        &mut self.calendar
+    }
+
+    // The remote node blocks `dependency.node`; its state stays unknown
+    // until resolved
+    pub fn add_remote_dependency(&mut self, dependency: RemoteDependency) -> Result<(),&'static str>{
+        if !self.uid_to_index.contains_key(&dependency.node){
+            return Err("The node does not exist in the graph");
+        }
+        if self.uid_to_index.contains_key(&dependency.remote.node){
+            return Err("The remote node is in this graph; connect the nodes instead");
+        }
+        if self.remote_dependencies.iter().any(|d| d.node == dependency.node && d.remote == dependency.remote){
+            return Err("The node already depends on that remote node");
+        }
+        self.remote_dependencies.push(dependency);
+        Ok(())
+    }
+
+    pub fn remove_remote_dependency(&mut self, node: Uuid, remote: &RemoteRef) -> bool{
+        let before = self.remote_dependencies.len();
+        self.remote_dependencies.retain(|d| d.node != node || &d.remote != remote);
+        self.remote_dependencies.len() != before
+    }
+
+    pub fn remote_dependencies(&self) -> &[RemoteDependency]{
+        &self.remote_dependencies
+    }
+
+    pub fn remote_dependencies_of(&self, node: Uuid) -> impl Iterator<Item = &RemoteDependency>{
+        self.remote_dependencies.iter().filter(move |d| d.node == node)
+    }
+
+    // Records what the remote node looks like now, for every dependency on it
+    pub fn set_remote_state(&mut self, remote: &RemoteRef, state: Option<RemoteNode>){
+        for dependency in self.remote_dependencies.iter_mut().filter(|d| &d.remote == remote){
+            dependency.last_known = state.clone();
+        }
+    }
+
+    pub fn get_sync_state(&self) -> &SyncState{
+        &self.sync_state
+    }
+
+    pub fn get_sync_state_mut(&mut self) -> &mut SyncState{
+        &mut self.sync_state
+    }
+
+    pub fn get_fiscal_calendar(&self) -> &FiscalCalendar{
+        &self.fiscal_calendar
+    }
+
+    pub fn set_fiscal_calendar(&mut self, fiscal_calendar: FiscalCalendar){
+        self.fiscal_calendar = fiscal_calendar;
+    }
+
+    pub fn add_sprint(&mut self, sprint: Sprint) -> Result<(),&'static str>{
+        if self.sprints.contains_key(&sprint.id){
+            return Err("The sprint has already been added to the graph");
+        }
+        self.sprints.insert(sprint.id, sprint);
+        Ok(())
+    }
+
+    // Adds a sprint of the project's default length (settings.sprint_days),
+    // starting when the latest sprint ends, or at `start` for the first one
+    pub fn plan_next_sprint(&mut self, name: &str, start: DateTime<Utc>) -> Result<Uuid,&'static str>{
+        let start = self.sprints.values().map(|s| s.end).max().unwrap_or(start);
+        let sprint = self.settings.sprint(Uuid::now_v6(SPRINT_ID), name.to_string(), start)?;
+        let id = sprint.id;
+        self.add_sprint(sprint)?;
+        Ok(id)
+    }
+
+    pub fn get_sprint(&self, id: Uuid) -> Option<&Sprint>{
+        self.sprints.get(&id)
+    }
+
+    pub fn sprints(&self) -> impl Iterator<Item = &Sprint>{
+        self.sprints.values()
+    }
+
+    pub fn add_to_sprint(&mut self, sprint_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        match self.get_node(node_id){
+            Some(Node::UserStory{..}) | Some(Node::Tasks{..}) => {}
+            Some(_) => return Err("Only User Stories and Tasks can be planned into a sprint"),
+            None => return Err("The node does not exist in the graph"),
+        }
+
+        let sprint = self.sprints.get_mut(&sprint_id).ok_or("The sprint does not exist")?;
+        if !sprint.add_item(node_id){
+            return Err("The node is already planned into this sprint");
+        }
+        Ok(())
+    }
+
+    pub fn remove_from_sprint(&mut self, sprint_id: Uuid, node_id: Uuid) -> Result<(),&'static str>{
+        let sprint = self.sprints.get_mut(&sprint_id).ok_or("The sprint does not exist")?;
+        if !sprint.remove_item(node_id){
+            return Err("The node is not planned into this sprint");
+        }
+        Ok(())
+    }
+
+    pub fn add_team(&mut self, team: Team) -> Result<(),&'static str>{
+        if self.teams.contains_key(&team.name){
+            return Err("A team with this name already exists");
+        }
+        self.teams.insert(team.name.clone(), team);
+        Ok(())
+    }
+
+    pub fn get_team(&self, name: &str) -> Option<&Team>{
+        self.teams.get(name)
+    }
+
+    pub fn teams(&self) -> impl Iterator<Item = &Team>{
+        self.teams.values()
+    }
+
+    // The team a person belongs to; the first by name if they are on several
+    pub fn team_of(&self, person: &str) -> Option<&Team>{
+        self.teams.values()
+            .filter(|t| t.has_member(person))
+            .min_by(|a, b| a.name.cmp(&b.name))
+    }
+
+    pub fn set_project_timezone(&mut self, project_id: Uuid, tz: Tz) -> Result<(),&'static str>{
+        let idx = *self.uid_to_index.get(&project_id).ok_or("The node does not exist in the graph")?;
+        self.graph[idx].set_timezone(tz)
+    }
+
+    // Owner's timezone, else the nearest enclosing Project's, else UTC
+    pub fn timezone_for(&self, id: Uuid) -> Tz{
+        let owner_tz = self.get_node(id)
+            .and_then(|n| n.get_owner())
+            .and_then(|o| self.people.get(o))
+            .and_then(|p| p.timezone);
+        if let Some(tz) = owner_tz{
+            return tz;
+        }
+
+        let mut current = Some(id);
+        while let Some(cur) = current{
+            if let Some(tz) = self.get_node(cur).and_then(|n| n.get_timezone()){
+                return tz;
+            }
+            current = self.get_parent(cur);
+        }
+        Tz::UTC
+    }
+
+    // Cuts a task in two. The original keeps its id, key, predecessors and
+    // worklogs and covers the first part; a new task right after it (and
+    // blocked by it) takes the rest of the points, time and cost along with
+    // the original's successors. Fails without changes if either part would
+    // be left without points. Returns the new task's id.
+    pub fn split_task(&mut self, id: Uuid, at: SplitAt) -> Result<Uuid,&'static str>{
+        let _span = trace::span(module_path!(), "split_task", &[("node", &id)]);
+        let node = self.get_node(id).ok_or("The node does not exist in the graph")?.clone();
+        if !matches!(node, Node::Tasks{..}){
+            return Err("Only tasks can be split");
+        }
+        let timeline = node.get_timeline().ok_or("The task has no timeline")?;
+        let start = timeline.start;
+        let end = timeline.end
+            .or_else(|| timeline.duration.as_ref().map(|d| start + d.to_time_delta()))
+            .ok_or("The task needs an end date to be split")?;
+        let span = (end - start).num_seconds();
+
+        // Share of the work that stays with the original, strictly between 0 and 1
+        let (cut, fraction) = match at{
+            SplitAt::Points(p) => {
+                let total = node.get_points().ok_or("The task has no points to split")?;
+                let fraction = p as f64 / total as f64;
+                (start + TimeDelta::seconds((span as f64 * fraction).round() as i64), fraction)
+            }
+            SplitAt::Date(d) => {
+                if d <= start || d >= end{
+                    return Err("The split date must fall inside the task's timeline");
+                }
+                (d, (d - start).num_seconds() as f64 / span as f64)
+            }
+        };
+        let points = node.get_points().map(|total| match at{
+            SplitAt::Points(p) => (p.min(total), total.saturating_sub(p)),
+            SplitAt::Date(_) => {
+                let first = (total as f64 * fraction).round() as u32;
+                (first, total - first)
+            }
+        });
+        if points.is_some_and(|(first, rest)| first == 0 || rest == 0){
+            return Err("The split must leave points on both parts");
+        }
+        if points.is_some_and(|(first, rest)| !self.settings.accepts_points(first) || !self.settings.accepts_points(rest)){
+            return Err("Both parts must end up on the project's point scale");
+        }
+        let cost = node.get_estimated_cost().map(|c| (c * fraction, c - c * fraction));
+        let effort = self.get_effort(id).map(|e| (
+            Effort{ hours: e.hours * fraction, ..e },
+            Effort{ hours: e.hours - e.hours * fraction, ..e },
+        ));
+
+        let new_id = Uuid::now_v6(SPLIT_ID);
+        let mut second = node.clone();
+        second.set_id(new_id);
+        second.set_name(format!("{} (part 2)", node.get_name()));
+        second.set_timeline(Timeline::from_start_end(cut, end));
+        for external in node.get_external_refs(){
+            second.remove_external_ref(&external.system, &external.key);
+        }
+        if let Some((_, rest)) = points{
+            second.set_points(rest)?;
+        }
+        if let Some((_, rest)) = cost{
+            second.set_estimated_cost(rest)?;
+        }
+        // Work on a copy so a refused status change leaves the graph untouched
+        let mut split = self.clone();
+        split.create_node(&second)?;
+        // The rest of the work has not been started, whatever the original's state
+        if !node.is_done() && node.get_status() != Some(Status::NotStarted){
+            split.change_status(new_id, Status::NotStarted, Utc::now(), None)?;
+        }
+        if let Some(currency) = split.cost_currencies.get(&id).cloned(){
+            split.cost_currencies.insert(new_id, currency);
+        }
+
+        // The votes were for the whole task, not for either part
+        split.estimates.remove(&id);
+        split.set_timeline(id, Timeline::from_start_end(start, cut))?;
+        if let Some((first, _)) = points{
+            split.set_points(id, first)?;
+        }
+        if let Some((first, _)) = cost{
+            split.set_estimated_cost(id, first)?;
+        }
+        if let Some((first, rest)) = effort{
+            split.set_effort(id, first)?;
+            split.efforts.insert(new_id, rest);
+        }
+
+        // Successors now wait for the second part, which waits for the first
+        let from = split.uid_to_index[&id];
+        let to = split.uid_to_index[&new_id];
+        let mut outgoing: Vec<(EdgeIndex,NodeIndex)> = split.graph.edges(from)
+            .filter(|e| e.weight().kind.is_scheduling())
+            .map(|e| (e.id(), e.target()))
+            .collect();
+        // Removing an edge moves the last one into its slot, so go from the back
+        outgoing.sort_by_key(|(e, _)| std::cmp::Reverse(*e));
+        for (edge, target) in outgoing{
+            let dependency = split.graph.remove_edge(edge).expect("edge was just listed");
+            split.graph.add_edge(to, target, dependency);
+        }
+        if let Some(parent) = split.get_parent(id){
+            split.connect_unchecked(parent, new_id, Dependency::new(DependencyType::Contains))?;
+            split.record_change(new_id, "parent", Value::Null, json!(parent));
+        }
+        split.connect_unchecked(id, new_id, Dependency::new(DependencyType::Blocks))?;
+
+        if let Some(Constraint::FinishNoLaterThan(d)) = split.get_constraint(id){
+            split.constraints.remove(&id);
+            split.constraints.insert(new_id, Constraint::FinishNoLaterThan(d));
+        }
+        for sprint in split.sprints.values_mut().filter(|s| s.get_items().contains(&id)){
+            sprint.add_item(new_id);
+        }
+        for release in split.releases.values_mut().filter(|r| r.contains(id)){
+            release.add_to_scope(new_id, Utc::now());
+        }
+        if let Some(priority) = split.get_priority(id){
+            split.priorities.insert(new_id, priority);
+        }
+        if let Some(rank) = split.get_rank(id){
+            split.backlog.insert(rank, new_id);
+        }
+        if let Some(description) = split.descriptions.get(&id).cloned(){
+            split.set_description(new_id, &description)?;
+        }
+        for risk in split.risks.values_mut().filter(|r| r.get_linked().contains(&id)){
+            risk.link(new_id);
+        }
+        for key_result in split.objectives.values_mut().flat_map(|o| o.get_key_results_mut().iter_mut()){
+            if let Some(weight) = key_result.get_contributions().get(&id).copied(){
+                key_result.set_contribution(id, weight * fraction)?;
+                key_result.set_contribution(new_id, weight * (1.0 - fraction))?;
+            }
+        }
+        *self = split;
+        Ok(new_id)
+    }
+
+    // Folds several tasks under the same parent into the one that starts
+    // first. It spans all of them, sums their points, cost and worklogs,
+    // takes the highest priority, and inherits every dependency, tag,
+    // external ref and membership of the others, which are removed. Fails
+    // without changes if the result would contain a cycle. Returns the surviving task's id.
+    pub fn merge_tasks(&mut self, ids: &[Uuid]) -> Result<Uuid,&'static str>{
+        let _span = trace::span(module_path!(), "merge_tasks", &[("tasks", &ids.len())]);
+        let mut tasks: Vec<&Node> = Vec::new();
+        for id in ids{
+            let node = self.get_node(*id).ok_or("The node does not exist in the graph")?;
+            if !matches!(node, Node::Tasks{..}){
+                return Err("Only tasks can be merged");
+            }
+            if !tasks.iter().any(|t| t.get_id() == *id){
+                tasks.push(node);
+            }
+        }
+        if tasks.len() < 2{
+            return Err("At least two distinct tasks are needed to merge");
+        }
+        let parent = self.get_parent(tasks[0].get_id());
+        if tasks.iter().any(|t| self.get_parent(t.get_id()) != parent){
+            return Err("Only tasks under the same parent can be merged");
+        }
+        tasks.sort_by_key(|t| t.get_timeline().map(|tl| tl.start));
+
+        let keep = tasks[0].get_id();
+        let kept_points = tasks[0].get_points();
+        let absorbed: Vec<Uuid> = tasks[1..].iter().map(|t| t.get_id()).collect();
+        let start = tasks.iter().filter_map(|t| t.get_timeline()).map(|tl| tl.start).min().expect("tasks have timelines");
+        let end = tasks.iter().filter_map(|t| t.get_timeline())
+            .map(|tl| tl.end.or_else(|| tl.duration.as_ref().map(|d| tl.start + d.to_time_delta())).unwrap_or(tl.start))
+            .max()
+            .expect("tasks have timelines");
+        let points = tasks.iter().any(|t| t.get_points().is_some())
+            .then(|| tasks.iter().filter_map(|t| t.get_points()).sum::<u32>());
+        // Summed in the base currency, whatever each part was in
+        let cost = tasks.iter().any(|t| t.get_estimated_cost().is_some())
+            .then(|| tasks.iter().filter_map(|t| self.cost_in_base(t.get_id())).sum::<f64>());
+        // Summed hours at the first allocation given, the survivor's if it has one
+        let efforts: Vec<Effort> = tasks.iter().filter_map(|t| self.get_effort(t.get_id())).collect();
+        let effort = efforts.first().map(|e| Effort{ hours: efforts.iter().map(|e| e.hours).sum(), ..*e });
+        let statuses: Vec<Status> = tasks.iter().filter_map(|t| t.get_status()).collect();
+        let status = if statuses.iter().all(|s| s.is_done()){
+            Status::Done
+        }else if statuses.contains(&Status::Blocked){
+            Status::Blocked
+        }else if statuses.iter().any(|s| *s != Status::NotStarted){
+            Status::InProgress
+        }else{
+            Status::NotStarted
+        };
+        // Started with the first part to start; finished with the last
+        let actuals = Actuals{
+            start: tasks.iter().filter_map(|t| self.actual_start(t.get_id())).min(),
+            finish: status.is_done().then(|| tasks.iter().filter_map(|t| self.actual_finish(t.get_id())).max()).flatten(),
+        };
+        let priority = tasks.iter().filter_map(|t| self.get_priority(t.get_id())).max();
+        let tags: Vec<Arc<str>> = tasks.iter().flat_map(|t| t.get_tags().iter().cloned()).collect();
+        let externals: Vec<ExternalRef> = tasks[1..].iter().flat_map(|t| t.get_external_refs().iter().cloned()).collect();
+
+        // Work on a copy so a cycle leaves the graph untouched
+        let mut merged = self.clone();
+        let keep_idx = merged.uid_to_index[&keep];
+        for id in &absorbed{
+            let idx = merged.uid_to_index[id];
+            let mut rewired: Vec<(NodeIndex,NodeIndex,Dependency)> = Vec::new();
+            for e in merged.graph.edges_directed(idx, petgraph::Direction::Outgoing){
+                rewired.push((keep_idx, e.target(), e.weight().clone()));
+            }
+            for e in merged.graph.edges_directed(idx, petgraph::Direction::Incoming){
+                rewired.push((e.source(), keep_idx, e.weight().clone()));
+            }
+            for (from, to, dependency) in rewired{
+                let inside = |i: NodeIndex| i == keep_idx || absorbed.contains(&merged.graph[i].get_id());
+                let clashes = merged.check_parallel_edge(from, to, dependency.kind).is_err();
+                if dependency.kind == DependencyType::Contains || (inside(from) && inside(to)) || clashes{
+                    continue;
+                }
+                merged.graph.add_edge(from, to, dependency);
+            }
+
+            if let Some(worklogs) = merged.worklogs.remove(id){
+                merged.worklogs.entry(keep).or_default().extend(worklogs);
+            }
+            if let Some(comments) = merged.comments.remove(id){
+                merged.comments.entry(keep).or_default().extend(comments);
+            }
+            if let Some(description) = merged.descriptions.remove(id){
+                merged.descriptions.entry(keep).or_insert(description);
+            }
+            for sprint in merged.sprints.values_mut().filter(|s| s.get_items().contains(id)){
+                sprint.add_item(keep);
+            }
+            for release in merged.releases.values_mut().filter(|r| r.contains(*id)){
+                release.add_to_scope(keep, Utc::now());
+            }
+            for risk in merged.risks.values_mut().filter(|r| r.get_linked().contains(id)){
+                risk.link(keep);
+            }
+            for key_result in merged.objectives.values_mut().flat_map(|o| o.get_key_results_mut().iter_mut()){
+                if let Some(weight) = key_result.get_contributions().get(id).copied(){
+                    let total = weight + key_result.get_contributions().get(&keep).copied().unwrap_or(0.0);
+                    key_result.set_contribution(keep, total)?;
+                }
+            }
+        }
+        // The survivor takes the best backlog position of the merged tasks
+        if let Some(top) = merged.backlog.iter().position(|b| *b == keep || absorbed.contains(b)){
+            merged.backlog.retain(|b| *b != keep);
+            merged.backlog.insert(top, keep);
+        }
+        for id in &absorbed{
+            merged.remove_node_entry(*id);
+        }
+        // The votes were for the survivor alone, not the merged work
+        merged.estimates.remove(&keep);
+        if cost.is_some(){
+            merged.cost_currencies.remove(&keep);
+        }
+
+        merged.update_indexed(keep, |node, interner| {
+            node.set_timeline(Timeline::from_start_end(start, end));
+            if let Some(points) = points{
+                node.set_points(points)?;
+            }
+            if let Some(cost) = cost{
+                node.set_estimated_cost(cost)?;
+            }
+            for tag in &tags{
+                node.add_tag(interner.intern(tag));
+            }
+            for external in &externals{
+                node.add_external_ref(external.clone());
+            }
+            Ok(())
+        })?;
+        if let Some(points) = points{
+            merged.record_change(keep, "points", json!(kept_points), json!(points));
+        }
+        merged.set_status(keep, status)?;
+        if actuals.is_empty(){
+            merged.actuals.remove(&keep);
+        }else{
+            merged.actuals.insert(keep, actuals);
+        }
+        if let Some(effort) = effort{
+            merged.set_effort(keep, effort)?;
+        }
+        if let Some(priority) = priority{
+            merged.priorities.insert(keep, priority);
+        }
+        merged.rollups.clear();
+
+        if !merged.is_acyclic(){
+            return Err("Merging these tasks would create a cycle");
+        }
+        *self = merged;
+        Ok(keep)
+    }
+
+    // Drops a node, its edges and every reference to it; the last node moves
+    // into the freed petgraph slot, so its index entry is patched up. It is
+    // logged as losing its points and then its parent, so scope churn sees
+    // the work leave.
+    fn remove_node_entry(&mut self, id: Uuid){
+        if let Some(node) = self.get_node(id){
+            let points = node.get_points();
+            self.record_change(id, "points", json!(points), Value::Null);
+        }
+        if let Some(parent) = self.get_parent(id){
+            self.record_change(id, "parent", json!(parent), Value::Null);
+        }
+        let Some(idx) = self.uid_to_index.remove(&id) else {
+            return;
+        };
+        trace::event(Level::Debug, module_path!(), "node removed", &[("node", &id)]);
+        if let Some(node) = self.graph.remove_node(idx){
+            self.indexes.remove(&node);
+        }
+        if let Some(moved) = self.graph.node_weight(idx){
+            self.uid_to_index.insert(moved.get_id(), idx);
+        }
+        self.forget_node(id);
+    }
+
+    // Drops everything recorded against a node that is no longer in the graph
+    fn forget_node(&mut self, id: Uuid){
+        self.keys.remove(id);
+        self.constraints.remove(&id);
+        self.priorities.remove(&id);
+        self.backlog.retain(|b| *b != id);
+        self.worklogs.remove(&id);
+        self.estimates.remove(&id);
+        self.descriptions.remove(&id);
+        self.comments.remove(&id);
+        self.efforts.remove(&id);
+        self.actuals.remove(&id);
+        self.progress.remove(&id);
+        self.checklists.remove(&id);
+        self.approvals.retain(|a| a.subject != id);
+        self.blocked_periods.remove(&id);
+        self.cost_currencies.remove(&id);
+        self.decision_links.remove(&id);
+        self.decision_links.retain(|_, linked| {
+            linked.remove(&id);
+            !linked.is_empty()
+        });
+        for period in self.blocked_periods.values_mut().flatten(){
+            if let Some(reason) = period.reason.as_mut().filter(|r| r.blocker == Some(Blocker::Node(id))){
+                reason.blocker = None;
+            }
+        }
+        self.remote_dependencies.retain(|d| d.node != id);
+        self.search.remove(id);
+        self.states.remove(&id);
+        for sprint in self.sprints.values_mut(){
+            sprint.remove_item(id);
+        }
+        for release in self.releases.values_mut(){
+            release.remove_from_scope(id, Utc::now());
+        }
+        for risk in self.risks.values_mut(){
+            risk.unlink(id);
+        }
+        for key_result in self.objectives.values_mut().flat_map(|o| o.get_key_results_mut().iter_mut()){
+            key_result.remove_contribution(id);
+        }
+        self.settings.publish_targets.retain(|t| t.project != id);
+    }
+
+    // Nodes referenced from the tables kept next to the graph, by table
+    fn referenced_nodes(&self) -> Vec<(&'static str,Uuid)>{
+        let mut refs: Vec<(&'static str,Uuid)> = Vec::new();
+        refs.extend(self.constraints.keys().map(|id| ("constraints", *id)));
+        refs.extend(self.priorities.keys().map(|id| ("priorities", *id)));
+        refs.extend(self.backlog.iter().map(|id| ("backlog", *id)));
+        refs.extend(self.worklogs.keys().map(|id| ("worklogs", *id)));
+        refs.extend(self.estimates.keys().map(|id| ("estimates", *id)));
+        refs.extend(self.descriptions.keys().map(|id| ("descriptions", *id)));
+        refs.extend(self.comments.keys().map(|id| ("comments", *id)));
+        refs.extend(self.efforts.keys().map(|id| ("efforts", *id)));
+        refs.extend(self.actuals.keys().map(|id| ("actual dates", *id)));
+        refs.extend(self.progress.keys().map(|id| ("progress", *id)));
+        refs.extend(self.checklists.keys().map(|id| ("checklists", *id)));
+        refs.extend(self.blocked_periods.keys().map(|id| ("blocked periods", *id)));
+        refs.extend(self.decision_links.iter().flat_map(|(d, l)| std::iter::once(d).chain(l)).map(|id| ("decision links", *id)));
+        refs.extend(self.cost_currencies.keys().map(|id| ("cost currencies", *id)));
+        refs.extend(self.blocked_periods.values().flatten().filter_map(|p| match p.reason.as_ref()?.blocker{
+            Some(Blocker::Node(id)) => Some(("blockers", id)),
+            _ => None,
+        }));
+        refs.extend(self.approvals.iter().filter(|a| !self.releases.contains_key(&a.subject)).map(|a| ("approvals", a.subject)));
+        refs.extend(self.remote_dependencies.iter().map(|d| ("remote dependencies", d.node)));
+        refs.extend(self.states.keys().map(|id| ("workflow states", *id)));
+        refs.extend(self.sprints.values().flat_map(|s| s.get_items().iter().map(|id| ("sprints", *id))));
+        refs.extend(self.releases.values().flat_map(|r| r.get_scope().iter().map(|id| ("releases", *id))));
+        refs.extend(self.risks.values().flat_map(|r| r.get_linked().iter().map(|id| ("risks", *id))));
+        refs.extend(self.objectives.values()
+            .flat_map(|o| o.get_key_results().iter())
+            .flat_map(|kr| kr.get_contributions().keys().map(|id| ("key results", *id))));
+        refs.extend(self.settings.publish_targets.iter().map(|t| ("publish targets", t.project)));
+        refs.sort();
+        refs.dedup();
+        refs
+    }
+
+    // What is inconsistent between the nodes and the graph's bookkeeping,
+    // empty for a sound graph
+    pub fn check_integrity(&self) -> Vec<IntegrityIssue>{
+        let mut issues = Vec::new();
+
+        let mut seen: HashMap<Uuid,usize> = HashMap::new();
+        for node in self.graph.node_weights(){
+            *seen.entry(node.get_id()).or_default() += 1;
+        }
+        let mut shared: Vec<(Uuid,usize)> = seen.iter().filter(|(_, n)| **n > 1).map(|(id, n)| (*id, *n)).collect();
+        shared.sort();
+        issues.extend(shared.into_iter().map(|(id, count)| IntegrityIssue::DuplicateId{ id, count }));
+
+        let mut stale: Vec<(Uuid,usize)> = self.uid_to_index.iter()
+            .filter(|(id, idx)| self.graph.node_weight(**idx).is_none_or(|n| n.get_id() != **id))
+            .map(|(id, idx)| (*id, idx.index()))
+            .collect();
+        stale.sort();
+        issues.extend(stale.into_iter().map(|(id, index)| IntegrityIssue::StaleIndex{ id, index }));
+        for idx in self.graph.node_indices(){
+            let id = self.graph[idx].get_id();
+            if self.uid_to_index.get(&id) != Some(&idx) && seen[&id] == 1{
+                issues.push(IntegrityIssue::Unindexed{ id, index: idx.index() });
+            }
+        }
+
+        let mut edges: Vec<(NodeIndex,NodeIndex,DependencyType)> = Vec::new();
+        for edge in self.graph.edge_references(){
+            let (from, to, kind) = (edge.source(), edge.target(), edge.weight().kind);
+            if from == to{
+                issues.push(IntegrityIssue::SelfLoop{ id: self.graph[from].get_id() });
+            }else if edges.contains(&(from, to, kind)){
+                issues.push(IntegrityIssue::DuplicateEdge{ from: self.graph[from].get_id(), to: self.graph[to].get_id(), kind });
+            }else{
+                edges.push((from, to, kind));
+            }
+        }
+        // Self-loops are reported on their own; look for longer cycles
+        let without_loops = EdgeFiltered::from_fn(&self.graph, |e| e.source() != e.target());
+        if is_cyclic_directed(&without_loops){
+            issues.push(IntegrityIssue::Cycle);
+        }
+
+        let mut keyed: Vec<Uuid> = self.keys.uids().collect();
+        keyed.sort();
+        issues.extend(keyed.iter().filter(|id| !seen.contains_key(id)).map(|id| IntegrityIssue::StaleKey{ id: *id }));
+        issues.extend(self.graph.node_weights()
+            .filter(|n| self.keys.get_key(n.get_id()).is_none())
+            .map(|n| IntegrityIssue::MissingKey{ id: n.get_id() }));
+
+        issues.extend(self.referenced_nodes().into_iter()
+            .filter(|(_, id)| !seen.contains_key(id))
+            .map(|(table, id)| IntegrityIssue::Dangling{ table, id }));
+        issues
+    }
+
+    // Fixes what check_integrity found and returns it: rebuilds the index
+    // from the nodes, drops self-loops, repeated edges and references to
+    // missing nodes, and keys unkeyed nodes. Nodes sharing an id and cycles
+    // need a person to decide, so nothing is changed when there are any.
+    pub fn repair(&mut self) -> Result<Vec<IntegrityIssue>,&'static str>{
+        let issues = self.check_integrity();
+        if issues.contains(&IntegrityIssue::Cycle){
+            return Err("The dependencies contain a cycle; remove one of its edges by hand");
+        }
+        if issues.is_empty(){
+            return Ok(issues);
+        }
+
+        self.rebuild_index()?;
+
+        let mut kept: Vec<(NodeIndex,NodeIndex,DependencyType)> = Vec::new();
+        let mut extra: Vec<EdgeIndex> = Vec::new();
+        for edge in self.graph.edge_references(){
+            let key = (edge.source(), edge.target(), edge.weight().kind);
+            if edge.source() == edge.target() || kept.contains(&key){
+                extra.push(edge.id());
+            }else{
+                kept.push(key);
+            }
+        }
+        // Removing an edge moves the last one into its slot, so go from the back
+        extra.sort_by_key(|e| std::cmp::Reverse(*e));
+        for edge in extra{
+            self.graph.remove_edge(edge);
+        }
+
+        self.prune_keys();
+        for idx in self.graph.node_indices(){
+            let node = &self.graph[idx];
+            if self.keys.get_key(node.get_id()).is_none(){
+                self.keys.assign(node.get_key_prefix(), node.get_id());
+            }
+        }
+        let missing: Vec<Uuid> = self.referenced_nodes().into_iter()
+            .map(|(_, id)| id)
+            .filter(|id| !self.uid_to_index.contains_key(id))
+            .collect();
+        for id in missing{
+            self.forget_node(id);
+        }
+        self.rebuild_caches();
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::core::NodeBuilder;
+
+    fn tasks(graph: &mut ProjectGraph, count: u128) -> Vec<Uuid>{
+        (1..=count).map(|n| {
+            let timeline = Timeline::from_start_end(Utc::now(), Utc::now() + TimeDelta::days(1));
+            let task = NodeBuilder::new().with_id(Uuid::from_u128(n)).with_name(format!("Task {}", n))
+                .with_timeline(timeline).build_tasks().unwrap();
+            graph.add_node(&task).unwrap();
+            task.get_id()
+        }).collect()
+    }
+
+    #[test]
+    fn a_node_cannot_depend_on_itself(){
+        let mut graph = ProjectGraph::new();
+        let ids = tasks(&mut graph, 1);
+        assert_eq!(graph.connect(ids[0], ids[0], DependencyType::Blocks), Err(SELF_DEPENDENCY));
+    }
+
+    #[test]
+    fn the_same_dependency_cannot_be_added_twice(){
+        let mut graph = ProjectGraph::new();
+        let ids = tasks(&mut graph, 2);
+        graph.connect(ids[0], ids[1], DependencyType::Blocks).unwrap();
+        assert_eq!(graph.connect(ids[0], ids[1], DependencyType::Blocks), Err(DUPLICATE_DEPENDENCY));
+
+        let settings = ProjectSettings{ parallel_edges: ParallelEdges::Deny, ..graph.get_settings().clone() };
+        graph.set_settings(settings).unwrap();
+        assert_eq!(graph.connect(ids[0], ids[1], DependencyType::ResourcesRequiredFor), Err(PARALLEL_DEPENDENCY));
+    }
+}
@@ -0,0 +1,103 @@
+// Hierarchy rules - which kinds of work may contain which, and how deep a
+// tree of work may go
+//
+// Organizations structure work differently: some put tasks straight under
+// epics, some want every story inside an epic. The rules name, by key prefix,
+// the kinds each kind may contain directly; the defaults are the built-in
+// hierarchy (projects hold projects, epics and stories, epics hold stories,
+// stories hold tasks). Work only ever contains work at its own level or
+// below, and specs and decisions keep their fixed places.
+//
+// New edges are checked against the rules; edges already in a file are
+// reported by validation instead, so tightening the rules never stops a
+// file from loading.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+// Work kinds from the top of the hierarchy down
+pub const WORK_KINDS: [&str; 4] = ["PROJ", "EPIC", "STORY", "TASK"];
+
+// How far down the hierarchy a kind sits; None for specs and decisions
+pub fn level(prefix: &str) -> Option<usize>{
+    WORK_KINDS.iter().position(|k| *k == prefix)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HierarchyRules{
+    // Key prefix -> prefixes of the work it may contain directly
+    pub contains: BTreeMap<String,BTreeSet<String>>,
+    // Most levels of work below a top-level node (Project > Epic > Story >
+    // Task is 3); unlimited without one
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+impl Default for HierarchyRules{
+    fn default() -> Self{
+        "PROJ>PROJ,PROJ>EPIC,PROJ>STORY,EPIC>STORY,STORY>TASK".parse().expect("valid default hierarchy")
+    }
+}
+
+impl HierarchyRules{
+    pub fn is_default(&self) -> bool{
+        *self == HierarchyRules::default()
+    }
+
+    pub fn allows(&self, parent: &str, child: &str) -> bool{
+        self.contains.get(parent).is_some_and(|children| children.contains(child))
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        for (parent, children) in &self.contains{
+            let parent_level = level(parent).ok_or("Hierarchy rules are between PROJ, EPIC, STORY and TASK")?;
+            for child in children{
+                let child_level = level(child).ok_or("Hierarchy rules are between PROJ, EPIC, STORY and TASK")?;
+                if child_level < parent_level{
+                    return Err("Work can only contain work at its own level or below");
+                }
+            }
+        }
+        if self.max_depth == Some(0){
+            return Err("A hierarchy depth limit must allow at least one level");
+        }
+        Ok(())
+    }
+}
+
+// "PROJ>EPIC, EPIC>STORY, STORY>TASK"
+impl fmt::Display for HierarchyRules{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        let pairs: Vec<String> = self.contains.iter()
+            .flat_map(|(parent, children)| children.iter().map(move |child| format!("{}>{}", parent, child)))
+            .collect();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
+
+// Pairs of key prefixes or kind names, e.g. "epic>task,story>task"; the
+// depth limit is left unset
+impl FromStr for HierarchyRules{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        let prefix = |kind: &str| -> Result<String,&'static str>{
+            let kind = kind.trim().to_ascii_uppercase();
+            let kind = match kind.as_str(){
+                "PROJECT" => "PROJ",
+                other => other,
+            };
+            level(kind).map(|_| kind.to_string()).ok_or("Hierarchy rules are between project, epic, story and task")
+        };
+        let mut contains: BTreeMap<String,BTreeSet<String>> = BTreeMap::new();
+        for pair in s.split(',').filter(|p| !p.trim().is_empty()){
+            let (parent, child) = pair.split_once('>').ok_or("Write hierarchy rules as PARENT>CHILD pairs")?;
+            contains.entry(prefix(parent)?).or_default().insert(prefix(child)?);
+        }
+        let rules = HierarchyRules{ contains, max_depth: None };
+        rules.validate()?;
+        Ok(rules)
+    }
+}
@@ -0,0 +1,90 @@
+// Holiday feeds - public holidays read from iCalendar (.ics) or CSV text
+//
+// iCalendar: every VEVENT's DTSTART (date or date-time) through the day
+// before its DTEND, named by its SUMMARY. CSV: `date,name` rows with ISO
+// dates; a header row and blank lines are skipped.
+
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday{
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+// Folded lines (continued with a leading space or tab) joined back up
+fn unfold(text: &str) -> Vec<String>{
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines(){
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()){
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// The date of a DTSTART/DTEND value: 20260101 or 20260101T090000[Z]
+fn ics_date(value: &str) -> Result<NaiveDate,&'static str>{
+    let digits = value.get(..8).ok_or("Invalid date in the calendar feed")?;
+    NaiveDate::parse_from_str(digits, "%Y%m%d").map_err(|_| "Invalid date in the calendar feed")
+}
+
+fn ics_unescape(value: &str) -> String{
+    value.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+pub fn parse_ics(text: &str) -> Result<Vec<Holiday>,&'static str>{
+    let mut holidays = Vec::new();
+    let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, String)> = None;
+    for line in unfold(text){
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as ;VALUE=DATE or ;TZID=... don't matter for whole days
+        let name = property.split(';').next().unwrap_or_default().to_ascii_uppercase();
+        match (name.as_str(), event.as_mut()){
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => event = Some((None, None, String::new())),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let (start, end, summary) = event.take().expect("inside an event");
+                let start = start.ok_or("A calendar event has no DTSTART")?;
+                // DTEND is exclusive; a missing or same-day end means one day
+                let end = end.filter(|e| *e > start).and_then(|e| e.pred_opt()).unwrap_or(start);
+                for date in start.iter_days().take_while(|d| *d <= end){
+                    holidays.push(Holiday{ date, name: summary.clone() });
+                }
+            }
+            ("DTSTART", Some(e)) => e.0 = Some(ics_date(value)?),
+            ("DTEND", Some(e)) => e.1 = Some(ics_date(value)?),
+            ("SUMMARY", Some(e)) => e.2 = ics_unescape(value),
+            _ => {}
+        }
+    }
+    if event.is_some(){
+        return Err("The calendar feed ends inside an event");
+    }
+    holidays.sort_by_key(|h| h.date);
+    Ok(holidays)
+}
+
+pub fn parse_csv(text: &str) -> Result<Vec<Holiday>,&'static str>{
+    let mut holidays = Vec::new();
+    for (i, line) in text.lines().enumerate(){
+        let line = line.trim();
+        if line.is_empty(){
+            continue;
+        }
+        let (date, name) = line.split_once(',').unwrap_or((line, ""));
+        let date = date.trim().trim_matches('"');
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            if i == 0{
+                continue;
+            }
+            return Err("Invalid date in the holiday list; expected YYYY-MM-DD");
+        };
+        holidays.push(Holiday{ date, name: name.trim().trim_matches('"').to_string() });
+    }
+    holidays.sort_by_key(|h| h.date);
+    Ok(holidays)
+}
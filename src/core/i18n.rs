@@ -0,0 +1,451 @@
+// Report language - message catalogs for the text reports and exports print,
+// so a status report can go out in the language of the team reading it
+//
+// Messages are looked up by key ("report-title") and may hold {name}
+// placeholders. English, German, French and Spanish are built in; a project
+// adds languages or rewords built-in messages with catalogs of its own, kept
+// in the settings. A message a catalog lacks falls back to English.
+
+use super::status::Status;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const EN: &[(&str, &str)] = &[
+    ("report-title", "Status: {title}"),
+    ("report-completion", "Completion"),
+    ("report-status", "Status"),
+    ("report-items", "Items"),
+    ("report-by", "By {grouping}"),
+    ("report-lane", "Lane"),
+    ("report-scope-churn", "Scope churn"),
+    ("report-week-of", "Week of"),
+    ("report-added", "Added"),
+    ("report-removed", "Removed"),
+    ("report-net", "Net"),
+    ("report-churn-week", "Week of {week}: +{added} / -{removed}"),
+    ("report-decisions", "Decisions"),
+    ("report-open", "open"),
+    ("report-affects", "affects {keys}"),
+    ("report-top-risks", "Top risks (total exposure {score})"),
+    ("report-no-risks", "No open risks."),
+    ("report-risk-score", "score {score}"),
+    ("report-mitigation", "mitigation: {text}"),
+    ("status-not-started", "Not Started"),
+    ("status-in-progress", "In Progress"),
+    ("status-blocked", "Blocked"),
+    ("status-done", "Done"),
+    ("group-epic", "epic"),
+    ("group-owner", "owner"),
+    ("group-tag", "tag"),
+    ("group-priority", "priority"),
+    ("compare-title", "Scenario comparison: {baseline} vs {scenario}"),
+    ("compare-change", "Change"),
+    ("compare-end-date", "End date"),
+    ("compare-budget", "Budget"),
+    ("compare-spent", "Spent"),
+    ("compare-peak", "Peak daily load"),
+    ("compare-conflicts", "Schedule conflicts"),
+    ("compare-moved", "End dates that move"),
+    ("compare-none-moved", "No project or epic ends on a different date."),
+    ("compare-item", "Item"),
+    ("compare-busiest", "Busiest day per owner"),
+    ("compare-owner", "Owner"),
+    ("compare-critical-path", "Critical path"),
+    ("compare-now-critical", "Now critical: {items}"),
+    ("compare-no-longer-critical", "No longer critical: {items}"),
+    ("compare-days", "{days} days"),
+    ("brief-title", "Project brief: {title}"),
+    ("brief-as-of", "As of {date}"),
+    ("brief-objective", "Objective"),
+    ("brief-no-objective", "No objective written down yet."),
+    ("brief-timeline", "Timeline"),
+    ("brief-start", "Start"),
+    ("brief-planned-end", "Planned end"),
+    ("brief-forecast-end", "Forecast end"),
+    ("brief-on-plan", "on plan"),
+    ("brief-behind", "{days} days behind plan"),
+    ("brief-ahead", "{days} days ahead of plan"),
+    ("brief-current-status", "Current status"),
+    ("brief-health", "Health"),
+    ("brief-healthy", "healthy"),
+    ("brief-at-risk", "at risk"),
+    ("brief-critical", "critical"),
+    ("brief-milestones", "Milestones"),
+    ("brief-no-milestones", "No releases or epic ends planned."),
+    ("brief-milestone", "Milestone"),
+    ("brief-date", "Date"),
+    ("brief-reached", "reached"),
+    ("brief-due", "due"),
+    ("brief-overdue", "overdue"),
+    ("brief-team", "Team"),
+    ("brief-no-team", "No work has an owner yet."),
+    ("brief-person", "Person"),
+    ("brief-open", "Open"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("report-title", "Status: {title}"),
+    ("report-completion", "Fertigstellung"),
+    ("report-status", "Status"),
+    ("report-items", "Einträge"),
+    ("report-by", "Nach {grouping}"),
+    ("report-lane", "Bahn"),
+    ("report-scope-churn", "Umfangsänderungen"),
+    ("report-week-of", "Woche ab"),
+    ("report-added", "Hinzugefügt"),
+    ("report-removed", "Entfernt"),
+    ("report-net", "Netto"),
+    ("report-churn-week", "Woche ab {week}: +{added} / -{removed}"),
+    ("report-decisions", "Entscheidungen"),
+    ("report-open", "offen"),
+    ("report-affects", "betrifft {keys}"),
+    ("report-top-risks", "Größte Risiken (Gesamtexposition {score})"),
+    ("report-no-risks", "Keine offenen Risiken."),
+    ("report-risk-score", "Bewertung {score}"),
+    ("report-mitigation", "Gegenmaßnahme: {text}"),
+    ("status-not-started", "Nicht begonnen"),
+    ("status-in-progress", "In Arbeit"),
+    ("status-blocked", "Blockiert"),
+    ("status-done", "Erledigt"),
+    ("group-epic", "Epic"),
+    ("group-owner", "Verantwortlichen"),
+    ("group-tag", "Tag"),
+    ("group-priority", "Priorität"),
+    ("compare-title", "Szenariovergleich: {baseline} vs. {scenario}"),
+    ("compare-change", "Änderung"),
+    ("compare-end-date", "Enddatum"),
+    ("compare-budget", "Budget"),
+    ("compare-spent", "Ausgegeben"),
+    ("compare-peak", "Höchste Tageslast"),
+    ("compare-conflicts", "Terminkonflikte"),
+    ("compare-moved", "Verschobene Enddaten"),
+    ("compare-none-moved", "Kein Projekt und kein Epic endet an einem anderen Datum."),
+    ("compare-item", "Element"),
+    ("compare-busiest", "Auslastungsspitze je Verantwortlichen"),
+    ("compare-owner", "Verantwortlich"),
+    ("compare-critical-path", "Kritischer Pfad"),
+    ("compare-now-critical", "Neu kritisch: {items}"),
+    ("compare-no-longer-critical", "Nicht mehr kritisch: {items}"),
+    ("compare-days", "{days} Tage"),
+    ("brief-title", "Projektsteckbrief: {title}"),
+    ("brief-as-of", "Stand {date}"),
+    ("brief-objective", "Ziel"),
+    ("brief-no-objective", "Noch kein Ziel festgehalten."),
+    ("brief-timeline", "Zeitplan"),
+    ("brief-start", "Beginn"),
+    ("brief-planned-end", "Geplantes Ende"),
+    ("brief-forecast-end", "Prognostiziertes Ende"),
+    ("brief-on-plan", "im Plan"),
+    ("brief-behind", "{days} Tage hinter Plan"),
+    ("brief-ahead", "{days} Tage vor Plan"),
+    ("brief-current-status", "Aktueller Stand"),
+    ("brief-health", "Gesundheit"),
+    ("brief-healthy", "gesund"),
+    ("brief-at-risk", "gefährdet"),
+    ("brief-critical", "kritisch"),
+    ("brief-milestones", "Meilensteine"),
+    ("brief-no-milestones", "Keine Releases oder Epic-Enden geplant."),
+    ("brief-milestone", "Meilenstein"),
+    ("brief-date", "Datum"),
+    ("brief-reached", "erreicht"),
+    ("brief-due", "fällig"),
+    ("brief-overdue", "überfällig"),
+    ("brief-team", "Team"),
+    ("brief-no-team", "Noch keine Arbeit hat einen Verantwortlichen."),
+    ("brief-person", "Person"),
+    ("brief-open", "Offen"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("report-title", "Statut : {title}"),
+    ("report-completion", "Avancement"),
+    ("report-status", "Statut"),
+    ("report-items", "Éléments"),
+    ("report-by", "Par {grouping}"),
+    ("report-lane", "Couloir"),
+    ("report-scope-churn", "Évolution du périmètre"),
+    ("report-week-of", "Semaine du"),
+    ("report-added", "Ajoutés"),
+    ("report-removed", "Retirés"),
+    ("report-net", "Net"),
+    ("report-churn-week", "Semaine du {week} : +{added} / -{removed}"),
+    ("report-decisions", "Décisions"),
+    ("report-open", "ouverte"),
+    ("report-affects", "concerne {keys}"),
+    ("report-top-risks", "Principaux risques (exposition totale {score})"),
+    ("report-no-risks", "Aucun risque ouvert."),
+    ("report-risk-score", "score {score}"),
+    ("report-mitigation", "atténuation : {text}"),
+    ("status-not-started", "Non commencé"),
+    ("status-in-progress", "En cours"),
+    ("status-blocked", "Bloqué"),
+    ("status-done", "Terminé"),
+    ("group-epic", "epic"),
+    ("group-owner", "responsable"),
+    ("group-tag", "étiquette"),
+    ("group-priority", "priorité"),
+    ("compare-title", "Comparaison de scénarios : {baseline} / {scenario}"),
+    ("compare-change", "Écart"),
+    ("compare-end-date", "Date de fin"),
+    ("compare-budget", "Budget"),
+    ("compare-spent", "Dépensé"),
+    ("compare-peak", "Charge journalière maximale"),
+    ("compare-conflicts", "Conflits de planning"),
+    ("compare-moved", "Dates de fin qui changent"),
+    ("compare-none-moved", "Aucun projet ni epic ne change de date de fin."),
+    ("compare-item", "Élément"),
+    ("compare-busiest", "Jour le plus chargé par responsable"),
+    ("compare-owner", "Responsable"),
+    ("compare-critical-path", "Chemin critique"),
+    ("compare-now-critical", "Désormais critique : {items}"),
+    ("compare-no-longer-critical", "Plus critique : {items}"),
+    ("compare-days", "{days} jours"),
+    ("brief-title", "Fiche projet : {title}"),
+    ("brief-as-of", "Au {date}"),
+    ("brief-objective", "Objectif"),
+    ("brief-no-objective", "Aucun objectif n'est encore formulé."),
+    ("brief-timeline", "Calendrier"),
+    ("brief-start", "Début"),
+    ("brief-planned-end", "Fin prévue"),
+    ("brief-forecast-end", "Fin estimée"),
+    ("brief-on-plan", "conforme au plan"),
+    ("brief-behind", "{days} jours de retard sur le plan"),
+    ("brief-ahead", "{days} jours d'avance sur le plan"),
+    ("brief-current-status", "État actuel"),
+    ("brief-health", "Santé"),
+    ("brief-healthy", "bonne"),
+    ("brief-at-risk", "à risque"),
+    ("brief-critical", "critique"),
+    ("brief-milestones", "Jalons"),
+    ("brief-no-milestones", "Aucune version ni fin d'epic prévue."),
+    ("brief-milestone", "Jalon"),
+    ("brief-date", "Date"),
+    ("brief-reached", "atteint"),
+    ("brief-due", "à venir"),
+    ("brief-overdue", "en retard"),
+    ("brief-team", "Équipe"),
+    ("brief-no-team", "Aucun travail n'a encore de responsable."),
+    ("brief-person", "Personne"),
+    ("brief-open", "Ouverts"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("report-title", "Estado: {title}"),
+    ("report-completion", "Avance"),
+    ("report-status", "Estado"),
+    ("report-items", "Elementos"),
+    ("report-by", "Por {grouping}"),
+    ("report-lane", "Carril"),
+    ("report-scope-churn", "Cambios de alcance"),
+    ("report-week-of", "Semana del"),
+    ("report-added", "Añadidos"),
+    ("report-removed", "Eliminados"),
+    ("report-net", "Neto"),
+    ("report-churn-week", "Semana del {week}: +{added} / -{removed}"),
+    ("report-decisions", "Decisiones"),
+    ("report-open", "abierta"),
+    ("report-affects", "afecta a {keys}"),
+    ("report-top-risks", "Riesgos principales (exposición total {score})"),
+    ("report-no-risks", "No hay riesgos abiertos."),
+    ("report-risk-score", "puntuación {score}"),
+    ("report-mitigation", "mitigación: {text}"),
+    ("status-not-started", "Sin empezar"),
+    ("status-in-progress", "En curso"),
+    ("status-blocked", "Bloqueado"),
+    ("status-done", "Hecho"),
+    ("group-epic", "épica"),
+    ("group-owner", "responsable"),
+    ("group-tag", "etiqueta"),
+    ("group-priority", "prioridad"),
+    ("compare-title", "Comparación de escenarios: {baseline} frente a {scenario}"),
+    ("compare-change", "Cambio"),
+    ("compare-end-date", "Fecha de fin"),
+    ("compare-budget", "Presupuesto"),
+    ("compare-spent", "Gastado"),
+    ("compare-peak", "Carga diaria máxima"),
+    ("compare-conflicts", "Conflictos de planificación"),
+    ("compare-moved", "Fechas de fin que cambian"),
+    ("compare-none-moved", "Ningún proyecto ni épica termina en otra fecha."),
+    ("compare-item", "Elemento"),
+    ("compare-busiest", "Día de más carga por responsable"),
+    ("compare-owner", "Responsable"),
+    ("compare-critical-path", "Ruta crítica"),
+    ("compare-now-critical", "Ahora crítico: {items}"),
+    ("compare-no-longer-critical", "Ya no es crítico: {items}"),
+    ("compare-days", "{days} días"),
+    ("brief-title", "Ficha del proyecto: {title}"),
+    ("brief-as-of", "A {date}"),
+    ("brief-objective", "Objetivo"),
+    ("brief-no-objective", "Aún no hay un objetivo por escrito."),
+    ("brief-timeline", "Calendario"),
+    ("brief-start", "Inicio"),
+    ("brief-planned-end", "Fin previsto"),
+    ("brief-forecast-end", "Fin estimado"),
+    ("brief-on-plan", "según lo previsto"),
+    ("brief-behind", "{days} días de retraso sobre el plan"),
+    ("brief-ahead", "{days} días de adelanto sobre el plan"),
+    ("brief-current-status", "Estado actual"),
+    ("brief-health", "Salud"),
+    ("brief-healthy", "buena"),
+    ("brief-at-risk", "en riesgo"),
+    ("brief-critical", "crítica"),
+    ("brief-milestones", "Hitos"),
+    ("brief-no-milestones", "No hay versiones ni finales de épica previstos."),
+    ("brief-milestone", "Hito"),
+    ("brief-date", "Fecha"),
+    ("brief-reached", "alcanzado"),
+    ("brief-due", "pendiente"),
+    ("brief-overdue", "vencido"),
+    ("brief-team", "Equipo"),
+    ("brief-no-team", "Ningún trabajo tiene responsable todavía."),
+    ("brief-person", "Persona"),
+    ("brief-open", "Abiertos"),
+];
+
+pub const BUILT_IN_LANGUAGES: [&str; 4] = ["en", "de", "fr", "es"];
+
+fn built_in(language: &str) -> Option<&'static [(&'static str, &'static str)]>{
+    match language{
+        "en" => Some(EN),
+        "de" => Some(DE),
+        "fr" => Some(FR),
+        "es" => Some(ES),
+        _ => None,
+    }
+}
+
+// "de-AT" -> "de"
+fn primary(language: &str) -> String{
+    language.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase()
+}
+
+pub fn message_keys() -> impl Iterator<Item = &'static str>{
+    EN.iter().map(|(key, _)| *key)
+}
+
+fn placeholders(text: &str) -> BTreeSet<&str>{
+    text.split('{').skip(1).filter_map(|rest| rest.split_once('}').map(|(name, _)| name)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct I18nSettings{
+    // Language reports are written in, e.g. "de" or "pt-BR"
+    pub language: String,
+    // Language -> key -> message, added to or overriding the built-in ones
+    #[serde(default)]
+    pub catalogs: BTreeMap<String,BTreeMap<String,String>>,
+}
+
+impl Default for I18nSettings{
+    fn default() -> Self{
+        I18nSettings{ language: "en".to_string(), catalogs: BTreeMap::new() }
+    }
+}
+
+impl I18nSettings{
+    pub fn is_default(&self) -> bool{
+        *self == I18nSettings::default()
+    }
+
+    pub fn has_language(&self, language: &str) -> bool{
+        built_in(&primary(language)).is_some() || self.catalogs.contains_key(language) || self.catalogs.contains_key(&primary(language))
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if !self.has_language(&self.language){
+            return Err("Reports can only be written in a built-in language or one with a catalog");
+        }
+        let english: BTreeMap<&str,&str> = EN.iter().copied().collect();
+        for messages in self.catalogs.values(){
+            for (key, text) in messages{
+                let source = english.get(key.as_str()).ok_or("A catalog has a message key reports do not use")?;
+                if !placeholders(text).is_subset(&placeholders(source)){
+                    return Err("A catalog message uses a placeholder its English original does not have");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The catalog for `language`, or the settings' own language; a regional
+    // catalog ("pt-BR") builds on its base language's ("pt")
+    pub fn catalog(&self, language: Option<&str>) -> Catalog{
+        let language = language.unwrap_or(&self.language);
+        let base = primary(language);
+        let mut messages: BTreeMap<String,String> = EN.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let layers = built_in(&base).into_iter().flatten().map(|(k, v)| (k.to_string(), v.to_string()))
+            .chain(self.catalogs.get(&base).into_iter().flatten().map(|(k, v)| (k.clone(), v.clone())))
+            .chain(self.catalogs.get(language).filter(|_| language != base).into_iter().flatten().map(|(k, v)| (k.clone(), v.clone())));
+        messages.extend(layers);
+        Catalog{ language: language.to_string(), messages }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog{
+    pub language: String,
+    messages: BTreeMap<String,String>,
+}
+
+impl Default for Catalog{
+    fn default() -> Self{
+        I18nSettings::default().catalog(None)
+    }
+}
+
+impl Catalog{
+    // The message, or its key when there is none
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str{
+        self.messages.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    // The message with its {name} placeholders filled in, in one pass so a
+    // value that itself reads like a placeholder is left as it is
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String{
+        let mut text = String::new();
+        let mut rest = self.get(key);
+        while let Some(open) = rest.find('{'){
+            text.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let value = after.find('}').and_then(|close| {
+                let name = &after[..close];
+                args.iter().find(|(n, _)| *n == name).map(|(_, value)| (*value, close))
+            });
+            match value{
+                Some((value, close)) => {
+                    text.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+
+    pub fn status(&self, status: Status) -> &str{
+        self.get(match status{
+            Status::NotStarted => "status-not-started",
+            Status::InProgress => "status-in-progress",
+            Status::Blocked => "status-blocked",
+            Status::Done => "status-done",
+        })
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = (&str, &str)>{
+        self.messages.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+// "key = message" lines; blank lines and lines starting with # are skipped
+pub fn parse_catalog(text: &str) -> Result<BTreeMap<String,String>,&'static str>{
+    let mut messages = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')){
+        let (key, message) = line.split_once('=').ok_or("Write catalog lines as key = message")?;
+        messages.insert(key.trim().to_string(), message.trim().to_string());
+    }
+    Ok(messages)
+}
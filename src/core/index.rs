@@ -0,0 +1,103 @@
+// Secondary indexes over the nodes of a graph, kept up to date by the
+// ProjectGraph mutators so lookups by owner, status, tag or external ref
+// avoid full scans
+
+use super::{Node, Status};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeIndexes{
+    by_owner: HashMap<Arc<str>,HashSet<Uuid>>,
+    by_status: HashMap<Status,HashSet<Uuid>>,
+    by_tag: HashMap<Arc<str>,HashSet<Uuid>>,
+    // (system, key) -> the one node linked to that external item
+    by_external: HashMap<(String,String),Uuid>,
+}
+
+fn add_entry<K: std::hash::Hash + Eq>(map: &mut HashMap<K,HashSet<Uuid>>, key: K, id: Uuid){
+    map.entry(key).or_default().insert(id);
+}
+
+fn remove_entry<K: std::hash::Hash + Eq>(map: &mut HashMap<K,HashSet<Uuid>>, key: &K, id: Uuid){
+    if let Some(ids) = map.get_mut(key){
+        ids.remove(&id);
+        if ids.is_empty(){
+            map.remove(key);
+        }
+    }
+}
+
+impl NodeIndexes{
+    pub fn new() -> Self{
+        NodeIndexes::default()
+    }
+
+    pub fn insert(&mut self, node: &Node){
+        let id = node.get_id();
+        if let Some(owner) = node.get_shared_owner(){
+            add_entry(&mut self.by_owner, owner.clone(), id);
+        }
+        if let Some(status) = node.get_status(){
+            add_entry(&mut self.by_status, status, id);
+        }
+        for tag in node.get_tags(){
+            add_entry(&mut self.by_tag, tag.clone(), id);
+        }
+        for external in node.get_external_refs(){
+            self.by_external.insert((external.system.clone(), external.key.clone()), id);
+        }
+    }
+
+    // Must be called with the node as it was indexed, before mutating it
+    pub fn remove(&mut self, node: &Node){
+        let id = node.get_id();
+        if let Some(owner) = node.get_shared_owner(){
+            remove_entry(&mut self.by_owner, owner, id);
+        }
+        if let Some(status) = node.get_status(){
+            remove_entry(&mut self.by_status, &status, id);
+        }
+        for tag in node.get_tags(){
+            remove_entry(&mut self.by_tag, tag, id);
+        }
+        for external in node.get_external_refs(){
+            let key = (external.system.clone(), external.key.clone());
+            if self.by_external.get(&key) == Some(&id){
+                self.by_external.remove(&key);
+            }
+        }
+    }
+
+    pub fn clear(&mut self){
+        self.by_owner.clear();
+        self.by_status.clear();
+        self.by_tag.clear();
+        self.by_external.clear();
+    }
+
+    pub fn by_owner(&self, owner: &str) -> Option<&HashSet<Uuid>>{
+        self.by_owner.get(owner)
+    }
+
+    pub fn by_status(&self, status: Status) -> Option<&HashSet<Uuid>>{
+        self.by_status.get(&status)
+    }
+
+    pub fn by_tag(&self, tag: &str) -> Option<&HashSet<Uuid>>{
+        self.by_tag.get(tag)
+    }
+
+    pub fn by_external(&self, system: &str, key: &str) -> Option<Uuid>{
+        self.by_external.get(&(system.to_string(), key.to_string())).copied()
+    }
+
+    pub fn owners(&self) -> impl Iterator<Item = &str>{
+        self.by_owner.keys().map(|o| o.as_ref())
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = &str>{
+        self.by_tag.keys().map(|t| t.as_ref())
+    }
+}
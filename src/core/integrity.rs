@@ -0,0 +1,50 @@
+// Integrity - the graph's bookkeeping agreeing with its nodes
+//
+// A hand-edited or partially migrated file can leave the id -> index map, the
+// keys or the tables kept next to the nodes (sprints, releases, risks, ...)
+// pointing at nodes that are not there. ProjectGraph::check_integrity lists
+// what is off; ProjectGraph::repair fixes everything that can be fixed
+// without guessing, which is all but nodes sharing an id and cycles.
+
+use super::graph::DependencyType;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue{
+    // The map sends the id to an empty slot or to a node with another id
+    StaleIndex{ id: Uuid, index: usize },
+    // A node the map does not lead to
+    Unindexed{ id: Uuid, index: usize },
+    DuplicateId{ id: Uuid, count: usize },
+    SelfLoop{ id: Uuid },
+    DuplicateEdge{ from: Uuid, to: Uuid, kind: DependencyType },
+    Cycle,
+    // A key of a node that is not in the graph
+    StaleKey{ id: Uuid },
+    MissingKey{ id: Uuid },
+    // Something recorded against a node that is not in the graph
+    Dangling{ table: &'static str, id: Uuid },
+}
+
+impl IntegrityIssue{
+    pub fn is_repairable(&self) -> bool{
+        !matches!(self, IntegrityIssue::DuplicateId{..} | IntegrityIssue::Cycle)
+    }
+}
+
+impl fmt::Display for IntegrityIssue{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            IntegrityIssue::StaleIndex{ id, index } => write!(f, "{} is indexed at {}, which holds another node", id, index),
+            IntegrityIssue::Unindexed{ id, index } => write!(f, "node {} at {} is missing from the index", id, index),
+            IntegrityIssue::DuplicateId{ id, count } => write!(f, "{} nodes share the id {}", count, id),
+            IntegrityIssue::SelfLoop{ id } => write!(f, "{} depends on itself", id),
+            IntegrityIssue::DuplicateEdge{ from, to, kind } => write!(f, "{} -> {} ({:?}) is stored more than once", from, to, kind),
+            IntegrityIssue::Cycle => write!(f, "the dependencies contain a cycle"),
+            IntegrityIssue::StaleKey{ id } => write!(f, "a key is kept for {}, which is not in the graph", id),
+            IntegrityIssue::MissingKey{ id } => write!(f, "{} has no key", id),
+            IntegrityIssue::Dangling{ table, id } => write!(f, "{} refers to {}, which is not in the graph", table, id),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Shared storage for strings repeated across many nodes (owners,
+// participants, tags), so each distinct value is allocated once and
+// equal values can be compared by pointer
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+pub struct Interner{
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner{
+    pub fn new() -> Self{
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, value: &str) -> Arc<str>{
+        if let Some(existing) = self.strings.get(value){
+            return existing.clone();
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.strings.insert(shared.clone());
+        shared
+    }
+
+    // The shared copy of `value`, if any node uses it
+    pub fn get(&self, value: &str) -> Option<&Arc<str>>{
+        self.strings.get(value)
+    }
+
+    pub fn len(&self) -> usize{
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.strings.is_empty()
+    }
+
+    // Drops strings no longer referenced outside the interner
+    pub fn collect_garbage(&mut self){
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+}
+
+// Pointer comparison first, falling back to comparing contents for
+// strings that were created outside an interner
+pub fn same(a: &Arc<str>, b: &Arc<str>) -> bool{
+    Arc::ptr_eq(a, b) || a == b
+}
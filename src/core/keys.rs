@@ -0,0 +1,68 @@
+// NodeKeys - short human-friendly keys (PROJ-12, EPIC-3) for nodes
+//
+// Keys are assigned by the graph when a node is inserted and can be used
+// anywhere a Uuid is accepted through ProjectGraph::resolve_id.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeKeys{
+    uid_to_key: HashMap<Uuid,String>,
+    key_to_uid: HashMap<String,Uuid>,
+    counters: HashMap<String,u32>,
+}
+
+impl NodeKeys{
+    pub fn new() -> Self{
+        NodeKeys::default()
+    }
+
+    pub fn assign(&mut self, prefix: &str, uid: Uuid) -> String{
+        if let Some(existing) = self.uid_to_key.get(&uid){
+            return existing.clone();
+        }
+
+        let counter = self.counters.entry(prefix.to_string()).or_insert(0);
+        *counter += 1;
+
+        let key = format!("{}-{}", prefix, counter);
+        self.uid_to_key.insert(uid, key.clone());
+        self.key_to_uid.insert(key.clone(), uid);
+        key
+    }
+
+    pub fn remove(&mut self, uid: Uuid){
+        if let Some(key) = self.uid_to_key.remove(&uid){
+            self.key_to_uid.remove(&key);
+        }
+    }
+
+    pub fn get_key(&self, uid: Uuid) -> Option<&str>{
+        self.uid_to_key.get(&uid).map(|k| k.as_str())
+    }
+
+    pub fn get_uid(&self, key: &str) -> Option<Uuid>{
+        self.key_to_uid.get(&key.to_ascii_uppercase()).copied()
+    }
+
+    // Nodes that have a key
+    pub fn uids(&self) -> impl Iterator<Item = Uuid> + '_{
+        self.uid_to_key.keys().copied()
+    }
+
+    pub fn clear(&mut self){
+        self.uid_to_key.clear();
+        self.key_to_uid.clear();
+        self.counters.clear();
+    }
+
+    // Drops keys of nodes for which `keep` returns false
+    pub fn retain(&mut self, keep: impl Fn(Uuid) -> bool){
+        let stale: Vec<Uuid> = self.uid_to_key.keys().copied().filter(|uid| !keep(*uid)).collect();
+        for uid in stale{
+            self.remove(uid);
+        }
+    }
+}
@@ -1,11 +1,113 @@
 // Core module - contains the main data structures
 
+pub mod actor;
+pub mod actual;
+pub mod approval;
+pub mod automation;
+pub mod blocked;
+pub mod calendar;
+pub mod checklist;
+pub mod comment;
+pub mod commit;
+pub mod constraint;
+pub mod dates;
+pub mod effort;
+pub mod estimate;
+pub mod event;
+pub mod external;
+pub mod fiscal;
 pub mod graph;
+pub mod hierarchy;
+pub mod holidays;
+pub mod i18n;
+pub mod index;
+pub mod integrity;
+pub mod interner;
+pub mod keys;
+pub mod money;
 pub mod node;
+pub mod okr;
+pub mod points;
+pub mod person;
+pub mod plugin;
+pub mod priority;
+pub mod publishing;
+pub mod query;
+pub mod quickadd;
+pub mod release;
+pub mod remote;
+pub mod risk;
+pub mod rollup;
+pub mod scope;
+pub mod search;
+pub mod settings;
+pub mod snapshot;
+mod sorted;
+mod stored;
+pub mod sprint;
+pub mod status;
+pub mod sync_state;
+pub mod team;
+pub mod templates;
+pub mod theme;
 pub mod timeline;
+pub mod timezone;
+pub mod validation;
+pub mod view;
+pub mod worklog;
+pub mod workflow;
 
 // Re-export main types for convenience
 pub use node::Node;
+pub use actor::{Actor, ActorGuard};
+pub use actual::{ActualDates, Actuals};
+pub use blocked::{BlockReason, BlockedPeriod, BlockedReasons, Blocker};
+pub use approval::{Approval, ApprovalGate, GateTarget, PendingApproval};
+pub use automation::{Action, Firing, Rule, Trigger};
 pub use node::NodeBuilder;
 pub use timeline::Timeline;
+pub use keys::NodeKeys;
+pub use interner::Interner;
+pub use index::NodeIndexes;
+pub use query::{Query, SortKey};
+pub use publishing::{CalendarSync, Destination, PublishTarget};
+pub use quickadd::{parse_quickadd, parse_quickadd_at, QuickAdd};
+pub use rollup::Rollup;
+pub use snapshot::{SharedGraph, Snapshot};
+pub use sync_state::{CachedPull, PullState, SyncState};
+pub use scope::Scope;
+pub use status::Status;
+pub use release::Release;
+pub use risk::Risk;
+pub use okr::{KeyResult, Objective};
+pub use priority::Priority;
+pub use person::{Person, Unavailability, UnavailabilityKind};
+pub use plugin::{Finding, GraphPlugin, Metric, Plugins};
+pub use worklog::Worklog;
+pub use checklist::{ChecklistItem, DefinitionOfDone, DodPolicy};
+pub use comment::Comment;
+pub use commit::{apply_commit_message, parse_commit_message, CommitAction, CommitChange, CommitOutcome};
+pub use search::{SearchHit, SearchIndex, Snippet};
+pub use estimate::{Consensus, Estimate};
+pub use effort::{Effort, EffortCurve};
+pub use calendar::Calendar;
+pub use holidays::Holiday;
+pub use constraint::Constraint;
+pub use event::{Event, EventKind, EventLog};
+pub use integrity::IntegrityIssue;
+pub use external::ExternalRef;
+pub use remote::{RemoteDependency, RemoteNode, RemoteRef};
+pub use fiscal::{FiscalCalendar, NamedPeriod};
+pub use points::PointScale;
+pub use hierarchy::HierarchyRules;
+pub use i18n::{Catalog, I18nSettings};
+pub use money::{format_money, format_number, MoneySettings};
+pub use settings::{ConnectionPolicy, ParallelEdges, ProjectSettings};
+pub use sprint::Sprint;
+pub use team::Team;
+pub use templates::{ReportTemplate, TemplateReport};
+pub use theme::{FillBy, Theme};
+pub use view::{Filter, SavedView};
+pub use validation::{validate, ValidationReport, Warning};
+pub use workflow::{Workflow, WorkflowState};
 //pub use graph::ProjectGraph;
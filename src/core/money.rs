@@ -0,0 +1,159 @@
+// Money - the currencies costs and rates are given in, exchange rates into
+// the project's base currency, and numbers written the way a locale expects
+//
+// Costs and hourly rates without a currency are in the base currency, and
+// reports convert everything into it. Every other currency in use needs a
+// rate; the graph refuses costs, rates and settings that would leave one
+// without.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// ISO 4217, e.g. USD, EUR
+pub fn is_currency_code(code: &str) -> bool{
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoneySettings{
+    // What reports are in, and what costs without a currency are in
+    pub currency: String,
+    // Units of the base currency one unit of each other currency is worth
+    #[serde(default)]
+    pub rates: BTreeMap<String,f64>,
+    // BCP 47 tag numbers are written for, e.g. en-US, de-DE, fr-FR
+    pub locale: String,
+}
+
+impl Default for MoneySettings{
+    fn default() -> Self{
+        MoneySettings{ currency: "USD".to_string(), rates: BTreeMap::new(), locale: "en-US".to_string() }
+    }
+}
+
+impl MoneySettings{
+    pub fn is_default(&self) -> bool{
+        *self == MoneySettings::default()
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if !is_currency_code(&self.currency) || !self.rates.keys().all(|c| is_currency_code(c)){
+            return Err("Currencies are three-letter ISO 4217 codes such as USD or EUR");
+        }
+        if self.rates.values().any(|r| !(r.is_finite() && *r > 0.0)){
+            return Err("Exchange rates must be positive numbers");
+        }
+        if self.locale.trim().is_empty(){
+            return Err("The number locale needs a tag such as en-US");
+        }
+        Ok(())
+    }
+
+    pub fn converts(&self, currency: &str) -> bool{
+        currency == self.currency || self.rates.contains_key(currency)
+    }
+
+    // `amount` of `currency` in the base currency; None without a rate
+    pub fn to_base(&self, amount: f64, currency: &str) -> Option<f64>{
+        if currency == self.currency{
+            return Some(amount);
+        }
+        self.rates.get(currency).map(|rate| amount * rate)
+    }
+
+    // Make `currency` the base, restating every rate against it; the old base
+    // keeps a rate so amounts in it still convert
+    pub fn rebase(&mut self, currency: &str) -> Result<(),&'static str>{
+        if currency == self.currency{
+            return Ok(());
+        }
+        if self.rates.is_empty(){
+            self.currency = currency.to_string();
+            return Ok(());
+        }
+        let worth = self.rates.remove(currency).ok_or("Give the new base currency an exchange rate before switching to it")?;
+        for rate in self.rates.values_mut(){
+            *rate /= worth;
+        }
+        self.rates.insert(std::mem::replace(&mut self.currency, currency.to_string()), 1.0 / worth);
+        Ok(())
+    }
+
+    // An amount of the base currency, e.g. "$1,234.50" or "1.234,50 €"
+    pub fn format(&self, amount: f64) -> String{
+        format_money(amount, &self.currency, &self.locale)
+    }
+}
+
+// Separators and where the currency symbol goes for a locale; unknown
+// languages are written the English way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat{
+    pub group: &'static str,
+    pub decimal: char,
+    pub symbol_after: bool,
+}
+
+pub fn number_format(locale: &str) -> NumberFormat{
+    let tag = locale.replace('_', "-").to_ascii_lowercase();
+    let language = tag.split('-').next().unwrap_or_default();
+    match language{
+        _ if tag == "de-ch" => NumberFormat{ group: "\u{2019}", decimal: '.', symbol_after: false },
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "tr" | "id" => NumberFormat{ group: ".", decimal: ',', symbol_after: true },
+        "fr" | "sv" | "nb" | "fi" | "pl" | "cs" | "ru" | "uk" => NumberFormat{ group: "\u{202f}", decimal: ',', symbol_after: true },
+        _ => NumberFormat{ group: ",", decimal: '.', symbol_after: false },
+    }
+}
+
+// `value` rounded to `decimals` places with the locale's separators
+pub fn format_number(value: f64, decimals: usize, locale: &str) -> String{
+    let format = number_format(locale);
+    let fixed = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate(){
+        if i > 0 && (whole.len() - i) % 3 == 0{
+            grouped.push_str(format.group);
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+    match fraction.is_empty(){
+        true => format!("{}{}", sign, grouped),
+        false => format!("{}{}{}{}", sign, grouped, format.decimal, fraction),
+    }
+}
+
+fn symbol(currency: &str) -> &str{
+    match currency{
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        other => other,
+    }
+}
+
+// Minor units the currency is written with
+fn decimals(currency: &str) -> usize{
+    match currency{
+        "JPY" | "KRW" | "ISK" | "CLP" | "VND" => 0,
+        _ => 2,
+    }
+}
+
+pub fn format_money(amount: f64, currency: &str, locale: &str) -> String{
+    let number = format_number(amount, decimals(currency), locale);
+    let symbol = symbol(currency);
+    // Codes stand apart from the number; symbols only do after it
+    let spaced = symbol.len() == 3 && symbol.chars().all(|c| c.is_ascii_uppercase());
+    match (number_format(locale).symbol_after, number.strip_prefix('-')){
+        (true, _) => format!("{}\u{a0}{}", number, symbol),
+        (false, Some(positive)) if spaced => format!("-{}\u{a0}{}", symbol, positive),
+        (false, Some(positive)) => format!("-{}{}", symbol, positive),
+        (false, None) if spaced => format!("{}\u{a0}{}", symbol, number),
+        (false, None) => format!("{}{}", symbol, number),
+    }
+}
@@ -1,9 +1,16 @@
 use super::Timeline;
+use super::Status;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use super::interner::Interner;
+use super::external::ExternalRef;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
 use uuid::Uuid;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 
-type Participants =  HashSet<String>;
+type Participants =  HashSet<Arc<str>>;
+type Tags = HashSet<Arc<str>>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Node {
@@ -12,14 +19,29 @@ pub enum Node {
         name: String,
         link: Option<String>,
         timeline: Option<Timeline>,
-        owner: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
+        #[serde(serialize_with = "super::sorted::sorted_opt_set")]
         participants: Option<Participants>,
+        #[serde(default)]
+        estimated_cost: Option<f64>,
+        #[serde(default)]
+        status: Status,
+        #[serde(default)]
+        timezone: Option<Tz>,
     },
     Spec {
         id: Uuid,
         name: String,
         link: Option<String>,
-        owner: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
     },
     Epic {
         id: Uuid,
@@ -27,8 +49,17 @@ pub enum Node {
         link: Option<String>,
         timeline: Timeline,
         points: Option<u32>,
-        owner: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
+        #[serde(serialize_with = "super::sorted::sorted_opt_set")]
         participants: Option<Participants>,
+        #[serde(default)]
+        estimated_cost: Option<f64>,
+        #[serde(default)]
+        status: Status,
     },
     UserStory {
         id: Uuid,
@@ -36,7 +67,15 @@ pub enum Node {
         link: Option<String>,
         timeline: Timeline,
         points: Option<u32>,
-        owner: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
+        #[serde(default)]
+        estimated_cost: Option<f64>,
+        #[serde(default)]
+        status: Status,
     },
     Tasks {
         id: Uuid,
@@ -44,7 +83,36 @@ pub enum Node {
         link: Option<String>,
         timeline: Timeline,
         points: Option<u32>,
-        owner: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
+        #[serde(default)]
+        estimated_cost: Option<f64>,
+        #[serde(default)]
+        status: Status,
+    },
+    // An architectural or product decision; linked to the work it affects
+    // through ProjectGraph::link_decision
+    Decision {
+        id: Uuid,
+        question: String,
+        link: Option<String>,
+        owner: Option<Arc<str>>,
+        #[serde(default, serialize_with = "super::sorted::sorted_set")]
+        tags: Tags,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        external_refs: BTreeSet<ExternalRef>,
+        #[serde(default)]
+        options: Vec<String>,
+        // One of the options, once decided
+        #[serde(default)]
+        chosen: Option<String>,
+        #[serde(default)]
+        decided_on: Option<NaiveDate>,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        deciders: BTreeSet<Arc<str>>,
     },
 }
 
@@ -53,6 +121,7 @@ impl Node{
         match self{
             Node::Project { id,..}|
             Node::Spec{id,..}|
+            Node::Decision{id,..}|
             Node::Epic{id,..} |
             Node::UserStory { id, ..}|
             Node::Tasks { id,..} => {
@@ -65,6 +134,7 @@ impl Node{
         match self{
             Node::Project {name,..}|
             Node::Spec{name,..}|
+            Node::Decision{question: name,..}|
             Node::Epic{name,..} |
             Node::UserStory {name, ..}|
             Node::Tasks {name,..} => {
@@ -73,10 +143,314 @@ impl Node{
         }
     }
 
+    pub fn get_timeline(&self) -> Option<&Timeline>{
+        match self{
+            Node::Project{timeline,..} => timeline.as_ref(),
+            Node::Spec{..} | Node::Decision{..} => None,
+            Node::Epic{timeline,..} |
+            Node::UserStory{timeline,..}|
+            Node::Tasks{timeline,..} => Some(timeline),
+        }
+    }
+
+    pub fn get_owner(&self) -> Option<&str>{
+        match self{
+            Node::Project{owner,..} |
+            Node::Spec{owner,..}|
+            Node::Decision{owner,..}|
+            Node::Epic{owner,..} |
+            Node::UserStory{owner,..}|
+            Node::Tasks{owner,..} => owner.as_deref(),
+        }
+    }
+
+    // The owner as stored, for callers comparing against interned strings
+    pub fn get_shared_owner(&self) -> Option<&Arc<str>>{
+        match self{
+            Node::Project{owner,..} |
+            Node::Spec{owner,..}|
+            Node::Decision{owner,..}|
+            Node::Epic{owner,..} |
+            Node::UserStory{owner,..}|
+            Node::Tasks{owner,..} => owner.as_ref(),
+        }
+    }
+
+    pub fn get_tags(&self) -> &Tags{
+        match self{
+            Node::Project{tags,..} |
+            Node::Spec{tags,..}|
+            Node::Decision{tags,..}|
+            Node::Epic{tags,..} |
+            Node::UserStory{tags,..}|
+            Node::Tasks{tags,..} => tags,
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool{
+        self.get_tags().contains(tag)
+    }
+
+    fn tags_mut(&mut self) -> &mut Tags{
+        match self{
+            Node::Project{tags,..} |
+            Node::Spec{tags,..}|
+            Node::Decision{tags,..}|
+            Node::Epic{tags,..} |
+            Node::UserStory{tags,..}|
+            Node::Tasks{tags,..} => tags,
+        }
+    }
+
+    pub fn get_external_refs(&self) -> &BTreeSet<ExternalRef>{
+        match self{
+            Node::Project{external_refs,..} |
+            Node::Spec{external_refs,..}|
+            Node::Decision{external_refs,..}|
+            Node::Epic{external_refs,..} |
+            Node::UserStory{external_refs,..}|
+            Node::Tasks{external_refs,..} => external_refs,
+        }
+    }
+
+    pub fn get_external_ref(&self, system: &str) -> Option<&ExternalRef>{
+        self.get_external_refs().iter().find(|r| r.system == system)
+    }
+
+    fn external_refs_mut(&mut self) -> &mut BTreeSet<ExternalRef>{
+        match self{
+            Node::Project{external_refs,..} |
+            Node::Spec{external_refs,..}|
+            Node::Decision{external_refs,..}|
+            Node::Epic{external_refs,..} |
+            Node::UserStory{external_refs,..}|
+            Node::Tasks{external_refs,..} => external_refs,
+        }
+    }
+
+    // Replaces any ref with the same system and key (e.g. to update its url)
+    pub fn add_external_ref(&mut self, external: ExternalRef){
+        let refs = self.external_refs_mut();
+        refs.retain(|r| r.system != external.system || r.key != external.key);
+        refs.insert(external);
+    }
+
+    // Returns false if the node had no such ref
+    pub fn remove_external_ref(&mut self, system: &str, key: &str) -> bool{
+        let refs = self.external_refs_mut();
+        let before = refs.len();
+        refs.retain(|r| r.system != system || r.key != key);
+        refs.len() != before
+    }
+
+    // Returns false if the tag was already present
+    pub fn add_tag(&mut self, tag: Arc<str>) -> bool{
+        self.tags_mut().insert(tag)
+    }
+
+    // Returns false if the tag was not present
+    pub fn remove_tag(&mut self, tag: &str) -> bool{
+        self.tags_mut().remove(tag)
+    }
+
+    // Swaps owner, participant and tag strings for the interner's shared copies
+    pub fn intern_strings(&mut self, interner: &mut Interner){
+        match self{
+            Node::Project{owner, participants, tags,..} |
+            Node::Epic{owner, participants, tags,..} => {
+                if let Some(p) = participants{
+                    *p = p.iter().map(|s| interner.intern(s)).collect();
+                }
+                Self::intern_owner_and_tags(owner, tags, interner);
+            }
+            Node::Decision{owner, tags, deciders,..} => {
+                *deciders = deciders.iter().map(|d| interner.intern(d)).collect();
+                Self::intern_owner_and_tags(owner, tags, interner);
+            }
+            Node::Spec{owner, tags,..} |
+            Node::UserStory{owner, tags,..} |
+            Node::Tasks{owner, tags,..} => {
+                Self::intern_owner_and_tags(owner, tags, interner);
+            }
+        }
+    }
+
+    fn intern_owner_and_tags(owner: &mut Option<Arc<str>>, tags: &mut Tags, interner: &mut Interner){
+        if let Some(o) = owner{
+            *o = interner.intern(o);
+        }
+        *tags = tags.iter().map(|t| interner.intern(t)).collect();
+    }
+
+    pub fn get_points(&self) -> Option<u32>{
+        match self{
+            Node::Epic{points,..}|
+            Node::UserStory{points,..}|
+            Node::Tasks{points,..} => *points,
+            _ => None,
+        }
+    }
+
+    pub fn get_estimated_cost(&self) -> Option<f64>{
+        match self{
+            Node::Project{estimated_cost,..} |
+            Node::Epic{estimated_cost,..} |
+            Node::UserStory{estimated_cost,..}|
+            Node::Tasks{estimated_cost,..} => *estimated_cost,
+            Node::Spec{..} | Node::Decision{..} => None,
+        }
+    }
+
+    pub fn set_estimated_cost(&mut self, cost: f64) -> Result<(),&'static str>{
+        match self{
+            Node::Project{estimated_cost,..} |
+            Node::Epic{estimated_cost,..} |
+            Node::UserStory{estimated_cost,..}|
+            Node::Tasks{estimated_cost,..} => {
+                *estimated_cost = Some(cost);
+                Ok(())
+            }
+            Node::Spec{..} | Node::Decision{..} => {
+                Err("This node type does not carry a cost")
+            }
+        }
+    }
+
+    pub fn get_status(&self) -> Option<Status>{
+        match self{
+            Node::Project{status,..} |
+            Node::Epic{status,..} |
+            Node::UserStory{status,..}|
+            Node::Tasks{status,..} => Some(*status),
+            Node::Spec{..} | Node::Decision{..} => None,
+        }
+    }
+
+    pub fn is_done(&self) -> bool{
+        self.get_status().is_some_and(|s| s.is_done())
+    }
+
+    pub fn set_status(&mut self, new_status: Status) -> Result<(),&'static str>{
+        match self{
+            Node::Project{status,..} |
+            Node::Epic{status,..} |
+            Node::UserStory{status,..}|
+            Node::Tasks{status,..} => {
+                *status = new_status;
+                Ok(())
+            }
+            Node::Spec{..} | Node::Decision{..} => {
+                Err("This node type does not have a status")
+            }
+        }
+    }
+
+    pub fn get_options(&self) -> &[String]{
+        match self{
+            Node::Decision{options,..} => options,
+            _ => &[],
+        }
+    }
+
+    // The chosen option and the day it was chosen, once decided
+    pub fn get_decision(&self) -> Option<(&str, NaiveDate)>{
+        match self{
+            Node::Decision{chosen: Some(chosen), decided_on: Some(on),..} => Some((chosen, *on)),
+            _ => None,
+        }
+    }
+
+    // Sorted; empty until decided
+    pub fn get_deciders(&self) -> Vec<&str>{
+        match self{
+            Node::Decision{deciders,..} => deciders.iter().map(|d| d.as_ref()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn add_option(&mut self, option: &str) -> Result<(),&'static str>{
+        match self{
+            Node::Decision{options,..} => {
+                let option = option.trim();
+                if option.is_empty(){
+                    return Err("A decision option needs some text");
+                }
+                if options.iter().any(|o| o == option){
+                    return Err("The decision already has this option");
+                }
+                options.push(option.to_string());
+                Ok(())
+            }
+            _ => Err("Only decisions carry options"),
+        }
+    }
+
+    // Records `option` as chosen; deciding again replaces the earlier choice
+    pub fn decide(&mut self, option: &str, on: NaiveDate, by: &[String]) -> Result<(),&'static str>{
+        match self{
+            Node::Decision{options, chosen, decided_on, deciders,..} => {
+                if !options.iter().any(|o| o == option.trim()){
+                    return Err("The chosen option is not one of the decision's options");
+                }
+                if by.is_empty(){
+                    return Err("A decision needs the people who made it");
+                }
+                *chosen = Some(option.trim().to_string());
+                *decided_on = Some(on);
+                *deciders = by.iter().map(|d| Arc::from(d.as_str())).collect();
+                Ok(())
+            }
+            _ => Err("Only decisions can be decided"),
+        }
+    }
+
+    pub fn get_timezone(&self) -> Option<Tz>{
+        match self{
+            Node::Project{timezone,..} => *timezone,
+            _ => None,
+        }
+    }
+
+    pub fn set_timezone(&mut self, tz: Tz) -> Result<(),&'static str>{
+        match self{
+            Node::Project{timezone,..} => {
+                *timezone = Some(tz);
+                Ok(())
+            }
+            _ => {
+                Err("Only projects carry a timezone")
+            }
+        }
+    }
+
+    // The kind as the command line and settings name it
+    pub fn get_kind_name(&self) -> &'static str{
+        match self{
+            Node::Project{..} => "project",
+            Node::Spec{..} => "spec",
+            Node::Epic{..} => "epic",
+            Node::UserStory{..} => "story",
+            Node::Tasks{..} => "task",
+            Node::Decision{..} => "decision",
+        }
+    }
+
+    pub fn get_key_prefix(&self) -> &'static str{
+        match self{
+            Node::Project{..} => "PROJ",
+            Node::Spec{..} => "SPEC",
+            Node::Epic{..} => "EPIC",
+            Node::UserStory{..} => "STORY",
+            Node::Tasks{..} => "TASK",
+            Node::Decision{..} => "DEC",
+        }
+    }
+
     pub fn set_id(&mut self, uid: Uuid){
         match self{
                 Node::Project{id,..} |
                 Node::Spec{id,..}|
+                Node::Decision{id,..}|
                 Node::Epic{id,..} |
                 Node::UserStory { id, ..}|
                 Node::Tasks { id,..} => {
@@ -89,6 +463,7 @@ impl Node{
         match self{
                 Node::Project{name,..}|
                 Node::Spec{name,..}|
+                Node::Decision{question: name,..}|
                 Node::Epic{name,..} |
                 Node::UserStory {name,..}|
                 Node::Tasks {name,..} => {
@@ -101,6 +476,7 @@ impl Node{
         match self{
                 Node::Project{link,..} |
                 Node::Spec{link,..}|
+                Node::Decision{link,..}|
                 Node::Epic{link,..} |
                 Node::UserStory {link,..}|
                 Node::Tasks {link,..} => {
@@ -114,7 +490,7 @@ impl Node{
                 Node::Project{timeline,..} =>{
                     *timeline = Some(new_timeline)
                 }
-                Node::Spec{..} => {}
+                Node::Spec{..} | Node::Decision{..} => {}
                 Node::Epic{timeline,..} |
                 Node::UserStory {timeline,..}|
                 Node::Tasks {timeline,..} => {
@@ -127,19 +503,44 @@ impl Node{
         match self{
                 Node::Project{owner,..} |
                 Node::Spec{owner,..}|
+                Node::Decision{owner,..}|
                 Node::Epic{owner,..} |
                 Node::UserStory {owner,..}|
                 Node::Tasks {owner,..} => {
-                    *owner = Some(new_owner);
+                    *owner = Some(Arc::from(new_owner));
                 }
         }
     }
 
+    pub fn clear_owner(&mut self){
+        match self{
+                Node::Project{owner,..} |
+                Node::Spec{owner,..}|
+                Node::Decision{owner,..}|
+                Node::Epic{owner,..} |
+                Node::UserStory {owner,..}|
+                Node::Tasks {owner,..} => {
+                    *owner = None;
+                }
+        }
+    }
+
+    // Sorted; empty for node types without participants
+    pub fn get_participants(&self) -> Vec<&str>{
+        let mut names: Vec<&str> = match self{
+            Node::Project{participants,..} |
+            Node::Epic{participants,..} => participants.iter().flatten().map(|p| p.as_ref()).collect(),
+            _ => Vec::new(),
+        };
+        names.sort();
+        names
+    }
+
     pub fn add_participant(&mut self, participant: String)->Result<(),&'static str>{
         match self{
                 Node::Project{participants,..} |
                 Node::Epic{participants,..} => {
-                        participants.get_or_insert_with(HashSet::new).insert(participant);
+                        participants.get_or_insert_with(HashSet::new).insert(Arc::from(participant));
                         Ok(())
                 }
                 _ => {
@@ -184,6 +585,24 @@ impl Node{
             }
         }
     }
+
+    pub fn clear_points(&mut self)-> Result<(),&'static str>{
+        match self{
+            Node::Epic{points,..}|
+            Node::UserStory{points,..}|
+            Node::Tasks{points,..}=> {
+                *points = None;
+                Ok(())
+            }
+            _=>{
+                Err("This node type does not contain points")
+            }
+        }
+    }
+}
+
+fn share_set(set: HashSet<String>) -> HashSet<Arc<str>>{
+    set.into_iter().map(Arc::from).collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize,Default)]
@@ -194,7 +613,16 @@ pub struct NodeBuilder{
     timeline: Option<Timeline>,
     owner: Option<String>,
     points : Option<u32>,
-    participants: Option<Participants>, 
+    participants: Option<HashSet<String>>,
+    #[serde(default)]
+    tags: HashSet<String>,
+    #[serde(default)]
+    external_refs: BTreeSet<ExternalRef>,
+    status: Option<Status>,
+    estimated_cost: Option<f64>,
+    timezone: Option<Tz>,
+    #[serde(default)]
+    options: Vec<String>,
 }
 
 impl NodeBuilder{
@@ -232,11 +660,41 @@ impl NodeBuilder{
         self
     }
 
-    pub fn with_participants(mut self, participants : Participants)->Self{
+    pub fn with_participants(mut self, participants : HashSet<String>)->Self{
         self.participants = Some(participants);
         self
     }
 
+    pub fn with_tag(mut self, tag: String)->Self{
+        self.tags.insert(tag);
+        self
+    }
+
+    pub fn with_external_ref(mut self, external: ExternalRef)->Self{
+        self.external_refs.insert(external);
+        self
+    }
+
+    pub fn with_status(mut self, status: Status)->Self{
+        self.status = Some(status);
+        self
+    }
+
+    pub fn with_estimated_cost(mut self, cost: f64)->Self{
+        self.estimated_cost = Some(cost);
+        self
+    }
+
+    pub fn with_timezone(mut self, tz: Tz)->Self{
+        self.timezone = Some(tz);
+        self
+    }
+
+    pub fn with_option(mut self, option: String)->Self{
+        self.options.push(option);
+        self
+    }
+
     pub fn build_project(self)->Result<Node, &'static str> {
         let id = self.id.ok_or("Failed to build project - missing project id")?;
         let name = self.name.ok_or("Failed to build project - missing project name")?;
@@ -246,15 +704,19 @@ impl NodeBuilder{
             name, 
             link: self.link, 
             timeline: self.timeline, 
-            owner: self.owner, 
-            participants: self.participants}) 
+            owner: self.owner.map(Arc::from), 
+            participants: self.participants.map(share_set),
+            tags: share_set(self.tags), external_refs: self.external_refs,
+            estimated_cost: self.estimated_cost,
+            timezone: self.timezone,
+            status: self.status.unwrap_or_default()}) 
     }
 
     pub fn build_spec(self)->Result<Node, &'static str> {
         let id = self.id.ok_or("Failed to build Spec - missing Spec id")?;
         let name = self.name.ok_or("Failed to build Spec - missing Spec name")?;
         
-        Ok(Node::Spec { id, name, link: self.link, owner: self.owner})
+        Ok(Node::Spec { id, name, owner: self.owner.map(Arc::from), tags: share_set(self.tags), external_refs: self.external_refs, link: self.link})
     }
 
     pub fn build_epic(self)->Result<Node, &'static str> {
@@ -262,7 +724,7 @@ impl NodeBuilder{
         let name =  self.name.ok_or("Failed to build Epic - missing Epic name")?;
         let timeline =  self.timeline.ok_or("Failed to build Epic - missing Epic timeline")?;
 
-        Ok(Node::Epic { id, name, link: self.link, timeline, points: self.points, owner: self.owner, participants: self.participants })
+        Ok(Node::Epic { id, name, link: self.link, timeline, points: self.points, owner: self.owner.map(Arc::from), participants: self.participants.map(share_set), tags: share_set(self.tags), external_refs: self.external_refs, estimated_cost: self.estimated_cost, status: self.status.unwrap_or_default() })
     }
 
     pub fn build_userstory(self)->Result<Node, &'static str> {
@@ -270,7 +732,7 @@ impl NodeBuilder{
         let name =  self.name.ok_or("Failed to build Userstory - missing Userstory name")?;
         let timeline =  self.timeline.ok_or("Failed to build Userstory - missing Userstory timeline")?;
 
-        Ok(Node::UserStory { id, name, link:self.link, timeline: timeline, points: self.points, owner: self.owner })
+        Ok(Node::UserStory { id, name, link:self.link, timeline, points: self.points, owner: self.owner.map(Arc::from), tags: share_set(self.tags), external_refs: self.external_refs, estimated_cost: self.estimated_cost, status: self.status.unwrap_or_default() })
     }
 
     pub fn build_tasks(self)->Result<Node, &'static str> {
@@ -278,10 +740,18 @@ impl NodeBuilder{
         let name =  self.name.ok_or("Failed to build Tasks - missing Tasks name")?;
         let timeline =  self.timeline.ok_or("Failed to build Tasks - missing Tasks timeline")?;
 
-        Ok(Node::Tasks { id, name, link:self.link, timeline: timeline, points: self.points, owner: self.owner })
+        Ok(Node::Tasks { id, name, link:self.link, timeline, points: self.points, owner: self.owner.map(Arc::from), tags: share_set(self.tags), external_refs: self.external_refs, estimated_cost: self.estimated_cost, status: self.status.unwrap_or_default() })
     }
 
-}
-
+    // The name is the question; decided later, see Node::decide
+    pub fn build_decision(self)->Result<Node, &'static str> {
+        let id = self.id.ok_or("Failed to build Decision - missing Decision id")?;
+        let question = self.name.ok_or("Failed to build Decision - missing Decision question")?;
+        if self.options.iter().enumerate().any(|(i, o)| o.trim().is_empty() || self.options[..i].contains(o)){
+            return Err("Failed to build Decision - options must be unique and not empty");
+        }
 
+        Ok(Node::Decision { id, question, link: self.link, owner: self.owner.map(Arc::from), tags: share_set(self.tags), external_refs: self.external_refs, options: self.options, chosen: None, decided_on: None, deciders: BTreeSet::new() })
+    }
 
+}
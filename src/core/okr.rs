@@ -0,0 +1,70 @@
+// Objectives and key results, linked to the Epics and Projects that deliver them
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyResult{
+    pub id: Uuid,
+    pub title: String,
+    // Linked Epic/Project -> relative contribution weight
+    contributions: HashMap<Uuid,f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Objective{
+    pub id: Uuid,
+    pub title: String,
+    pub owner: Option<String>,
+    key_results: Vec<KeyResult>,
+}
+
+impl KeyResult{
+    pub fn new(id: Uuid, title: String) -> Self{
+        KeyResult{ id, title, contributions: HashMap::new() }
+    }
+
+    pub fn set_contribution(&mut self, node: Uuid, weight: f64) -> Result<(),&'static str>{
+        if !weight.is_finite() || weight <= 0.0{
+            return Err("Contribution weight must be a positive number");
+        }
+        self.contributions.insert(node, weight);
+        Ok(())
+    }
+
+    pub fn remove_contribution(&mut self, node: Uuid) -> bool{
+        self.contributions.remove(&node).is_some()
+    }
+
+    pub fn get_contributions(&self) -> &HashMap<Uuid,f64>{
+        &self.contributions
+    }
+}
+
+impl Objective{
+    pub fn new(id: Uuid, title: String) -> Self{
+        Objective{ id, title, owner: None, key_results: Vec::new() }
+    }
+
+    pub fn get_key_results(&self) -> &[KeyResult]{
+        &self.key_results
+    }
+
+    // The graph checks key result ids are unique before adding one
+    pub(crate) fn add_key_result(&mut self, key_result: KeyResult){
+        self.key_results.push(key_result);
+    }
+
+    pub(crate) fn get_key_results_mut(&mut self) -> &mut [KeyResult]{
+        &mut self.key_results
+    }
+
+    pub fn get_key_result(&self, id: Uuid) -> Option<&KeyResult>{
+        self.key_results.iter().find(|kr| kr.id == id)
+    }
+
+    pub fn get_key_result_mut(&mut self, id: Uuid) -> Option<&mut KeyResult>{
+        self.key_results.iter_mut().find(|kr| kr.id == id)
+    }
+}
@@ -0,0 +1,91 @@
+// Person - someone who can own nodes and log work against them
+//
+// People are keyed by name, matching the owner strings stored on nodes.
+
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnavailabilityKind{
+    Vacation,
+    OnCall,
+    Sick,
+    Other(String),
+}
+
+// An inclusive range of days on which the person is not available for planned work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unavailability{
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub kind: UnavailabilityKind,
+}
+
+impl Unavailability{
+    pub fn new(start: NaiveDate, end: NaiveDate, kind: UnavailabilityKind) -> Result<Self,&'static str>{
+        if end < start{
+            return Err("Unavailability must not end before it starts");
+        }
+        Ok(Unavailability{ start, end, kind })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool{
+        self.start <= date && date <= self.end
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Person{
+    pub name: String,
+    pub hourly_rate: Option<f64>,
+    // Currency of the rate; the project's base currency without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_currency: Option<String>,
+    #[serde(default)]
+    pub unavailability: Vec<Unavailability>,
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    // Whose regional holidays apply, see Calendar
+    #[serde(default)]
+    pub region: Option<String>,
+    // What the person may approve, see ApprovalGate
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub roles: BTreeSet<String>,
+}
+
+impl Person{
+    pub fn new(name: String) -> Self{
+        Person{ name, hourly_rate: None, rate_currency: None, unavailability: Vec::new(), timezone: None, region: None, roles: BTreeSet::new() }
+    }
+
+    pub fn with_hourly_rate(mut self, rate: f64) -> Self{
+        self.hourly_rate = Some(rate);
+        self
+    }
+
+    pub fn with_timezone(mut self, tz: Tz) -> Self{
+        self.timezone = Some(tz);
+        self
+    }
+
+    pub fn with_region(mut self, region: &str) -> Self{
+        self.region = Some(region.to_string());
+        self
+    }
+
+    pub fn with_role(mut self, role: &str) -> Self{
+        self.roles.insert(role.trim().to_string());
+        self
+    }
+
+    pub fn with_unavailability(mut self, range: Unavailability) -> Self{
+        self.unavailability.push(range);
+        self
+    }
+
+    pub fn is_available(&self, date: NaiveDate) -> bool{
+        !self.unavailability.iter().any(|u| u.contains(date))
+    }
+}
@@ -0,0 +1,100 @@
+// Plugins - Rust extensions registered on a graph at runtime
+//
+//   graph.register_plugin(Arc::new(MyValidator))?;
+//
+// Every hook has a default that does nothing, so a plugin implements only
+// what it needs: react to changes, veto connections, add validation
+// warnings, contribute metrics, or export the graph in a format of its own.
+// Plugins are not saved with the project; whoever embeds the crate registers
+// them again after loading.
+
+use super::graph::{DependencyType, ProjectGraph};
+use super::Status;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// A finding of a plugin's validator, shown in the validation report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding{
+    pub nodes: Vec<Uuid>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric{
+    pub name: String,
+    pub value: f64,
+}
+
+pub trait GraphPlugin: Send + Sync{
+    // Unique among the graph's plugins
+    fn name(&self) -> &str;
+
+    fn on_register(&self, _graph: &ProjectGraph){}
+
+    fn on_unregister(&self, _graph: &ProjectGraph){}
+
+    fn on_node_created(&self, _graph: &ProjectGraph, _id: Uuid){}
+
+    fn on_status_change(&self, _graph: &ProjectGraph, _id: Uuid, _from: Status, _to: Status){}
+
+    // Runs before the edge is added; an error rejects it
+    fn validate_connection(&self, _graph: &ProjectGraph, _from: Uuid, _to: Uuid, _kind: DependencyType) -> Result<(),&'static str>{
+        Ok(())
+    }
+
+    fn validate(&self, _graph: &ProjectGraph) -> Vec<Finding>{
+        Vec::new()
+    }
+
+    fn metrics(&self, _graph: &ProjectGraph) -> Vec<Metric>{
+        Vec::new()
+    }
+
+    // The graph in `format`, or None when the plugin does not export it
+    fn export(&self, _graph: &ProjectGraph, _format: &str) -> Option<String>{
+        None
+    }
+}
+
+// The plugins of one graph, in registration order
+#[derive(Clone, Default)]
+pub struct Plugins(Vec<Arc<dyn GraphPlugin>>);
+
+impl Plugins{
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn GraphPlugin>>{
+        self.0.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn GraphPlugin>>{
+        self.0.iter().find(|p| p.name() == name)
+    }
+
+    pub(crate) fn add(&mut self, plugin: Arc<dyn GraphPlugin>) -> Result<(),&'static str>{
+        if self.get(plugin.name()).is_some(){
+            return Err("A plugin with this name is already registered");
+        }
+        self.0.push(plugin);
+        Ok(())
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> Option<Arc<dyn GraphPlugin>>{
+        let position = self.0.iter().position(|p| p.name() == name)?;
+        Some(self.0.remove(position))
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize{
+        self.0.len()
+    }
+}
+
+impl fmt::Debug for Plugins{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        f.debug_list().entries(self.0.iter().map(|p| p.name())).finish()
+    }
+}
@@ -0,0 +1,155 @@
+// Point scales - the values estimates may take
+//
+// T-shirt sizes are stored as the number each size stands for, so rollups,
+// velocity and capacity keep adding plain points; the size names only show
+// up when reading estimates in and writing reports out.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const FIBONACCI: [u32; 10] = [1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+const POWERS_OF_TWO: [u32; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
+const TSHIRT: [(&str, u32); 6] = [("XS", 1), ("S", 2), ("M", 3), ("L", 5), ("XL", 8), ("XXL", 13)];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointScale{
+    // Any whole number
+    #[default]
+    Any,
+    Fibonacci,
+    PowersOfTwo,
+    // Size names with the points they stand for, smallest first
+    TShirt(Vec<(String,u32)>),
+    // Allowed values in ascending order
+    Custom(Vec<u32>),
+}
+
+impl PointScale{
+    // XS, S, M, L, XL and XXL mapped onto the start of the Fibonacci scale
+    pub fn tshirt() -> Self{
+        PointScale::TShirt(TSHIRT.iter().map(|(size, points)| (size.to_string(), *points)).collect())
+    }
+
+    // The allowed values, smallest first; None when any value goes
+    pub fn values(&self) -> Option<Vec<u32>>{
+        match self{
+            PointScale::Any => None,
+            PointScale::Fibonacci => Some(FIBONACCI.to_vec()),
+            PointScale::PowersOfTwo => Some(POWERS_OF_TWO.to_vec()),
+            PointScale::TShirt(sizes) => Some(sizes.iter().map(|(_, p)| *p).collect()),
+            PointScale::Custom(values) => Some(values.clone()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if let PointScale::TShirt(sizes) = self{
+            if sizes.iter().any(|(size, _)| size.trim().is_empty()){
+                return Err("T-shirt sizes need a name");
+            }
+            for (i, (size, _)) in sizes.iter().enumerate(){
+                if sizes[..i].iter().any(|(other, _)| other.eq_ignore_ascii_case(size)){
+                    return Err("T-shirt size names must be unique");
+                }
+            }
+        }
+        match self.values(){
+            Some(values) if values.is_empty() => Err("A point scale needs at least one value"),
+            Some(values) if values.windows(2).any(|w| w[0] >= w[1]) => Err("Point values must be unique and in ascending order"),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn accepts(&self, points: u32) -> bool{
+        self.values().is_none_or(|values| values.contains(&points))
+    }
+
+    // The smallest allowed value that is at least `points`, or the largest
+    // when `points` is off the top of the scale
+    pub fn snap(&self, points: u32) -> u32{
+        match self.values(){
+            None => points,
+            Some(values) => values.iter().copied().find(|v| *v >= points).or(values.last().copied()).unwrap_or(points),
+        }
+    }
+
+    // A value from another scale expressed on this one
+    pub fn convert(&self, points: u32, from: &PointScale) -> u32{
+        self.snap(from.snap(points))
+    }
+
+    // How a value reads in reports: the size name on a t-shirt scale
+    // (rounded up to the next size), else the number
+    pub fn label(&self, points: u32) -> String{
+        match self{
+            PointScale::TShirt(sizes) => sizes.iter()
+                .find(|(_, p)| *p >= points)
+                .or(sizes.last())
+                .map(|(size, _)| size.clone())
+                .unwrap_or_else(|| points.to_string()),
+            _ => points.to_string(),
+        }
+    }
+
+    // Reads an estimate given as a number or, on a t-shirt scale, a size name
+    pub fn parse(&self, value: &str) -> Result<u32,&'static str>{
+        let value = value.trim();
+        let points = match (self, value.parse::<u32>()){
+            (_, Ok(points)) => points,
+            (PointScale::TShirt(sizes), Err(_)) => sizes.iter()
+                .find(|(size, _)| size.eq_ignore_ascii_case(value))
+                .map(|(_, p)| *p)
+                .ok_or("Unknown size on the project's point scale")?,
+            (_, Err(_)) => return Err("Points must be a whole number"),
+        };
+        if !self.accepts(points){
+            return Err("The points are not on the project's point scale");
+        }
+        Ok(points)
+    }
+}
+
+impl fmt::Display for PointScale{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            PointScale::Any => write!(f, "any"),
+            PointScale::Fibonacci => write!(f, "fibonacci"),
+            PointScale::PowersOfTwo => write!(f, "powers-of-two"),
+            PointScale::TShirt(sizes) => {
+                let sizes: Vec<String> = sizes.iter().map(|(size, p)| format!("{}={}", size, p)).collect();
+                write!(f, "{}", sizes.join(","))
+            }
+            PointScale::Custom(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "{}", values.join(","))
+            }
+        }
+    }
+}
+
+// "any", "fibonacci", "powers-of-two", "t-shirt", a list of values such as
+// "1,2,4,8", or sizes with their points such as "S=1,M=3,L=8"
+impl FromStr for PointScale{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        let scale = match s.trim().to_ascii_lowercase().as_str(){
+            "any" => PointScale::Any,
+            "fibonacci" | "fib" => PointScale::Fibonacci,
+            "powers-of-two" | "pow2" => PointScale::PowersOfTwo,
+            "t-shirt" | "tshirt" => PointScale::tshirt(),
+            _ if s.contains('=') => PointScale::TShirt(s.split(',')
+                .map(|pair| {
+                    let (size, points) = pair.split_once('=').ok_or("Sizes are written as NAME=POINTS")?;
+                    let points = points.trim().parse::<u32>().map_err(|_| "Sizes are written as NAME=POINTS")?;
+                    Ok((size.trim().to_string(), points))
+                })
+                .collect::<Result<Vec<_>,&'static str>>()?),
+            _ => PointScale::Custom(s.split(',')
+                .map(|v| v.trim().parse::<u32>().map_err(|_| "Unknown point scale; try fibonacci, powers-of-two, t-shirt or a list of values"))
+                .collect::<Result<Vec<_>,&'static str>>()?),
+        };
+        scale.validate()?;
+        Ok(scale)
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority{
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Priority{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let label = match self{
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+            Priority::Critical => "Critical",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl FromStr for Priority{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        match s.trim().to_lowercase().as_str(){
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            _ => Err("Unknown priority; expected low, medium, high or critical"),
+        }
+    }
+}
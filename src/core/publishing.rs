@@ -0,0 +1,97 @@
+// Publish targets - pages and calendars outside pm that a project is pushed
+// to, kept in the settings so every copy of the file publishes to the same
+// place. Credentials are never stored here; see publish/ for how they are read.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Destination{
+    // An existing page, overwritten on every publish
+    Confluence{ base_url: String, page_id: String },
+    // A database that gets a new page per publish
+    Notion{ database_id: String },
+}
+
+impl Destination{
+    pub fn system(&self) -> &'static str{
+        match self{
+            Destination::Confluence{..} => "confluence",
+            Destination::Notion{..} => "notion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishTarget{
+    pub name: String,
+    // The Project whose report is published
+    pub project: Uuid,
+    pub destination: Destination,
+    // Publish again once this many days have passed; on demand only without
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub every_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_published: Option<DateTime<Utc>>,
+    // Report template from the settings to publish with; the built-in one without
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl PublishTarget{
+    // Whether a scheduled run at `now` should publish
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool{
+        match (self.every_days, self.last_published){
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(days), Some(last)) => now - last >= TimeDelta::days(days as i64),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if self.name.trim().is_empty(){
+            return Err("A publish target needs a name");
+        }
+        if self.every_days == Some(0){
+            return Err("Scheduled publishing must wait at least a day");
+        }
+        let empty = match &self.destination{
+            Destination::Confluence{ base_url, page_id } => base_url.trim().is_empty() || page_id.trim().is_empty(),
+            Destination::Notion{ database_id } => database_id.trim().is_empty(),
+        };
+        if empty{
+            return Err("A publish target needs the page or database to publish to");
+        }
+        if let Destination::Confluence{ base_url, page_id } = &self.destination{
+            let host = base_url.trim().strip_prefix("https://").unwrap_or_default();
+            if host.is_empty() || host.starts_with('/') || base_url.contains(char::is_whitespace){
+                return Err("A Confluence base URL must be an https:// URL");
+            }
+            // It goes into the REST path as it is
+            if !page_id.chars().all(|c| c.is_ascii_digit()){
+                return Err("A Confluence page id is a number");
+            }
+        }
+        Ok(())
+    }
+}
+
+// Google calendars that milestones, sprint boundaries and deadlines are
+// synced to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarSync{
+    // Releases, epic ends and sprint boundaries, and deadlines of owners
+    // without a calendar of their own
+    pub calendar_id: String,
+    // Owner -> the calendar their deadlines go to
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub owner_calendars: BTreeMap<String,String>,
+}
+
+impl CalendarSync{
+    pub fn calendar_for(&self, owner: Option<&str>) -> &str{
+        owner.and_then(|o| self.owner_calendars.get(o)).unwrap_or(&self.calendar_id)
+    }
+}
@@ -0,0 +1,231 @@
+// Query builder over a ProjectGraph
+//
+//   graph.query().owner("alice").status(Status::InProgress).tag("backend").run()
+//
+// Owner, status and tag filters are answered from the graph's secondary
+// indexes, starting from the smallest matching set; scope and custom
+// predicates are then checked on the remaining candidates only.
+//
+// Results come ordered by id unless sorted otherwise; ties always fall back
+// to the id so pages stay stable between calls:
+//
+//   graph.query().status(Status::NotStarted).sort_by(SortKey::Start).offset(20).limit(10).run()
+
+use super::graph::ProjectGraph;
+use super::{Node, Scope, Status};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey{
+    #[default]
+    Id,
+    // Short keys compare by prefix, then numerically (TASK-2 before TASK-10)
+    Key,
+    Name,
+    Status,
+    Owner,
+    // Most urgent first
+    Priority,
+    Points,
+    Start,
+    End,
+}
+
+impl FromStr for SortKey{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        Ok(match s.trim().to_lowercase().as_str(){
+            "id" => SortKey::Id,
+            "key" => SortKey::Key,
+            "name" => SortKey::Name,
+            "status" => SortKey::Status,
+            "owner" => SortKey::Owner,
+            "priority" => SortKey::Priority,
+            "points" => SortKey::Points,
+            "start" => SortKey::Start,
+            "end" => SortKey::End,
+            _ => return Err("Unknown sort key; expected id, key, name, status, owner, priority, points, start or end"),
+        })
+    }
+}
+
+fn split_key(key: &str) -> (&str, u64){
+    match key.rsplit_once('-'){
+        Some((prefix, n)) => (prefix, n.parse().unwrap_or(0)),
+        None => (key, 0),
+    }
+}
+
+// Missing values sort after present ones in either direction
+fn present_first<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering{
+    match (a, b){
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+type Predicate<'a> = Box<dyn Fn(&Node) -> bool + 'a>;
+
+pub struct Query<'a>{
+    graph: &'a ProjectGraph,
+    owner: Option<String>,
+    status: Option<Status>,
+    tags: Vec<String>,
+    scope: Scope,
+    predicates: Vec<Predicate<'a>>,
+    sort: SortKey,
+    descending: bool,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl<'a> Query<'a>{
+    pub fn new(graph: &'a ProjectGraph) -> Self{
+        Query{
+            graph,
+            owner: None,
+            status: None,
+            tags: Vec::new(),
+            scope: Scope::All,
+            predicates: Vec::new(),
+            sort: SortKey::Id,
+            descending: false,
+            offset: 0,
+            limit: None,
+        }
+    }
+
+    pub fn owner(mut self, owner: &str) -> Self{
+        self.owner = Some(owner.to_string());
+        self
+    }
+
+    pub fn status(mut self, status: Status) -> Self{
+        self.status = Some(status);
+        self
+    }
+
+    // Repeated calls require every tag
+    pub fn tag(mut self, tag: &str) -> Self{
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn in_scope(mut self, scope: Scope) -> Self{
+        self.scope = scope;
+        self
+    }
+
+    // Any other condition, e.g. `.matching(|n| matches!(n, Node::Tasks{..}))`
+    pub fn matching<F: Fn(&Node) -> bool + 'a>(mut self, predicate: F) -> Self{
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    pub fn sort_by(mut self, key: SortKey) -> Self{
+        self.sort = key;
+        self
+    }
+
+    // Reverses the sort; the id tie-break and missing values stay last
+    pub fn descending(mut self) -> Self{
+        self.descending = true;
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self{
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self{
+        self.limit = Some(limit);
+        self
+    }
+
+    fn compare(&self, a: Uuid, b: Uuid) -> Ordering{
+        let (graph, desc) = (self.graph, self.descending);
+        let (Some(na), Some(nb)) = (graph.get_node(a), graph.get_node(b)) else {
+            return a.cmp(&b);
+        };
+        let primary = match self.sort{
+            SortKey::Id => Ordering::Equal,
+            SortKey::Key => present_first(graph.get_key(a).map(split_key), graph.get_key(b).map(split_key), desc),
+            SortKey::Name => present_first(Some(na.get_name()), Some(nb.get_name()), desc),
+            SortKey::Status => present_first(na.get_status().map(|s| s as u8), nb.get_status().map(|s| s as u8), desc),
+            SortKey::Owner => present_first(na.get_owner(), nb.get_owner(), desc),
+            SortKey::Priority => present_first(graph.get_priority(a).map(Reverse), graph.get_priority(b).map(Reverse), desc),
+            SortKey::Points => present_first(na.get_points(), nb.get_points(), desc),
+            SortKey::Start => present_first(na.get_timeline().map(|t| t.start), nb.get_timeline().map(|t| t.start), desc),
+            SortKey::End => present_first(na.get_timeline().and_then(|t| t.end), nb.get_timeline().and_then(|t| t.end), desc),
+        };
+        let by_id = if self.sort == SortKey::Id && desc { b.cmp(&a) } else { a.cmp(&b) };
+        primary.then(by_id)
+    }
+
+    fn indexed_sets(&self) -> Option<Vec<&'a HashSet<Uuid>>>{
+        let indexes = self.graph.indexes();
+        let mut sets = Vec::new();
+        if let Some(owner) = &self.owner{
+            sets.push(indexes.by_owner(owner)?);
+        }
+        if let Some(status) = self.status{
+            sets.push(indexes.by_status(status)?);
+        }
+        for tag in &self.tags{
+            sets.push(indexes.by_tag(tag)?);
+        }
+        Some(sets)
+    }
+
+    // Matching node ids in sort order, one page of them when paged
+    pub fn ids(&self) -> Vec<Uuid>{
+        let mut ids = self.matching_ids();
+        if self.sort != SortKey::Id || self.descending{
+            ids.sort_by(|a, b| self.compare(*a, *b));
+        }
+        ids.into_iter().skip(self.offset).take(self.limit.unwrap_or(usize::MAX)).collect()
+    }
+
+    fn matching_ids(&self) -> Vec<Uuid>{
+        // An indexed filter with no entry at all means nothing can match
+        let Some(mut sets) = self.indexed_sets() else {
+            return Vec::new();
+        };
+        sets.sort_by_key(|s| s.len());
+
+        let mut ids: Vec<Uuid> = match sets.split_first(){
+            Some((smallest, rest)) => smallest.iter()
+                .filter(|id| rest.iter().all(|s| s.contains(id)))
+                .copied()
+                .collect(),
+            None => self.graph.nodes().map(|n| n.get_id()).collect(),
+        };
+
+        if let Scope::Subtree(root) = self.scope{
+            let subtree: HashSet<Uuid> = self.graph.get_subtree(root).into_iter().collect();
+            ids.retain(|id| subtree.contains(id));
+        }
+        if !self.predicates.is_empty(){
+            ids.retain(|id| self.graph.get_node(*id).is_some_and(|n| self.predicates.iter().all(|p| p(n))));
+        }
+        ids.sort();
+        ids
+    }
+
+    pub fn run(&self) -> Vec<&'a Node>{
+        self.ids().into_iter().filter_map(|id| self.graph.get_node(id)).collect()
+    }
+
+    // All matches, ignoring offset and limit
+    pub fn count(&self) -> usize{
+        self.matching_ids().len()
+    }
+}
@@ -0,0 +1,115 @@
+// Quick add - a task from one line of text, e.g.
+//
+//   Fix login bug @alice #backend !p1 3pts due:fri
+//
+// Recognised words, anywhere in the line:
+//   @owner         the owner
+//   #tag           a tag, may repeat
+//   !p1 .. !p4     priority, p1 being Critical; !low .. !critical also work
+//   3pts, 3pt      points, checked against the point scale
+//   due:<date>     the end of the timeline, anything `parse_human` reads,
+//                  with dashes for spaces ("due:next-friday")
+// Everything else makes up the name. The timeline runs from today to the due
+// date, or is today alone without one.
+
+use super::calendar::Calendar;
+use super::dates::parse_human;
+use super::node::NodeBuilder;
+use super::points::PointScale;
+use super::priority::Priority;
+use super::timeline::Timeline;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+// Node id suffix for quick-added nodes
+const QUICKADD_ID: &[u8; 6] = b"pmquik";
+
+#[derive(Debug, Clone)]
+pub struct QuickAdd{
+    // Id, name, timeline and whatever else the line gave; ready for build_tasks
+    pub builder: NodeBuilder,
+    // Kept by the graph rather than the node, see ProjectGraph::set_priority
+    pub priority: Option<Priority>,
+}
+
+fn parse_priority(value: &str) -> Result<Priority,&'static str>{
+    match value.to_lowercase().as_str(){
+        "p1" => Ok(Priority::Critical),
+        "p2" => Ok(Priority::High),
+        "p3" => Ok(Priority::Medium),
+        "p4" => Ok(Priority::Low),
+        other => other.parse().map_err(|_| "Unknown priority; expected p1 to p4, or low, medium, high or critical"),
+    }
+}
+
+fn parse_points(word: &str) -> Option<&str>{
+    let lower = word.to_lowercase();
+    ["points", "pts", "pt"].iter()
+        .find(|suffix| lower.ends_with(*suffix))
+        .map(|suffix| &word[..word.len() - suffix.len()])
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn once<T>(slot: &mut Option<T>, value: T, err: &'static str) -> Result<(),&'static str>{
+    if slot.is_some(){
+        return Err(err);
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+pub fn parse_quickadd(input: &str) -> Result<QuickAdd,&'static str>{
+    parse_quickadd_at(input, Utc::now(), &Calendar::default(), &PointScale::Any)
+}
+
+// Same as `parse_quickadd`, reading dates from `now` on the project calendar
+// and points on the project scale
+pub fn parse_quickadd_at(input: &str, now: DateTime<Utc>, calendar: &Calendar, scale: &PointScale) -> Result<QuickAdd,&'static str>{
+    let mut name: Vec<&str> = Vec::new();
+    let mut owner = None;
+    let mut tags = Vec::new();
+    let mut priority = None;
+    let mut points = None;
+    let mut due: Option<NaiveDate> = None;
+
+    for word in input.split_whitespace(){
+        if let Some(o) = word.strip_prefix('@').filter(|o| !o.is_empty()){
+            once(&mut owner, o.to_string(), "Only one @owner may be given")?;
+        }else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()){
+            tags.push(tag.to_string());
+        }else if let Some(p) = word.strip_prefix('!').filter(|p| !p.is_empty()){
+            once(&mut priority, parse_priority(p)?, "Only one !priority may be given")?;
+        }else if let Some(n) = parse_points(word){
+            once(&mut points, scale.parse(n)?, "Only one points value may be given")?;
+        }else if let Some(date) = word.strip_prefix("due:").filter(|d| !d.is_empty()){
+            let date = parse_human(date, now, calendar).or_else(|_| parse_human(&date.replace('-', " "), now, calendar))?;
+            once(&mut due, date, "Only one due: date may be given")?;
+        }else{
+            name.push(word);
+        }
+    }
+    if name.is_empty(){
+        return Err("A quick-added task needs a name");
+    }
+
+    let today = now.date_naive();
+    let end = due.unwrap_or(today);
+    if end < today{
+        return Err("The due date is before today");
+    }
+    let midnight = |d: NaiveDate| d.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc();
+    let mut builder = NodeBuilder::new()
+        .with_id(Uuid::now_v6(QUICKADD_ID))
+        .with_name(name.join(" "))
+        .with_timeline(Timeline::from_start_end(midnight(today), midnight(end)));
+    if let Some(owner) = owner{
+        builder = builder.with_owner(owner);
+    }
+    if let Some(points) = points{
+        builder = builder.with_points(points);
+    }
+    for tag in tags{
+        builder = builder.with_tag(tag);
+    }
+    Ok(QuickAdd{ builder, priority })
+}
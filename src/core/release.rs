@@ -0,0 +1,96 @@
+// Release - a version that Epics and Stories can be targeted to
+//
+// Once a release is cut, every scope change is recorded so we can report
+// what was added or removed after planning was frozen.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScopeChangeKind{
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeChange{
+    pub node: Uuid,
+    pub kind: ScopeChangeKind,
+    pub at: DT,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release{
+    pub id: Uuid,
+    pub name: String,
+    pub target_date: DT,
+    pub cut_date: Option<DT>,
+    #[serde(serialize_with = "super::sorted::sorted_set")]
+    scope: HashSet<Uuid>,
+    scope_log: Vec<ScopeChange>,
+}
+
+impl Release{
+    pub fn new(id: Uuid, name: String, target_date: DT) -> Self{
+        Release{
+            id,
+            name,
+            target_date,
+            cut_date: None,
+            scope: HashSet::new(),
+            scope_log: Vec::new(),
+        }
+    }
+
+    pub fn is_cut(&self) -> bool{
+        self.cut_date.is_some()
+    }
+
+    pub fn cut(&mut self, at: DT) -> Result<(),&'static str>{
+        if self.is_cut(){
+            return Err("Release has already been cut");
+        }
+        self.cut_date = Some(at);
+        Ok(())
+    }
+
+    pub fn contains(&self, node: Uuid) -> bool{
+        self.scope.contains(&node)
+    }
+
+    pub fn get_scope(&self) -> &HashSet<Uuid>{
+        &self.scope
+    }
+
+    pub fn add_to_scope(&mut self, node: Uuid, at: DT) -> bool{
+        let inserted = self.scope.insert(node);
+        if inserted{
+            self.scope_log.push(ScopeChange{ node, kind: ScopeChangeKind::Added, at });
+        }
+        inserted
+    }
+
+    pub fn remove_from_scope(&mut self, node: Uuid, at: DT) -> bool{
+        let removed = self.scope.remove(&node);
+        if removed{
+            self.scope_log.push(ScopeChange{ node, kind: ScopeChangeKind::Removed, at });
+        }
+        removed
+    }
+
+    pub fn get_scope_log(&self) -> &[ScopeChange]{
+        &self.scope_log
+    }
+
+    // Changes recorded after the release was cut; empty if it hasn't been cut
+    pub fn scope_changes_after_cut(&self) -> Vec<&ScopeChange>{
+        match self.cut_date{
+            Some(cut) => self.scope_log.iter().filter(|c| c.at > cut).collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Dependencies on nodes that live in another project file
+//
+// A local node can wait on a node of another team's plan without the two
+// graphs being merged. The graph only stores the reference and the last
+// known state of the remote node; loading the other file to refresh that
+// state is left to the caller (see storage::remote), so it happens lazily
+// and only when someone asks.
+
+use super::status::Status;
+use super::timeline::{Duration, ToTimeDelta};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RemoteRef{
+    // Where the node lives: a project file path relative to this one, or
+    // the name of a project in the same portfolio
+    pub source: String,
+    pub node: Uuid,
+}
+
+impl RemoteRef{
+    pub fn new(source: &str, node: Uuid) -> Self{
+        RemoteRef{ source: source.to_string(), node }
+    }
+}
+
+// What the remote node looked like when it was last resolved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteNode{
+    pub name: String,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub status: Option<Status>,
+    // Scheduled finish in its own project
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    pub resolved_at: DateTime<Utc>,
+}
+
+// The remote node blocks the local one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteDependency{
+    pub remote: RemoteRef,
+    pub node: Uuid,
+    #[serde(default)]
+    pub lag: Option<Duration>,
+    // None until resolved, or when the remote node could not be found
+    #[serde(default)]
+    pub last_known: Option<RemoteNode>,
+}
+
+impl RemoteDependency{
+    pub fn new(remote: RemoteRef, node: Uuid) -> Self{
+        RemoteDependency{ remote, node, lag: None, last_known: None }
+    }
+
+    pub fn is_resolved(&self) -> bool{
+        self.last_known.is_some()
+    }
+
+    // Earliest the local node can start by what is known of the remote one
+    pub fn earliest_start(&self) -> Option<DateTime<Utc>>{
+        let end = self.last_known.as_ref()?.end?;
+        Some(end + self.lag.as_ref().map(|l| l.to_time_delta()).unwrap_or_default())
+    }
+
+    // Still holding the local node up, as far as anyone knows
+    pub fn is_open(&self) -> bool{
+        !self.last_known.as_ref().is_some_and(|r| r.status.is_some_and(|s| s.is_done()))
+    }
+}
@@ -0,0 +1,140 @@
+// Risk - an entry in the risk register, linkable to any node in the graph
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "StoredRisk")]
+pub struct Risk{
+    id: Uuid,
+    title: String,
+    // Likelihood between 0.0 and 1.0
+    probability: f64,
+    // Severity on a 1 (negligible) to 5 (critical) scale
+    impact: u8,
+    mitigation: Option<String>,
+    owner: Option<String>,
+    #[serde(serialize_with = "super::sorted::sorted_set")]
+    linked: HashSet<Uuid>,
+}
+
+#[derive(Deserialize)]
+struct StoredRisk{
+    id: Uuid,
+    title: String,
+    probability: f64,
+    impact: u8,
+    mitigation: Option<String>,
+    owner: Option<String>,
+    linked: HashSet<Uuid>,
+}
+
+// A file gets the same checks as the setters
+impl TryFrom<StoredRisk> for Risk{
+    type Error = &'static str;
+
+    fn try_from(stored: StoredRisk) -> Result<Self,Self::Error>{
+        let mut risk = Risk::new(stored.id, stored.title, stored.probability, stored.impact)?;
+        risk.mitigation = stored.mitigation;
+        risk.owner = stored.owner;
+        risk.linked = stored.linked;
+        Ok(risk)
+    }
+}
+
+impl Risk{
+    pub fn new(id: Uuid, title: String, probability: f64, impact: u8) -> Result<Self,&'static str>{
+        let mut risk = Risk{
+            id,
+            title,
+            probability: 0.0,
+            impact: 1,
+            mitigation: None,
+            owner: None,
+            linked: HashSet::new(),
+        };
+        risk.set_probability(probability)?;
+        risk.set_impact(impact)?;
+        Ok(risk)
+    }
+
+    pub fn with_mitigation(mut self, mitigation: String) -> Self{
+        self.mitigation = Some(mitigation);
+        self
+    }
+
+    pub fn with_owner(mut self, owner: String) -> Self{
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn get_id(&self) -> Uuid{
+        self.id
+    }
+
+    pub fn get_title(&self) -> &str{
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: String){
+        self.title = title;
+    }
+
+    pub fn get_probability(&self) -> f64{
+        self.probability
+    }
+
+    pub fn set_probability(&mut self, probability: f64) -> Result<(),&'static str>{
+        if !(0.0..=1.0).contains(&probability){
+            return Err("Risk probability must be between 0.0 and 1.0");
+        }
+        self.probability = probability;
+        Ok(())
+    }
+
+    pub fn get_impact(&self) -> u8{
+        self.impact
+    }
+
+    pub fn set_impact(&mut self, impact: u8) -> Result<(),&'static str>{
+        if !(1..=5).contains(&impact){
+            return Err("Risk impact must be between 1 and 5");
+        }
+        self.impact = impact;
+        Ok(())
+    }
+
+    pub fn get_mitigation(&self) -> Option<&str>{
+        self.mitigation.as_deref()
+    }
+
+    pub fn set_mitigation(&mut self, mitigation: Option<String>){
+        self.mitigation = mitigation;
+    }
+
+    pub fn get_owner(&self) -> Option<&str>{
+        self.owner.as_deref()
+    }
+
+    pub fn set_owner(&mut self, owner: Option<String>){
+        self.owner = owner;
+    }
+
+    // Expected impact, between 0.0 and 5.0
+    pub fn score(&self) -> f64{
+        self.probability * self.impact as f64
+    }
+
+    pub fn link(&mut self, node: Uuid) -> bool{
+        self.linked.insert(node)
+    }
+
+    pub fn unlink(&mut self, node: Uuid) -> bool{
+        self.linked.remove(&node)
+    }
+
+    pub fn get_linked(&self) -> &HashSet<Uuid>{
+        &self.linked
+    }
+}
@@ -0,0 +1,85 @@
+// Aggregates over a node's subtree (points, progress, planned span) and a
+// cache for them. The graph invalidates a node's entry and those of its
+// ancestors whenever something inside the subtree changes, so repeated
+// rollups only recompute what an edit actually touched.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rollup{
+    // Points and counts cover the contained work, not the node itself
+    pub total_points: u32,
+    pub done_points: u32,
+    pub items: usize,
+    pub done_items: usize,
+    // Whether the node itself is done, used when it contains no work
+    pub self_done: bool,
+    // Earliest planned start and latest planned end across the subtree
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Rollup{
+    // Percentage of done work, weighted by points when any item is estimated
+    pub fn completion(&self) -> f64{
+        if self.items == 0{
+            return if self.self_done { 100.0 } else { 0.0 };
+        }
+        if self.total_points > 0{
+            100.0 * self.done_points as f64 / self.total_points as f64
+        }else{
+            100.0 * self.done_items as f64 / self.items as f64
+        }
+    }
+}
+
+// Shared, thread-safe cache so rollups can be filled from `&ProjectGraph`
+#[derive(Debug, Default)]
+pub struct RollupCache{
+    entries: RwLock<HashMap<Uuid,Rollup>>,
+}
+
+impl RollupCache{
+    pub fn new() -> Self{
+        RollupCache::default()
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<Rollup>{
+        self.entries.read().ok()?.get(&id).copied()
+    }
+
+    pub fn insert(&self, id: Uuid, rollup: Rollup){
+        if let Ok(mut entries) = self.entries.write(){
+            entries.insert(id, rollup);
+        }
+    }
+
+    pub fn invalidate<I: IntoIterator<Item = Uuid>>(&mut self, ids: I){
+        let entries = self.entries.get_mut().unwrap_or_else(|e| e.into_inner());
+        for id in ids{
+            entries.remove(&id);
+        }
+    }
+
+    pub fn clear(&mut self){
+        self.entries.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    pub fn len(&self) -> usize{
+        self.entries.read().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+impl Clone for RollupCache{
+    fn clone(&self) -> Self{
+        let entries = self.entries.read().map(|e| e.clone()).unwrap_or_default();
+        RollupCache{ entries: RwLock::new(entries) }
+    }
+}
@@ -0,0 +1,10 @@
+// Scope - selects which part of the graph a view or report operates on
+
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope{
+    All,
+    // The node itself plus everything it (transitively) Contains
+    Subtree(Uuid),
+}
@@ -0,0 +1,219 @@
+// Full-text search over the names, tags, descriptions and comments of the
+// nodes of a graph
+//
+//   graph.search("login timeout", 10)
+//
+// The inverted index is kept up to date by the ProjectGraph mutators, like
+// the secondary indexes. A node matches when it contains every query word;
+// the last word also matches as a prefix so partial input finds something.
+// Hits are ranked by tf-idf, with words in the name and tags counting more
+// than words buried in a description or comment.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field{
+    Name,
+    Tag,
+    Description,
+    Comment,
+}
+
+impl Field{
+    fn weight(self) -> f64{
+        match self{
+            Field::Name => 3.0,
+            Field::Tag => 2.0,
+            Field::Description | Field::Comment => 1.0,
+        }
+    }
+}
+
+// Lowercased alphanumeric words of `text` with their byte ranges
+fn words(text: &str) -> impl Iterator<Item = (String,Range<usize>)> + '_{
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while chars.peek().is_some_and(|(_, c)| !c.is_alphanumeric()){
+            chars.next();
+        }
+        let (start, _) = *chars.peek()?;
+        let mut end = start;
+        while let Some((i, c)) = chars.peek().copied().filter(|(_, c)| c.is_alphanumeric()){
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        Some((text[start..end].to_lowercase(), start..end))
+    })
+}
+
+pub fn tokenize(text: &str) -> Vec<String>{
+    words(text).map(|(word, _)| word).collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex{
+    // term -> node -> term frequency weighted by field
+    postings: HashMap<String,HashMap<Uuid,f64>>,
+    // The terms each node contributed, so it can be dropped or re-indexed
+    terms: HashMap<Uuid,HashSet<String>>,
+}
+
+impl SearchIndex{
+    pub fn new() -> Self{
+        SearchIndex::default()
+    }
+
+    // Replaces whatever was indexed for the node with the given fields
+    pub fn insert(&mut self, id: Uuid, fields: &[(Field,&str)]){
+        self.remove(id);
+        let mut terms = HashSet::new();
+        for (field, text) in fields{
+            for term in tokenize(text){
+                *self.postings.entry(term.clone()).or_default().entry(id).or_default() += field.weight();
+                terms.insert(term);
+            }
+        }
+        if !terms.is_empty(){
+            self.terms.insert(id, terms);
+        }
+    }
+
+    pub fn remove(&mut self, id: Uuid){
+        for term in self.terms.remove(&id).unwrap_or_default(){
+            if let Some(nodes) = self.postings.get_mut(&term){
+                nodes.remove(&id);
+                if nodes.is_empty(){
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.terms.is_empty()
+    }
+
+    // Nodes containing every query word with their scores, best first;
+    // ties by id
+    pub fn search(&self, query: &str) -> Vec<(Uuid,f64)>{
+        let query = tokenize(query);
+        let Some(last) = query.len().checked_sub(1) else {
+            return Vec::new();
+        };
+        let total = self.len() as f64;
+        let mut scores: Option<HashMap<Uuid,f64>> = None;
+        for (i, word) in query.iter().enumerate(){
+            let mut matched: HashMap<Uuid,f64> = HashMap::new();
+            let mut add = |nodes: &HashMap<Uuid,f64>, factor: f64| {
+                let idf = (1.0 + total / nodes.len() as f64).ln();
+                for (id, tf) in nodes{
+                    *matched.entry(*id).or_default() += tf * idf * factor;
+                }
+            };
+            if let Some(nodes) = self.postings.get(word){
+                add(nodes, 1.0);
+            }
+            if i == last{
+                // Completions of a partial last word count half
+                for (_, nodes) in self.postings.iter().filter(|(term, _)| term.len() > word.len() && term.starts_with(word.as_str())){
+                    add(nodes, 0.5);
+                }
+            }
+            scores = Some(match scores{
+                None => matched,
+                Some(mut scores) => {
+                    scores.retain(|id, _| matched.contains_key(id));
+                    for (id, score) in scores.iter_mut(){
+                        *score += matched[id];
+                    }
+                    scores
+                }
+            });
+        }
+        let mut hits: Vec<(Uuid,f64)> = scores.unwrap_or_default().into_iter().collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits
+    }
+}
+
+// An excerpt of a field with the byte ranges of the matched words
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet{
+    pub text: String,
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl Snippet{
+    // Cuts a window of about `width` bytes around the first word of `text`
+    // matching the query, or None if no word does
+    pub fn find(text: &str, query: &str, width: usize) -> Option<Snippet>{
+        let query = tokenize(query);
+        let last = query.len().checked_sub(1)?;
+        let matches: Vec<Range<usize>> = words(text)
+            .filter(|(word, _)| query.iter().enumerate().any(|(i, q)| word == q || (i == last && word.starts_with(q.as_str()))))
+            .map(|(_, range)| range)
+            .collect();
+        let first = matches.first()?;
+
+        let mut start = first.start.saturating_sub(width / 3);
+        while !text.is_char_boundary(start){
+            start -= 1;
+        }
+        let mut end = (start + width).max(first.end).min(text.len());
+        while !text.is_char_boundary(end){
+            end += 1;
+        }
+        // Don't cut words in half at either edge
+        if start > 0{
+            start = text[start..first.start].find(char::is_whitespace).map(|i| start + i + 1).unwrap_or(first.start);
+        }
+        if end < text.len(){
+            end = text[first.end..end].rfind(char::is_whitespace).map(|i| first.end + i).unwrap_or(end);
+        }
+
+        let prefix = if start > 0 { "…" } else { "" };
+        let suffix = if end < text.len() { "…" } else { "" };
+        let offset = prefix.len();
+        let highlights = matches.into_iter()
+            .filter(|m| m.start >= start && m.end <= end)
+            .map(|m| m.start - start + offset..m.end - start + offset)
+            .collect();
+        Some(Snippet{
+            text: format!("{}{}{}", prefix, text[start..end].trim_end(), suffix),
+            highlights,
+        })
+    }
+
+    // The excerpt with each match wrapped in `open` and `close`
+    pub fn highlighted(&self, open: &str, close: &str) -> String{
+        let mut out = String::new();
+        let mut at = 0;
+        for range in &self.highlights{
+            if range.start < at || range.end > self.text.len(){
+                continue;
+            }
+            out.push_str(&self.text[at..range.start]);
+            out.push_str(open);
+            out.push_str(&self.text[range.clone()]);
+            out.push_str(close);
+            at = range.end;
+        }
+        out.push_str(&self.text[at..]);
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit{
+    pub id: Uuid,
+    pub score: f64,
+    // Where the snippet was taken from
+    pub field: Field,
+    pub snippet: Snippet,
+}
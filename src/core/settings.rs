@@ -0,0 +1,203 @@
+// ProjectSettings - defaults that differ from organization to organization
+//
+// Stored with the graph, so a project file carries its own working hours,
+// point scale, sprint length, status model and connection rules instead of
+// everyone getting the same built-in behavior.
+
+use super::actual::ActualDates;
+use super::approval::ApprovalGate;
+use super::blocked::BlockedReasons;
+use super::checklist::DefinitionOfDone;
+use super::hierarchy::HierarchyRules;
+use super::i18n::I18nSettings;
+use super::money::MoneySettings;
+use super::points::PointScale;
+use super::publishing::{CalendarSync, Destination, PublishTarget};
+use super::sprint::Sprint;
+use super::team::Team;
+use super::templates::{ReportTemplate, TemplateReport};
+use super::theme::Theme;
+use super::timeline::Duration;
+use super::workflow::Workflow;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+// Which nodes may block or feed each other; what may contain what is up to
+// the hierarchy rules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionPolicy{
+    // Same or neighboring levels only (a story may block an epic, ...)
+    #[default]
+    Strict,
+    // Any two work items, whatever their level; specs stay out of it
+    Relaxed,
+}
+
+// Whether two nodes may be joined by edges of different kinds (a Blocks and a
+// ResourcesRequiredFor, say); two edges of the same kind are never allowed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParallelEdges{
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSettings{
+    // Length of a working day; hour estimates are spread over days of this length
+    pub hours_per_day: f64,
+    // What set_points accepts and how reports label points
+    #[serde(default)]
+    pub point_scale: PointScale,
+    pub sprint_days: u32,
+    // Status model for work under Projects without a workflow of their own
+    #[serde(default)]
+    pub workflow: Option<Workflow>,
+    #[serde(default)]
+    pub connections: ConnectionPolicy,
+    #[serde(default)]
+    pub parallel_edges: ParallelEdges,
+    // Which kinds of work may contain which, see hierarchy.rs
+    #[serde(default, skip_serializing_if = "HierarchyRules::is_default")]
+    pub hierarchy: HierarchyRules,
+    #[serde(default)]
+    pub actual_dates: ActualDates,
+    // Most open items one person may hold in a sprint; unlimited without one
+    #[serde(default)]
+    pub wip_limit: Option<u32>,
+    // Where `pm publish` sends status reports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub publish_targets: Vec<PublishTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_sync: Option<CalendarSync>,
+    // Checklist templates per node kind and whether Done waits for them
+    #[serde(default, skip_serializing_if = "DefinitionOfDone::is_empty")]
+    pub definition_of_done: DefinitionOfDone,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approval_gates: Vec<ApprovalGate>,
+    // What a node may be Blocked for, see blocked.rs
+    #[serde(default)]
+    pub blocked_reasons: BlockedReasons,
+    // Base currency, exchange rates and number locale, see money.rs
+    #[serde(default, skip_serializing_if = "MoneySettings::is_default")]
+    pub money: MoneySettings,
+    // Language reports are written in and the project's own catalogs, see i18n.rs
+    #[serde(default, skip_serializing_if = "I18nSettings::is_default")]
+    pub i18n: I18nSettings,
+    // Report layouts the project ships, by name, see templates.rs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String,ReportTemplate>,
+    // Colours and fonts of the exports, see theme.rs
+    #[serde(default, skip_serializing_if = "Theme::is_default")]
+    pub theme: Theme,
+}
+
+impl Default for ProjectSettings{
+    fn default() -> Self{
+        ProjectSettings{
+            hours_per_day: 8.0,
+            point_scale: PointScale::Any,
+            sprint_days: 14,
+            workflow: None,
+            connections: ConnectionPolicy::Strict,
+            parallel_edges: ParallelEdges::Allow,
+            hierarchy: HierarchyRules::default(),
+            actual_dates: ActualDates::FromStatus,
+            wip_limit: None,
+            publish_targets: Vec::new(),
+            calendar_sync: None,
+            definition_of_done: DefinitionOfDone::default(),
+            approval_gates: Vec::new(),
+            blocked_reasons: BlockedReasons::default(),
+            money: MoneySettings::default(),
+            i18n: I18nSettings::default(),
+            templates: BTreeMap::new(),
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl ProjectSettings{
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if !(self.hours_per_day > 0.0 && self.hours_per_day <= 24.0){
+            return Err("A working day must last between 0 and 24 hours");
+        }
+        if self.sprint_days == 0{
+            return Err("Sprints must last at least a day");
+        }
+        if self.wip_limit == Some(0){
+            return Err("A WIP limit must allow at least one item");
+        }
+        self.point_scale.validate()?;
+        self.hierarchy.validate()?;
+        if let Some(workflow) = &self.workflow{
+            workflow.validate()?;
+        }
+        for (i, target) in self.publish_targets.iter().enumerate(){
+            target.validate()?;
+            if self.publish_targets[..i].iter().any(|t| t.name == target.name){
+                return Err("Two publish targets share a name");
+            }
+            if let Some(name) = &target.template{
+                let template = self.templates.get(name).ok_or("A publish target names a report template the project does not have")?;
+                let html = matches!(target.destination, Destination::Confluence{..});
+                if template.report != TemplateReport::Status || template.html != html{
+                    return Err("Publish targets take status report templates: HTML for Confluence, markdown for Notion");
+                }
+            }
+        }
+        if let Some(sync) = &self.calendar_sync{
+            if sync.calendar_id.trim().is_empty() || sync.owner_calendars.values().any(|c| c.trim().is_empty()){
+                return Err("Calendar sync needs a calendar id for every calendar");
+            }
+        }
+        self.definition_of_done.validate()?;
+        self.blocked_reasons.validate()?;
+        self.money.validate()?;
+        self.i18n.validate()?;
+        for (name, template) in &self.templates{
+            if name.trim().is_empty(){
+                return Err("A report template needs a name");
+            }
+            template.validate()?;
+        }
+        self.theme.validate()?;
+        for (i, gate) in self.approval_gates.iter().enumerate(){
+            gate.validate()?;
+            if self.approval_gates[..i].iter().any(|g| g.name == gate.name){
+                return Err("Two approval gates share a name");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn accepts_points(&self, points: u32) -> bool{
+        self.point_scale.accepts(points)
+    }
+
+    // How long a planned duration takes on the calendar; hours count as
+    // working hours, days and weeks as they are
+    pub fn span_of(&self, duration: &Duration) -> TimeDelta{
+        match duration{
+            Duration::Hours(h) => TimeDelta::seconds((*h as f64 / self.hours_per_day * 86_400.0).round() as i64),
+            Duration::Days(d) => TimeDelta::days(*d),
+            Duration::Weeks(w) => TimeDelta::weeks(*w),
+        }
+    }
+
+    pub fn publish_target(&self, name: &str) -> Option<&PublishTarget>{
+        self.publish_targets.iter().find(|t| t.name == name)
+    }
+
+    // A team working this project's hours
+    pub fn team(&self, name: String) -> Team{
+        Team::new(name).with_hours_per_day(self.hours_per_day)
+    }
+
+    // A sprint of the default length starting at `start`
+    pub fn sprint(&self, id: Uuid, name: String, start: DateTime<Utc>) -> Result<Sprint,&'static str>{
+        Sprint::new(id, name, start, start + TimeDelta::days(self.sprint_days as i64))
+    }
+}
@@ -0,0 +1,71 @@
+// Shared graph with cheap, consistent read snapshots
+//
+// The current graph lives behind an Arc. A snapshot just clones the Arc, so
+// taking one is O(1) and it never changes afterwards. Writers go through
+// Arc::make_mut: the graph is modified in place when no snapshot is alive,
+// and copied once first when readers still hold the previous version.
+
+use super::graph::ProjectGraph;
+use std::ops::Deref;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone)]
+pub struct Snapshot{
+    graph: Arc<ProjectGraph>,
+    version: u64,
+}
+
+impl Snapshot{
+    // Number of updates applied before this snapshot was taken
+    pub fn version(&self) -> u64{
+        self.version
+    }
+}
+
+impl Deref for Snapshot{
+    type Target = ProjectGraph;
+
+    fn deref(&self) -> &ProjectGraph{
+        &self.graph
+    }
+}
+
+impl Default for Snapshot{
+    fn default() -> Self{
+        Snapshot{ graph: Arc::new(ProjectGraph::new()), version: 0 }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SharedGraph{
+    current: RwLock<Snapshot>,
+}
+
+impl SharedGraph{
+    pub fn new(graph: ProjectGraph) -> Self{
+        SharedGraph{ current: RwLock::new(Snapshot{ graph: Arc::new(graph), version: 0 }) }
+    }
+
+    pub fn snapshot(&self) -> Snapshot{
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Applies `change` to the live graph; snapshots taken earlier keep seeing
+    // the old version
+    pub fn update<T>(&self, change: impl FnOnce(&mut ProjectGraph) -> T) -> T{
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        let result = change(Arc::make_mut(&mut current.graph));
+        current.version += 1;
+        result
+    }
+
+    pub fn version(&self) -> u64{
+        self.current.read().unwrap_or_else(|e| e.into_inner()).version
+    }
+
+    // The graph itself, copied only if snapshots are still alive
+    pub fn into_inner(self) -> ProjectGraph{
+        let current = self.current.into_inner().unwrap_or_else(|e| e.into_inner());
+        Arc::unwrap_or_clone(current.graph)
+    }
+}
@@ -0,0 +1,26 @@
+// Serializers that emit hash sets in sorted order, so saved files don't
+// depend on HashSet iteration order
+
+use serde::Serializer;
+use std::collections::HashSet;
+
+pub fn sorted_set<S, T>(set: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Ord + serde::Serialize,
+{
+    let mut items: Vec<&T> = set.iter().collect();
+    items.sort();
+    serializer.collect_seq(items)
+}
+
+pub fn sorted_opt_set<S, T>(set: &Option<HashSet<T>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Ord + serde::Serialize,
+{
+    match set{
+        Some(set) => sorted_set(set, serializer),
+        None => serializer.serialize_none(),
+    }
+}
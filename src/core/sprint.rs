@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sprint{
+    pub id: Uuid,
+    pub name: String,
+    pub start: DT,
+    pub end: DT,
+    #[serde(serialize_with = "super::sorted::sorted_set")]
+    items: HashSet<Uuid>,
+}
+
+impl Sprint{
+    pub fn new(id: Uuid, name: String, start: DT, end: DT) -> Result<Self,&'static str>{
+        if end <= start{
+            return Err("Sprint must end after it starts");
+        }
+        Ok(Sprint{ id, name, start, end, items: HashSet::new() })
+    }
+
+    pub fn add_item(&mut self, node: Uuid) -> bool{
+        self.items.insert(node)
+    }
+
+    pub fn remove_item(&mut self, node: Uuid) -> bool{
+        self.items.remove(&node)
+    }
+
+    pub fn get_items(&self) -> &HashSet<Uuid>{
+        &self.items
+    }
+}
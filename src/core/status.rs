@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Status{
+    #[default]
+    NotStarted,
+    InProgress,
+    Blocked,
+    Done,
+}
+
+impl Status{
+    pub fn is_done(&self) -> bool{
+        matches!(self, Status::Done)
+    }
+}
+
+impl fmt::Display for Status{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let label = match self{
+            Status::NotStarted => "Not Started",
+            Status::InProgress => "In Progress",
+            Status::Blocked => "Blocked",
+            Status::Done => "Done",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// Accepts the display form and common spellings: "In Progress", "in-progress", "inprogress"
+impl FromStr for Status{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        let normalized: String = s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+        match normalized.as_str(){
+            "notstarted" | "todo" => Ok(Status::NotStarted),
+            "inprogress" | "doing" => Ok(Status::InProgress),
+            "blocked" => Ok(Status::Blocked),
+            "done" => Ok(Status::Done),
+            _ => Err("Unknown status; expected not-started, in-progress, blocked or done"),
+        }
+    }
+}
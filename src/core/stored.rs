@@ -0,0 +1,88 @@
+// How the node graph is saved: nodes in order, and edges naming their ends
+// by Uuid rather than by petgraph index, so a file stays valid however the
+// indices shift when nodes are removed or the graph is compacted
+//
+// Files from before this layout, with edges as [from index, to index,
+// dependency], still load.
+
+use super::graph::{Dependency, DependencyType};
+use super::timeline::Duration;
+use super::Node;
+use petgraph::graph::NodeIndex;
+use petgraph::{Directed, Graph};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct GraphOut<'a>{
+    nodes: Vec<&'a Node>,
+    edges: Vec<EdgeOut<'a>>,
+}
+
+#[derive(Serialize)]
+struct EdgeOut<'a>{
+    from: Uuid,
+    to: Uuid,
+    kind: DependencyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lag: &'a Option<Duration>,
+}
+
+#[derive(Deserialize)]
+struct GraphIn{
+    nodes: Vec<Node>,
+    #[serde(default)]
+    edges: Vec<EdgeIn>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EdgeIn{
+    ById{
+        from: Uuid,
+        to: Uuid,
+        kind: DependencyType,
+        #[serde(default)]
+        lag: Option<Duration>,
+    },
+    ByIndex(usize, usize, Dependency),
+}
+
+pub fn serialize<S: Serializer>(graph: &Graph<Node,Dependency,Directed>, serializer: S) -> Result<S::Ok, S::Error>{
+    let id = |idx: NodeIndex| graph[idx].get_id();
+    GraphOut{
+        nodes: graph.node_weights().collect(),
+        edges: graph.raw_edges().iter()
+            .map(|e| EdgeOut{ from: id(e.source()), to: id(e.target()), kind: e.weight.kind, lag: &e.weight.lag })
+            .collect(),
+    }.serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Graph<Node,Dependency,Directed>, D::Error>{
+    let stored = GraphIn::deserialize(deserializer)?;
+    let mut graph = Graph::with_capacity(stored.nodes.len(), stored.edges.len());
+    let mut index: HashMap<Uuid,NodeIndex> = HashMap::with_capacity(stored.nodes.len());
+    for node in stored.nodes{
+        let id = node.get_id();
+        index.insert(id, graph.add_node(node));
+    }
+    let count = graph.node_count();
+    for edge in stored.edges{
+        let (from, to, dependency) = match edge{
+            EdgeIn::ById{ from, to, kind, lag } => {
+                let end = |id: Uuid| index.get(&id).copied().ok_or_else(|| D::Error::custom(format!("an edge refers to {}, which is not among the nodes", id)));
+                (end(from)?, end(to)?, Dependency{ kind, lag })
+            }
+            EdgeIn::ByIndex(from, to, dependency) => {
+                if from >= count || to >= count{
+                    return Err(D::Error::custom(format!("an edge refers to node {}, but there are only {}", from.max(to), count)));
+                }
+                (NodeIndex::new(from), NodeIndex::new(to), dependency)
+            }
+        };
+        graph.add_edge(from, to, dependency);
+    }
+    Ok(graph)
+}
@@ -0,0 +1,117 @@
+// Bookkeeping for syncing with external trackers, saved with the project
+// so an interrupted sync resumes where it stopped. Which node an external
+// item maps to is recorded on the node itself, see ExternalRef.
+//
+// The same goes the other way for systems pm pushes to (calendars): what was
+// last sent for each remote item, so unchanged items are not sent again and
+// items pm no longer produces can be deleted.
+//
+// Pull requests linked from nodes are cached too, so watching them stays
+// within the API's rate limits.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Field name -> value as of the last sync
+type FieldValues = BTreeMap<String,Option<String>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PullState{
+    Draft,
+    Open,
+    Merged,
+    // Closed without merging
+    Closed,
+}
+
+impl PullState{
+    // Merged and closed PRs are not looked up again
+    pub fn is_final(&self) -> bool{
+        matches!(self, PullState::Merged | PullState::Closed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedPull{
+    pub state: PullState,
+    pub checked: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState{
+    // Per system, the cursor after the last page that was applied
+    cursors: BTreeMap<String,String>,
+    // Per system and remote id, the field values both sides agreed on last;
+    // lets a sync tell local edits from remote ones
+    #[serde(default)]
+    base: BTreeMap<String,BTreeMap<String,FieldValues>>,
+    // Per system and remote id, what was last pushed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pushed: BTreeMap<String,BTreeMap<String,String>>,
+    // Per pull request ("owner/repo#42"), its state when last looked up
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pulls: BTreeMap<String,CachedPull>,
+}
+
+impl SyncState{
+    pub fn cursor(&self, system: &str) -> Option<&str>{
+        self.cursors.get(system).map(String::as_str)
+    }
+
+    pub fn set_cursor(&mut self, system: &str, cursor: Option<String>){
+        match cursor{
+            Some(cursor) => { self.cursors.insert(system.to_string(), cursor); }
+            None => { self.cursors.remove(system); }
+        }
+    }
+
+    // None when the field has never been synced
+    pub fn base_value(&self, system: &str, remote_id: &str, field: &str) -> Option<Option<&str>>{
+        self.base.get(system)?.get(remote_id)?.get(field).map(|v| v.as_deref())
+    }
+
+    pub fn set_base_value(&mut self, system: &str, remote_id: &str, field: &str, value: Option<String>){
+        self.base.entry(system.to_string()).or_default()
+            .entry(remote_id.to_string()).or_default()
+            .insert(field.to_string(), value);
+    }
+
+    pub fn pushed(&self, system: &str, remote_id: &str) -> Option<&str>{
+        self.pushed.get(system)?.get(remote_id).map(String::as_str)
+    }
+
+    pub fn pushed_ids(&self, system: &str) -> Vec<String>{
+        self.pushed.get(system).map(|p| p.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    // None once the remote item has been deleted
+    pub fn set_pushed(&mut self, system: &str, remote_id: &str, content: Option<String>){
+        let entries = self.pushed.entry(system.to_string()).or_default();
+        match content{
+            Some(content) => { entries.insert(remote_id.to_string(), content); }
+            None => { entries.remove(remote_id); }
+        }
+    }
+
+    pub fn pull(&self, key: &str) -> Option<&CachedPull>{
+        self.pulls.get(key)
+    }
+
+    pub fn pull_keys(&self) -> Vec<String>{
+        self.pulls.keys().cloned().collect()
+    }
+
+    // None once no node links the pull request any more
+    pub fn set_pull(&mut self, key: &str, pull: Option<CachedPull>){
+        match pull{
+            Some(pull) => { self.pulls.insert(key.to_string(), pull); }
+            None => { self.pulls.remove(key); }
+        }
+    }
+
+    // Forgets the cursor so the next sync starts from the beginning
+    pub fn reset(&mut self, system: &str){
+        self.cursors.remove(system);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team{
+    pub name: String,
+    // Names of the people on the team, matching node owners
+    pub members: Vec<String>,
+    // Share of a working day spent on planned work (meetings, support, ... excluded)
+    pub focus_factor: f64,
+    pub hours_per_day: f64,
+    // Points one person delivers in a fully focused day
+    pub points_per_day: f64,
+}
+
+impl Team{
+    pub fn new(name: String) -> Self{
+        Team{
+            name,
+            members: Vec::new(),
+            focus_factor: 0.7,
+            hours_per_day: 8.0,
+            points_per_day: 1.0,
+        }
+    }
+
+    pub fn with_member(mut self, member: String) -> Self{
+        if !self.members.contains(&member){
+            self.members.push(member);
+        }
+        self
+    }
+
+    pub fn with_focus_factor(mut self, focus_factor: f64) -> Self{
+        self.focus_factor = focus_factor;
+        self
+    }
+
+    pub fn with_hours_per_day(mut self, hours_per_day: f64) -> Self{
+        self.hours_per_day = hours_per_day;
+        self
+    }
+
+    pub fn with_points_per_day(mut self, points_per_day: f64) -> Self{
+        self.points_per_day = points_per_day;
+        self
+    }
+
+    pub fn has_member(&self, name: &str) -> bool{
+        self.members.iter().any(|m| m == name)
+    }
+}
@@ -0,0 +1,56 @@
+// Report templates a project ships in its settings - named layouts for the
+// status report or the scenario comparison, written in the template language
+// of views/template.rs. Publish targets may name one to publish with.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateReport{
+    Status,
+    Comparison,
+    Brief,
+}
+
+impl fmt::Display for TemplateReport{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        write!(f, "{}", match self{
+            TemplateReport::Status => "status",
+            TemplateReport::Comparison => "comparison",
+            TemplateReport::Brief => "brief",
+        })
+    }
+}
+
+impl FromStr for TemplateReport{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        match s.trim().to_ascii_lowercase().as_str(){
+            "status" | "report" => Ok(TemplateReport::Status),
+            "comparison" | "compare" => Ok(TemplateReport::Comparison),
+            "brief" => Ok(TemplateReport::Brief),
+            _ => Err("Templates are for the status report, the comparison or the brief"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportTemplate{
+    pub report: TemplateReport,
+    // HTML templates escape the values they print; markdown ones do not
+    #[serde(default)]
+    pub html: bool,
+    pub body: String,
+}
+
+impl ReportTemplate{
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if self.body.trim().is_empty(){
+            return Err("A report template needs a body");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+// Theme - the colours and fonts exports are drawn with: the Gantt chart (SVG
+// and Mermaid), the dependency diagram (SVG, DOT and the explorer page) and
+// the HTML reports
+//
+// Boxes and bars are filled by status, by kind of node or by the owner's
+// team, whichever `fill_by` says; whatever has no colour of its own gets the
+// neutral fill. Colours are CSS colours; DOT and Mermaid take them as they
+// are, so #rrggbb is the safe choice. Settings missing from a project file
+// keep the built-in look.
+
+use super::Status;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const STATUS_NAMES: [&str; 4] = ["not-started", "in-progress", "blocked", "done"];
+pub const KIND_NAMES: [&str; 6] = ["project", "spec", "epic", "story", "task", "decision"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FillBy{
+    #[default]
+    Status,
+    Kind,
+    Team,
+}
+
+impl std::str::FromStr for FillBy{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        match s{
+            "status" => Ok(FillBy::Status),
+            "kind" => Ok(FillBy::Kind),
+            "team" => Ok(FillBy::Team),
+            _ => Err("Fill by status, kind or team"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme{
+    // A CSS font-family list
+    pub font: String,
+    pub font_size: u32,
+    pub fill_by: FillBy,
+    // Fills keyed by STATUS_NAMES, KIND_NAMES and team name
+    pub status: BTreeMap<String,String>,
+    pub kinds: BTreeMap<String,String>,
+    pub teams: BTreeMap<String,String>,
+    pub neutral: String,
+    pub text: String,
+    // Secondary text: axis labels, allocations
+    pub muted: String,
+    // Edges, outlines and grid lines
+    pub line: String,
+    // The critical path, and the today line
+    pub critical: String,
+    // Days off in the Gantt
+    pub shade: String,
+    // Summary bars in the Gantt
+    pub summary: String,
+}
+
+fn colors(pairs: &[(&str, &str)]) -> BTreeMap<String,String>{
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+impl Default for Theme{
+    fn default() -> Self{
+        Theme{
+            font: "sans-serif".to_string(),
+            font_size: 12,
+            fill_by: FillBy::Status,
+            status: colors(&[("not-started", "#eceff1"), ("in-progress", "#bbdefb"), ("blocked", "#ffcdd2"), ("done", "#c8e6c9")]),
+            kinds: colors(&[("project", "#d1c4e9"), ("spec", "#f5f5f5"), ("epic", "#b3e5fc"), ("story", "#dcedc8"), ("task", "#fff9c4"), ("decision", "#ffe0b2")]),
+            teams: BTreeMap::new(),
+            neutral: "#ffffff".to_string(),
+            text: "#263238".to_string(),
+            muted: "#546e7a".to_string(),
+            line: "#607d8b".to_string(),
+            critical: "#d32f2f".to_string(),
+            shade: "#eceff1".to_string(),
+            summary: "#455a64".to_string(),
+        }
+    }
+}
+
+// Nothing that could close the attribute or style rule it is written into
+fn is_safe(value: &str) -> bool{
+    !value.trim().is_empty() && !value.contains(['"', '<', '>', ';', '{', '}', '\\', '\n'])
+}
+
+pub fn status_name(status: Status) -> &'static str{
+    match status{
+        Status::NotStarted => "not-started",
+        Status::InProgress => "in-progress",
+        Status::Blocked => "blocked",
+        Status::Done => "done",
+    }
+}
+
+impl Theme{
+    pub fn is_default(&self) -> bool{
+        *self == Theme::default()
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        if !(6..=48).contains(&self.font_size){
+            return Err("The theme's font size must be between 6 and 48");
+        }
+        if !self.status.keys().all(|k| STATUS_NAMES.contains(&k.as_str())){
+            return Err("Theme status colours are for not-started, in-progress, blocked and done");
+        }
+        if !self.kinds.keys().all(|k| KIND_NAMES.contains(&k.as_str())){
+            return Err("Theme kind colours are for project, spec, epic, story, task and decision");
+        }
+        let plain = [&self.neutral, &self.text, &self.muted, &self.line, &self.critical, &self.shade, &self.summary];
+        let fills = self.status.values().chain(self.kinds.values()).chain(self.teams.values());
+        if !is_safe(&self.font) || !plain.into_iter().chain(fills).all(|c| is_safe(c)){
+            return Err("Theme colours and fonts must not be empty or hold quotes, <, >, ;, braces or backslashes");
+        }
+        Ok(())
+    }
+
+    // What a box or bar is filled with
+    pub fn fill(&self, status: Option<Status>, kind: &str, team: Option<&str>) -> &str{
+        let color = match self.fill_by{
+            FillBy::Status => status.and_then(|s| self.status.get(status_name(s))),
+            FillBy::Kind => self.kinds.get(kind),
+            FillBy::Team => team.and_then(|t| self.teams.get(t)),
+        };
+        color.unwrap_or(&self.neutral)
+    }
+
+    pub fn status_fill(&self, status: Status) -> &str{
+        self.status.get(status_name(status)).unwrap_or(&self.neutral)
+    }
+}
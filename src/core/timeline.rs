@@ -12,7 +12,7 @@ pub enum Duration {
 
 type DT = DateTime<Utc>;
 
-trait ToTimeDelta {
+pub trait ToTimeDelta {
     fn to_time_delta(&self) -> TimeDelta;
 }
 
@@ -0,0 +1,45 @@
+// Timezone helpers - timelines are stored in UTC and converted at the edges
+//
+// A node's timezone comes from its owner, then its enclosing Project, then UTC.
+
+use chrono::{DateTime, Days, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+pub fn to_local(dt: DateTime<Utc>, tz: Tz) -> DateTime<Tz>{
+    dt.with_timezone(&tz)
+}
+
+pub fn local_date(dt: DateTime<Utc>, tz: Tz) -> NaiveDate{
+    to_local(dt, tz).date_naive()
+}
+
+// Midnight at the start of `date` in `tz`, expressed in UTC
+pub fn start_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc>{
+    local_to_utc(date, NaiveTime::MIN, tz)
+}
+
+// The last instant of `date` in `tz` (i.e. just before the next local midnight)
+pub fn end_of_day(date: NaiveDate, tz: Tz) -> DateTime<Utc>{
+    let next = date.checked_add_days(Days::new(1)).unwrap_or(date);
+    start_of_day(next, tz) - chrono::Duration::seconds(1)
+}
+
+pub fn format_local(dt: DateTime<Utc>, tz: Tz, fmt: &str) -> String{
+    to_local(dt, tz).format(fmt).to_string()
+}
+
+// DST gaps resolve to the first valid instant after the gap, overlaps to the earlier one
+fn local_to_utc(date: NaiveDate, time: NaiveTime, tz: Tz) -> DateTime<Utc>{
+    let naive = date.and_time(time);
+    match tz.from_local_datetime(&naive){
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => {
+            let shifted = naive + chrono::Duration::hours(1);
+            tz.from_local_datetime(&shifted)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|| Utc.from_utc_datetime(&naive))
+        }
+    }
+}
@@ -0,0 +1,178 @@
+// Validation report - things a graph allows but someone should look at
+//
+// Unlike the checks that reject a change outright, warnings describe
+// structure that is legal yet usually needs a conversation, such as work in
+// one project waiting on work in another, or a backlog ranking work above
+// what it depends on.
+
+use super::graph::{DependencyType, ProjectGraph};
+use super::hierarchy::level;
+use super::Node;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning{
+    // A Blocks or ResourcesRequiredFor edge between nodes under different
+    // top-level Projects; the teams involved need to coordinate on it
+    CrossProject{ from: Uuid, to: Uuid, kind: DependencyType, from_project: Uuid, to_project: Uuid },
+    // A backlog item ranked above open work it waits on; `dependency_rank`
+    // is None when that work is not in the backlog at all
+    RankedAboveDependency{ item: Uuid, rank: usize, dependency: Uuid, dependency_rank: Option<usize> },
+    // Found by a registered plugin's validator
+    Plugin{ plugin: String, nodes: Vec<Uuid>, message: String },
+    // Done with definition-of-done items left unticked
+    UntickedChecklist{ node: Uuid, open: usize },
+    // An open Epic no Spec is linked to; only raised once the file has specs
+    UnspecifiedEpic{ epic: Uuid },
+    // A Contains edge the hierarchy rules no longer allow
+    HierarchyViolation{ parent: Uuid, child: Uuid },
+    // The topmost work past the hierarchy's depth limit
+    TooDeep{ node: Uuid, depth: usize, max: usize },
+}
+
+impl Warning{
+    // The nodes the warning is about
+    pub fn nodes(&self) -> Vec<Uuid>{
+        match self{
+            Warning::CrossProject{ from, to, .. } => vec![*from, *to],
+            Warning::RankedAboveDependency{ item, dependency, .. } => vec![*item, *dependency],
+            Warning::Plugin{ nodes, .. } => nodes.clone(),
+            Warning::UntickedChecklist{ node, .. } => vec![*node],
+            Warning::UnspecifiedEpic{ epic } => vec![*epic],
+            Warning::HierarchyViolation{ parent, child } => vec![*parent, *child],
+            Warning::TooDeep{ node, .. } => vec![*node],
+        }
+    }
+
+    pub fn code(&self) -> &'static str{
+        match self{
+            Warning::CrossProject{..} => "cross-project",
+            Warning::RankedAboveDependency{..} => "rank-order",
+            Warning::Plugin{..} => "plugin",
+            Warning::UntickedChecklist{..} => "definition-of-done",
+            Warning::UnspecifiedEpic{..} => "unspecified-epic",
+            Warning::HierarchyViolation{..} => "hierarchy",
+            Warning::TooDeep{..} => "hierarchy-depth",
+        }
+    }
+
+    pub fn message(&self, graph: &ProjectGraph) -> String{
+        let label = |id: Uuid| graph.get_key(id).map(str::to_string)
+            .or_else(|| graph.get_node(id).map(|n| n.get_name().to_string()))
+            .unwrap_or_else(|| id.to_string());
+        match self{
+            Warning::CrossProject{ from, to, kind, from_project, to_project } => {
+                let verb = match kind{
+                    DependencyType::ResourcesRequiredFor => "provides resources for",
+                    _ => "blocks",
+                };
+                format!("{} ({}) {} {} ({}) in another project", label(*from), label(*from_project), verb, label(*to), label(*to_project))
+            }
+            Warning::RankedAboveDependency{ item, rank, dependency, dependency_rank } => {
+                let below = match dependency_rank{
+                    Some(r) => format!("ranked #{}", r),
+                    None => "not in the backlog".to_string(),
+                };
+                format!("{} ranked #{} waits on {}, {}", label(*item), rank, label(*dependency), below)
+            }
+            Warning::Plugin{ plugin, message, .. } => format!("{}: {}", plugin, message),
+            Warning::UntickedChecklist{ node, open } => format!("{} is done with {} definition-of-done item{} unticked", label(*node), open, if *open == 1 { "" } else { "s" }),
+            Warning::UnspecifiedEpic{ epic } => format!("{} is not linked to a spec", label(*epic)),
+            Warning::HierarchyViolation{ parent, child } => format!("{} contains {}, which the hierarchy rules do not allow", label(*parent), label(*child)),
+            Warning::TooDeep{ node, depth, max } => format!("{} is {} levels deep, past the limit of {}", label(*node), depth, max),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport{
+    pub warnings: Vec<Warning>,
+}
+
+impl ValidationReport{
+    pub fn is_clean(&self) -> bool{
+        self.warnings.is_empty()
+    }
+
+    pub fn len(&self) -> usize{
+        self.warnings.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.warnings.is_empty()
+    }
+}
+
+// Where work sits in the backlog: its own rank, or for a container the
+// lowest-placed rank of its open work
+fn effective_rank(graph: &ProjectGraph, id: Uuid) -> Option<usize>{
+    graph.get_rank(id).or_else(|| {
+        graph.get_subtree(id).into_iter()
+            .filter(|d| graph.get_node(*d).is_some_and(|n| !n.is_done()))
+            .filter_map(|d| graph.get_rank(d))
+            .max()
+    })
+}
+
+fn rank_warnings(graph: &ProjectGraph) -> Vec<Warning>{
+    let mut warnings = Vec::new();
+    for (position, item) in graph.backlog().iter().enumerate(){
+        let rank = position + 1;
+        let mut dependencies: Vec<Uuid> = std::iter::once(*item)
+            .chain(graph.get_ancestors(*item))
+            .flat_map(|s| graph.get_predecessors(s))
+            .map(|(pred, _)| pred)
+            .filter(|pred| graph.get_node(*pred).is_some_and(|n| !n.is_done()))
+            .collect();
+        dependencies.sort();
+        dependencies.dedup();
+        for dependency in dependencies{
+            let dependency_rank = effective_rank(graph, dependency);
+            if dependency_rank.is_none_or(|r| r > rank){
+                warnings.push(Warning::RankedAboveDependency{ item: *item, rank, dependency, dependency_rank });
+            }
+        }
+    }
+    warnings
+}
+
+fn hierarchy_warnings(graph: &ProjectGraph) -> Vec<Warning>{
+    let rules = &graph.get_settings().hierarchy;
+    let prefix = |id: Uuid| graph.get_node(id).map(|n| n.get_key_prefix()).filter(|p| level(p).is_some());
+    let mut warnings: Vec<Warning> = graph.edges()
+        .filter(|(_, _, dependency)| dependency.kind == DependencyType::Contains)
+        .filter(|(parent, child, _)| matches!((prefix(*parent), prefix(*child)), (Some(p), Some(c)) if !rules.allows(p, c)))
+        .map(|(parent, child, _)| Warning::HierarchyViolation{ parent, child })
+        .collect();
+    if let Some(max) = rules.max_depth{
+        warnings.extend(graph.nodes()
+            .filter(|n| prefix(n.get_id()).is_some())
+            .filter(|n| graph.work_depth(n.get_id()) == max + 1)
+            .map(|n| Warning::TooDeep{ node: n.get_id(), depth: max + 1, max }));
+    }
+    warnings
+}
+
+pub fn validate(graph: &ProjectGraph) -> ValidationReport{
+    let mut warnings: Vec<Warning> = graph.edges()
+        .filter_map(|(from, to, dependency)| graph.connection_warning(from, to, dependency.kind))
+        .collect();
+    warnings.extend(rank_warnings(graph));
+    warnings.extend(hierarchy_warnings(graph));
+    warnings.extend(graph.nodes()
+        .filter(|n| n.is_done())
+        .filter_map(|n| {
+            let open = graph.open_checklist_items(n.get_id());
+            (open > 0).then_some(Warning::UntickedChecklist{ node: n.get_id(), open })
+        }));
+    if graph.nodes().any(|n| matches!(n, Node::Spec{..})){
+        warnings.extend(graph.nodes()
+            .filter(|n| matches!(n, Node::Epic{..}) && !n.is_done() && graph.specs_for(n.get_id()).is_empty())
+            .map(|n| Warning::UnspecifiedEpic{ epic: n.get_id() }));
+    }
+    for plugin in graph.plugins().iter(){
+        warnings.extend(plugin.validate(graph).into_iter().map(|f| Warning::Plugin{ plugin: plugin.name().to_string(), nodes: f.nodes, message: f.message }));
+    }
+    warnings.sort_by_key(|w| w.nodes());
+    ValidationReport{ warnings }
+}
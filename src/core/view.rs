@@ -0,0 +1,102 @@
+// Saved views - named filters stored with the project, e.g. "Backend team
+// this sprint", so everyone runs the same selection by name
+
+use super::graph::ProjectGraph;
+use super::query::Query;
+use super::{Node, Priority, Scope, Status};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Every field that is set must match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Filter{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    // Root of the subtree to look in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprint: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<Uuid>,
+    // This priority or more urgent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_priority: Option<Priority>,
+}
+
+impl Filter{
+    pub fn query<'a>(&self, graph: &'a ProjectGraph) -> Query<'a>{
+        let mut query = graph.query();
+        if let Some(owner) = &self.owner{
+            query = query.owner(owner);
+        }
+        if let Some(status) = self.status{
+            query = query.status(status);
+        }
+        for tag in &self.tags{
+            query = query.tag(tag);
+        }
+        if let Some(root) = self.scope{
+            query = query.in_scope(Scope::Subtree(root));
+        }
+        if let Some(sprint) = self.sprint{
+            let items = graph.get_sprint(sprint).map(|s| s.get_items().clone()).unwrap_or_default();
+            query = query.matching(move |n| items.contains(&n.get_id()));
+        }
+        if let Some(release) = self.release{
+            query = query.matching(move |n| graph.get_release(release).is_some_and(|r| r.contains(n.get_id())));
+        }
+        if let Some(min) = self.min_priority{
+            query = query.matching(move |n| graph.get_priority(n.get_id()).is_some_and(|p| p >= min));
+        }
+        query
+    }
+
+    // Whether a single node passes, without running a query
+    pub fn matches(&self, graph: &ProjectGraph, node: &Node) -> bool{
+        self.matches_with(graph, node, graph.get_priority(node.get_id()))
+    }
+
+    // Same, with the node's priority given rather than looked up, for nodes
+    // as they were at some earlier point
+    pub fn matches_with(&self, graph: &ProjectGraph, node: &Node, priority: Option<Priority>) -> bool{
+        let id = node.get_id();
+        self.owner.as_deref().is_none_or(|o| node.get_owner() == Some(o))
+            && self.status.is_none_or(|s| node.get_status() == Some(s))
+            && self.tags.iter().all(|t| node.get_tags().iter().any(|have| have.as_ref() == t))
+            && self.scope.is_none_or(|root| root == id || graph.get_ancestors(id).contains(&root))
+            && self.sprint.is_none_or(|s| graph.get_sprint(s).is_some_and(|s| s.get_items().contains(&id)))
+            && self.release.is_none_or(|r| graph.get_release(r).is_some_and(|r| r.contains(id)))
+            && self.min_priority.is_none_or(|min| priority.is_some_and(|p| p >= min))
+    }
+
+    // Ids the filter points at that are gone from the graph
+    pub fn dangling(&self, graph: &ProjectGraph) -> bool{
+        self.scope.is_some_and(|id| graph.get_node(id).is_none())
+            || self.sprint.is_some_and(|id| graph.get_sprint(id).is_none())
+            || self.release.is_some_and(|id| graph.get_release(id).is_none())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedView{
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub filter: Filter,
+}
+
+impl SavedView{
+    pub fn new(name: String, filter: Filter) -> Self{
+        SavedView{ name, description: None, filter }
+    }
+
+    pub fn with_description(mut self, description: String) -> Self{
+        self.description = Some(description);
+        self
+    }
+}
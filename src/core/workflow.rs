@@ -0,0 +1,115 @@
+// Workflow - a custom status model for a Project subtree
+//
+// Teams name their own states ("In QA", "Ready for Release", ...) and say
+// which moves between them are allowed. Each state belongs to one of the
+// built-in Status categories, which is what scheduling, rollups and reports
+// keep working with; the state name is the finer-grained label on top.
+
+use super::Status;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowState{
+    pub name: String,
+    pub category: Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workflow{
+    pub name: String,
+    // In display order; the first one is where new work starts
+    states: Vec<WorkflowState>,
+    // Allowed moves by state name; a state without an entry is final
+    transitions: BTreeMap<String,BTreeSet<String>>,
+}
+
+impl Workflow{
+    pub fn new(name: String) -> Self{
+        Workflow{ name, states: Vec::new(), transitions: BTreeMap::new() }
+    }
+
+    // The built-in statuses with every move allowed
+    pub fn standard() -> Self{
+        let all = [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done];
+        let mut workflow = Workflow::new("Standard".to_string());
+        for s in all{
+            workflow = workflow.with_state(s.to_string(), s);
+        }
+        for from in all{
+            for to in all.iter().filter(|to| **to != from){
+                workflow = workflow.with_transition(&from.to_string(), &to.to_string());
+            }
+        }
+        workflow
+    }
+
+    pub fn with_state(mut self, name: String, category: Status) -> Self{
+        self.states.push(WorkflowState{ name, category });
+        self
+    }
+
+    pub fn with_transition(mut self, from: &str, to: &str) -> Self{
+        self.transitions.entry(from.to_string()).or_default().insert(to.to_string());
+        self
+    }
+
+    pub fn states(&self) -> &[WorkflowState]{
+        &self.states
+    }
+
+    pub fn get_state(&self, name: &str) -> Option<&WorkflowState>{
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    pub fn initial(&self) -> Option<&WorkflowState>{
+        self.states.first()
+    }
+
+    // The state a node of this category is in when it has no explicit one
+    pub fn default_state_for(&self, category: Status) -> Option<&WorkflowState>{
+        self.states.iter().find(|s| s.category == category)
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool{
+        self.transitions.get(from).is_some_and(|targets| targets.contains(to))
+    }
+
+    pub fn next_states(&self, from: &str) -> Vec<&WorkflowState>{
+        self.states.iter().filter(|s| self.can_transition(from, &s.name)).collect()
+    }
+
+    pub fn validate(&self) -> Result<(),&'static str>{
+        let initial = self.initial().ok_or("A workflow needs at least one state")?;
+        let mut names = HashSet::new();
+        for s in &self.states{
+            if s.name.trim().is_empty(){
+                return Err("Workflow state names must not be empty");
+            }
+            if !names.insert(s.name.as_str()){
+                return Err("Workflow state names must be unique");
+            }
+        }
+        if self.transitions.iter().any(|(from, to)| !names.contains(from.as_str()) || to.iter().any(|t| !names.contains(t.as_str()))){
+            return Err("A workflow transition refers to an unknown state");
+        }
+        if !self.states.iter().any(|s| s.category.is_done()){
+            return Err("A workflow needs a state in the Done category");
+        }
+
+        // Every state must be reachable from the initial one
+        let mut seen: HashSet<&str> = HashSet::from([initial.name.as_str()]);
+        let mut stack = vec![initial.name.as_str()];
+        while let Some(cur) = stack.pop(){
+            for next in self.transitions.get(cur).into_iter().flatten(){
+                if seen.insert(next.as_str()){
+                    stack.push(next.as_str());
+                }
+            }
+        }
+        if seen.len() != self.states.len(){
+            return Err("Every workflow state must be reachable from the first one");
+        }
+        Ok(())
+    }
+}
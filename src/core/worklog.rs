@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Worklog{
+    pub person: String,
+    pub hours: f64,
+    pub date: DateTime<Utc>,
+}
+
+impl Worklog{
+    pub fn new(person: String, hours: f64, date: DateTime<Utc>) -> Result<Self,&'static str>{
+        if !hours.is_finite() || hours <= 0.0{
+            return Err("Logged hours must be a positive number");
+        }
+        Ok(Worklog{ person, hours, date })
+    }
+}
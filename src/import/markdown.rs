@@ -0,0 +1,323 @@
+// Outline import and export - a Markdown or org-mode outline as a Contains
+// hierarchy, and the hierarchy written back as a Markdown outline
+//
+// Headings and bullets both make nodes; a bullet belongs to the heading above
+// it, and deeper headings or further-indented bullets are contained by the
+// item before them. Each item's kind follows its parent's: a Project holds
+// Epics, an Epic Stories and a Story Tasks. Items at the top of the outline
+// go under the node given, or become Projects without one.
+//
+// Status comes from a checkbox ("[ ]", "[-]", "[x]") or an org keyword (TODO,
+// DOING, WAITING, DONE); items with neither start Not Started. Org heading
+// tags (":backend:ui:") become tags. Other lines are ignored.
+//
+// The export ends each item with its id in an HTML comment ("<!-- id: ... -->").
+// Items carrying an id already in the graph update that node's name, status
+// and, when nested under another item, its container, so an exported outline
+// can be edited and imported again without duplicating anything.
+
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Node, NodeBuilder, Status, Timeline};
+use std::fmt::Write;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+// Node id suffix for imported nodes
+const IMPORT_ID: &[u8; 6] = b"pmimpt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineFormat{
+    Markdown,
+    Org,
+}
+
+impl OutlineFormat{
+    // Org for .org files, Markdown for anything else
+    pub fn from_path(path: &std::path::Path) -> OutlineFormat{
+        match path.extension().and_then(|e| e.to_str()){
+            Some(ext) if ext.eq_ignore_ascii_case("org") => OutlineFormat::Org,
+            _ => OutlineFormat::Markdown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineItem{
+    // 1 for top-level headings; bullets sit one below their heading
+    pub level: usize,
+    pub title: String,
+    pub status: Option<Status>,
+    pub tags: Vec<String>,
+    // From a trailing "<!-- id: ... -->"
+    pub id: Option<Uuid>,
+    // 1-based, for error messages
+    pub line: usize,
+}
+
+fn checkbox(text: &str) -> (Option<Status>, &str){
+    let status = match text.get(..3){
+        Some("[ ]") => Status::NotStarted,
+        Some("[-]" | "[~]" | "[/]") => Status::InProgress,
+        Some("[x]" | "[X]") => Status::Done,
+        Some("[!]") => Status::Blocked,
+        _ => return (None, text),
+    };
+    (Some(status), text[3..].trim_start())
+}
+
+fn org_keyword(text: &str) -> (Option<Status>, &str){
+    let (word, rest) = text.split_once(' ').unwrap_or((text, ""));
+    let status = match word{
+        "TODO" => Status::NotStarted,
+        "DOING" | "STARTED" | "NEXT" => Status::InProgress,
+        "WAITING" | "HOLD" => Status::Blocked,
+        "DONE" => Status::Done,
+        _ => return (None, text),
+    };
+    (Some(status), rest.trim_start())
+}
+
+fn id_comment(text: &str) -> Result<(&str, Option<Uuid>),String>{
+    let Some(rest) = text.strip_suffix("-->") else {
+        return Ok((text, None));
+    };
+    let Some((title, comment)) = rest.rsplit_once("<!--") else {
+        return Ok((text, None));
+    };
+    let Some(id) = comment.trim().strip_prefix("id:") else {
+        return Ok((text, None));
+    };
+    let id = id.trim().parse::<Uuid>().map_err(|_| format!("'{}' is not a node id", id.trim()))?;
+    Ok((title.trim_end(), Some(id)))
+}
+
+// Trailing ":a:b:" on an org heading
+fn org_tags(text: &str) -> (&str, Vec<String>){
+    if let Some((title, last)) = text.rsplit_once(' '){
+        if last.len() > 2 && last.starts_with(':') && last.ends_with(':') && !last.contains(' '){
+            let tags = last.split(':').filter(|t| !t.is_empty()).map(str::to_string).collect();
+            return (title.trim_end(), tags);
+        }
+    }
+    (text, Vec::new())
+}
+
+fn heading(line: &str, format: OutlineFormat) -> Option<(usize, &str)>{
+    let mark = match format{
+        OutlineFormat::Markdown => '#',
+        OutlineFormat::Org => '*',
+    };
+    let depth = line.chars().take_while(|c| *c == mark).count();
+    let rest = &line[depth..];
+    let limit = if format == OutlineFormat::Markdown { 6 } else { usize::MAX };
+    (depth > 0 && depth <= limit && rest.starts_with(' ')).then(|| (depth, rest.trim()))
+}
+
+// The text after a list marker: "-", "*", "+" or "1." / "1)"
+fn bullet(line: &str) -> Option<&str>{
+    let trimmed = line.trim_start();
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = if digits > 0{
+        trimmed[digits..].strip_prefix(['.', ')'])?
+    }else{
+        trimmed.strip_prefix(['-', '*', '+'])?
+    };
+    rest.starts_with(' ').then(|| rest.trim())
+}
+
+pub fn parse_outline(text: &str, format: OutlineFormat) -> Result<Vec<OutlineItem>>{
+    let mut items = Vec::new();
+    let mut heading_level = 0;
+    // Indents of the open bullets since the last heading
+    let mut indents: Vec<usize> = Vec::new();
+    let mut in_fence = false;
+
+    for (i, line) in text.lines().enumerate(){
+        let line = line.trim_end_matches('\r');
+        let trimmed = line.trim_start();
+        if format == OutlineFormat::Markdown && (trimmed.starts_with("```") || trimmed.starts_with("~~~")){
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence{
+            continue;
+        }
+
+        let (level, text, is_heading) = if let Some((depth, text)) = heading(line, format){
+            heading_level = depth;
+            indents.clear();
+            (depth, text, true)
+        }else if let Some(text) = bullet(line){
+            let indent = line.len() - trimmed.len();
+            while indents.last().is_some_and(|top| *top > indent){
+                indents.pop();
+            }
+            if indents.last() != Some(&indent){
+                indents.push(indent);
+            }
+            (heading_level + indents.len(), text, false)
+        }else{
+            continue;
+        };
+
+        let (text, id) = id_comment(text).map_err(|e| anyhow!("line {}: {}", i + 1, e))?;
+        let (mut status, mut text) = checkbox(text);
+        let mut tags = Vec::new();
+        if format == OutlineFormat::Org && is_heading{
+            (status, text) = match org_keyword(text){
+                (Some(s), rest) => (Some(s), rest),
+                (None, _) => (status, text),
+            };
+            (text, tags) = org_tags(text);
+        }
+        if text.is_empty(){
+            bail!("line {}: the item has no title", i + 1);
+        }
+        items.push(OutlineItem{ level, title: text.to_string(), status, tags, id, line: i + 1 });
+    }
+    Ok(items)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind{
+    Project,
+    Epic,
+    Story,
+    Task,
+}
+
+fn child_kind(parent: Option<&Node>) -> Option<Kind>{
+    match parent{
+        None | Some(Node::Spec{..}) => Some(Kind::Project),
+        Some(Node::Project{..}) => Some(Kind::Epic),
+        Some(Node::Epic{..}) => Some(Kind::Story),
+        Some(Node::UserStory{..}) => Some(Kind::Task),
+        Some(Node::Tasks{..} | Node::Decision{..}) => None,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutlineImport{
+    // In outline order
+    pub created: Vec<Uuid>,
+    // Nodes named by an id comment that were already in the graph, and
+    // changed by the outline
+    pub updated: Vec<Uuid>,
+    // Nodes named by an id comment that the outline left as they were
+    pub unchanged: Vec<Uuid>,
+}
+
+// Adds the outline under `parent` (or as new Projects). Dated work starts and
+// ends on `now`'s day. Top-level items already in the graph stay in their
+// container unless `parent` is given. Nothing changes if any item fails.
+pub fn import_outline(graph: &mut ProjectGraph, text: &str, format: OutlineFormat, parent: Option<Uuid>, now: DateTime<Utc>) -> Result<OutlineImport>{
+    let items = parse_outline(text, format)?;
+    if let Some(parent) = parent{
+        graph.get_node(parent).ok_or_else(|| anyhow!("The parent does not exist in the graph"))?;
+    }
+    let today = now.date_naive().and_hms_opt(0, 0, 0).expect("midnight exists").and_utc();
+
+    let mut trial = graph.clone();
+    let mut result = OutlineImport::default();
+    // (level, node) of the items that may still take children
+    let mut open: Vec<(usize,Uuid)> = Vec::new();
+    for item in items{
+        while open.last().is_some_and(|(level, _)| *level >= item.level){
+            open.pop();
+        }
+        let nested = open.last().map(|(_, id)| *id);
+        let container = nested.or(parent);
+        let at_line = |e: &str| anyhow!("line {}: {}", item.line, e);
+
+        if let Some(id) = item.id.filter(|id| trial.get_node(*id).is_some()){
+            if result.updated.contains(&id) || result.unchanged.contains(&id) || result.created.contains(&id){
+                return Err(at_line("the id appears more than once in the outline"));
+            }
+            let node = trial.get_node(id).expect("checked above");
+            let (name, status) = (node.get_name() != item.title, node.get_status());
+            let status = item.status.filter(|to| status.is_some_and(|s| s != *to));
+            let moved = container.filter(|c| trial.get_parent(id) != Some(*c));
+            if name{
+                trial.set_name(id, &item.title).map_err(at_line)?;
+            }
+            if let Some(to) = status{
+                trial.set_status_at(id, to, now).map_err(at_line)?;
+            }
+            if moved.is_some(){
+                trial.set_parent(id, moved).map_err(at_line)?;
+            }
+            match name || status.is_some() || moved.is_some(){
+                true => result.updated.push(id),
+                false => result.unchanged.push(id),
+            }
+            open.push((item.level, id));
+            continue;
+        }
+
+        let kind = child_kind(container.and_then(|id| trial.get_node(id)))
+            .ok_or_else(|| anyhow!("line {}: '{}' is nested under a task, which cannot contain anything", item.line, item.title))?;
+        // An id from another file keeps its id here
+        let id = item.id.unwrap_or_else(|| Uuid::now_v6(IMPORT_ID));
+        let mut builder = NodeBuilder::new().with_id(id).with_name(item.title.clone()).with_status(item.status.unwrap_or_default());
+        if kind != Kind::Project{
+            builder = builder.with_timeline(Timeline::from_start_end(today, today));
+        }
+        for tag in item.tags{
+            builder = builder.with_tag(tag);
+        }
+        let node = match kind{
+            Kind::Project => builder.build_project(),
+            Kind::Epic => builder.build_epic(),
+            Kind::Story => builder.build_userstory(),
+            Kind::Task => builder.build_tasks(),
+        }.map_err(at_line)?;
+
+        trial.create_node(&node).map_err(at_line)?;
+        if let Some(container) = container{
+            trial.connect(container, id, DependencyType::Contains).map_err(at_line)?;
+        }
+        result.created.push(id);
+        open.push((item.level, id));
+    }
+    *graph = trial;
+    Ok(result)
+}
+
+fn checkbox_of(status: Option<Status>) -> &'static str{
+    match status{
+        None => "",
+        Some(Status::NotStarted) => "[ ] ",
+        Some(Status::InProgress) => "[-] ",
+        Some(Status::Blocked) => "[!] ",
+        Some(Status::Done) => "[x] ",
+    }
+}
+
+fn export_item(graph: &ProjectGraph, id: Uuid, depth: usize, out: &mut String){
+    let Some(node) = graph.get_node(id) else {
+        return;
+    };
+    let _ = writeln!(out, "{}- {}{} <!-- id: {} -->", "  ".repeat(depth), checkbox_of(node.get_status()), node.get_name(), id);
+    // v6 ids sort in creation order
+    let mut children = graph.get_children(id);
+    children.sort();
+    for child in children{
+        export_item(graph, child, depth + 1, out);
+    }
+}
+
+// `root` and everything it contains as nested checklist bullets, or every
+// top-level node without one; `import_outline` reads it back
+pub fn export_outline(graph: &ProjectGraph, root: Option<Uuid>) -> String{
+    let mut roots: Vec<Uuid> = match root{
+        Some(root) => vec![root],
+        None => graph.nodes().map(|n| n.get_id()).filter(|id| graph.get_parent(*id).is_none()).collect(),
+    };
+    roots.sort();
+    let mut out = String::new();
+    for root in roots{
+        export_item(graph, root, 0, &mut out);
+    }
+    out
+}
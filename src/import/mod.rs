@@ -0,0 +1,6 @@
+// Import module - building graph nodes from documents written elsewhere, and
+// writing them back out in the same form
+
+pub mod markdown;
+
+pub use markdown::{export_outline, import_outline, parse_outline, OutlineFormat, OutlineImport, OutlineItem};
@@ -1,6 +1,17 @@
 // Library root - exports all public modules
 
+pub mod analytics;
 pub mod cli;
 pub mod core;
+pub mod import;
+pub mod notify;
+pub mod planning;
+pub mod portfolio;
+pub mod publish;
+pub mod scheduler;
 pub mod storage;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trace;
 pub mod views;
@@ -1,3 +1,13 @@
-fn main() {
-    println!("Hello, world!");
+use clap::Parser;
+use project_manager::cli::{self, Cli};
+use std::process::ExitCode;
+
+fn main() -> ExitCode{
+    match cli::run(Cli::parse()){
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
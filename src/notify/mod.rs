@@ -0,0 +1,10 @@
+// Notify module - alerts raised by checks over the graph and the sinks that
+// deliver them
+
+pub mod notification;
+pub mod rules;
+pub mod stale;
+
+pub use notification::{Notification, Notifier, Outbox, Severity};
+pub use rules::rule_notifications;
+pub use stale::{stale_blockers, StaleBlocker, StaleBlockerPolicy};
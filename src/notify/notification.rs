@@ -0,0 +1,64 @@
+// Notification - one alert for a person about a node, and the Notifier
+// trait every delivery channel implements
+
+use crate::core::Actor;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity{
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification{
+    pub at: DateTime<Utc>,
+    pub severity: Severity,
+    // Who should act on it; None goes to whoever watches the project
+    pub recipient: Option<String>,
+    pub node: Option<Uuid>,
+    pub title: String,
+    pub body: String,
+    // Whose change raised it; System for pm's own checks
+    #[serde(default)]
+    pub actor: Option<Actor>,
+}
+
+pub trait Notifier{
+    fn notify(&mut self, notification: &Notification) -> anyhow::Result<()>;
+
+    // Stops at the first failure
+    fn notify_all(&mut self, notifications: &[Notification]) -> anyhow::Result<()>{
+        for n in notifications{
+            self.notify(n)?;
+        }
+        Ok(())
+    }
+}
+
+// Keeps everything it is sent; for batching and for callers that render
+// notifications themselves
+#[derive(Debug, Clone, Default)]
+pub struct Outbox{
+    pub sent: Vec<Notification>,
+}
+
+impl Outbox{
+    pub fn new() -> Self{
+        Outbox::default()
+    }
+
+    pub fn for_recipient<'a>(&'a self, recipient: &'a str) -> impl Iterator<Item = &'a Notification>{
+        self.sent.iter().filter(move |n| n.recipient.as_deref() == Some(recipient))
+    }
+}
+
+impl Notifier for Outbox{
+    fn notify(&mut self, notification: &Notification) -> anyhow::Result<()>{
+        self.sent.push(notification.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+// Rule notifications - the Notify actions of automation rules as notifications
+
+use super::{Notification, Severity};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Action, Actor, Firing};
+use chrono::Utc;
+
+// One per Notify firing, addressed to the rule's channel
+pub fn rule_notifications(graph: &ProjectGraph, firings: &[Firing]) -> Vec<Notification>{
+    firings.iter()
+        .filter_map(|firing| {
+            let Action::Notify{ channel, message } = &firing.action else {
+                return None;
+            };
+            let name = graph.get_node(firing.node).map(|n| n.get_name()).unwrap_or("?");
+            let label = match graph.get_key(firing.node){
+                Some(key) => format!("{} {}", key, name),
+                None => name.to_string(),
+            };
+            Some(Notification{
+                at: Utc::now(),
+                severity: Severity::Info,
+                recipient: Some(channel.clone()),
+                node: Some(firing.node),
+                title: message.clone().unwrap_or_else(|| format!("{}: {}", firing.rule, label)),
+                body: format!("{} matched rule '{}' on {}", label, firing.rule, firing.at.format("%Y-%m-%d %H:%M")),
+                actor: Some(Actor::System),
+            })
+        })
+        .collect()
+}
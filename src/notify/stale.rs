@@ -0,0 +1,97 @@
+// Stale blockers - Blocks dependencies whose blocking node has not moved in
+// a while even though the node it blocks is about to (or should already)
+// start
+//
+// Progress is any status change or logged work on the blocker; a blocker
+// with neither counts from its planned start.
+
+use super::{Notification, Severity};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Actor, EventKind};
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct StaleBlockerPolicy{
+    // How long a blocker may go without progress
+    pub stale_after: TimeDelta,
+    // How close the blocked node's start must be to raise an alert
+    pub warn_within: TimeDelta,
+}
+
+impl Default for StaleBlockerPolicy{
+    fn default() -> Self{
+        StaleBlockerPolicy{ stale_after: TimeDelta::days(5), warn_within: TimeDelta::days(7) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StaleBlocker{
+    pub blocker: Uuid,
+    pub blocked: Uuid,
+    pub last_progress: Option<DT>,
+    pub blocked_start: DT,
+    // The blocked node should already have started
+    pub overdue: bool,
+}
+
+impl StaleBlocker{
+    // Addressed to the blocker's owner, who is the one able to unstick it
+    pub fn to_notification(&self, graph: &ProjectGraph, now: DT) -> Notification{
+        let label = |id: Uuid| graph.get_key(id)
+            .map(str::to_string)
+            .or_else(|| graph.get_node(id).map(|n| n.get_name().to_string()))
+            .unwrap_or_else(|| id.to_string());
+        let idle = self.last_progress
+            .map(|at| format!("no progress for {} days", (now - at).num_days()))
+            .unwrap_or_else(|| "no recorded progress".to_string());
+        let when = if self.overdue { "was due to start" } else { "starts" };
+        Notification{
+            at: now,
+            severity: if self.overdue { Severity::Critical } else { Severity::Warning },
+            recipient: graph.get_node(self.blocker).and_then(|n| n.get_owner()).map(str::to_string),
+            node: Some(self.blocker),
+            title: format!("{} is holding up {}", label(self.blocker), label(self.blocked)),
+            body: format!("{} has had {}; {} {} on {}",
+                label(self.blocker), idle, label(self.blocked), when, self.blocked_start.format("%Y-%m-%d")),
+            actor: Some(Actor::System),
+        }
+    }
+}
+
+// Status moves and logged work; renaming or re-estimating isn't progress
+fn last_progress(graph: &ProjectGraph, id: Uuid) -> Option<DT>{
+    let event = graph.get_events().for_node(id)
+        .filter(|e| !matches!(e.kind, EventKind::FieldChanged{..}))
+        .map(|e| e.at)
+        .max();
+    let work = graph.get_worklogs(id).iter().map(|w| w.date).max();
+    event.max(work)
+}
+
+pub fn stale_blockers(graph: &ProjectGraph, policy: &StaleBlockerPolicy, now: DT) -> Vec<StaleBlocker>{
+    let mut stale: Vec<StaleBlocker> = graph.edges()
+        .filter(|(_, _, dep)| dep.kind == DependencyType::Blocks)
+        .filter_map(|(from, to, _)| {
+            let blocker = graph.get_node(from)?;
+            let blocked = graph.get_node(to)?;
+            if blocker.is_done() || blocked.is_done(){
+                return None;
+            }
+            let blocked_start = blocked.get_timeline()?.start;
+            if blocked_start - now > policy.warn_within{
+                return None;
+            }
+            let progress = last_progress(graph, from);
+            let since = progress.or_else(|| blocker.get_timeline().map(|t| t.start))?;
+            if now - since < policy.stale_after{
+                return None;
+            }
+            Some(StaleBlocker{ blocker: from, blocked: to, last_progress: progress, blocked_start, overdue: blocked_start <= now })
+        })
+        .collect();
+    stale.sort_by_key(|s| (s.blocked_start, s.blocker, s.blocked));
+    stale
+}
@@ -0,0 +1,177 @@
+// Quarterly allocation - a first draft of which team takes which epic when
+//
+// Greedy: epics go in dependency order, more urgent first, each into the
+// earliest quarter not before the epics it depends on, to the team with room
+// for it. A team whose members own work in the epic is preferred over one
+// that merely has room. The result is a starting point to rearrange by hand,
+// not an optimum.
+//
+// Capacity is in points, as in sprint capacity: available days of each
+// member x focus factor x points per day. An epic's size is its own points,
+// or else the points of its open work.
+
+use super::capacity::team_points;
+use crate::core::graph::ProjectGraph;
+use crate::core::{NamedPeriod, Node, Priority};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement{
+    pub epic: Uuid,
+    pub team: String,
+    pub quarter: String,
+    pub points: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Unallocated{
+    // Bigger than any team's quarter; split it first
+    TooLarge{ epic: Uuid, points: u32 },
+    // No team has room for it from its earliest quarter on
+    NoCapacity{ epic: Uuid, points: u32 },
+    // Depends on an epic that could not be placed
+    Waiting{ epic: Uuid, on: Uuid },
+}
+
+impl Unallocated{
+    pub fn epic(&self) -> Uuid{
+        match self{
+            Unallocated::TooLarge{ epic, .. } | Unallocated::NoCapacity{ epic, .. } | Unallocated::Waiting{ epic, .. } => *epic,
+        }
+    }
+}
+
+// Capacity and planned points of one team in one quarter
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamQuarter{
+    pub team: String,
+    pub quarter: String,
+    pub capacity: f64,
+    pub planned: u32,
+}
+
+impl TeamQuarter{
+    pub fn remaining(&self) -> f64{
+        self.capacity - self.planned as f64
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DraftRoadmap{
+    pub placements: Vec<Placement>,
+    pub unallocated: Vec<Unallocated>,
+    pub load: Vec<TeamQuarter>,
+}
+
+impl DraftRoadmap{
+    pub fn placement(&self, epic: Uuid) -> Option<&Placement>{
+        self.placements.iter().find(|p| p.epic == epic)
+    }
+
+    pub fn is_complete(&self) -> bool{
+        self.unallocated.is_empty()
+    }
+}
+
+fn epic_points(graph: &ProjectGraph, epic: Uuid) -> u32{
+    if let Some(points) = graph.get_node(epic).and_then(|n| n.get_points()){
+        return points;
+    }
+    graph.get_subtree(epic).into_iter()
+        .filter(|id| *id != epic && graph.get_children(*id).is_empty())
+        .filter_map(|id| graph.get_node(id))
+        .filter(|n| !n.is_done())
+        .filter_map(|n| n.get_points())
+        .sum()
+}
+
+// Epics of the list that `epic` (or anything in it) waits on
+fn depends_on(graph: &ProjectGraph, epic: Uuid, epics: &[Uuid]) -> HashSet<Uuid>{
+    graph.get_subtree(epic).into_iter()
+        .flat_map(|id| graph.get_predecessors(id))
+        .filter_map(|(pred, _)| epics.iter().find(|e| **e != epic && graph.get_subtree(**e).contains(&pred)).copied())
+        .collect()
+}
+
+// Quarters are taken in the given order
+pub fn allocate(graph: &ProjectGraph, quarters: &[NamedPeriod], teams: &[&str], epics: &[Uuid]) -> Result<DraftRoadmap,&'static str>{
+    if quarters.is_empty() || teams.is_empty(){
+        return Err("Allocation needs at least one quarter and one team");
+    }
+    for epic in epics{
+        if !matches!(graph.get_node(*epic), Some(Node::Epic{..})){
+            return Err("Only Epics can be allocated to quarters");
+        }
+    }
+    let teams = teams.iter()
+        .map(|t| graph.get_team(t).ok_or("The team does not exist"))
+        .collect::<Result<Vec<_>,_>>()?;
+
+    let mut load: Vec<TeamQuarter> = quarters.iter()
+        .flat_map(|q| teams.iter().map(move |t| TeamQuarter{
+            team: t.name.clone(),
+            quarter: q.name.clone(),
+            capacity: team_points(graph, t, q.start, q.end),
+            planned: 0,
+        }))
+        .collect();
+    let largest = load.iter().map(|l| l.capacity).fold(0.0, f64::max);
+
+    // Dependency order; among the epics ready at each step, the most urgent
+    // first, then the order given
+    let deps: HashMap<Uuid,HashSet<Uuid>> = epics.iter().map(|e| (*e, depends_on(graph, *e, epics))).collect();
+    let mut pending: Vec<Uuid> = epics.to_vec();
+    let mut order = Vec::new();
+    while !pending.is_empty(){
+        let ready = pending.iter()
+            .enumerate()
+            .filter(|(_, e)| deps[*e].iter().all(|d| order.contains(d)))
+            .max_by_key(|(i, e)| (graph.get_priority(**e).unwrap_or(Priority::Medium), std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+            .ok_or("The epics depend on each other in a cycle")?;
+        order.push(pending.remove(ready));
+    }
+
+    let mut roadmap = DraftRoadmap::default();
+    let mut quarter_of: HashMap<Uuid,usize> = HashMap::new();
+    for epic in order{
+        let points = epic_points(graph, epic);
+        if let Some(on) = deps[&epic].iter().copied().filter(|d| !quarter_of.contains_key(d)).min(){
+            roadmap.unallocated.push(Unallocated::Waiting{ epic, on });
+            continue;
+        }
+        if points as f64 > largest{
+            roadmap.unallocated.push(Unallocated::TooLarge{ epic, points });
+            continue;
+        }
+
+        let owners: HashSet<&str> = graph.get_subtree(epic).into_iter()
+            .filter_map(|id| graph.get_node(id).and_then(|n| n.get_owner()))
+            .collect();
+        let affinity = |team: &str| teams.iter()
+            .find(|t| t.name == team)
+            .map_or(0, |t| t.members.iter().filter(|m| owners.contains(m.as_str())).count());
+
+        let earliest = deps[&epic].iter().map(|d| quarter_of[d]).max().unwrap_or(0);
+        let slot = (earliest..quarters.len()).find_map(|q| {
+            load.iter()
+                .enumerate()
+                .filter(|(_, l)| l.quarter == quarters[q].name && l.remaining() >= points as f64)
+                .max_by(|(_, a), (_, b)| affinity(&a.team).cmp(&affinity(&b.team))
+                    .then_with(|| a.remaining().total_cmp(&b.remaining()))
+                    .then_with(|| b.team.cmp(&a.team)))
+                .map(|(i, _)| (q, i))
+        });
+        match slot{
+            Some((q, i)) => {
+                load[i].planned += points;
+                quarter_of.insert(epic, q);
+                roadmap.placements.push(Placement{ epic, team: load[i].team.clone(), quarter: load[i].quarter.clone(), points });
+            }
+            None => roadmap.unallocated.push(Unallocated::NoCapacity{ epic, points }),
+        }
+    }
+    roadmap.load = load;
+    Ok(roadmap)
+}
@@ -0,0 +1,46 @@
+// Flags planned work that falls on days its owner is unavailable
+
+use crate::core::graph::ProjectGraph;
+use crate::core::timezone::local_date;
+use crate::core::Scope;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct AvailabilityConflict{
+    pub person: String,
+    pub node: Uuid,
+    // Working days inside the node's timeline on which the owner is away
+    pub dates: Vec<NaiveDate>,
+}
+
+pub fn availability_conflicts(graph: &ProjectGraph, scope: &Scope) -> Vec<AvailabilityConflict>{
+    let mut conflicts = Vec::new();
+
+    for node in graph.nodes_in_scope(scope){
+        if node.is_done(){
+            continue;
+        }
+        let (Some(owner), Some(tl)) = (node.get_owner(), node.get_timeline()) else {
+            continue;
+        };
+        let Some(person) = graph.get_person(owner) else {
+            continue;
+        };
+
+        let tz = graph.timezone_for(node.get_id());
+        let start = local_date(tl.start, tz);
+        let end = local_date(tl.end.unwrap_or(tl.start), tz);
+        let dates: Vec<NaiveDate> = graph.get_calendar().working_days_in(start, end, person.region.as_deref())
+            .into_iter()
+            .filter(|d| !person.is_available(*d))
+            .collect();
+
+        if !dates.is_empty(){
+            conflicts.push(AvailabilityConflict{ person: owner.to_string(), node: node.get_id(), dates });
+        }
+    }
+
+    conflicts.sort_by(|a, b| a.person.cmp(&b.person).then_with(|| a.dates[0].cmp(&b.dates[0])));
+    conflicts
+}
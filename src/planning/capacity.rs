@@ -0,0 +1,89 @@
+// Sprint capacity: members x working days x focus factor vs committed points
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Team;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct MemberCapacity{
+    pub name: String,
+    pub available_days: usize,
+    pub available_hours: f64,
+    pub available_points: f64,
+    pub committed_points: u32,
+}
+
+impl MemberCapacity{
+    pub fn is_overcommitted(&self) -> bool{
+        self.committed_points as f64 > self.available_points
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CapacityReport{
+    pub sprint_id: Uuid,
+    pub team: String,
+    pub working_days: usize,
+    pub members: Vec<MemberCapacity>,
+}
+
+impl CapacityReport{
+    pub fn available_hours(&self) -> f64{
+        self.members.iter().map(|m| m.available_hours).sum()
+    }
+
+    pub fn available_points(&self) -> f64{
+        self.members.iter().map(|m| m.available_points).sum()
+    }
+
+    pub fn committed_points(&self) -> u32{
+        self.members.iter().map(|m| m.committed_points).sum()
+    }
+
+    pub fn is_overcommitted(&self) -> bool{
+        self.committed_points() as f64 > self.available_points()
+    }
+
+    pub fn overcommitted_members(&self) -> Vec<&MemberCapacity>{
+        self.members.iter().filter(|m| m.is_overcommitted()).collect()
+    }
+}
+
+// Points the whole team can deliver from `start` to `end`, both included
+pub fn team_points(graph: &ProjectGraph, team: &Team, start: NaiveDate, end: NaiveDate) -> f64{
+    team.members.iter()
+        .map(|m| graph.available_days(m, start, end).len() as f64 * team.focus_factor * team.points_per_day)
+        .sum()
+}
+
+pub fn capacity(graph: &ProjectGraph, sprint_id: Uuid, team_name: &str) -> Result<CapacityReport,&'static str>{
+    let sprint = graph.get_sprint(sprint_id).ok_or("The sprint does not exist")?;
+    let team = graph.get_team(team_name).ok_or("The team does not exist")?;
+
+    let start = sprint.start.date_naive();
+    let end = sprint.end.date_naive();
+    let working_days = graph.get_calendar().working_days(start, end).len();
+
+    let members = team.members.iter()
+        .map(|member| {
+            let available_days = graph.available_days(member, start, end).len();
+            let focused_days = available_days as f64 * team.focus_factor;
+            let committed_points = sprint.get_items().iter()
+                .filter_map(|id| graph.get_node(*id))
+                .filter(|n| n.get_owner() == Some(member.as_str()))
+                .filter_map(|n| n.get_points())
+                .sum();
+
+            MemberCapacity{
+                name: member.clone(),
+                available_days,
+                available_hours: focused_days * team.hours_per_day,
+                available_points: focused_days * team.points_per_day,
+                committed_points,
+            }
+        })
+        .collect();
+
+    Ok(CapacityReport{ sprint_id, team: team.name.clone(), working_days, members })
+}
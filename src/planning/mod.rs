@@ -0,0 +1,11 @@
+// Planning module - sprint and quarter planning helpers
+
+pub mod allocation;
+pub mod availability;
+pub mod capacity;
+pub mod sprint_fill;
+
+pub use allocation::{allocate, DraftRoadmap, Placement, TeamQuarter, Unallocated};
+pub use availability::{availability_conflicts, AvailabilityConflict};
+pub use capacity::{capacity, team_points, CapacityReport, MemberCapacity};
+pub use sprint_fill::{fill_sprint, FillStrategy, Skipped, SprintProposal};
@@ -0,0 +1,183 @@
+// Sprint filling - a proposed commitment pulled from the backlog
+//
+// Open, estimated User Stories not yet planned into any sprint are taken in
+// the strategy's order. A story goes in when everything it (or its parents)
+// depends on is done or already in the sprint, its owner is on the team and
+// under the WIP limit, and both the owner and the team have points left.
+// After each pick the backlog is walked from the top again, so a story
+// unblocked by the pick still gets its turn before less urgent ones.
+//
+// Nothing is changed until the proposal is applied.
+
+use super::capacity::capacity;
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Priority, Status};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillStrategy{
+    // Most urgent first; among equals the smaller story, so more fits
+    #[default]
+    Priority,
+    // Smallest first, committing to as many stories as possible
+    SmallestFirst,
+    // Largest first, leaving small stories to top the sprint up
+    LargestFirst,
+}
+
+// Why a backlog story stayed out
+#[derive(Debug, Clone, PartialEq)]
+pub enum Skipped{
+    Unestimated,
+    // Marked Blocked
+    Blocked,
+    // Waits on work that is neither done nor in the sprint
+    Waiting{ on: Uuid },
+    NotOnTeam{ owner: String },
+    OverWipLimit{ owner: String },
+    OverCapacity,
+}
+
+#[derive(Debug, Clone)]
+pub struct SprintProposal{
+    pub sprint_id: Uuid,
+    pub team: String,
+    pub capacity: f64,
+    // Points of the items already in the sprint
+    pub committed: u32,
+    // Stories to add, in the order they were picked
+    pub proposed: Vec<Uuid>,
+    pub proposed_points: u32,
+    pub skipped: Vec<(Uuid, Skipped)>,
+}
+
+impl SprintProposal{
+    pub fn total_points(&self) -> u32{
+        self.committed + self.proposed_points
+    }
+
+    pub fn remaining(&self) -> f64{
+        self.capacity - self.total_points() as f64
+    }
+
+    // Plans the proposed stories into the sprint
+    pub fn apply(&self, graph: &mut ProjectGraph) -> Result<(),&'static str>{
+        for id in &self.proposed{
+            graph.add_to_sprint(self.sprint_id, *id)?;
+        }
+        Ok(())
+    }
+}
+
+struct Fill<'a>{
+    graph: &'a ProjectGraph,
+    in_sprint: HashSet<Uuid>,
+    team_left: f64,
+    // Points each team member has left
+    member_left: HashMap<String,f64>,
+    // Open items each person holds in the sprint
+    open: HashMap<String,u32>,
+    wip_limit: Option<u32>,
+}
+
+impl Fill<'_>{
+    fn waiting_on(&self, id: Uuid) -> Option<Uuid>{
+        std::iter::once(id)
+            .chain(self.graph.get_ancestors(id))
+            .flat_map(|s| self.graph.get_predecessors(s))
+            .map(|(pred, _)| pred)
+            .filter(|pred| !self.in_sprint.contains(pred) && !self.graph.get_node(*pred).is_some_and(|n| n.is_done()))
+            .min()
+    }
+
+    fn obstacle(&self, node: &Node) -> Option<Skipped>{
+        let Some(points) = node.get_points() else {
+            return Some(Skipped::Unestimated);
+        };
+        if node.get_status() == Some(Status::Blocked){
+            return Some(Skipped::Blocked);
+        }
+        if let Some(on) = self.waiting_on(node.get_id()){
+            return Some(Skipped::Waiting{ on });
+        }
+        if let Some(owner) = node.get_owner(){
+            let Some(left) = self.member_left.get(owner) else {
+                return Some(Skipped::NotOnTeam{ owner: owner.to_string() });
+            };
+            if self.wip_limit.is_some_and(|limit| self.open.get(owner).copied().unwrap_or(0) >= limit){
+                return Some(Skipped::OverWipLimit{ owner: owner.to_string() });
+            }
+            if *left < points as f64{
+                return Some(Skipped::OverCapacity);
+            }
+        }
+        if self.team_left < points as f64{
+            return Some(Skipped::OverCapacity);
+        }
+        None
+    }
+
+    fn take(&mut self, node: &Node){
+        let points = node.get_points().unwrap_or(0) as f64;
+        self.in_sprint.insert(node.get_id());
+        self.team_left -= points;
+        if let Some(owner) = node.get_owner(){
+            *self.member_left.entry(owner.to_string()).or_default() -= points;
+            *self.open.entry(owner.to_string()).or_default() += 1;
+        }
+    }
+}
+
+pub fn fill_sprint(graph: &ProjectGraph, sprint_id: Uuid, team: &str, strategy: FillStrategy) -> Result<SprintProposal,&'static str>{
+    let report = capacity(graph, sprint_id, team)?;
+    let sprint = graph.get_sprint(sprint_id).ok_or("The sprint does not exist")?;
+
+    let items: Vec<&Node> = sprint.get_items().iter().filter_map(|id| graph.get_node(*id)).collect();
+    let committed: u32 = items.iter().filter_map(|n| n.get_points()).sum();
+    let mut open: HashMap<String,u32> = HashMap::new();
+    for owner in items.iter().filter(|n| !n.is_done()).filter_map(|n| n.get_owner()){
+        *open.entry(owner.to_string()).or_default() += 1;
+    }
+    let mut fill = Fill{
+        graph,
+        in_sprint: sprint.get_items().clone(),
+        team_left: report.available_points() - committed as f64,
+        member_left: report.members.iter().map(|m| (m.name.clone(), m.available_points - m.committed_points as f64)).collect(),
+        open,
+        wip_limit: graph.get_settings().wip_limit,
+    };
+
+    let planned: HashSet<Uuid> = graph.sprints().flat_map(|s| s.get_items().iter().copied()).collect();
+    let mut backlog: Vec<&Node> = graph.nodes()
+        .filter(|n| matches!(n, Node::UserStory{..}) && !n.is_done() && !planned.contains(&n.get_id()))
+        .collect();
+    let points = |n: &Node| n.get_points().unwrap_or(u32::MAX);
+    let priority = |n: &Node| graph.get_priority(n.get_id()).unwrap_or(Priority::Medium);
+    match strategy{
+        FillStrategy::Priority => backlog.sort_by_key(|n| (Reverse(priority(n)), points(n), n.get_id())),
+        FillStrategy::SmallestFirst => backlog.sort_by_key(|n| (points(n), Reverse(priority(n)), n.get_id())),
+        FillStrategy::LargestFirst => backlog.sort_by_key(|n| (Reverse(n.get_points().unwrap_or(0)), Reverse(priority(n)), n.get_id())),
+    }
+
+    let mut proposed = Vec::new();
+    while let Some(next) = backlog.iter().copied().find(|n| !fill.in_sprint.contains(&n.get_id()) && fill.obstacle(n).is_none()){
+        fill.take(next);
+        proposed.push(next.get_id());
+    }
+    let skipped = backlog.iter()
+        .filter(|n| !fill.in_sprint.contains(&n.get_id()))
+        .filter_map(|n| fill.obstacle(n).map(|reason| (n.get_id(), reason)))
+        .collect();
+
+    Ok(SprintProposal{
+        sprint_id,
+        team: report.team.clone(),
+        capacity: report.available_points(),
+        committed,
+        proposed_points: proposed.iter().filter_map(|id| graph.get_node(*id)).filter_map(|n| n.get_points()).sum(),
+        proposed,
+        skipped,
+    })
+}
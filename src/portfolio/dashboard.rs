@@ -0,0 +1,181 @@
+// Portfolio dashboard - progress per project and overall, and how the
+// cross-project links shape the combined schedule
+//
+// Each project is scheduled twice: on its own, and inside the combined
+// graph where cross-project links can hold its work back. The difference
+// between the two finishes is the project's slip due to the others.
+
+use super::program::{CrossLink, NodeRef, Portfolio};
+use crate::analytics::health_score;
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Status};
+use crate::scheduler::{critical_chain, schedule, BufferMethod, ChainLink};
+use crate::views::escape_cell;
+use chrono::{DateTime, TimeDelta, Utc};
+
+type DT = DateTime<Utc>;
+
+const STATUSES: [Status; 4] = [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done];
+
+#[derive(Debug, Clone)]
+pub struct ProjectSummary{
+    pub name: String,
+    pub status_counts: Vec<(Status, usize)>,
+    pub completion: f64,
+    // Finish within the portfolio, and with the project scheduled on its own
+    pub finish: Option<DT>,
+    pub standalone_finish: Option<DT>,
+    // Cross-project links into and out of the project whose blocker isn't done
+    pub waiting_on: usize,
+    pub holding_up: usize,
+    // Health of the file's Project node, the worst one when it holds several
+    pub health: Option<f64>,
+}
+
+impl ProjectSummary{
+    // How much later the project finishes because of the cross-project links
+    pub fn slip(&self) -> TimeDelta{
+        match (self.finish, self.standalone_finish){
+            (Some(finish), Some(alone)) if finish > alone => finish - alone,
+            _ => TimeDelta::zero(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dashboard{
+    pub name: String,
+    pub projects: Vec<ProjectSummary>,
+    pub status_counts: Vec<(Status, usize)>,
+    pub completion: f64,
+    pub finish: Option<DT>,
+    // Leaves on the critical chain across all projects, first to last, with
+    // what each waits for: the previous step's work or its owner
+    pub critical_chain: Vec<(NodeRef,ChainLink)>,
+    pub project_buffer: TimeDelta,
+    // Cross-project links whose blocker isn't done yet
+    pub open_links: Vec<CrossLink>,
+}
+
+// Leaf work carries the status; containers would count it twice
+fn leaf_work(graph: &ProjectGraph) -> Vec<&Node>{
+    graph.nodes()
+        .filter(|n| n.get_status().is_some() && graph.get_children(n.get_id()).is_empty())
+        .collect()
+}
+
+fn count_statuses(work: &[&Node]) -> Vec<(Status, usize)>{
+    STATUSES.into_iter()
+        .map(|s| (s, work.iter().filter(|n| n.get_status() == Some(s)).count()))
+        .collect()
+}
+
+pub fn dashboard(portfolio: &Portfolio) -> Result<Dashboard,&'static str>{
+    let combined = portfolio.combined()?;
+    let combined_schedule = schedule(&combined)?;
+
+    let is_done = |r: &NodeRef| portfolio.project(&r.project)
+        .and_then(|g| g.get_node(r.node))
+        .is_some_and(|n| n.is_done());
+    let open_links: Vec<CrossLink> = portfolio.links().iter()
+        .filter(|l| !is_done(&l.from))
+        .cloned()
+        .collect();
+
+    let mut projects = Vec::new();
+    for (name, graph) in portfolio.projects(){
+        let work = leaf_work(graph);
+        let finish = graph.nodes()
+            .filter_map(|n| combined_schedule.get(n.get_id()))
+            .map(|n| n.end)
+            .max();
+        projects.push(ProjectSummary{
+            name: name.to_string(),
+            status_counts: count_statuses(&work),
+            completion: ProjectGraph::completion_of(&work),
+            finish,
+            standalone_finish: schedule(graph)?.end(),
+            waiting_on: open_links.iter().filter(|l| l.to.project == name).count(),
+            holding_up: open_links.iter().filter(|l| l.from.project == name).count(),
+            health: graph.nodes()
+                .filter(|n| matches!(n, Node::Project{..}))
+                .filter_map(|n| health_score(graph, n.get_id()).ok())
+                .map(|h| h.score)
+                .min_by(f64::total_cmp),
+        });
+    }
+
+    let work = leaf_work(&combined);
+    let chain = critical_chain(&combined, BufferMethod::default())?;
+    let critical_chain = chain.steps.into_iter()
+        .filter_map(|step| portfolio.node_ref(step.id).map(|r| (r, step.link)))
+        .collect();
+
+    Ok(Dashboard{
+        name: portfolio.name.clone(),
+        projects,
+        status_counts: count_statuses(&work),
+        completion: ProjectGraph::completion_of(&work),
+        finish: combined_schedule.end(),
+        critical_chain,
+        project_buffer: chain.project_buffer,
+        open_links,
+    })
+}
+
+impl Dashboard{
+    // Projects the critical chain runs through, in the order it reaches them
+    pub fn critical_projects(&self) -> Vec<&str>{
+        let mut projects: Vec<&str> = Vec::new();
+        for (step, _) in &self.critical_chain{
+            if projects.last() != Some(&step.project.as_str()){
+                projects.push(&step.project);
+            }
+        }
+        projects
+    }
+
+    pub fn render_markdown(&self, portfolio: &Portfolio) -> String{
+        let date = |d: Option<DT>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string());
+        let label = |r: &NodeRef| {
+            let graph = portfolio.project(&r.project);
+            let name = graph.and_then(|g| g.get_node(r.node)).map(|n| n.get_name()).unwrap_or("?");
+            match graph.and_then(|g| g.get_key(r.node)){
+                Some(key) => format!("{}/{} {}", r.project, key, name),
+                None => format!("{}/{}", r.project, name),
+            }
+        };
+
+        let mut out = format!("# Portfolio: {}\n\n", self.name);
+        out.push_str(&format!("Completion: {:.0}%, finishing {}\n\n", self.completion, date(self.finish)));
+
+        out.push_str("| Project | Done | Completion | Finish | Slip (days) | Waiting on | Holding up | Health |\n");
+        out.push_str("|---|---|---|---|---|---|---|---|\n");
+        for project in &self.projects{
+            let total: usize = project.status_counts.iter().map(|(_, c)| c).sum();
+            let done = project.status_counts.iter().find(|(s, _)| *s == Status::Done).map(|(_, c)| *c).unwrap_or(0);
+            let health = project.health.map(|h| format!("{:.0}", h)).unwrap_or_else(|| "-".to_string());
+            out.push_str(&format!("| {} | {}/{} | {:.0}% | {} | {} | {} | {} | {} |\n",
+                escape_cell(&project.name), done, total, project.completion, date(project.finish),
+                project.slip().num_days(), project.waiting_on, project.holding_up, health));
+        }
+
+        if !self.critical_chain.is_empty(){
+            out.push_str(&format!("\n## Critical chain ({})\n\n", self.critical_projects().join(" → ")));
+            for (step, link) in &self.critical_chain{
+                match link{
+                    ChainLink::Resource(owner) => out.push_str(&format!("- {} (waits for {})\n", label(step), owner)),
+                    _ => out.push_str(&format!("- {}\n", label(step))),
+                }
+            }
+            out.push_str(&format!("\nProject buffer: {} days\n", self.project_buffer.num_days()));
+        }
+        if !self.open_links.is_empty(){
+            out.push_str("\n## Open cross-project dependencies\n\n");
+            for link in &self.open_links{
+                out.push_str(&format!("- {} blocks {}\n", label(&link.from), label(&link.to)));
+            }
+        }
+        out
+    }
+}
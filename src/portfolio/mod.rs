@@ -0,0 +1,8 @@
+// Portfolio module - several projects tracked together, with dependencies
+// running between them
+
+pub mod dashboard;
+pub mod program;
+
+pub use dashboard::{dashboard, Dashboard, ProjectSummary};
+pub use program::{CrossLink, NodeRef, Portfolio};
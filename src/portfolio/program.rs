@@ -0,0 +1,209 @@
+// Portfolio - several project graphs managed together, with Blocks edges
+// that cross from one project into another
+//
+// Each project stays a self-contained ProjectGraph that can be saved and
+// edited on its own; the cross-project links live on the portfolio. For
+// scheduling, the projects and links are flattened into one combined graph.
+
+use crate::core::graph::{Dependency, DependencyType, ProjectGraph};
+use crate::core::timeline::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+// A node within one of the portfolio's projects
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NodeRef{
+    pub project: String,
+    pub node: Uuid,
+}
+
+impl NodeRef{
+    pub fn new(project: &str, node: Uuid) -> Self{
+        NodeRef{ project: project.to_string(), node }
+    }
+}
+
+// `from` blocks `to`, like a Blocks edge inside a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossLink{
+    pub from: NodeRef,
+    pub to: NodeRef,
+    #[serde(default)]
+    pub lag: Option<Duration>,
+}
+
+impl CrossLink{
+    pub fn new(from: NodeRef, to: NodeRef) -> Self{
+        CrossLink{ from, to, lag: None }
+    }
+}
+
+// Kept on disk as a manifest of project files, see storage::portfolio
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio{
+    pub name: String,
+    projects: BTreeMap<String,ProjectGraph>,
+    links: Vec<CrossLink>,
+}
+
+impl Portfolio{
+    pub fn new(name: &str) -> Self{
+        Portfolio{ name: name.to_string(), projects: BTreeMap::new(), links: Vec::new() }
+    }
+
+    // Node ids must be unique across the whole portfolio
+    pub fn add_project(&mut self, name: &str, graph: ProjectGraph) -> Result<(),&'static str>{
+        if self.projects.contains_key(name){
+            return Err("The portfolio already has a project with that name");
+        }
+        if graph.nodes().any(|n| self.project_of(n.get_id()).is_some()){
+            return Err("The project shares nodes with another project of the portfolio");
+        }
+        self.projects.insert(name.to_string(), graph);
+        Ok(())
+    }
+
+    // Drops the project along with every link into or out of it
+    pub fn remove_project(&mut self, name: &str) -> Option<ProjectGraph>{
+        let graph = self.projects.remove(name)?;
+        self.links.retain(|l| l.from.project != name && l.to.project != name);
+        Some(graph)
+    }
+
+    pub fn project(&self, name: &str) -> Option<&ProjectGraph>{
+        self.projects.get(name)
+    }
+
+    // Callers that remove nodes should follow up with prune_links
+    pub fn project_mut(&mut self, name: &str) -> Option<&mut ProjectGraph>{
+        self.projects.get_mut(name)
+    }
+
+    pub fn projects(&self) -> impl Iterator<Item = (&str,&ProjectGraph)>{
+        self.projects.iter().map(|(name, graph)| (name.as_str(), graph))
+    }
+
+    // Name of the project holding the node
+    pub fn project_of(&self, id: Uuid) -> Option<&str>{
+        self.projects.iter()
+            .find(|(_, graph)| graph.get_node(id).is_some())
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn node_ref(&self, id: Uuid) -> Option<NodeRef>{
+        self.project_of(id).map(|project| NodeRef::new(project, id))
+    }
+
+    pub fn links(&self) -> &[CrossLink]{
+        &self.links
+    }
+
+    // Links touching a project, in either direction
+    pub fn links_of<'a>(&'a self, project: &'a str) -> impl Iterator<Item = &'a CrossLink>{
+        self.links.iter().filter(move |l| l.from.project == project || l.to.project == project)
+    }
+
+    // Whether the two nodes can be linked, cycles aside
+    fn check_link(&self, link: &CrossLink) -> Result<(),&'static str>{
+        if link.from.project == link.to.project{
+            return Err("Links within a project belong in the project graph");
+        }
+        for end in [&link.from, &link.to]{
+            let graph = self.projects.get(&end.project).ok_or("The portfolio has no such project")?;
+            if graph.get_node(end.node).is_none(){
+                return Err("The node does not exist in the project");
+            }
+        }
+        if self.links.iter().any(|l| l.from == link.from && l.to == link.to){
+            return Err("The projects are already linked that way");
+        }
+        Ok(())
+    }
+
+    // Adds a cross-project Blocks link; fails without changes if the two
+    // nodes can't be linked that way or the link would close a cycle
+    pub fn link(&mut self, link: CrossLink) -> Result<(),&'static str>{
+        self.check_link(&link)?;
+        self.links.push(link);
+        if let Err(e) = self.combined(){
+            self.links.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    // Adds every link that can be added and hands back the rest with the
+    // reason. The combined graph is checked once for the whole batch; only
+    // when it has a cycle are the links retried one at a time to find the
+    // ones closing it.
+    pub fn link_all(&mut self, links: Vec<CrossLink>) -> Vec<(CrossLink,&'static str)>{
+        let mut rejected = Vec::new();
+        let before = self.links.len();
+        for link in links{
+            match self.check_link(&link){
+                Ok(()) => self.links.push(link),
+                Err(e) => rejected.push((link, e)),
+            }
+        }
+        if self.combined().is_err(){
+            for link in self.links.split_off(before){
+                if let Err(e) = self.link(link.clone()){
+                    rejected.push((link, e));
+                }
+            }
+        }
+        rejected
+    }
+
+    pub fn unlink(&mut self, from: &NodeRef, to: &NodeRef) -> bool{
+        let before = self.links.len();
+        self.links.retain(|l| &l.from != from || &l.to != to);
+        self.links.len() != before
+    }
+
+    // Drops links whose nodes no longer exist
+    pub fn prune_links(&mut self) -> usize{
+        let before = self.links.len();
+        let projects = &self.projects;
+        let exists = |r: &NodeRef| projects.get(&r.project).is_some_and(|g| g.get_node(r.node).is_some());
+        self.links.retain(|l| exists(&l.from) && exists(&l.to));
+        before - self.links.len()
+    }
+
+    // All projects in one graph, joined by the cross-project links; carries
+    // the nodes, edges and constraints, which is what scheduling needs
+    pub fn combined(&self) -> Result<ProjectGraph,&'static str>{
+        let mut combined = ProjectGraph::new();
+        for graph in self.projects.values(){
+            for node in graph.nodes(){
+                combined.add_node(node)?;
+            }
+        }
+        for graph in self.projects.values(){
+            for (from, to, dependency) in graph.edges(){
+                combined.connect_unchecked(from, to, dependency.clone())?;
+            }
+            for node in graph.nodes(){
+                if let Some(constraint) = graph.get_constraint(node.get_id()){
+                    combined.set_constraint(node.get_id(), constraint)?;
+                }
+            }
+        }
+        for link in &self.links{
+            let dependency = Dependency{ kind: DependencyType::Blocks, lag: link.lag.clone() };
+            combined.connect_unchecked(link.from.node, link.to.node, dependency)?;
+        }
+        // Remote dependencies on another project of the portfolio become real edges
+        for graph in self.projects.values(){
+            for remote in graph.remote_dependencies().iter().filter(|d| self.project_of(d.remote.node).is_some()){
+                let dependency = Dependency{ kind: DependencyType::Blocks, lag: remote.lag.clone() };
+                combined.connect_unchecked(remote.remote.node, remote.node, dependency)?;
+            }
+        }
+        if !combined.is_acyclic(){
+            return Err("The cross-project links create a cycle");
+        }
+        Ok(combined)
+    }
+}
@@ -0,0 +1,60 @@
+// Confluence - overwrites an existing page with the HTML report
+//
+// Uses the REST content API: read the page for its title and version, then
+// put the new body at the next version. Authenticates with an account email
+// and API token.
+
+use super::transport::{Request, Transport};
+use anyhow::{anyhow, bail, Result};
+use serde_json::json;
+
+pub struct Credentials{
+    pub user: String,
+    pub token: String,
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(input: &[u8]) -> String{
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3){
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4{
+            if i <= chunk.len(){
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            }else{
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// Returns the page's web link
+pub fn publish(transport: &mut dyn Transport, base_url: &str, page_id: &str, credentials: &Credentials, html: &str) -> Result<String>{
+    let base = base_url.trim_end_matches('/');
+    let url = format!("{}/rest/api/content/{}", base, page_id);
+    let auth = format!("Basic {}", base64(format!("{}:{}", credentials.user, credentials.token).as_bytes()));
+
+    let current = transport.send(&Request::new("GET", format!("{}?expand=version", url)).header("Authorization", &auth))?;
+    if !current.is_success(){
+        bail!("Confluence page {} could not be read (HTTP {}): {}", page_id, current.status, current.body);
+    }
+    let version = current.body["version"]["number"].as_u64().ok_or_else(|| anyhow!("Confluence gave no version for page {}", page_id))?;
+    let title = current.body["title"].as_str().unwrap_or("Status report").to_string();
+
+    let update = Request::new("PUT", url).header("Authorization", &auth).json(json!({
+        "id": page_id,
+        "type": "page",
+        "title": title,
+        "version": { "number": version + 1 },
+        "body": { "storage": { "value": html, "representation": "storage" } },
+    }));
+    let response = transport.send(&update)?;
+    if !response.is_success(){
+        bail!("Confluence rejected the update of page {} (HTTP {}): {}", page_id, response.status, response.body);
+    }
+    let link = response.body["_links"]["webui"].as_str().map(|path| format!("{}{}", base, path));
+    Ok(link.unwrap_or_else(|| format!("{}/pages/viewpage.action?pageId={}", base, page_id)))
+}
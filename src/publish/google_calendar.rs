@@ -0,0 +1,187 @@
+// Google Calendar - milestones, sprint boundaries and deadlines as all-day
+// events, kept in step with the graph
+//
+//   milestones   release target dates and the planned ends of open epics
+//   sprints      one event on the first day and one on the last
+//   deadlines    planned ends of open leaf work with an owner, in the owner's
+//                calendar when the settings name one
+//
+// Event ids come from the Uuid of the release, epic, sprint or node, so a sync
+// updates the events it made before instead of adding new ones. What was sent
+// is remembered in the sync state: unchanged events are skipped, and events
+// for work that is done, removed or moved to another calendar are deleted.
+
+use super::transport::{Request, Transport};
+use crate::core::graph::ProjectGraph;
+use crate::core::{CalendarSync, Node};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Days, Utc};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const API: &str = "https://www.googleapis.com/calendar/v3/calendars";
+const SYSTEM: &str = "google-calendar";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent{
+    pub calendar: String,
+    // Lowercase hex and a-v only, as the API requires
+    pub event_id: String,
+    pub summary: String,
+    pub description: String,
+    pub day: DateTime<Utc>,
+}
+
+impl CalendarEvent{
+    fn body(&self) -> Value{
+        let date = |d: DateTime<Utc>| d.format("%Y-%m-%d").to_string();
+        // All-day events end the day after
+        let end = self.day.checked_add_days(Days::new(1)).unwrap_or(self.day);
+        json!({
+            "id": self.event_id,
+            "summary": self.summary,
+            "description": self.description,
+            "start": { "date": date(self.day) },
+            "end": { "date": date(end) },
+            "status": "confirmed",
+            "transparency": "transparent",
+        })
+    }
+
+    // Sync state key: the same event id in another calendar is another event
+    fn key(&self) -> String{
+        format!("{}/{}", self.calendar, self.event_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalendarSyncReport{
+    pub written: usize,
+    pub unchanged: usize,
+    pub deleted: usize,
+}
+
+fn event_id(id: Uuid, suffix: &str) -> String{
+    format!("pm{}{}", id.simple(), suffix)
+}
+
+fn label(graph: &ProjectGraph, node: &Node) -> String{
+    match graph.get_key(node.get_id()){
+        Some(key) => format!("{} {}", key, node.get_name()),
+        None => node.get_name().to_string(),
+    }
+}
+
+// Every event the graph calls for, in no particular order
+pub fn calendar_events(graph: &ProjectGraph, sync: &CalendarSync) -> Vec<CalendarEvent>{
+    let main = sync.calendar_id.clone();
+    let mut events = Vec::new();
+    for release in graph.releases(){
+        events.push(CalendarEvent{
+            calendar: main.clone(),
+            event_id: event_id(release.id, ""),
+            summary: format!("Release {}", release.name),
+            description: format!("{} items in scope", release.get_scope().len()),
+            day: release.target_date,
+        });
+    }
+    for sprint in graph.sprints(){
+        for (suffix, what, day) in [("start", "starts", sprint.start), ("end", "ends", sprint.end)]{
+            events.push(CalendarEvent{
+                calendar: main.clone(),
+                event_id: event_id(sprint.id, suffix),
+                summary: format!("{} {}", sprint.name, what),
+                description: format!("{} items", sprint.get_items().len()),
+                day,
+            });
+        }
+    }
+    for node in graph.nodes().filter(|n| !n.is_done()){
+        let Some(end) = node.get_timeline().and_then(|tl| tl.end) else {
+            continue;
+        };
+        let id = node.get_id();
+        let event = match node{
+            Node::Epic{..} => CalendarEvent{
+                calendar: main.clone(),
+                event_id: event_id(id, ""),
+                summary: format!("{} ends", label(graph, node)),
+                description: node.get_owner().map(|o| format!("Owner: {}", o)).unwrap_or_default(),
+                day: end,
+            },
+            _ if graph.get_children(id).is_empty() && node.get_status().is_some() => {
+                let Some(owner) = node.get_owner() else {
+                    continue;
+                };
+                CalendarEvent{
+                    calendar: sync.calendar_for(Some(owner)).to_string(),
+                    event_id: event_id(id, ""),
+                    summary: format!("Due: {}", label(graph, node)),
+                    description: format!("Owner: {}", owner),
+                    day: end,
+                }
+            }
+            _ => continue,
+        };
+        events.push(event);
+    }
+    events
+}
+
+fn encode(segment: &str) -> String{
+    segment.bytes().map(|b| match b{
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+fn upsert(transport: &mut dyn Transport, auth: &str, event: &CalendarEvent) -> Result<()>{
+    let events = format!("{}/{}/events", API, encode(&event.calendar));
+    let update = Request::new("PUT", format!("{}/{}", events, event.event_id)).header("Authorization", auth).json(event.body());
+    let response = transport.send(&update)?;
+    if response.status == 404{
+        let insert = Request::new("POST", events).header("Authorization", auth).json(event.body());
+        let response = transport.send(&insert)?;
+        if !response.is_success(){
+            bail!("Google Calendar refused event {} (HTTP {}): {}", event.summary, response.status, response.body);
+        }
+    }else if !response.is_success(){
+        bail!("Google Calendar refused event {} (HTTP {}): {}", event.summary, response.status, response.body);
+    }
+    Ok(())
+}
+
+// Brings the calendars in line with the graph; `token` is an OAuth access
+// token with the calendar.events scope
+pub fn sync_calendar(graph: &mut ProjectGraph, transport: &mut dyn Transport, token: &str) -> Result<CalendarSyncReport>{
+    let sync = graph.get_settings().calendar_sync.clone().ok_or_else(|| anyhow!("Calendar sync is not set up"))?;
+    let auth = format!("Bearer {}", token);
+    let wanted: BTreeMap<String,CalendarEvent> = calendar_events(graph, &sync).into_iter().map(|e| (e.key(), e)).collect();
+    let mut report = CalendarSyncReport::default();
+
+    for (key, event) in &wanted{
+        let content = event.body().to_string();
+        if graph.get_sync_state().pushed(SYSTEM, key) == Some(content.as_str()){
+            report.unchanged += 1;
+            continue;
+        }
+        upsert(transport, &auth, event)?;
+        // Saved per event, so a failure part way leaves the rest to the next sync
+        graph.get_sync_state_mut().set_pushed(SYSTEM, key, Some(content));
+        report.written += 1;
+    }
+
+    for key in graph.get_sync_state().pushed_ids(SYSTEM).into_iter().filter(|k| !wanted.contains_key(k)){
+        let (calendar, id) = key.rsplit_once('/').unwrap_or(("", &key));
+        let delete = Request::new("DELETE", format!("{}/{}/events/{}", API, encode(calendar), id)).header("Authorization", &auth);
+        let response = transport.send(&delete)?;
+        // Already gone is as good as deleted
+        if !response.is_success() && response.status != 404 && response.status != 410{
+            bail!("Google Calendar would not delete event {} (HTTP {}): {}", id, response.status, response.body);
+        }
+        graph.get_sync_state_mut().set_pushed(SYSTEM, &key, None);
+        report.deleted += 1;
+    }
+    Ok(report)
+}
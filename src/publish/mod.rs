@@ -0,0 +1,73 @@
+// Publish module - pushing a project's status report to Confluence or Notion,
+// and its dates to Google Calendar
+//
+// Targets live in the project settings (core::publishing). Credentials come
+// from the environment so they never end up in a project file:
+//   PM_CONFLUENCE_USER, PM_CONFLUENCE_TOKEN   account email and API token
+//   PM_NOTION_TOKEN                           integration token
+//   PM_GOOGLE_TOKEN                           OAuth access token
+
+pub mod confluence;
+pub mod google_calendar;
+pub mod notion;
+pub mod transport;
+
+pub use google_calendar::{calendar_events, sync_calendar, CalendarEvent, CalendarSyncReport};
+pub use transport::{CurlTransport, Request, Response, Transport};
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Destination, PublishTarget};
+use crate::views::{status_report, StatusReport, Template};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Published{
+    pub target: String,
+    pub at: DateTime<Utc>,
+    // Where the report can be read
+    pub link: String,
+}
+
+pub(crate) fn env(name: &str) -> Result<String>{
+    std::env::var(name).ok().filter(|v| !v.is_empty()).with_context(|| format!("{} is not set", name))
+}
+
+// Publishes the target's report through `transport`; the caller records the
+// time with `mark_published` once it has been saved
+pub fn publish(graph: &ProjectGraph, target: &PublishTarget, transport: &mut dyn Transport, now: DateTime<Utc>) -> Result<Published>{
+    let report = status_report(graph, target.project).ok_or_else(|| anyhow!("Publish target {} does not point at a Project", target.name))?;
+    let link = match &target.destination{
+        Destination::Confluence{ base_url, page_id } => {
+            let credentials = confluence::Credentials{ user: env("PM_CONFLUENCE_USER")?, token: env("PM_CONFLUENCE_TOKEN")? };
+            confluence::publish(transport, base_url, page_id, &credentials, &render(graph, target, &report, true)?)?
+        }
+        Destination::Notion{ database_id } => {
+            let title = format!("{}, {}", report.title, now.format("%Y-%m-%d"));
+            notion::publish(transport, database_id, &env("PM_NOTION_TOKEN")?, &title, &render(graph, target, &report, false)?)?
+        }
+    };
+    Ok(Published{ target: target.name.clone(), at: now, link })
+}
+
+// The report through the target's template, or the built-in layout
+fn render(graph: &ProjectGraph, target: &PublishTarget, report: &StatusReport, html: bool) -> Result<String>{
+    let Some(name) = &target.template else {
+        return Ok(if html { report.render_html() } else { report.render_markdown() });
+    };
+    let template = graph.get_settings().templates.get(name).ok_or_else(|| anyhow!("No report template '{}'", name))?;
+    let parsed = Template::parse(&template.body).with_context(|| format!("Report template {}", name))?;
+    report.render_with(&parsed, html).with_context(|| format!("Report template {}", name))
+}
+
+// Targets on a schedule whose time has come
+pub fn due_targets(graph: &ProjectGraph, now: DateTime<Utc>) -> Vec<&PublishTarget>{
+    graph.get_settings().publish_targets.iter().filter(|t| t.is_due(now)).collect()
+}
+
+pub fn mark_published(graph: &mut ProjectGraph, name: &str, at: DateTime<Utc>) -> Result<()>{
+    let mut settings = graph.get_settings().clone();
+    let target = settings.publish_targets.iter_mut().find(|t| t.name == name).ok_or_else(|| anyhow!("No publish target '{}'", name))?;
+    target.last_published = Some(at);
+    graph.set_settings(settings).map_err(|e| anyhow!(e))
+}
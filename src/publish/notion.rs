@@ -0,0 +1,82 @@
+// Notion - adds the Markdown report to a database as a new page
+//
+// The page title goes in the database's title property, which must be
+// called "Name" (the default). The report becomes blocks: headings, bullets
+// and paragraphs; table rows are kept as code lines so their columns stay
+// lined up. Notion takes at most 100 blocks per request, so longer reports
+// are cut off with a note.
+
+use super::transport::{Request, Transport};
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+
+const API: &str = "https://api.notion.com/v1";
+const VERSION: &str = "2022-06-28";
+const MAX_BLOCKS: usize = 100;
+// Notion's limit on one piece of rich text
+const MAX_TEXT: usize = 2000;
+
+fn text(content: &str) -> Value{
+    let content: String = content.chars().take(MAX_TEXT).collect();
+    json!([{ "type": "text", "text": { "content": content } }])
+}
+
+fn block(kind: &str, content: &str) -> Value{
+    json!({ "object": "block", "type": kind, kind: { "rich_text": text(content) } })
+}
+
+pub fn markdown_blocks(markdown: &str) -> Vec<Value>{
+    let mut blocks = Vec::new();
+    let mut table: Vec<&str> = Vec::new();
+    let flush = |table: &mut Vec<&str>, blocks: &mut Vec<Value>| {
+        if !table.is_empty(){
+            blocks.push(json!({ "object": "block", "type": "code", "code": { "language": "markdown", "rich_text": text(&table.join("\n")) } }));
+            table.clear();
+        }
+    };
+    for line in markdown.lines(){
+        let line = line.trim_end();
+        if line.starts_with('|'){
+            table.push(line);
+            continue;
+        }
+        flush(&mut table, &mut blocks);
+        if line.trim().is_empty(){
+            continue;
+        }
+        blocks.push(if let Some(rest) = line.strip_prefix("### "){
+            block("heading_3", rest)
+        }else if let Some(rest) = line.strip_prefix("## "){
+            block("heading_2", rest)
+        }else if let Some(rest) = line.strip_prefix("# "){
+            block("heading_1", rest)
+        }else if let Some(rest) = line.strip_prefix("- "){
+            block("bulleted_list_item", rest)
+        }else{
+            block("paragraph", line)
+        });
+    }
+    flush(&mut table, &mut blocks);
+    if blocks.len() > MAX_BLOCKS{
+        blocks.truncate(MAX_BLOCKS - 1);
+        blocks.push(block("paragraph", "(report shortened to fit one Notion page)"));
+    }
+    blocks
+}
+
+// Returns the new page's link
+pub fn publish(transport: &mut dyn Transport, database_id: &str, token: &str, title: &str, markdown: &str) -> Result<String>{
+    let request = Request::new("POST", format!("{}/pages", API))
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("Notion-Version", VERSION)
+        .json(json!({
+            "parent": { "database_id": database_id },
+            "properties": { "Name": { "title": text(title) } },
+            "children": markdown_blocks(markdown),
+        }));
+    let response = transport.send(&request)?;
+    if !response.is_success(){
+        bail!("Notion rejected the page for database {} (HTTP {}): {}", database_id, response.status, response.body);
+    }
+    Ok(response.body["url"].as_str().unwrap_or_default().to_string())
+}
@@ -0,0 +1,135 @@
+// How publishers reach the outside world: one JSON request in, one JSON
+// response out. Tests and embedders supply their own Transport; the CLI uses
+// CurlTransport, which hands the request to the curl binary so pm needs no
+// HTTP or TLS stack of its own.
+
+use crate::trace;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request{
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(String,String)>,
+    pub body: Option<Value>,
+}
+
+impl Request{
+    pub fn new(method: &'static str, url: String) -> Self{
+        Request{ method, url, headers: Vec::new(), body: None }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self{
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn json(mut self, body: Value) -> Self{
+        self.body = Some(body);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response{
+    pub status: u16,
+    // As received; names keep their case, see header()
+    pub headers: Vec<(String,String)>,
+    // Null for an empty body
+    pub body: Value,
+}
+
+impl Response{
+    pub fn is_success(&self) -> bool{
+        (200..300).contains(&self.status)
+    }
+
+    // The first header with the name, matched without regard to case
+    pub fn header(&self, name: &str) -> Option<&str>{
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+// Splits what curl --include printed into the final response's headers and
+// the body; interim 1xx responses come first and are skipped
+fn split_head(text: &str) -> (Vec<(String,String)>,&str){
+    let mut rest = text;
+    let mut headers = Vec::new();
+    while rest.starts_with("HTTP/"){
+        let (head, body) = match (rest.find("\r\n\r\n"), rest.find("\n\n")){
+            (Some(crlf), _) => (&rest[..crlf], &rest[crlf + 4..]),
+            (None, Some(lf)) => (&rest[..lf], &rest[lf + 2..]),
+            (None, None) => (rest, ""),
+        };
+        headers = head.lines().skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        rest = body;
+        let interim = head.split_whitespace().nth(1).is_some_and(|code| code.starts_with('1'));
+        if !interim{
+            break;
+        }
+    }
+    (headers, rest)
+}
+
+pub trait Transport{
+    fn send(&mut self, request: &Request) -> Result<Response>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CurlTransport;
+
+// A value for curl's config file syntax, quoted so that nothing in it is
+// read as an option
+fn quoted(value: &str) -> String{
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Transport for CurlTransport{
+    fn send(&mut self, request: &Request) -> Result<Response>{
+        // Headers carry the credentials, so only the method and URL are traced
+        let mut span = trace::span(module_path!(), "send", &[("method", &request.method), ("url", &request.url)]);
+        if request.headers.iter().any(|(name, value)| name.contains(['\r', '\n']) || value.contains(['\r', '\n'])){
+            bail!("A request header spans several lines");
+        }
+        if request.url.contains(['\r', '\n']){
+            bail!("The request URL spans several lines");
+        }
+        // The URL, headers and body go to curl on stdin, as a config file, so
+        // tokens stay out of the process list and the URL cannot pass for an option
+        let mut config = format!("url = {}\n", quoted(&request.url));
+        for (name, value) in &request.headers{
+            config.push_str(&format!("header = {}\n", quoted(&format!("{}: {}", name, value))));
+        }
+        if let Some(body) = &request.body{
+            config.push_str("header = \"Content-Type: application/json\"\n");
+            config.push_str(&format!("data-binary = {}\n", quoted(&body.to_string())));
+        }
+        let mut command = Command::new("curl");
+        command.args(["--silent", "--show-error", "--include", "--request", request.method, "--write-out", "\n%{http_code}", "--config", "-"]);
+
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped())
+            .spawn()
+            .context("Could not run curl; publishing needs it on the PATH")?;
+        if let Some(mut stdin) = child.stdin.take(){
+            stdin.write_all(config.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success(){
+            bail!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let (response, status) = text.rsplit_once('\n').unwrap_or(("", &text));
+        let status = status.trim().parse::<u16>().context("curl gave no HTTP status")?;
+        span.record("status", &status);
+        let (headers, body) = split_head(response);
+        let body = if body.trim().is_empty() { Value::Null } else { serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string())) };
+        Ok(Response{ status, headers, body })
+    }
+}
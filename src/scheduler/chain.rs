@@ -0,0 +1,221 @@
+// Critical chain - the critical path once people can only do one thing at a time
+//
+// The plain schedule lets an owner work on any number of leaves at once.
+// Here leaves are placed one after another in order of their plain start;
+// each waits for its predecessors (as leveled) and for its owner to finish
+// whatever they were placed on before. The critical chain is the longest run
+// of leaves tied together by dependencies or by sharing an owner.
+//
+// Buffers follow critical chain project management: the project buffer
+// protects the end date and goes after the last chain leaf, and each chain
+// of leaves feeding into the critical chain gets a feeding buffer where it
+// joins. Both are sized from the lengths of the leaves they protect.
+
+use super::{schedule, Schedule, ScheduledNode, StartCause};
+use crate::core::graph::ProjectGraph;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BufferMethod{
+    // Half the summed length of the leaves protected
+    #[default]
+    CutAndPaste,
+    // Square root of the summed squares of half each leaf's length; smaller
+    // for long chains, where delays and gains tend to cancel out
+    RootSquareError,
+}
+
+impl BufferMethod{
+    pub fn size(&self, lengths: &[TimeDelta]) -> TimeDelta{
+        match self{
+            BufferMethod::CutAndPaste => lengths.iter().copied().sum::<TimeDelta>() / 2,
+            BufferMethod::RootSquareError => {
+                let squares: f64 = lengths.iter().map(|l| (l.num_seconds() as f64 / 2.0).powi(2)).sum();
+                TimeDelta::seconds(squares.sqrt().round() as i64)
+            }
+        }
+    }
+}
+
+// How a chain step is tied to the step before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainLink{
+    // The first step
+    Start,
+    Dependency,
+    // Same owner, so it had to wait until they were free
+    Resource(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainStep{
+    pub id: Uuid,
+    pub start: DT,
+    pub end: DT,
+    pub link: ChainLink,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedingBuffer{
+    // The chain leaf the feeding chain joins
+    pub joins: Uuid,
+    // Leaves of the feeding chain, first to last
+    pub chain: Vec<Uuid>,
+    pub size: TimeDelta,
+}
+
+#[derive(Debug, Clone)]
+pub struct CriticalChain{
+    pub steps: Vec<ChainStep>,
+    // The schedule with resource contention resolved
+    pub leveled: Schedule,
+    pub project_buffer: TimeDelta,
+    pub feeding_buffers: Vec<FeedingBuffer>,
+}
+
+impl CriticalChain{
+    pub fn contains(&self, id: Uuid) -> bool{
+        self.steps.iter().any(|s| s.id == id)
+    }
+
+    // Finish of the last chain step, before the project buffer
+    pub fn finish(&self) -> Option<DT>{
+        self.steps.last().map(|s| s.end)
+    }
+
+    // The date to commit to: the chain's finish plus the project buffer
+    pub fn buffered_finish(&self) -> Option<DT>{
+        self.finish().map(|f| f + self.project_buffer)
+    }
+
+    // Leaves that start later than in the plain schedule because their owner was busy
+    pub fn delayed_by_contention(&self) -> Vec<Uuid>{
+        let mut ids: Vec<Uuid> = self.leveled.iter()
+            .filter(|n| matches!(n.cause, StartCause::Resource{..}))
+            .map(|n| n.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+}
+
+// Leaves a dependency on `id` waits for: `id` itself, or every scheduled
+// leaf under it when it is a container
+fn leaves_under(graph: &ProjectGraph, plain: &Schedule, id: Uuid, is_leaf: &dyn Fn(Uuid) -> bool) -> Vec<Uuid>{
+    graph.get_subtree(id).into_iter().filter(|n| plain.get(*n).is_some() && is_leaf(*n)).collect()
+}
+
+pub fn critical_chain(graph: &ProjectGraph, method: BufferMethod) -> Result<CriticalChain,&'static str>{
+    let plain = schedule(graph)?;
+    let is_leaf = |id: Uuid| graph.get_children(id).iter().all(|c| plain.get(*c).is_none());
+    let mut leaves: Vec<&ScheduledNode> = plain.iter().filter(|n| is_leaf(n.id)).collect();
+    leaves.sort_by_key(|n| (n.start, n.end, n.id));
+
+    // Dependencies of each leaf, its ancestors' included, as (leaf, lag)
+    let depends_on: HashMap<Uuid,Vec<(Uuid,TimeDelta)>> = leaves.iter()
+        .map(|n| {
+            let sources = std::iter::once(n.id).chain(graph.get_ancestors(n.id));
+            let deps = sources
+                .flat_map(|s| graph.get_predecessors(s))
+                .flat_map(|(pred, lag)| leaves_under(graph, &plain, pred, &is_leaf).into_iter().map(move |l| (l, lag)))
+                .collect();
+            (n.id, deps)
+        })
+        .collect();
+
+    // Serial placement: a leaf goes once everything it depends on is placed,
+    // earliest plain start first
+    let mut placed: HashMap<Uuid,ScheduledNode> = HashMap::new();
+    let mut owner_free: HashMap<&str,(DT,Uuid)> = HashMap::new();
+    let mut pending: Vec<&ScheduledNode> = leaves.clone();
+    while !pending.is_empty(){
+        let Some(position) = pending.iter().position(|n| depends_on[&n.id].iter().all(|(d, _)| placed.contains_key(d))) else {
+            return Err("Dependencies are unsatisfiable: a node ends up waiting on itself");
+        };
+        let node = pending.remove(position);
+        let length = node.end - node.start;
+        let (mut start, mut cause, mut driver) = (node.start, node.cause.clone(), node.driver);
+        for (dep, lag) in &depends_on[&node.id]{
+            let ready = placed[dep].end + *lag;
+            if ready > start{
+                start = ready;
+                cause = StartCause::Predecessor{ id: *dep, via: None, finish: placed[dep].end, lag: *lag };
+                driver = Some(*dep);
+            }
+        }
+        let owner = graph.get_node(node.id).and_then(|n| n.get_owner());
+        if let Some((free, previous)) = owner.and_then(|o| owner_free.get(o)){
+            if *free > start{
+                start = *free;
+                cause = StartCause::Resource{ owner: owner.unwrap_or_default().to_string(), after: *previous };
+                driver = Some(*previous);
+            }
+        }
+        let end = start + length;
+        if let Some(owner) = owner{
+            owner_free.insert(owner, (end, node.id));
+        }
+        placed.insert(node.id, ScheduledNode{ id: node.id, start, end, driver, cause });
+    }
+
+    // Containers span their leveled children again
+    let mut nodes = placed.clone();
+    for node in plain.iter().filter(|n| !is_leaf(n.id)){
+        let spans: Vec<&ScheduledNode> = leaves_under(graph, &plain, node.id, &is_leaf).iter().filter_map(|l| placed.get(l)).collect();
+        let (Some(first), Some(last)) = (
+            spans.iter().min_by(|a, b| a.start.cmp(&b.start).then_with(|| a.id.cmp(&b.id))),
+            spans.iter().max_by(|a, b| a.end.cmp(&b.end).then_with(|| b.id.cmp(&a.id))),
+        ) else {
+            continue;
+        };
+        nodes.insert(node.id, ScheduledNode{ id: node.id, start: first.start, end: last.end, driver: Some(last.id), cause: StartCause::Children{ first: first.id } });
+    }
+    let leveled = Schedule{ nodes, conflicts: plain.conflicts.clone() };
+
+    // Back from the leaf finishing last, along whatever held each one up
+    let mut steps = Vec::new();
+    let mut current = placed.values().max_by(|a, b| a.end.cmp(&b.end).then_with(|| b.id.cmp(&a.id)));
+    while let Some(node) = current{
+        let link = match &node.cause{
+            StartCause::Predecessor{..} => ChainLink::Dependency,
+            StartCause::Resource{ owner, .. } => ChainLink::Resource(owner.clone()),
+            _ => ChainLink::Start,
+        };
+        steps.push(ChainStep{ id: node.id, start: node.start, end: node.end, link: link.clone() });
+        current = if link == ChainLink::Start { None } else { node.driver.and_then(|d| placed.get(&d)) };
+    }
+    steps.reverse();
+    if let Some(first) = steps.first_mut(){
+        first.link = ChainLink::Start;
+    }
+
+    let on_chain: HashSet<Uuid> = steps.iter().map(|s| s.id).collect();
+    let lengths: Vec<TimeDelta> = steps.iter().map(|s| s.end - s.start).collect();
+    let project_buffer = method.size(&lengths);
+
+    // Feeding chains: off-chain leaves a chain leaf depends on, followed back
+    // along their own drivers until they run out or reach the chain
+    let mut feeding_buffers = Vec::new();
+    for step in &steps{
+        let mut entries: Vec<Uuid> = depends_on[&step.id].iter().map(|(d, _)| *d).filter(|d| !on_chain.contains(d)).collect();
+        entries.sort();
+        entries.dedup();
+        for entry in entries{
+            let mut chain = vec![entry];
+            let mut cursor = placed[&entry].driver;
+            while let Some(id) = cursor.filter(|id| !on_chain.contains(id) && !chain.contains(id)){
+                chain.push(id);
+                cursor = placed.get(&id).and_then(|n| n.driver);
+            }
+            chain.reverse();
+            let lengths: Vec<TimeDelta> = chain.iter().map(|id| placed[id].end - placed[id].start).collect();
+            feeding_buffers.push(FeedingBuffer{ joins: step.id, size: method.size(&lengths), chain });
+        }
+    }
+
+    Ok(CriticalChain{ steps, leveled, project_buffer, feeding_buffers })
+}
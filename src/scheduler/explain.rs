@@ -0,0 +1,154 @@
+// Explain - why the scheduler put a node where it did
+//
+//   let why = explain(&graph, id)?;
+//   print!("{}", why.render_text(&graph));
+//
+// The start comes from the cause the forward pass recorded; the length from
+// the node's effort or its planned timeline. Days inside the span on which
+// the owner does not work (weekends, holidays, absences) are listed as gaps,
+// since effort-based work stretches across them.
+
+use super::{schedule, StartCause};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Constraint, Effort};
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LengthSource{
+    // Spread over the owner's working days at their allocation
+    Effort(Effort),
+    // The planned timeline's length
+    Timeline,
+    // A container spans its children
+    Children,
+}
+
+#[derive(Debug, Clone)]
+pub struct Explanation{
+    pub id: Uuid,
+    pub start: DT,
+    pub end: DT,
+    pub planned_start: Option<DT>,
+    pub cause: StartCause,
+    pub length: LengthSource,
+    // Days in [start, end) the owner (or, without one, everyone) has off
+    pub gaps: Vec<NaiveDate>,
+    // For a start pushed by a predecessor: the chain of predecessors that
+    // drove each other, nearest first
+    pub chain: Vec<Uuid>,
+}
+
+pub fn explain(graph: &ProjectGraph, id: Uuid) -> Result<Explanation,&'static str>{
+    let node = graph.get_node(id).ok_or("The node does not exist in the graph")?;
+    let scheduled = schedule(graph)?;
+    let this = scheduled.get(id).ok_or("The node has no timeline, so the scheduler leaves it out")?;
+
+    let length = if matches!(this.cause, StartCause::Children{..}){
+        LengthSource::Children
+    }else{
+        match graph.get_effort(id){
+            Some(effort) => LengthSource::Effort(effort),
+            None => LengthSource::Timeline,
+        }
+    };
+
+    let gaps = if length == LengthSource::Children{
+        Vec::new()
+    }else{
+        let end = this.end.date_naive();
+        this.start.date_naive().iter_days()
+            .take_while(|d| *d < end)
+            .filter(|d| !graph.is_available_on(node.get_owner(), *d))
+            .collect()
+    };
+
+    let mut chain = Vec::new();
+    let mut current = this;
+    while let StartCause::Predecessor{ id: pred, .. } = current.cause{
+        if chain.contains(&pred){
+            break;
+        }
+        chain.push(pred);
+        match scheduled.get(pred){
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+
+    Ok(Explanation{
+        id,
+        start: this.start,
+        end: this.end,
+        planned_start: node.get_timeline().map(|tl| tl.start),
+        cause: this.cause.clone(),
+        length,
+        gaps,
+        chain,
+    })
+}
+
+impl Explanation{
+    // Whether the scheduler moved the node off its planned start
+    pub fn is_moved(&self) -> bool{
+        self.planned_start.is_some_and(|p| p != self.start)
+    }
+
+    pub fn render_text(&self, graph: &ProjectGraph) -> String{
+        let label = |id: Uuid| {
+            let name = graph.get_node(id).map(|n| n.get_name()).unwrap_or("?");
+            match graph.get_key(id){
+                Some(key) => format!("{} {}", key, name),
+                None => name.to_string(),
+            }
+        };
+        let date = |d: DT| d.format("%Y-%m-%d %H:%M").to_string();
+
+        let mut out = format!("{}\n  scheduled {} to {}\n", label(self.id), date(self.start), date(self.end));
+        if let Some(planned) = self.planned_start.filter(|_| self.is_moved()){
+            out.push_str(&format!("  planned to start {}\n", date(planned)));
+        }
+        let why = match &self.cause{
+            StartCause::Planned => "starts on its planned date".to_string(),
+            StartCause::Constraint{ on, constraint } => {
+                let kind = match constraint{
+                    Constraint::MustStartOn(_) => "must start on",
+                    Constraint::StartNoEarlierThan(_) => "may not start before",
+                    Constraint::FinishNoLaterThan(_) => "must finish by",
+                };
+                let whose = if *on == self.id { String::new() } else { format!(" of {}", label(*on)) };
+                format!("held by a constraint{}: {} {}", whose, kind, constraint.earliest_start().map(date).unwrap_or_default())
+            }
+            StartCause::Remote{ on, remote, finish } => {
+                let whose = if *on == self.id { String::new() } else { format!(" (through {})", label(*on)) };
+                format!("waits for {} in {}{}, last known to finish {}", remote.node, remote.source, whose, date(*finish))
+            }
+            StartCause::Predecessor{ id, via, finish, lag } => {
+                let through = via.map(|v| format!(" (a dependency of {})", label(v))).unwrap_or_default();
+                let lag = if lag.is_zero() { String::new() } else { format!(" plus {}h lag", lag.num_hours()) };
+                format!("waits for {}{}, finishing {}{}", label(*id), through, date(*finish), lag)
+            }
+            StartCause::Children{ first } => format!("starts with its earliest child, {}", label(*first)),
+            StartCause::Resource{ owner, after } => format!("waits for {} to finish {}", owner, label(*after)),
+            StartCause::Away{ owner, back } => format!("{} is away until {}", owner, date(*back)),
+        };
+        out.push_str(&format!("  start: {}\n", why));
+        if self.chain.len() > 1{
+            let chain: Vec<String> = self.chain.iter().map(|id| label(*id)).collect();
+            out.push_str(&format!("  driving chain: {}\n", chain.join(" <- ")));
+        }
+        let length = match &self.length{
+            LengthSource::Effort(e) => format!("{} hours of effort at {}% allocation", e.hours, e.allocation),
+            LengthSource::Timeline => "the planned timeline".to_string(),
+            LengthSource::Children => "its children".to_string(),
+        };
+        out.push_str(&format!("  length: {}\n", length));
+        if !self.gaps.is_empty(){
+            let gaps: Vec<String> = self.gaps.iter().map(|d| d.format("%a %Y-%m-%d").to_string()).collect();
+            out.push_str(&format!("  days off inside the span: {}\n", gaps.join(", ")));
+        }
+        out
+    }
+}
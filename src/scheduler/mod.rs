@@ -0,0 +1,328 @@
+// Scheduler - forward pass over the dependency graph
+//
+// Leaves keep their planned duration and start no earlier than their planned
+// start or the end of any predecessor (plus lag) of theirs or of an ancestor.
+// Containers (anything with Contains children) span their scheduled children.
+// Start constraints (MSO/SNET) on a node or its ancestors act as a lower bound
+// on leaf starts; constraints that cannot be met are reported as conflicts.
+// Remote dependencies bound them too, by the last known remote finish.
+// Leaves with effort take as long as the effort needs at their owner's
+// allocation and their team's working day, over the days the owner works,
+// instead of their planned length. Leaves without effort start on their
+// owner's first day back from time off and stretch over days off in between.
+
+mod chain;
+mod explain;
+
+pub use chain::{critical_chain, BufferMethod, ChainLink, ChainStep, CriticalChain, FeedingBuffer};
+pub use explain::{explain, Explanation, LengthSource};
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Constraint, RemoteRef, Timeline};
+use crate::trace::{self, Level};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone)]
+pub struct ScheduledNode{
+    pub id: Uuid,
+    pub start: DT,
+    pub end: DT,
+    // For a leaf, the predecessor that pushed its start past the planned date;
+    // for a container, the child that finishes last
+    pub driver: Option<Uuid>,
+    // What decided the start
+    pub cause: StartCause,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StartCause{
+    // The node's own planned start
+    Planned,
+    // A start constraint on the node or, when `on` differs, on an ancestor
+    Constraint{ on: Uuid, constraint: Constraint },
+    // The last known finish of a node in another file, which `on` waits for
+    Remote{ on: Uuid, remote: RemoteRef, finish: DT },
+    // The finish of a predecessor plus lag; `via` is the ancestor the
+    // dependency is attached to, when it is not the node itself
+    Predecessor{ id: Uuid, via: Option<Uuid>, finish: DT, lag: TimeDelta },
+    // A container starts with its earliest child
+    Children{ first: Uuid },
+    // The owner was busy with `after` until then; only in leveled
+    // schedules, see critical_chain
+    Resource{ owner: String, after: Uuid },
+    // The owner is away on the planned start and back on `back`
+    Away{ owner: String, back: DT },
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduleConflict{
+    pub node: Uuid,
+    pub constraint: Constraint,
+    pub start: DT,
+    pub end: DT,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Schedule{
+    nodes: HashMap<Uuid,ScheduledNode>,
+    conflicts: Vec<ScheduleConflict>,
+}
+
+impl Schedule{
+    pub fn get(&self, id: Uuid) -> Option<&ScheduledNode>{
+        self.nodes.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ScheduledNode>{
+        self.nodes.values()
+    }
+
+    pub fn timeline(&self, id: Uuid) -> Option<Timeline>{
+        self.nodes.get(&id).map(|n| Timeline::from_start_end(n.start, n.end))
+    }
+
+    pub fn end(&self) -> Option<DT>{
+        self.nodes.values().map(|n| n.end).max()
+    }
+
+    // Constraints the dependencies made impossible to honor
+    pub fn conflicts(&self) -> &[ScheduleConflict]{
+        &self.conflicts
+    }
+
+    pub fn is_feasible(&self) -> bool{
+        self.conflicts.is_empty()
+    }
+}
+
+pub fn schedule(graph: &ProjectGraph) -> Result<Schedule,&'static str>{
+    schedule_with(graph, &|_, planned| planned)
+}
+
+// Same as schedule, with each leaf's planned duration passed through
+// `duration` first; used by simulations that perturb estimates
+pub fn schedule_with(graph: &ProjectGraph, duration: &dyn Fn(Uuid, TimeDelta) -> TimeDelta) -> Result<Schedule,&'static str>{
+    let mut span = trace::span(module_path!(), "schedule", &[("nodes", &graph.nodes().count())]);
+    let mut scheduler = Scheduler{ graph, duration, computed: HashMap::new(), visiting: HashSet::new() };
+    for node in graph.nodes(){
+        if let Err(e) = scheduler.visit(node.get_id()){
+            trace::event(Level::Error, module_path!(), e, &[("node", &node.get_id())]);
+            return Err(e);
+        }
+    }
+
+    let nodes: HashMap<Uuid,ScheduledNode> = scheduler.computed.into_iter()
+        .filter_map(|(id, n)| n.map(|n| (id, n)))
+        .collect();
+
+    let mut conflicts: Vec<ScheduleConflict> = nodes.values()
+        .filter_map(|n| {
+            let constraint = graph.get_constraint(n.id)?;
+            // Only the date the constraint is about is checked, so a container
+            // whose children moved its other end is not in conflict
+            (!constraint.is_satisfied(n.start, n.end))
+                .then_some(ScheduleConflict{ node: n.id, constraint, start: n.start, end: n.end })
+        })
+        .collect();
+    conflicts.sort_by_key(|c| (c.start, c.node));
+    span.record("scheduled", &nodes.len());
+    span.record("conflicts", &conflicts.len());
+
+    Ok(Schedule{ nodes, conflicts })
+}
+
+// The scheduled span of a node and everything it Contains, lag included
+pub fn rollup_timeline(graph: &ProjectGraph, id: Uuid) -> Result<Option<Timeline>,&'static str>{
+    Ok(schedule(graph)?.timeline(id))
+}
+
+// Leaves on the longest driving chain, from first to last
+pub fn critical_path(graph: &ProjectGraph, schedule: &Schedule) -> Vec<Uuid>{
+    let is_leaf = |id: Uuid| graph.get_children(id).iter().all(|c| schedule.get(*c).is_none());
+
+    // The leaf scan walks every node's children, the costly part on big graphs
+    #[cfg(feature = "parallel")]
+    let leaves: Vec<&ScheduledNode> = schedule.nodes.par_iter().map(|(_, n)| n).filter(|n| is_leaf(n.id)).collect();
+    #[cfg(not(feature = "parallel"))]
+    let leaves: Vec<&ScheduledNode> = schedule.iter().filter(|n| is_leaf(n.id)).collect();
+
+    let Some(last) = leaves.into_iter()
+        .max_by(|a, b| a.end.cmp(&b.end).then_with(|| b.id.cmp(&a.id)))
+    else {
+        return Vec::new();
+    };
+
+    let mut path = vec![last.id];
+    let mut current = last.driver;
+    while let Some(id) = current{
+        let Some(node) = schedule.get(id) else {
+            break;
+        };
+        if is_leaf(id){
+            path.push(id);
+        }
+        current = node.driver;
+    }
+
+    path.reverse();
+    path
+}
+
+struct Scheduler<'a>{
+    graph: &'a ProjectGraph,
+    duration: &'a dyn Fn(Uuid, TimeDelta) -> TimeDelta,
+    computed: HashMap<Uuid,Option<ScheduledNode>>,
+    visiting: HashSet<Uuid>,
+}
+
+impl Scheduler<'_>{
+    fn visit(&mut self, id: Uuid) -> Result<Option<(DT,DT)>,&'static str>{
+        if let Some(done) = self.computed.get(&id){
+            return Ok(done.as_ref().map(|n| (n.start, n.end)));
+        }
+        if !self.visiting.insert(id){
+            return Err("Dependencies are unsatisfiable: a node ends up waiting on itself");
+        }
+
+        let mut scheduled = None;
+        let mut children = Vec::new();
+        for child in self.graph.get_children(id){
+            if let Some((start, end)) = self.visit(child)?{
+                children.push((child, start, end));
+            }
+        }
+
+        if !children.is_empty(){
+            let (first, start, _) = *children.iter()
+                .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)))
+                .expect("children is not empty");
+            let (driver, _, end) = *children.iter()
+                .max_by(|a, b| a.2.cmp(&b.2).then_with(|| b.0.cmp(&a.0)))
+                .expect("children is not empty");
+            scheduled = Some(ScheduledNode{ id, start, end, driver: Some(driver), cause: StartCause::Children{ first } });
+        }else if let Some(tl) = self.graph.get_node(id).and_then(|n| n.get_timeline()){
+            let (mut start, mut cause) = match self.graph.get_constraint(id){
+                Some(constraint @ Constraint::MustStartOn(date)) => (date, StartCause::Constraint{ on: id, constraint }),
+                _ => (tl.start, StartCause::Planned),
+            };
+            let mut driver = None;
+
+            let mut sources = vec![id];
+            sources.extend(self.graph.get_ancestors(id));
+            for source in &sources{
+                let constraint = self.graph.get_constraint(*source)
+                    .and_then(|c| c.earliest_start().map(|bound| (bound, StartCause::Constraint{ on: *source, constraint: c })));
+                let remote = self.graph.remote_dependencies_of(*source)
+                    .filter_map(|d| d.earliest_start().map(|bound| (bound, StartCause::Remote{ on: *source, remote: d.remote.clone(), finish: bound })));
+                for (bound, why) in constraint.into_iter().chain(remote){
+                    if bound > start{
+                        start = bound;
+                        cause = why;
+                        driver = None;
+                    }
+                }
+            }
+            for source in sources{
+                for (pred, lag) in self.graph.get_predecessors(source){
+                    if let Some((_, pred_end)) = self.visit(pred)?{
+                        if pred_end + lag > start{
+                            start = pred_end + lag;
+                            cause = StartCause::Predecessor{ id: pred, via: (source != id).then_some(source), finish: pred_end, lag };
+                            driver = Some(pred);
+                        }
+                    }
+                }
+            }
+
+            // Effort spread over the owner's working days wins over the timeline's
+            // length; without an end, a planned duration counts in working hours
+            let effort = self.graph.effort_span(id, start);
+            let planned = match (effort, tl.end, &tl.duration){
+                (Some(span), _, _) => span,
+                (None, Some(end), _) => end - tl.start,
+                (None, None, Some(duration)) => self.graph.get_settings().span_of(duration),
+                (None, None, None) => TimeDelta::zero(),
+            };
+            let mut duration = (self.duration)(id, planned);
+            // Effort already skips the owner's days off; dated work moves off them
+            if effort.is_none(){
+                let pinned = matches!(cause, StartCause::Constraint{ on, constraint: Constraint::MustStartOn(_) } if on == id);
+                let (back, length) = self.graph.absence_span(id, start, duration, pinned);
+                if back != start{
+                    let owner = self.graph.get_node(id).and_then(|n| n.get_owner()).unwrap_or_default().to_string();
+                    cause = StartCause::Away{ owner, back };
+                    driver = None;
+                }
+                (start, duration) = (back, length);
+            }
+            scheduled = Some(ScheduledNode{ id, start, end: start + duration, driver, cause });
+        }
+
+        self.visiting.remove(&id);
+        let span = scheduled.as_ref().map(|n| (n.start, n.end));
+        self.computed.insert(id, scheduled);
+        Ok(span)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use crate::core::graph::DependencyType;
+    use crate::core::{NodeBuilder, Person, Unavailability, UnavailabilityKind};
+    use chrono::TimeZone;
+
+    fn day(month: u32, day: u32) -> DT{
+        Utc.with_ymd_and_hms(2026, month, day, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn containers_whose_own_plan_breaks_a_constraint_are_in_conflict(){
+        let mut graph = ProjectGraph::new();
+        let timeline = Timeline::from_start_end(day(1, 1), day(3, 31));
+        let epic = NodeBuilder::new().with_id(Uuid::from_u128(1)).with_name("Epic".to_string()).with_timeline(timeline.clone()).build_epic().unwrap();
+        let story = NodeBuilder::new().with_id(Uuid::from_u128(2)).with_name("Story".to_string()).with_timeline(timeline).build_userstory().unwrap();
+        graph.add_node(&epic).unwrap();
+        graph.add_node(&story).unwrap();
+        graph.connect(epic.get_id(), story.get_id(), DependencyType::Contains).unwrap();
+
+        graph.set_constraint(epic.get_id(), Constraint::FinishNoLaterThan(day(2, 28))).unwrap();
+        let conflicts = schedule(&graph).unwrap().conflicts().to_vec();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].node, epic.get_id());
+
+        graph.set_constraint(epic.get_id(), Constraint::FinishNoLaterThan(day(3, 31))).unwrap();
+        assert!(schedule(&graph).unwrap().is_feasible());
+    }
+
+    #[test]
+    fn dated_work_moves_off_its_owners_time_off(){
+        let mut graph = ProjectGraph::new();
+        graph.add_person(Person::new("alice".to_string())).unwrap();
+        let march = Unavailability::new(day(3, 1).date_naive(), day(3, 31).date_naive(), UnavailabilityKind::Vacation).unwrap();
+        graph.add_unavailability("alice", march).unwrap();
+        let task = NodeBuilder::new().with_id(Uuid::from_u128(1)).with_name("Task".to_string())
+            .with_timeline(Timeline::from_start_end(day(3, 2), day(3, 6))).with_owner("alice".to_string())
+            .build_tasks().unwrap();
+        graph.add_node(&task).unwrap();
+
+        let scheduled = schedule(&graph).unwrap().get(task.get_id()).cloned().unwrap();
+        assert_eq!((scheduled.start, scheduled.end), (day(4, 1), day(4, 7)));
+        assert!(matches!(scheduled.cause, StartCause::Away{ .. }));
+
+        // Held to its date, it keeps its start and stretches past her time off
+        graph.set_constraint(task.get_id(), Constraint::MustStartOn(day(3, 2))).unwrap();
+        let scheduled = schedule(&graph).unwrap().get(task.get_id()).cloned().unwrap();
+        assert_eq!((scheduled.start, scheduled.end), (day(3, 2), day(4, 7)));
+        let load = crate::analytics::daily_load(&graph, &crate::core::Scope::All).unwrap();
+        assert!(load.hours["alice"].keys().all(|d| *d >= day(4, 1).date_naive()));
+        assert_eq!(load.hours["alice"].values().sum::<f64>(), 32.0);
+    }
+}
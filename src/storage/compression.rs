@@ -0,0 +1,42 @@
+// Transparent gzip compression for project snapshots
+//
+// Files are compressed when saved under a `.gz` name and decompressed on load
+// whenever they start with the gzip magic bytes, whatever their name.
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub fn is_compressed(data: &[u8]) -> bool{
+    data.starts_with(&GZIP_MAGIC)
+}
+
+pub fn wants_compression(path: &Path) -> bool{
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+pub fn compress(data: &[u8]) -> Result<Vec<u8>>{
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>>{
+    let mut out = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut out).context("Failed to decompress gzip data")?;
+    Ok(out)
+}
+
+// Decompresses gzip data and passes anything else through untouched
+pub fn maybe_decompress(data: Vec<u8>) -> Result<Vec<u8>>{
+    if is_compressed(&data){
+        decompress(&data)
+    }else{
+        Ok(data)
+    }
+}
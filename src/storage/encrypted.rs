@@ -0,0 +1,93 @@
+// Encrypted project files (feature "encryption")
+//
+// Layout: MAGIC | salt (16 bytes) | nonce (12 bytes) | AES-256-GCM ciphertext.
+// The key is derived from a passphrase, or from the contents of a keyfile,
+// with Argon2id using the per-file random salt.
+
+use super::json::{from_json, to_json, JsonMode};
+use crate::core::graph::ProjectGraph;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8] = b"PMENC1\0";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone)]
+pub enum Secret{
+    Passphrase(String),
+    Keyfile(PathBuf),
+}
+
+impl Secret{
+    fn material(&self) -> Result<Vec<u8>>{
+        match self{
+            Secret::Passphrase(p) => Ok(p.as_bytes().to_vec()),
+            Secret::Keyfile(path) => fs::read(path)
+                .with_context(|| format!("Failed to read keyfile {}", path.display())),
+        }
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool{
+    data.starts_with(MAGIC)
+}
+
+pub fn encrypt(plaintext: &[u8], secret: &Secret) -> Result<Vec<u8>>{
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(secret, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher.encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt(data: &[u8], secret: &Secret) -> Result<Vec<u8>>{
+    if !is_encrypted(data){
+        bail!("Not an encrypted project file");
+    }
+    let body = &data[MAGIC.len()..];
+    if body.len() < SALT_LEN + NONCE_LEN{
+        bail!("Encrypted project file is truncated");
+    }
+
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(&derive_key(secret, salt)?);
+
+    cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Decryption failed: wrong passphrase/keyfile or corrupted file"))
+}
+
+pub fn save_encrypted(graph: &ProjectGraph, path: &Path, secret: &Secret) -> Result<()>{
+    let json = to_json(graph, JsonMode::Compact)?;
+    let data = encrypt(json.as_bytes(), secret)?;
+    fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn load_encrypted(path: &Path, secret: &Secret) -> Result<ProjectGraph>{
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let json = String::from_utf8(decrypt(&data, secret)?).context("Decrypted project is not UTF-8")?;
+    from_json(&json)
+}
+
+fn derive_key(secret: &Secret, salt: &[u8]) -> Result<Key<Aes256Gcm>>{
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(&secret.material()?, salt, &mut key)
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
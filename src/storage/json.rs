@@ -0,0 +1,78 @@
+// JSON persistence for ProjectGraph
+//
+// Canonical mode is meant for files kept in version control: object keys are
+// sorted, sets are emitted in order and edges are sorted by endpoints, so the
+// same graph always produces byte-identical output.
+
+use super::compression::{compress, maybe_decompress, wants_compression};
+use crate::core::graph::ProjectGraph;
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonMode{
+    Compact,
+    Pretty,
+    #[default]
+    Canonical,
+}
+
+pub fn to_json(graph: &ProjectGraph, mode: JsonMode) -> Result<String>{
+    let json = match mode{
+        JsonMode::Compact => serde_json::to_string(graph)?,
+        JsonMode::Pretty => serde_json::to_string_pretty(graph)?,
+        JsonMode::Canonical => {
+            let mut value = serde_json::to_value(graph)?;
+            sort_edges(&mut value);
+            to_canonical(&value)?
+        }
+    };
+    Ok(json)
+}
+
+// Pretty-printed with sorted keys; going through Value sorts every object
+pub(crate) fn to_canonical<T: Serialize>(value: &T) -> Result<String>{
+    let value = serde_json::to_value(value)?;
+    let mut json = serde_json::to_string_pretty(&value)?;
+    json.push('\n');
+    Ok(json)
+}
+
+pub fn from_json(json: &str) -> Result<ProjectGraph>{
+    let graph: ProjectGraph = serde_json::from_str(json).context("Failed to parse project graph")?;
+    graph.check_workflows().map_err(|e| anyhow!(e))?;
+    Ok(graph)
+}
+
+// Saving to a `.gz` path compresses the output
+pub fn save(graph: &ProjectGraph, path: &Path, mode: JsonMode) -> Result<()>{
+    let json = to_json(graph, mode)?;
+    let data = if wants_compression(path){
+        compress(json.as_bytes())?
+    }else{
+        json.into_bytes()
+    };
+    fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+// Gzip-compressed files are detected by their magic bytes
+pub fn load(path: &Path) -> Result<ProjectGraph>{
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let json = String::from_utf8(maybe_decompress(data)?).context("Project file is not UTF-8")?;
+    from_json(&json).with_context(|| format!("Invalid project file {}", path.display()))
+}
+
+// Edges are saved as {from, to, kind, lag}; order them by endpoints
+fn sort_edges(value: &mut Value){
+    let Some(Value::Array(edges)) = value.pointer_mut("/graph/edges") else {
+        return;
+    };
+
+    edges.sort_by(|a, b| {
+        let key = |e: &Value| (e["from"].as_str().map(str::to_string), e["to"].as_str().map(str::to_string), e["kind"].to_string());
+        key(a).cmp(&key(b))
+    });
+}
@@ -0,0 +1,118 @@
+// Streaming JSON Lines format for very large graphs
+//
+// One record per line: the graph-level data first, then every node, then
+// every edge. Records are written and read one at a time, so neither side
+// ever holds the whole document as a single string or JSON value.
+
+use super::compression::{is_compressed, wants_compression};
+use super::multifile::EdgeRecord;
+use crate::core::graph::{Dependency, ProjectGraph};
+use crate::core::Node;
+use anyhow::{anyhow, bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Serialize)]
+#[serde(tag = "record", content = "data", rename_all = "lowercase")]
+enum RecordRef<'a>{
+    Project(&'a ProjectGraph),
+    Node(&'a Node),
+    Edge(&'a EdgeRecord),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "record", content = "data", rename_all = "lowercase")]
+enum Record{
+    Project(Box<ProjectGraph>),
+    Node(Box<Node>),
+    Edge(EdgeRecord),
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &RecordRef) -> Result<()>{
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+pub fn write_jsonl<W: Write>(graph: &ProjectGraph, mut writer: W) -> Result<()>{
+    write_record(&mut writer, &RecordRef::Project(&graph.metadata()))?;
+    for node in graph.nodes(){
+        write_record(&mut writer, &RecordRef::Node(node))?;
+    }
+    for (from, to, dep) in graph.edges(){
+        let edge = EdgeRecord{ from, to, kind: dep.kind, lag: dep.lag.clone() };
+        write_record(&mut writer, &RecordRef::Edge(&edge))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_jsonl<R: BufRead>(reader: R) -> Result<ProjectGraph>{
+    let mut graph: Option<ProjectGraph> = None;
+
+    for (number, line) in reader.lines().enumerate(){
+        let line = line?;
+        if line.trim().is_empty(){
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid record on line {}", number + 1))?;
+
+        match record{
+            Record::Project(project) => {
+                if graph.is_some(){
+                    bail!("Line {}: duplicate project record", number + 1);
+                }
+                graph = Some(*project);
+            }
+            Record::Node(node) => {
+                let graph = graph.as_mut().ok_or_else(|| anyhow!("Line {}: node before project record", number + 1))?;
+                graph.add_node(&node).map_err(|e| anyhow!("Line {}: node {}: {}", number + 1, node.get_id(), e))?;
+            }
+            Record::Edge(edge) => {
+                let graph = graph.as_mut().ok_or_else(|| anyhow!("Line {}: edge before project record", number + 1))?;
+                let dependency = Dependency{ kind: edge.kind, lag: edge.lag };
+                graph.connect_unchecked(edge.from, edge.to, dependency)
+                    .map_err(|e| anyhow!("Line {}: edge {} -> {}: {}", number + 1, edge.from, edge.to, e))?;
+            }
+        }
+    }
+
+    let mut graph = graph.ok_or_else(|| anyhow!("Missing project record"))?;
+    if !graph.is_acyclic(){
+        bail!("The dependencies contain a cycle");
+    }
+    graph.prune_keys();
+    graph.check_workflows().map_err(|e| anyhow!(e))?;
+    Ok(graph)
+}
+
+// Writes gzip-compressed output when the path ends in `.gz`
+pub fn save_jsonl(graph: &ProjectGraph, path: &Path) -> Result<()>{
+    let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let writer = BufWriter::new(file);
+    if wants_compression(path){
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        write_jsonl(graph, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    }else{
+        write_jsonl(graph, writer)
+    }
+}
+
+pub fn load_jsonl(path: &Path) -> Result<ProjectGraph>{
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let result = if is_compressed(reader.fill_buf()?){
+        read_jsonl(BufReader::new(GzDecoder::new(reader)))
+    }else{
+        read_jsonl(reader)
+    };
+    result.with_context(|| format!("Failed to load {}", path.display()))
+}
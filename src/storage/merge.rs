@@ -0,0 +1,311 @@
+// Structure-aware three-way merge of project graphs
+//
+// Graphs are compared as keyed documents (nodes by Uuid, edges by endpoints
+// and kind, graph-level data by field), so edits to different nodes or to
+// different fields of the same node merge automatically. Anything changed
+// differently on both sides is reported as a conflict and resolved to "ours"
+// in the merged graph (a value deleted on one side but edited on the other is
+// kept), as is an edge of theirs that would close a cycle with ours. Edges
+// left without a node and nodes given two parents are reported the same
+// way. The event log is the exception: both sides' events are kept.
+
+use super::multifile::{from_parts, to_parts, EdgeRecord, GraphParts};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Event, Node};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct MergeConflict{
+    // Location in the keyed document, e.g. ["nodes", "<uuid>", "Epic", "name"]
+    pub path: Vec<String>,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult{
+    pub graph: ProjectGraph,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+pub fn merge(base: &ProjectGraph, ours: &ProjectGraph, theirs: &ProjectGraph) -> Result<MergeResult>{
+    // Short keys are taken from our side rather than merged; nodes only added
+    // by them get fresh keys when the graph is rebuilt, so keys never collide
+    let mut base_doc = to_document(base)?;
+    let mut ours_doc = to_document(ours)?;
+    let mut theirs_doc = to_document(theirs)?;
+    let our_keys = take_keys(&mut ours_doc);
+    take_keys(&mut base_doc);
+    take_keys(&mut theirs_doc);
+    // The log is append-only, so both sides' events are kept
+    take_events(&mut base_doc);
+    let events = merge_events(take_events(&mut ours_doc), take_events(&mut theirs_doc));
+
+    let mut conflicts = Vec::new();
+    let mut merged = merge_value(&mut Vec::new(), Some(&base_doc), Some(&ours_doc), Some(&theirs_doc), &mut conflicts)
+        .unwrap_or(Value::Null);
+    if let (Some(Value::Object(project)), Some(keys)) = (merged.get_mut("project"), our_keys){
+        project.insert("keys".to_string(), keys);
+    }
+    if let Some(Value::Object(project)) = merged.get_mut("project"){
+        project.insert("events".to_string(), serde_json::json!({ "events": events }));
+    }
+    let docs = [&base_doc, &ours_doc, &theirs_doc];
+    drop_dangling_edges(&mut merged, docs, &mut conflicts);
+    keep_one_parent(&mut merged, docs, &mut conflicts);
+
+    let graph = match from_document(merged.clone()){
+        Ok(graph) => graph,
+        Err(_) => drop_their_edges(merged, &ours_doc, &mut conflicts)?,
+    };
+    Ok(MergeResult{ graph, conflicts })
+}
+
+fn take_events(doc: &mut Value) -> Vec<Event>{
+    doc.get_mut("project")
+        .and_then(|p| p.get_mut("events"))
+        .and_then(|e| e.get_mut("events"))
+        .map(Value::take)
+        .and_then(|events| serde_json::from_value(events).ok())
+        .unwrap_or_default()
+}
+
+// Our events keep their numbers; theirs that we lack are renumbered after
+// them when the log is loaded. Both sides share the events from before they
+// split, so an event is matched on everything but its number.
+fn merge_events(ours: Vec<Event>, theirs: Vec<Event>) -> Vec<Event>{
+    let unnumbered = |e: &Event| Event{ seq: 0, ..e.clone() };
+    let known: Vec<Event> = ours.iter().map(unnumbered).collect();
+    let mut events = ours;
+    events.extend(theirs.iter().map(unnumbered).filter(|e| !known.contains(e)));
+    events.sort_by_key(|e| e.at);
+    events
+}
+
+// The value at `path` in each of base, ours and theirs
+fn sides(docs: [&Value; 3], path: &[&str]) -> [Option<Value>; 3]{
+    docs.map(|doc| path.iter().try_fold(doc, |v, key| v.get(*key)).cloned())
+}
+
+// An edge whose node one side deleted while the other connected it cannot
+// be kept; it is dropped and reported
+fn drop_dangling_edges(merged: &mut Value, docs: [&Value; 3], conflicts: &mut Vec<MergeConflict>){
+    let nodes: BTreeSet<String> = merged.get("nodes").and_then(Value::as_object)
+        .map(|n| n.keys().cloned().collect())
+        .unwrap_or_default();
+    let Some(Value::Object(edges)) = merged.get_mut("edges") else {
+        return;
+    };
+    let endpoint = |edge: &Value, end: &str| edge.get(end).and_then(Value::as_str).is_some_and(|id| nodes.contains(id));
+    let dangling: Vec<String> = edges.iter()
+        .filter(|(_, edge)| !endpoint(edge, "from") || !endpoint(edge, "to"))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in dangling{
+        edges.remove(&key);
+        let [base, ours, theirs] = sides(docs, &["edges", &key]);
+        conflicts.push(MergeConflict{ path: vec!["edges".to_string(), key], base, ours, theirs });
+    }
+}
+
+// Both sides can move a node under different parents. Our parent is kept
+// (or, when we had none, the first of theirs) and the move reported.
+fn keep_one_parent(merged: &mut Value, docs: [&Value; 3], conflicts: &mut Vec<MergeConflict>){
+    let Some(Value::Object(edges)) = merged.get_mut("edges") else {
+        return;
+    };
+    let contains = |edge: &Value| edge.get("kind").and_then(Value::as_str) == Some("Contains");
+    let mut parents: BTreeMap<String,Vec<String>> = BTreeMap::new();
+    for (key, edge) in edges.iter().filter(|(_, e)| contains(e)){
+        if let Some(child) = edge.get("to").and_then(Value::as_str){
+            parents.entry(child.to_string()).or_default().push(key.clone());
+        }
+    }
+    let parent_in = |doc: &Value, child: &str| doc.get("edges").and_then(Value::as_object)
+        .and_then(|edges| edges.values().find(|e| contains(e) && e.get("to").and_then(Value::as_str) == Some(child)))
+        .and_then(|e| e.get("from").cloned());
+    for (child, keys) in parents.into_iter().filter(|(_, keys)| keys.len() > 1){
+        let ours_edges = docs[1].get("edges").and_then(Value::as_object);
+        let kept = keys.iter().find(|k| ours_edges.is_some_and(|o| o.contains_key(*k))).unwrap_or(&keys[0]);
+        for key in keys.iter().filter(|k| *k != kept){
+            edges.remove(key);
+        }
+        let [base, ours, theirs] = docs.map(|doc| parent_in(doc, &child));
+        conflicts.push(MergeConflict{ path: vec!["nodes".to_string(), child, "parent".to_string()], base, ours, theirs });
+    }
+}
+
+// Edges that only their side added can together with ours close a cycle
+// neither side had. Each one the merged graph cannot take is left out and
+// reported as a conflict, so the user decides which dependency to keep.
+fn drop_their_edges(mut merged: Value, ours: &Value, conflicts: &mut Vec<MergeConflict>) -> Result<ProjectGraph>{
+    let ours_edges = ours.get("edges").and_then(Value::as_object);
+    let Some(Value::Object(edges)) = merged.get_mut("edges") else {
+        return from_document(merged).context("The merged graph is invalid");
+    };
+    let added: Vec<String> = edges.keys().filter(|k| ours_edges.is_none_or(|o| !o.contains_key(*k))).cloned().collect();
+    let mut theirs: Vec<(String,Value)> = added.into_iter().filter_map(|k| edges.remove(&k).map(|v| (k, v))).collect();
+    theirs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut graph = from_document(merged.clone()).context("The merged graph is invalid")?;
+    for (key, edge) in theirs{
+        let mut trial = merged.clone();
+        if let Some(Value::Object(edges)) = trial.get_mut("edges"){
+            edges.insert(key.clone(), edge.clone());
+        }
+        match from_document(trial.clone()){
+            Ok(with_edge) => {
+                graph = with_edge;
+                merged = trial;
+            }
+            Err(_) => conflicts.push(MergeConflict{
+                path: vec!["edges".to_string(), key],
+                base: None,
+                ours: None,
+                theirs: Some(edge),
+            }),
+        }
+    }
+    Ok(graph)
+}
+
+impl MergeResult{
+    pub fn has_conflicts(&self) -> bool{
+        !self.conflicts.is_empty()
+    }
+
+    // Git-style conflict markers, one block per conflicting value
+    pub fn render_conflicts(&self) -> String{
+        let mut out = String::new();
+        for conflict in &self.conflicts{
+            let mut location = conflict.path.join("/");
+            if conflict.path.first().is_some_and(|p| p == "nodes"){
+                let key = conflict.path.get(1)
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                    .and_then(|id| self.graph.get_key(id));
+                if let Some(key) = key{
+                    location = format!("{} ({})", location, key);
+                }
+            }
+            if conflict.path.first().is_some_and(|p| p == "edges"){
+                let keys: Option<Vec<&str>> = conflict.path.get(1).and_then(|edge| edge.split('>').take(2)
+                    .map(|id| Uuid::parse_str(id).ok().and_then(|id| self.graph.get_key(id)))
+                    .collect());
+                if let Some(keys) = keys{
+                    location = format!("{} ({})", location, keys.join(" > "));
+                }
+            }
+
+            out.push_str(&format!("<<<<<<< ours {}\n{}\n", location, show(&conflict.ours)));
+            out.push_str(&format!("||||||| base\n{}\n", show(&conflict.base)));
+            out.push_str(&format!("=======\n{}\n>>>>>>> theirs\n", show(&conflict.theirs)));
+        }
+        out
+    }
+}
+
+fn show(value: &Option<Value>) -> String{
+    match value{
+        Some(v) => serde_json::to_string_pretty(v).unwrap_or_default(),
+        None => "(deleted)".to_string(),
+    }
+}
+
+fn take_keys(doc: &mut Value) -> Option<Value>{
+    doc.get_mut("project")?.as_object_mut()?.remove("keys")
+}
+
+fn edge_key(edge: &EdgeRecord) -> String{
+    format!("{}>{}>{:?}", edge.from, edge.to, edge.kind)
+}
+
+fn to_document(graph: &ProjectGraph) -> Result<Value>{
+    let parts = to_parts(graph)?;
+
+    let mut nodes = Map::new();
+    for node in &parts.nodes{
+        nodes.insert(node.get_id().to_string(), serde_json::to_value(node)?);
+    }
+    let mut edges = Map::new();
+    for edge in &parts.edges{
+        edges.insert(edge_key(edge), serde_json::to_value(edge)?);
+    }
+
+    let mut doc = Map::new();
+    doc.insert("project".to_string(), parts.project);
+    doc.insert("nodes".to_string(), Value::Object(nodes));
+    doc.insert("edges".to_string(), Value::Object(edges));
+    Ok(Value::Object(doc))
+}
+
+fn from_document(doc: Value) -> Result<ProjectGraph>{
+    let project = doc.get("project").cloned().unwrap_or(Value::Object(Map::new()));
+
+    let mut nodes = Vec::new();
+    if let Some(Value::Object(map)) = doc.get("nodes"){
+        for value in map.values(){
+            let node: Node = serde_json::from_value(value.clone())?;
+            nodes.push(node);
+        }
+    }
+
+    let mut edges = Vec::new();
+    if let Some(Value::Object(map)) = doc.get("edges"){
+        for value in map.values(){
+            let edge: EdgeRecord = serde_json::from_value(value.clone())?;
+            edges.push(edge);
+        }
+    }
+
+    from_parts(GraphParts{ project, nodes, edges })
+}
+
+fn merge_value(
+    path: &mut Vec<String>,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<Value>{
+    if ours == theirs{
+        return ours.cloned();
+    }
+    if ours == base{
+        return theirs.cloned();
+    }
+    if theirs == base{
+        return ours.cloned();
+    }
+
+    // Both sides changed: descend into objects, otherwise it's a conflict
+    let empty = Map::new();
+    let base_map = match base{
+        Some(Value::Object(m)) => Some(m),
+        None => Some(&empty),
+        _ => None,
+    };
+    if let (Some(b), Some(Value::Object(o)), Some(Value::Object(t))) = (base_map, ours, theirs){
+        let keys: BTreeSet<&String> = b.keys().chain(o.keys()).chain(t.keys()).collect();
+        let mut merged = Map::new();
+        for key in keys{
+            path.push(key.clone());
+            if let Some(v) = merge_value(path, b.get(key), o.get(key), t.get(key), conflicts){
+                merged.insert(key.clone(), v);
+            }
+            path.pop();
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push(MergeConflict{
+        path: path.clone(),
+        base: base.cloned(),
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    });
+    ours.or(theirs).cloned()
+}
@@ -1 +1,53 @@
+// Storage module - saving and loading project graphs
 
+pub mod audit;
+pub mod compression;
+#[cfg(feature = "encryption")]
+pub mod encrypted;
+pub mod json;
+pub mod jsonl;
+pub mod merge;
+pub mod multifile;
+pub mod portfolio;
+pub mod remote;
+mod sha256;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+
+pub use audit::{audit, AuditEntry, AuditTrail};
+pub use json::{load, save, to_json, JsonMode};
+pub use jsonl::{load_jsonl, save_jsonl};
+pub use merge::{merge, MergeConflict, MergeResult};
+pub use multifile::{load_dir, save_dir};
+pub use portfolio::{load_portfolio, save_portfolio, PortfolioManifest};
+pub use remote::resolve_remotes;
+
+use crate::core::graph::ProjectGraph;
+use std::path::Path;
+
+fn is_jsonl(path: &Path) -> bool{
+    let name = path.to_string_lossy().to_ascii_lowercase();
+    name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")
+}
+
+// Loads a single JSON file, a JSON Lines stream or a multi-file project directory
+pub fn open(path: &Path) -> anyhow::Result<ProjectGraph>{
+    if path.is_dir(){
+        load_dir(path)
+    }else if is_jsonl(path){
+        load_jsonl(path)
+    }else{
+        load(path)
+    }
+}
+
+// Writes back in the layout `path` already uses; new paths get a single canonical file
+pub fn write(graph: &ProjectGraph, path: &Path) -> anyhow::Result<()>{
+    if path.is_dir(){
+        save_dir(graph, path)
+    }else if is_jsonl(path){
+        save_jsonl(graph, path)
+    }else{
+        save(graph, path, JsonMode::Canonical)
+    }
+}
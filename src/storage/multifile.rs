@@ -0,0 +1,153 @@
+// Multi-file project layout for version control
+//
+//   <dir>/project.json     graph-level data (keys, releases, people, ...)
+//   <dir>/edges.json       every dependency, sorted, referenced by Uuid
+//   <dir>/nodes/<id>.json  one file per node
+//
+// Concurrent edits to different nodes touch different files, so they merge
+// cleanly instead of conflicting inside one large document.
+
+use super::json::to_canonical;
+use crate::core::graph::{Dependency, DependencyType, ProjectGraph};
+use crate::core::timeline::Duration;
+use crate::core::Node;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+const PROJECT_FILE: &str = "project.json";
+const EDGES_FILE: &str = "edges.json";
+const NODES_DIR: &str = "nodes";
+
+// A graph split into its keyed parts: graph-level data, nodes and edges.
+// This is both the on-disk layout and what the three-way merge operates on.
+#[derive(Debug, Clone)]
+pub struct GraphParts{
+    pub project: Value,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<EdgeRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeRecord{
+    pub from: Uuid,
+    pub to: Uuid,
+    pub kind: DependencyType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag: Option<Duration>,
+}
+
+impl EdgeRecord{
+    pub fn sort_key(&self) -> (Uuid, Uuid, String){
+        (self.from, self.to, format!("{:?}", self.kind))
+    }
+}
+
+pub fn to_parts(graph: &ProjectGraph) -> Result<GraphParts>{
+    let mut project = serde_json::to_value(graph.metadata())?;
+    if let Value::Object(fields) = &mut project{
+        fields.remove("graph");
+    }
+
+    let mut edges: Vec<EdgeRecord> = graph.edges()
+        .map(|(from, to, dep)| EdgeRecord{ from, to, kind: dep.kind, lag: dep.lag.clone() })
+        .collect();
+    edges.sort_by_key(|e| e.sort_key());
+
+    Ok(GraphParts{ project, nodes: graph.nodes().cloned().collect(), edges })
+}
+
+pub fn from_parts(parts: GraphParts) -> Result<ProjectGraph>{
+    // Start from an empty graph so fields missing from the project data get defaults
+    let mut value = serde_json::to_value(ProjectGraph::new())?;
+    let (Value::Object(base), Value::Object(fields)) = (&mut value, parts.project) else {
+        bail!("Project data must be a JSON object");
+    };
+    for (key, field) in fields{
+        if key != "graph" && key != "uid_to_index"{
+            base.insert(key, field);
+        }
+    }
+    let mut graph: ProjectGraph = serde_json::from_value(value)?;
+
+    for node in parts.nodes{
+        graph.add_node(&node).map_err(|e| anyhow!("Node {}: {}", node.get_id(), e))?;
+    }
+
+    for edge in parts.edges{
+        let dependency = Dependency{ kind: edge.kind, lag: edge.lag };
+        graph.connect_unchecked(edge.from, edge.to, dependency)
+            .map_err(|e| anyhow!("Edge {} -> {}: {}", edge.from, edge.to, e))?;
+    }
+    if !graph.is_acyclic(){
+        bail!("The dependencies contain a cycle");
+    }
+    graph.prune_keys();
+    graph.check_workflows().map_err(|e| anyhow!(e))?;
+    Ok(graph)
+}
+
+pub fn save_dir(graph: &ProjectGraph, dir: &Path) -> Result<()>{
+    let nodes_dir = dir.join(NODES_DIR);
+    fs::create_dir_all(&nodes_dir).with_context(|| format!("Failed to create {}", nodes_dir.display()))?;
+
+    let parts = to_parts(graph)?;
+    write(&dir.join(PROJECT_FILE), &to_canonical(&parts.project)?)?;
+    write(&dir.join(EDGES_FILE), &to_canonical(&parts.edges)?)?;
+
+    let mut written = HashSet::new();
+    for node in &parts.nodes{
+        let file_name = format!("{}.json", node.get_id());
+        write(&nodes_dir.join(&file_name), &to_canonical(node)?)?;
+        written.insert(file_name);
+    }
+
+    // Drop files of nodes that no longer exist
+    for entry in fs::read_dir(&nodes_dir)?{
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".json") && !written.contains(&name){
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+pub fn load_dir(dir: &Path) -> Result<ProjectGraph>{
+    let project: Value = serde_json::from_str(&read(&dir.join(PROJECT_FILE))?)
+        .with_context(|| format!("Invalid {}", PROJECT_FILE))?;
+
+    let nodes_dir = dir.join(NODES_DIR);
+    let mut files: Vec<_> = fs::read_dir(&nodes_dir)
+        .with_context(|| format!("Failed to read {}", nodes_dir.display()))?
+        .collect::<Result<_,_>>()?;
+    files.sort_by_key(|e| e.file_name());
+
+    let mut nodes = Vec::new();
+    for entry in files{
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json"){
+            continue;
+        }
+        let node: Node = serde_json::from_str(&read(&path)?)
+            .with_context(|| format!("Invalid node file {}", path.display()))?;
+        nodes.push(node);
+    }
+
+    let edges: Vec<EdgeRecord> = serde_json::from_str(&read(&dir.join(EDGES_FILE))?)
+        .with_context(|| format!("Invalid {}", EDGES_FILE))?;
+
+    from_parts(GraphParts{ project, nodes, edges })
+}
+
+fn write(path: &Path, contents: &str) -> Result<()>{
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn read(path: &Path) -> Result<String>{
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
@@ -0,0 +1,41 @@
+// Async storage for tokio-based callers (server, sync clients); syncs
+// themselves have async counterparts in sync/nonblocking.rs
+//
+// Loading and saving run on tokio's blocking pool, so file IO and the
+// (de)serialization of large graphs never stall the async workers. Every
+// layout `open`/`write` understand is supported.
+
+use crate::core::graph::ProjectGraph;
+use anyhow::{Context, Result};
+use std::ops::Deref;
+use std::path::PathBuf;
+use tokio::task::spawn_blocking;
+
+// Async counterpart of storage::open
+pub async fn open(path: impl Into<PathBuf>) -> Result<ProjectGraph>{
+    let path = path.into();
+    spawn_blocking(move || super::open(&path)).await.context("Storage task failed")?
+}
+
+// Async counterpart of storage::write. Takes the graph by owned handle (a
+// Snapshot, an Arc, ...) so writes can carry on while it is being saved.
+pub async fn write<G>(graph: G, path: impl Into<PathBuf>) -> Result<()>
+where
+    G: Deref<Target = ProjectGraph> + Send + 'static,
+{
+    let path = path.into();
+    spawn_blocking(move || super::write(&graph, &path)).await.context("Storage task failed")?
+}
+
+pub async fn load_jsonl(path: impl Into<PathBuf>) -> Result<ProjectGraph>{
+    let path = path.into();
+    spawn_blocking(move || super::load_jsonl(&path)).await.context("Storage task failed")?
+}
+
+pub async fn save_jsonl<G>(graph: G, path: impl Into<PathBuf>) -> Result<()>
+where
+    G: Deref<Target = ProjectGraph> + Send + 'static,
+{
+    let path = path.into();
+    spawn_blocking(move || super::save_jsonl(&graph, &path)).await.context("Storage task failed")?
+}
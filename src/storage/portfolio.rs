@@ -0,0 +1,86 @@
+// Portfolio manifests - a JSON file naming each project's file (relative to
+// the manifest) and the links between them:
+//
+//   { "name": "Platform", "projects": { "web": "web/project.json", "api": "api" },
+//     "links": [{ "from": { "project": "api", "node": "…" }, "to": { "project": "web", "node": "…" } }] }
+
+use super::json::to_canonical;
+use super::remote::remote_node;
+use crate::portfolio::{CrossLink, Portfolio};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortfolioManifest{
+    pub name: String,
+    pub projects: BTreeMap<String,PathBuf>,
+    #[serde(default)]
+    pub links: Vec<CrossLink>,
+}
+
+impl PortfolioManifest{
+    pub fn read(path: &Path) -> Result<Self>{
+        let text = fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Invalid portfolio manifest {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()>{
+        fs::write(path, to_canonical(self)?).with_context(|| format!("Cannot write {}", path.display()))
+    }
+
+    // Where a project's file is, resolved against the manifest's directory
+    pub fn project_path(&self, manifest: &Path, project: &str) -> Option<PathBuf>{
+        let relative = self.projects.get(project)?;
+        Some(manifest.parent().unwrap_or(Path::new("")).join(relative))
+    }
+}
+
+// Remote dependencies between projects of the portfolio are resolved
+// against the loaded projects; references to other files are left alone.
+// Links that no longer fit (a node was deleted, a project dropped, or they
+// close a cycle) don't stop the load; they come back with the reason.
+pub fn load_portfolio(path: &Path) -> Result<(Portfolio,Vec<(CrossLink,&'static str)>)>{
+    let manifest = PortfolioManifest::read(path)?;
+    let mut portfolio = Portfolio::new(&manifest.name);
+    for name in manifest.projects.keys(){
+        let file = manifest.project_path(path, name).expect("listed project");
+        let graph = super::open(&file).with_context(|| format!("Project {}", name))?;
+        portfolio.add_project(name, graph).map_err(|e| anyhow!("Project {}: {}", name, e))?;
+    }
+    let skipped = portfolio.link_all(manifest.links);
+
+    let now = Utc::now();
+    let mut states = Vec::new();
+    for (name, graph) in portfolio.projects(){
+        for dependency in graph.remote_dependencies(){
+            let Some(owner) = portfolio.project_of(dependency.remote.node) else {
+                continue;
+            };
+            let other = portfolio.project(owner).expect("owner was just found");
+            states.push((name.to_string(), dependency.remote.clone(), remote_node(other, &dependency.remote, now)));
+        }
+    }
+    for (name, remote, state) in states{
+        portfolio.project_mut(&name).expect("listed project").set_remote_state(&remote, state);
+    }
+    Ok((portfolio, skipped))
+}
+
+// Writes each project back to its own file and the links to the manifest
+pub fn save_portfolio(portfolio: &Portfolio, path: &Path) -> Result<()>{
+    let mut manifest = if path.exists() { PortfolioManifest::read(path)? } else { PortfolioManifest::default() };
+    manifest.name = portfolio.name.clone();
+    manifest.projects.retain(|name, _| portfolio.project(name).is_some());
+    for (name, graph) in portfolio.projects(){
+        let file = manifest.projects.entry(name.to_string())
+            .or_insert_with(|| PathBuf::from(format!("{}.json", name)))
+            .clone();
+        super::write(graph, &manifest.project_path(path, name).unwrap_or(file))?;
+    }
+    manifest.links = portfolio.links().to_vec();
+    manifest.write(path)
+}
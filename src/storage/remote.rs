@@ -0,0 +1,54 @@
+// Resolving remote dependencies - loads each referenced project file once
+// and records the current state of the remote nodes on the graph
+//
+// Sources are paths relative to the directory of the file holding the
+// graph, in any layout storage::open understands.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{RemoteNode, RemoteRef};
+use crate::scheduler::schedule;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+// Looks a node up in an already loaded graph
+pub fn remote_node(graph: &ProjectGraph, remote: &RemoteRef, now: DateTime<Utc>) -> Option<RemoteNode>{
+    let node = graph.get_node(remote.node)?;
+    let end = schedule(graph).ok()
+        .and_then(|s| s.timeline(remote.node))
+        .and_then(|t| t.end)
+        .or_else(|| node.get_timeline().and_then(|t| t.end));
+    Some(RemoteNode{
+        name: node.get_name().to_string(),
+        key: graph.get_key(remote.node).map(str::to_string),
+        status: node.get_status(),
+        end,
+        resolved_at: now,
+    })
+}
+
+// Refreshes every remote dependency of `graph`, whose file is `path`.
+// Returns the references that could not be resolved; a missing file is an
+// error, a missing node just leaves the reference unresolved.
+pub fn resolve_remotes(graph: &mut ProjectGraph, path: &Path) -> Result<Vec<RemoteRef>>{
+    let base = path.parent().unwrap_or(Path::new(""));
+    let mut by_source: BTreeMap<String,BTreeSet<RemoteRef>> = BTreeMap::new();
+    for dependency in graph.remote_dependencies(){
+        by_source.entry(dependency.remote.source.clone()).or_default().insert(dependency.remote.clone());
+    }
+
+    let now = Utc::now();
+    let mut unresolved = Vec::new();
+    for (source, remotes) in by_source{
+        let other = super::open(&base.join(&source)).map_err(|e| anyhow!("Remote source {}: {}", source, e))?;
+        for remote in remotes{
+            let state = remote_node(&other, &remote, now);
+            if state.is_none(){
+                unresolved.push(remote.clone());
+            }
+            graph.set_remote_state(&remote, state);
+        }
+    }
+    Ok(unresolved)
+}
@@ -0,0 +1,92 @@
+// Fields the sync engine reconciles and the conflicts it reports when both
+// sides changed one since the last sync
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Node;
+use super::source::RemoteItem;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field{
+    Name,
+    Status,
+    Owner,
+    Points,
+}
+
+impl Field{
+    pub const ALL: [Field; 4] = [Field::Name, Field::Status, Field::Owner, Field::Points];
+
+    pub fn name(&self) -> &'static str{
+        match self{
+            Field::Name => "name",
+            Field::Status => "status",
+            Field::Owner => "owner",
+            Field::Points => "points",
+        }
+    }
+
+    pub fn local(&self, node: &Node) -> Option<String>{
+        match self{
+            Field::Name => Some(node.get_name().to_string()),
+            Field::Status => node.get_status().map(|s| s.to_string()),
+            Field::Owner => node.get_owner().map(str::to_string),
+            // Unestimated reads as 0, which is how trackers report cleared points
+            Field::Points => Some(node.get_points().unwrap_or(0).to_string()),
+        }
+    }
+
+    // None when the tracker doesn't report the field, which leaves it alone
+    pub fn remote(&self, item: &RemoteItem) -> Option<String>{
+        match self{
+            Field::Name => Some(item.name.clone()),
+            Field::Status => item.status.map(|s| s.to_string()),
+            Field::Owner => item.owner.clone(),
+            Field::Points => item.points.map(|p| p.to_string()),
+        }
+    }
+
+    pub(crate) fn apply_remote(&self, graph: &mut ProjectGraph, id: Uuid, item: &RemoteItem) -> Result<(),&'static str>{
+        match self{
+            Field::Name => graph.set_name(id, &item.name),
+            Field::Status => graph.set_status(id, item.status.unwrap_or_default()),
+            Field::Owner => graph.set_owner(id, item.owner.as_deref().unwrap_or_default()),
+            Field::Points => match item.points.filter(|p| *p > 0){
+                Some(points) => {
+                    let points = graph.get_settings().point_scale.snap(points);
+                    graph.set_points(id, points)
+                }
+                None => graph.clear_points(id),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution{
+    KeepLocal,
+    TakeRemote,
+    // Leave both sides as they are; the conflict comes back next sync
+    Defer,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncConflict{
+    pub remote_id: String,
+    pub node: Uuid,
+    pub field: Field,
+    // Value at the last sync, if known
+    pub base: Option<String>,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub resolution: Resolution,
+}
+
+// A field changed only locally; a two-way connector should push it
+#[derive(Debug, Clone)]
+pub struct LocalChange{
+    pub remote_id: String,
+    pub node: Uuid,
+    pub field: Field,
+    pub value: Option<String>,
+}
@@ -0,0 +1,354 @@
+// Sync engine - pulls pages from a Source into the graph
+//
+// Every request goes through the rate limiter and is retried with backoff
+// on rate limiting and transient failures. Pages are applied one at a time
+// and the cursor is stored in the graph's SyncState after each, so a sync
+// that fails half-way resumes from the last applied page. A dry run
+// applies the same pages to a copy and only reports what would change,
+// conflicts included, without resolving any.
+//
+// For items seen before, each field is compared against the value both
+// sides agreed on at the last sync: remote-only edits are applied, local-only
+// edits are kept and reported for pushing, and edits on both sides become
+// conflicts handed to the resolver (deferred when there is none).
+
+use super::conflict::{Field, LocalChange, Resolution, SyncConflict};
+use super::source::{FetchError, Page, RemoteItem, RemoteKind, Source};
+use super::throttle::{RateLimiter, RetryPolicy};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Actor, ExternalRef, NodeBuilder, PointScale, Timeline};
+use crate::trace::{self, Level};
+use anyhow::{anyhow, bail, Result};
+use std::time::Duration;
+use uuid::Uuid;
+
+// Node id suffix for nodes created by a sync
+const NODE_ID: &[u8; 6] = b"pmsync";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeAction{
+    Create,
+    // Names of the fields that differ
+    Update(Vec<&'static str>),
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedChange{
+    pub remote_id: String,
+    pub node: Uuid,
+    pub name: String,
+    pub action: ChangeAction,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport{
+    pub changes: Vec<PlannedChange>,
+    pub pages: usize,
+    // False for a dry run
+    pub applied: bool,
+    // Items that were synced but could not be placed in the hierarchy
+    pub warnings: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    pub local_changes: Vec<LocalChange>,
+}
+
+impl SyncReport{
+    pub fn created(&self) -> impl Iterator<Item = &PlannedChange>{
+        self.changes.iter().filter(|c| c.action == ChangeAction::Create)
+    }
+
+    pub fn updated(&self) -> impl Iterator<Item = &PlannedChange>{
+        self.changes.iter().filter(|c| matches!(c.action, ChangeAction::Update(_)))
+    }
+}
+
+pub struct SyncEngine<S: Source>{
+    source: S,
+    limiter: RateLimiter,
+    retry: RetryPolicy,
+    // Where new items without a known parent are attached
+    root: Option<Uuid>,
+    sleep: Box<dyn FnMut(Duration) + Send>,
+    resolver: Option<Box<Resolver>>,
+}
+
+// Send so an engine can be handed to another thread, see nonblocking.rs
+type Resolver = dyn FnMut(&SyncConflict) -> Resolution + Send;
+
+impl<S: Source> SyncEngine<S>{
+    pub fn new(source: S) -> Self{
+        SyncEngine{
+            source,
+            limiter: RateLimiter::unlimited(),
+            retry: RetryPolicy::default(),
+            root: None,
+            sleep: Box::new(std::thread::sleep),
+            resolver: None,
+        }
+    }
+
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self{
+        self.limiter = limiter;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self{
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_root(mut self, root: Uuid) -> Self{
+        self.root = Some(root);
+        self
+    }
+
+    // Replaces thread::sleep, e.g. to run without real delays
+    pub fn with_sleeper(mut self, sleep: impl FnMut(Duration) + Send + 'static) -> Self{
+        self.sleep = Box::new(sleep);
+        self
+    }
+
+    // Decides conflicts as they are found, e.g. by asking the user
+    pub fn with_resolver(mut self, resolver: impl FnMut(&SyncConflict) -> Resolution + Send + 'static) -> Self{
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    pub fn source(&self) -> &S{
+        &self.source
+    }
+
+    fn fetch_page(&mut self, cursor: Option<&str>) -> Result<Page>{
+        let mut attempt = 1;
+        loop{
+            let wait = self.limiter.acquire();
+            if !wait.is_zero(){
+                (self.sleep)(wait);
+            }
+
+            let error = match self.source.fetch(cursor){
+                Ok(page) => return Ok(page),
+                Err(FetchError::Fatal(msg)) => {
+                    trace::event(Level::Error, module_path!(), "fetch failed", &[("system", &self.source.system()), ("error", &msg)]);
+                    bail!("{}: {}", self.source.system(), msg)
+                }
+                Err(e) => e,
+            };
+            if attempt >= self.retry.max_attempts{
+                trace::event(Level::Error, module_path!(), "giving up", &[("system", &self.source.system()), ("attempts", &attempt)]);
+                return Err(anyhow!(error).context(format!("{}: giving up after {} attempts", self.source.system(), attempt)));
+            }
+            let delay = match error{
+                FetchError::RateLimited(Some(wait)) => wait,
+                _ => self.retry.delay(attempt),
+            };
+            trace::event(Level::Warn, module_path!(), "retrying fetch", &[("system", &self.source.system()), ("attempt", &attempt), ("delay_ms", &delay.as_millis())]);
+            (self.sleep)(delay);
+            attempt += 1;
+        }
+    }
+
+    // Syncs every page after the stored cursor into `graph`
+    pub fn run(&mut self, graph: &mut ProjectGraph) -> Result<SyncReport>{
+        let mut report = self.pull(graph)?;
+        report.applied = true;
+        Ok(report)
+    }
+
+    // What `run` would change, leaving `graph` untouched; conflicts are
+    // reported as deferred rather than handed to the resolver
+    pub fn dry_run(&mut self, graph: &ProjectGraph) -> Result<SyncReport>{
+        let mut preview = graph.clone();
+        let resolver = self.resolver.take();
+        let report = self.pull(&mut preview);
+        self.resolver = resolver;
+        report
+    }
+
+    fn pull(&mut self, graph: &mut ProjectGraph) -> Result<SyncReport>{
+        let system = self.source.system().to_string();
+        let _as_source = Actor::integration(&system).enter();
+        let mut span = trace::span(module_path!(), "pull", &[("system", &system)]);
+        let mut report = SyncReport::default();
+        let mut cursor = graph.get_sync_state().cursor(&system).map(str::to_string);
+
+        loop{
+            let page = self.fetch_page(cursor.as_deref())?;
+            let mut pass = PagePass{ graph, system: &system, root: self.root, resolver: self.resolver.as_deref_mut(), report: &mut report };
+            if let Err(e) = pass.apply(&page){
+                trace::event(Level::Error, module_path!(), "page not applied", &[("system", &system), ("page", &(report.pages + 1)), ("error", &e)]);
+                return Err(e);
+            }
+            report.pages += 1;
+
+            // Past the last page, keep its cursor so the next sync re-reads
+            // it and picks up anything appended since
+            let Some(next) = page.next_cursor else {
+                graph.get_sync_state_mut().set_cursor(&system, cursor);
+                break;
+            };
+            graph.get_sync_state_mut().set_cursor(&system, Some(next.clone()));
+            cursor = Some(next);
+        }
+        span.record("pages", &report.pages);
+        span.record("changes", &report.changes.len());
+        span.record("conflicts", &report.conflicts.len());
+        Ok(report)
+    }
+}
+
+// Remote points are rounded up onto the project's scale, which the other
+// system need not share
+fn build_node(system: &str, item: &RemoteItem, id: Uuid, scale: &PointScale) -> Result<crate::core::Node>{
+    let start = item.start.unwrap_or(item.updated);
+    let timeline = Timeline::from_start_end(start, item.due.unwrap_or(start).max(start));
+    let mut external = ExternalRef::new(system, &item.remote_id);
+    external.url = item.url.clone();
+    let mut builder = NodeBuilder::new().with_id(id).with_name(item.name.clone()).with_timeline(timeline)
+        .with_external_ref(external);
+    if let Some(status) = item.status{
+        builder = builder.with_status(status);
+    }
+    if let Some(owner) = &item.owner{
+        builder = builder.with_owner(owner.clone());
+    }
+    if let Some(points) = item.points.filter(|p| *p > 0){
+        builder = builder.with_points(scale.snap(points));
+    }
+    let node = match item.kind{
+        RemoteKind::Epic => builder.build_epic(),
+        RemoteKind::Story => builder.build_userstory(),
+        RemoteKind::Task => builder.build_tasks(),
+    };
+    node.map_err(|e| anyhow!(e))
+}
+
+struct PagePass<'a>{
+    graph: &'a mut ProjectGraph,
+    system: &'a str,
+    root: Option<Uuid>,
+    resolver: Option<&'a mut Resolver>,
+    report: &'a mut SyncReport,
+}
+
+impl PagePass<'_>{
+    // Reconciles the item's fields with an existing node, returning those
+    // that took the remote value
+    fn update(&mut self, id: Uuid, item: &RemoteItem) -> Result<Vec<&'static str>>{
+        let mut changed = Vec::new();
+        for field in Field::ALL{
+            let Some(remote) = field.remote(item) else {
+                continue;
+            };
+            let node = self.graph.get_node(id).ok_or_else(|| anyhow!("Mapped node {} is missing", id))?;
+            let local = field.local(node);
+            let state = self.graph.get_sync_state();
+            let base = state.base_value(self.system, &item.remote_id, field.name()).map(|b| b.map(str::to_string));
+
+            if local.as_deref() == Some(remote.as_str()){
+                self.set_base(item, field, Some(remote));
+                continue;
+            }
+
+            let take_remote = match &base{
+                // Never synced this field: the tracker wins
+                None => true,
+                Some(base) if *base == local => true,
+                Some(base) if base.as_deref() == Some(remote.as_str()) => {
+                    self.report.local_changes.push(LocalChange{ remote_id: item.remote_id.clone(), node: id, field, value: local });
+                    false
+                }
+                Some(base) => {
+                    let mut conflict = SyncConflict{
+                        remote_id: item.remote_id.clone(), node: id, field,
+                        base: base.clone(), local: local.clone(), remote: Some(remote.clone()),
+                        resolution: Resolution::Defer,
+                    };
+                    if let Some(resolver) = self.resolver.as_mut(){
+                        conflict.resolution = resolver(&conflict);
+                    }
+                    let resolution = conflict.resolution;
+                    self.report.conflicts.push(conflict);
+
+                    match resolution{
+                        Resolution::TakeRemote => true,
+                        Resolution::KeepLocal => {
+                            // The remote value is now the agreed base, so the
+                            // local one shows up as a change to push
+                            self.set_base(item, field, Some(remote.clone()));
+                            self.report.local_changes.push(LocalChange{ remote_id: item.remote_id.clone(), node: id, field, value: local });
+                            false
+                        }
+                        Resolution::Defer => false,
+                    }
+                }
+            };
+
+            if take_remote{
+                field.apply_remote(self.graph, id, item).map_err(|e| anyhow!(e))?;
+                self.set_base(item, field, Some(remote));
+                changed.push(field.name());
+            }
+        }
+        Ok(changed)
+    }
+
+    fn refresh_url(&mut self, id: Uuid, item: &RemoteItem) -> Result<()>{
+        let Some(url) = &item.url else {
+            return Ok(());
+        };
+        let current = self.graph.get_node(id).and_then(|n| n.get_external_ref(self.system)).and_then(|r| r.url.as_ref());
+        if current != Some(url){
+            let external = ExternalRef::new(self.system, &item.remote_id).with_url(url.clone());
+            self.graph.add_external_ref(id, external).map_err(|e| anyhow!(e))?;
+        }
+        Ok(())
+    }
+
+    fn set_base(&mut self, item: &RemoteItem, field: Field, value: Option<String>){
+        self.graph.get_sync_state_mut().set_base_value(self.system, &item.remote_id, field.name(), value);
+    }
+
+    fn apply(&mut self, page: &Page) -> Result<()>{
+        let mut created = Vec::new();
+        for item in &page.items{
+            let mapped = self.graph.find_by_external(self.system, &item.remote_id);
+
+            let (node, action) = match mapped{
+                Some(id) => {
+                    self.refresh_url(id, item)?;
+                    let changed = self.update(id, item)?;
+                    if changed.is_empty(){
+                        continue;
+                    }
+                    (id, ChangeAction::Update(changed))
+                }
+                None => {
+                    let id = Uuid::now_v6(NODE_ID);
+                    self.graph.create_node(&build_node(self.system, item, id, &self.graph.get_settings().point_scale)?).map_err(|e| anyhow!(e))?;
+                    for field in Field::ALL{
+                        if let Some(value) = field.remote(item){
+                            self.set_base(item, field, Some(value));
+                        }
+                    }
+                    created.push((id, item));
+                    (id, ChangeAction::Create)
+                }
+            };
+            self.report.changes.push(PlannedChange{ remote_id: item.remote_id.clone(), node, name: item.name.clone(), action });
+        }
+
+        // Place new nodes once the whole page exists, so parents may come later in it
+        for (id, item) in created{
+            let parent = item.parent.as_deref()
+                .and_then(|p| self.graph.find_by_external(self.system, p))
+                .or(self.root);
+            if let Some(parent) = parent{
+                if let Err(e) = self.graph.connect(parent, id, DependencyType::Contains){
+                    self.report.warnings.push(format!("{} ({}): {}", item.name, item.remote_id, e));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+// GitHub pull requests - moving work along as the PRs linked to it are
+// opened, marked ready and merged
+//
+// A node links a PR with an external ref in the "github-pr" system
+// ("owner/repo#42"), or with any external ref whose url is a PR page. On
+// each run:
+//   draft PR                 the node moves to In Progress
+//   open PR                  the node moves to its workflow's review state
+//                            ("In Review", "Review", "Code Review"), or to
+//                            In Progress without one
+//   all merged or closed,    the node moves to Done
+//   at least one merged
+// Nodes only ever move forward, and a PR closed without merging moves nothing.
+//
+// GitHub allows 5000 requests an hour with a token and 60 without, so PR
+// states are cached in the sync state: merged and closed PRs are never
+// looked up again, open ones once `refresh` has passed, and a rate-limit
+// response ends the lookups with the rest left for the next run.
+
+use super::source::FetchError;
+use crate::core::graph::ProjectGraph;
+use crate::core::{Actor, CachedPull, ExternalRef, PullState, Status};
+use crate::publish::{Request, Response, Transport};
+use crate::trace::{self, Level};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+use uuid::Uuid;
+
+pub const SYSTEM: &str = "github-pr";
+
+const API: &str = "https://api.github.com/repos";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PullRef{
+    // "owner/repo"
+    pub repo: String,
+    pub number: u64,
+}
+
+impl PullRef{
+    // "owner/repo#42" or https://github.com/owner/repo/pull/42
+    pub fn parse(value: &str) -> Option<Self>{
+        let value = value.trim();
+        let (repo, number) = match value.split_once("github.com/"){
+            Some((_, path)) => {
+                let mut parts = path.split('/');
+                let (owner, repo) = (parts.next()?, parts.next()?);
+                if parts.next()? != "pull"{
+                    return None;
+                }
+                (format!("{}/{}", owner, repo), parts.next()?)
+            }
+            None => {
+                let (repo, number) = value.split_once('#')?;
+                (repo.to_string(), number)
+            }
+        };
+        let valid = repo.split('/').count() == 2 && repo.split('/').all(|p| !p.is_empty());
+        let number = number.split(['#', '?']).next()?.parse().ok()?;
+        valid.then_some(PullRef{ repo, number })
+    }
+
+    pub fn from_external(external: &ExternalRef) -> Option<Self>{
+        if external.system == SYSTEM{
+            return PullRef::parse(&external.key);
+        }
+        external.url.as_deref().and_then(PullRef::parse)
+    }
+
+    pub fn key(&self) -> String{
+        format!("{}#{}", self.repo, self.number)
+    }
+
+    pub fn url(&self) -> String{
+        format!("https://github.com/{}/pull/{}", self.repo, self.number)
+    }
+
+    pub fn external_ref(&self) -> ExternalRef{
+        ExternalRef::new(SYSTEM, &self.key()).with_url(self.url())
+    }
+}
+
+// Where pull request states come from; GitHubApi in production
+pub trait PullRequests{
+    fn state(&mut self, pull: &PullRef) -> Result<PullState,FetchError>;
+}
+
+pub struct GitHubApi<'a>{
+    transport: &'a mut dyn Transport,
+    // Without one only public repositories can be read, at 60 requests an hour
+    token: Option<String>,
+}
+
+impl<'a> GitHubApi<'a>{
+    pub fn new(transport: &'a mut dyn Transport, token: Option<String>) -> Self{
+        GitHubApi{ transport, token }
+    }
+}
+
+impl PullRequests for GitHubApi<'_>{
+    fn state(&mut self, pull: &PullRef) -> Result<PullState,FetchError>{
+        let mut request = Request::new("GET", format!("{}/{}/pulls/{}", API, pull.repo, pull.number))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = &self.token{
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+        let response = self.transport.send(&request).map_err(|e| FetchError::Transient(e.to_string()))?;
+        match response.status{
+            200 => {}
+            403 | 429 if rate_limited(&response) => return Err(FetchError::RateLimited(retry_after(&response))),
+            401 | 403 => return Err(FetchError::Fatal(format!("{}: GitHub refused access (HTTP {}); check the token and that it can read the repository", pull.key(), response.status))),
+            500..=599 => return Err(FetchError::Transient(format!("HTTP {}", response.status))),
+            status => return Err(FetchError::Fatal(format!("{}: HTTP {}", pull.key(), status))),
+        }
+        let body = &response.body;
+        Ok(if body["merged"].as_bool() == Some(true) || !body["merged_at"].is_null(){
+            PullState::Merged
+        }else if body["state"] == "closed"{
+            PullState::Closed
+        }else if body["draft"].as_bool() == Some(true){
+            PullState::Draft
+        }else{
+            PullState::Open
+        })
+    }
+}
+
+// GitHub answers 403 both for an exhausted rate limit and for a token that
+// may not read the repository; only the first says no requests are left or
+// when to retry, or mentions the rate limit in its message
+fn rate_limited(response: &Response) -> bool{
+    response.status == 429
+        || response.header("x-ratelimit-remaining") == Some("0")
+        || response.header("retry-after").is_some()
+        || response.body["message"].as_str().is_some_and(|m| m.to_ascii_lowercase().contains("rate limit"))
+}
+
+// From Retry-After, or else from when the rate limit window resets
+fn retry_after(response: &Response) -> Option<Duration>{
+    if let Some(seconds) = response.header("retry-after").and_then(|s| s.trim().parse().ok()){
+        return Some(Duration::from_secs(seconds));
+    }
+    let reset: i64 = response.header("x-ratelimit-reset")?.trim().parse().ok()?;
+    u64::try_from(reset - Utc::now().timestamp()).ok().map(Duration::from_secs)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullMove{
+    pub node: Uuid,
+    // The status or workflow state moved to
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PullReport{
+    pub fetched: usize,
+    // Answered from the cache
+    pub cached: usize,
+    // Not looked up after GitHub asked to slow down
+    pub deferred: usize,
+    pub moved: Vec<PullMove>,
+    pub errors: Vec<String>,
+}
+
+// Every pull request each node with a status links to
+pub fn linked_pulls(graph: &ProjectGraph) -> BTreeMap<Uuid,BTreeSet<PullRef>>{
+    graph.nodes()
+        .filter(|n| n.get_status().is_some())
+        .filter_map(|n| {
+            let pulls: BTreeSet<PullRef> = n.get_external_refs().iter().filter_map(PullRef::from_external).collect();
+            (!pulls.is_empty()).then_some((n.get_id(), pulls))
+        })
+        .collect()
+}
+
+fn is_review(name: &str) -> bool{
+    let name: String = name.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    matches!(name.as_str(), "inreview" | "review" | "codereview")
+}
+
+// How far along a node is: 0 not started, 1 in progress or blocked, 2 in
+// review, 3 done
+fn progress(graph: &ProjectGraph, id: Uuid) -> u8{
+    match graph.get_node(id).and_then(|n| n.get_status()){
+        Some(Status::Done) => 3,
+        _ if graph.get_state(id).is_some_and(|s| is_review(&s.name)) => 2,
+        Some(Status::InProgress | Status::Blocked) => 1,
+        _ => 0,
+    }
+}
+
+// Moves the node to `status`, through its workflow's state for it when it has
+// one; `review` picks the workflow's review state instead
+fn advance(graph: &mut ProjectGraph, id: Uuid, status: Status, review: bool, now: DateTime<Utc>) -> Result<String,&'static str>{
+    let state = graph.workflow_for(id).and_then(|w| {
+        let review_state = review.then(|| w.states().iter().find(|s| is_review(&s.name))).flatten();
+        review_state.or_else(|| w.default_state_for(status)).map(|s| s.name.clone())
+    });
+    match state{
+        Some(state) => {
+            graph.set_state_at(id, &state, now)?;
+            Ok(state)
+        }
+        None => {
+            graph.set_status_at(id, status, now)?;
+            Ok(status.to_string())
+        }
+    }
+}
+
+// Looks up the linked pull requests that are not cached, then moves the
+// nodes their states call for
+pub fn watch_pulls(graph: &mut ProjectGraph, source: &mut dyn PullRequests, refresh: TimeDelta, now: DateTime<Utc>) -> PullReport{
+    let _span = trace::span(module_path!(), "watch_pulls", &[]);
+    let _as_github = Actor::integration("github").enter();
+    let links = linked_pulls(graph);
+    let pulls: BTreeSet<&PullRef> = links.values().flatten().collect();
+    let mut report = PullReport::default();
+
+    let mut limited = false;
+    for pull in &pulls{
+        let key = pull.key();
+        let fresh = graph.get_sync_state().pull(&key).is_some_and(|c| c.state.is_final() || now - c.checked < refresh);
+        if fresh{
+            report.cached += 1;
+            continue;
+        }
+        if limited{
+            report.deferred += 1;
+            continue;
+        }
+        match source.state(pull){
+            Ok(state) => {
+                graph.get_sync_state_mut().set_pull(&key, Some(CachedPull{ state, checked: now }));
+                report.fetched += 1;
+            }
+            Err(FetchError::RateLimited(_)) => {
+                trace::event(Level::Warn, module_path!(), "rate limited", &[("pull", &key)]);
+                limited = true;
+                report.deferred += 1;
+            }
+            Err(e) => report.errors.push(format!("{}: {}", key, e)),
+        }
+    }
+    let linked: BTreeSet<String> = pulls.iter().map(|p| p.key()).collect();
+    for key in graph.get_sync_state().pull_keys().into_iter().filter(|k| !linked.contains(k)){
+        graph.get_sync_state_mut().set_pull(&key, None);
+    }
+
+    for (id, pulls) in links{
+        let states: Vec<PullState> = pulls.iter()
+            .filter_map(|p| graph.get_sync_state().pull(&p.key()).map(|c| c.state))
+            .collect();
+        // Only decide on the full picture
+        if states.len() < pulls.len(){
+            continue;
+        }
+        let (target, status, review) = if states.iter().all(PullState::is_final) && states.contains(&PullState::Merged){
+            (3, Status::Done, false)
+        }else if states.contains(&PullState::Open){
+            (2, Status::InProgress, true)
+        }else if states.contains(&PullState::Draft){
+            (1, Status::InProgress, false)
+        }else{
+            continue;
+        };
+        // With no review state to go to, review means In Progress
+        let can_review = graph.workflow_for(id).is_some_and(|w| w.states().iter().any(|s| is_review(&s.name)));
+        let target = if target == 2 && !can_review { 1 } else { target };
+        if progress(graph, id) >= target{
+            continue;
+        }
+        match advance(graph, id, status, review, now){
+            Ok(to) => report.moved.push(PullMove{ node: id, to }),
+            Err(e) => report.errors.push(format!("{}: {}", graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string()), e)),
+        }
+    }
+    report
+}
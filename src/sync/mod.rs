@@ -0,0 +1,16 @@
+// Sync module - shared machinery for pulling work from external trackers,
+// and for following the pull requests linked to work
+
+pub mod conflict;
+pub mod engine;
+pub mod github;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod source;
+pub mod throttle;
+
+pub use conflict::{Field, LocalChange, Resolution, SyncConflict};
+pub use engine::{ChangeAction, PlannedChange, SyncEngine, SyncReport};
+pub use github::{linked_pulls, watch_pulls, GitHubApi, PullMove, PullRef, PullReport, PullRequests};
+pub use source::{FetchError, Page, RemoteItem, RemoteKind, Source};
+pub use throttle::{RateLimiter, RetryPolicy};
@@ -0,0 +1,37 @@
+// Async syncs for tokio-based callers (server, schedulers)
+//
+// A sync is a run of blocking fetches with sleeps between retries, so the
+// whole pass runs on tokio's blocking pool, Source and all; any Source that
+// works with SyncEngine::run works here. The engine and graph are moved in
+// and handed back with the report, as the pass needs both exclusively.
+
+use super::engine::{SyncEngine, SyncReport};
+use super::source::Source;
+use crate::core::graph::ProjectGraph;
+use anyhow::{Context, Result};
+use tokio::task::spawn_blocking;
+
+// Async counterpart of SyncEngine::run. On failure the graph keeps every
+// page applied before it, as with the blocking run, but is dropped along
+// with the engine; reload it to resume from the stored cursor.
+pub async fn run<S>(mut engine: SyncEngine<S>, mut graph: ProjectGraph) -> Result<(SyncEngine<S>, ProjectGraph, SyncReport)>
+where
+    S: Source + Send + 'static,
+{
+    spawn_blocking(move || {
+        let report = engine.run(&mut graph)?;
+        Ok((engine, graph, report))
+    }).await.context("Sync task failed")?
+}
+
+// Async counterpart of SyncEngine::dry_run; the graph is only read, so it
+// can be a snapshot shared with other readers
+pub async fn dry_run<S>(mut engine: SyncEngine<S>, graph: std::sync::Arc<ProjectGraph>) -> Result<(SyncEngine<S>, SyncReport)>
+where
+    S: Source + Send + 'static,
+{
+    spawn_blocking(move || {
+        let report = engine.dry_run(&graph)?;
+        Ok((engine, report))
+    }).await.context("Sync task failed")?
+}
@@ -0,0 +1,69 @@
+// What an external tracker hands the sync engine: pages of items behind an
+// opaque cursor
+
+use crate::core::Status;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteKind{
+    Epic,
+    Story,
+    Task,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteItem{
+    pub remote_id: String,
+    pub kind: RemoteKind,
+    pub name: String,
+    // Link to the item in the tracker's UI
+    pub url: Option<String>,
+    pub status: Option<Status>,
+    pub owner: Option<String>,
+    pub points: Option<u32>,
+    // Remote id of the containing item, if any
+    pub parent: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub due: Option<DateTime<Utc>>,
+    pub updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Page{
+    pub items: Vec<RemoteItem>,
+    // None once the last page has been returned
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum FetchError{
+    // The tracker asked us to slow down, optionally saying for how long
+    RateLimited(Option<Duration>),
+    // Worth retrying: timeouts, 5xx responses, dropped connections
+    Transient(String),
+    // Retrying won't help: bad credentials, unknown project, ...
+    Fatal(String),
+}
+
+impl fmt::Display for FetchError{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            FetchError::RateLimited(Some(wait)) => write!(f, "rate limited, retry after {:?}", wait),
+            FetchError::RateLimited(None) => write!(f, "rate limited"),
+            FetchError::Transient(msg) => write!(f, "transient error: {}", msg),
+            FetchError::Fatal(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FetchError{}
+
+pub trait Source{
+    // Short name of the system, e.g. "jira"; keys cursors and id mappings
+    fn system(&self) -> &str;
+
+    // The page after `cursor`, or the first page when there is none
+    fn fetch(&mut self, cursor: Option<&str>) -> Result<Page,FetchError>;
+}
@@ -0,0 +1,57 @@
+// Request pacing for external APIs: a minimum spacing between calls and
+// exponential backoff between retries
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct RateLimiter{
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter{
+    pub fn per_second(requests: u32) -> Self{
+        RateLimiter{ interval: Duration::from_secs(1) / requests.max(1), last: None }
+    }
+
+    pub fn unlimited() -> Self{
+        RateLimiter{ interval: Duration::ZERO, last: None }
+    }
+
+    // How long to wait before the next request may go out; the request is
+    // counted as sent at the end of that wait
+    pub fn acquire(&mut self) -> Duration{
+        let now = Instant::now();
+        let wait = self.last
+            .map(|last| (last + self.interval).saturating_duration_since(now))
+            .unwrap_or(Duration::ZERO);
+        self.last = Some(now + wait);
+        wait
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy{
+    // Attempts per request, including the first
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy{
+    fn default() -> Self{
+        RetryPolicy{ max_attempts: 5, base_delay: Duration::from_millis(500), max_delay: Duration::from_secs(60) }
+    }
+}
+
+impl RetryPolicy{
+    pub fn none() -> Self{
+        RetryPolicy{ max_attempts: 1, ..RetryPolicy::default() }
+    }
+
+    // Delay before retry number `attempt` (1 for the first retry)
+    pub fn delay(&self, attempt: u32) -> Duration{
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
@@ -0,0 +1,91 @@
+// Invariants any ProjectGraph should satisfy, whatever sequence of
+// operations produced it. Each check returns a description of the first
+// violation found.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Query, Scope, Status};
+use crate::storage::json::{from_json, to_json, JsonMode};
+use crate::storage::jsonl::{read_jsonl, write_jsonl};
+use uuid::Uuid;
+
+pub fn check_acyclic(graph: &ProjectGraph) -> Result<(), String>{
+    if graph.is_acyclic(){
+        Ok(())
+    }else{
+        Err("the dependency graph contains a cycle".into())
+    }
+}
+
+// Cached rollups must match ones computed from scratch off the leaves
+pub fn check_rollups(graph: &ProjectGraph) -> Result<(), String>{
+    let mut fresh = graph.clone();
+    fresh.rebuild_caches();
+    for node in graph.nodes(){
+        let id = node.get_id();
+        let (cached, expected) = (graph.rollup(id), fresh.rollup(id));
+        if cached != expected{
+            return Err(format!("rollup of {} is {:?}, expected {:?}", id, cached, expected));
+        }
+    }
+    Ok(())
+}
+
+fn check_query(graph: &ProjectGraph, label: &str, query: Query, expected: impl Fn(&crate::core::Node) -> bool) -> Result<(), String>{
+    let mut scanned: Vec<Uuid> = graph.nodes().filter(|n| expected(n)).map(|n| n.get_id()).collect();
+    scanned.sort();
+    if query.ids() != scanned{
+        return Err(format!("index lookup by {} disagrees with a full scan", label));
+    }
+    Ok(())
+}
+
+// Index-backed queries must return what a full scan would
+pub fn check_indexes(graph: &ProjectGraph) -> Result<(), String>{
+    let mut owners: Vec<String> = graph.nodes().filter_map(|n| n.get_owner()).map(str::to_string).collect();
+    owners.sort();
+    owners.dedup();
+    for owner in &owners{
+        check_query(graph, &format!("owner {}", owner), graph.query().owner(owner), |n| n.get_owner() == Some(owner.as_str()))?;
+    }
+
+    for status in [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done]{
+        check_query(graph, &format!("status {}", status), graph.query().status(status), |n| n.get_status() == Some(status))?;
+    }
+
+    let mut tags: Vec<String> = graph.nodes().flat_map(|n| n.get_tags().iter().map(|t| t.to_string())).collect();
+    tags.sort();
+    tags.dedup();
+    for tag in &tags{
+        check_query(graph, &format!("tag {}", tag), graph.query().tag(tag), |n| n.has_tag(tag))?;
+    }
+    Ok(())
+}
+
+// Saving and loading through each text format must give the same graph
+pub fn check_round_trip(graph: &ProjectGraph) -> Result<(), String>{
+    let canonical = to_json(graph, JsonMode::Canonical).map_err(|e| e.to_string())?;
+
+    let from_json = from_json(&to_json(graph, JsonMode::Compact).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    if to_json(&from_json, JsonMode::Canonical).map_err(|e| e.to_string())? != canonical{
+        return Err("JSON round-trip changed the graph".into());
+    }
+
+    let mut lines = Vec::new();
+    write_jsonl(graph, &mut lines).map_err(|e| e.to_string())?;
+    let from_jsonl = read_jsonl(lines.as_slice()).map_err(|e| e.to_string())?;
+    if to_json(&from_jsonl, JsonMode::Canonical).map_err(|e| e.to_string())? != canonical{
+        return Err("JSON Lines round-trip changed the graph".into());
+    }
+
+    if from_json.nodes_in_scope(&Scope::All).len() != graph.nodes().count(){
+        return Err("round-trip lost nodes".into());
+    }
+    Ok(())
+}
+
+pub fn check_all(graph: &ProjectGraph) -> Result<(), String>{
+    check_acyclic(graph)?;
+    check_rollups(graph)?;
+    check_indexes(graph)?;
+    check_round_trip(graph)
+}
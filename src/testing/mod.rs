@@ -0,0 +1,9 @@
+// Testing module - proptest strategies for random valid graphs and checks
+// for the invariants every graph should keep. Enabled with the `testing`
+// feature so downstream crates can fuzz their own code against it.
+
+pub mod invariants;
+pub mod strategies;
+
+pub use invariants::{check_acyclic, check_all, check_indexes, check_rollups, check_round_trip};
+pub use strategies::{apply_op, arb_graph, arb_op, arb_ops, GraphOp};
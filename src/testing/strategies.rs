@@ -0,0 +1,156 @@
+// Strategies producing random but valid project graphs: one Project with
+// Epics, Stories and Tasks, random points/status/owners/tags, and Blocks
+// edges between Tasks wherever they don't close a cycle
+
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{NodeBuilder, Status, Timeline};
+use chrono::{TimeZone, TimeDelta, Utc};
+use proptest::prelude::*;
+use uuid::Uuid;
+
+const OWNERS: [&str; 4] = ["alice", "bob", "carol", "dave"];
+const TAGS: [&str; 4] = ["backend", "frontend", "infra", "qa"];
+
+#[derive(Debug, Clone)]
+struct TaskSpec{
+    points: Option<u32>,
+    status: Status,
+    owner: Option<usize>,
+    tag: Option<usize>,
+    start_day: i64,
+    length: i64,
+}
+
+type StorySpec = Vec<TaskSpec>;
+type EpicSpec = Vec<StorySpec>;
+
+pub fn arb_status() -> impl Strategy<Value = Status>{
+    prop_oneof![
+        Just(Status::NotStarted),
+        Just(Status::InProgress),
+        Just(Status::Blocked),
+        Just(Status::Done),
+    ]
+}
+
+fn arb_task() -> impl Strategy<Value = TaskSpec>{
+    (proptest::option::of(1u32..13), arb_status(), proptest::option::of(0..OWNERS.len()),
+        proptest::option::of(0..TAGS.len()), 0i64..60, 1i64..10)
+        .prop_map(|(points, status, owner, tag, start_day, length)| TaskSpec{ points, status, owner, tag, start_day, length })
+}
+
+fn arb_epic() -> impl Strategy<Value = EpicSpec>{
+    prop::collection::vec(prop::collection::vec(arb_task(), 0..5), 0..4)
+}
+
+// Graphs with up to `max_epics` Epics, up to 3 Stories each and up to 4
+// Tasks per Story, ids numbered from 1
+pub fn arb_graph(max_epics: usize) -> impl Strategy<Value = ProjectGraph>{
+    (prop::collection::vec(arb_epic(), 0..=max_epics), prop::collection::vec((any::<usize>(), any::<usize>()), 0..20))
+        .prop_map(|(epics, blocks)| build(&epics, &blocks))
+}
+
+fn build(epics: &[EpicSpec], blocks: &[(usize, usize)]) -> ProjectGraph{
+    let mut graph = ProjectGraph::new();
+    let mut next = 0u128;
+    let mut id = || { next += 1; Uuid::from_u128(next) };
+    let origin = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+    let span = |start: i64, length: i64| Timeline::from_start_end(origin + TimeDelta::days(start), origin + TimeDelta::days(start + length));
+
+    let project = id();
+    graph.add_node(&NodeBuilder::new().with_id(project).with_name("Generated".into()).build_project().expect("valid project")).expect("fresh id");
+
+    let mut tasks = Vec::new();
+    for (e, stories) in epics.iter().enumerate(){
+        let epic = id();
+        let node = NodeBuilder::new().with_id(epic).with_name(format!("Epic {}", e)).with_timeline(span(0, 1)).build_epic().expect("valid epic");
+        graph.add_node(&node).expect("fresh id");
+        graph.connect(project, epic, DependencyType::Contains).expect("project contains epic");
+
+        for (s, specs) in stories.iter().enumerate(){
+            let story = id();
+            let node = NodeBuilder::new().with_id(story).with_name(format!("Story {}.{}", e, s)).with_timeline(span(0, 1)).build_userstory().expect("valid story");
+            graph.add_node(&node).expect("fresh id");
+            graph.connect(epic, story, DependencyType::Contains).expect("epic contains story");
+
+            for (t, spec) in specs.iter().enumerate(){
+                let task = id();
+                let mut builder = NodeBuilder::new().with_id(task).with_name(format!("Task {}.{}.{}", e, s, t))
+                    .with_timeline(span(spec.start_day, spec.length)).with_status(spec.status);
+                if let Some(points) = spec.points{
+                    builder = builder.with_points(points);
+                }
+                if let Some(owner) = spec.owner{
+                    builder = builder.with_owner(OWNERS[owner].to_string());
+                }
+                if let Some(tag) = spec.tag{
+                    builder = builder.with_tag(TAGS[tag].to_string());
+                }
+                graph.add_node(&builder.build_tasks().expect("valid task")).expect("fresh id");
+                graph.connect(story, task, DependencyType::Contains).expect("story contains task");
+                tasks.push(task);
+            }
+        }
+    }
+
+    if !tasks.is_empty(){
+        for (a, b) in blocks{
+            let (from, to) = (tasks[a % tasks.len()], tasks[b % tasks.len()]);
+            // Self-loops and cycles are rejected, which is fine here
+            let _ = graph.connect(from, to, DependencyType::Blocks);
+        }
+    }
+    graph
+}
+
+// A single mutation, addressing nodes by their position in id order
+#[derive(Debug, Clone)]
+pub enum GraphOp{
+    SetStatus(usize, Status),
+    SetPoints(usize, u32),
+    SetOwner(usize, String),
+    AddTag(usize, String),
+    RemoveTag(usize, String),
+    Connect(usize, usize, DependencyType),
+}
+
+pub fn arb_op() -> impl Strategy<Value = GraphOp>{
+    let owner = (0..OWNERS.len()).prop_map(|i| OWNERS[i].to_string());
+    let tag = || (0..TAGS.len()).prop_map(|i| TAGS[i].to_string());
+    let kind = prop_oneof![
+        Just(DependencyType::Blocks),
+        Just(DependencyType::ResourcesRequiredFor),
+        Just(DependencyType::Contains),
+    ];
+    prop_oneof![
+        (any::<usize>(), arb_status()).prop_map(|(n, s)| GraphOp::SetStatus(n, s)),
+        (any::<usize>(), 0u32..20).prop_map(|(n, p)| GraphOp::SetPoints(n, p)),
+        (any::<usize>(), owner).prop_map(|(n, o)| GraphOp::SetOwner(n, o)),
+        (any::<usize>(), tag()).prop_map(|(n, t)| GraphOp::AddTag(n, t)),
+        (any::<usize>(), tag()).prop_map(|(n, t)| GraphOp::RemoveTag(n, t)),
+        (any::<usize>(), any::<usize>(), kind).prop_map(|(a, b, k)| GraphOp::Connect(a, b, k)),
+    ]
+}
+
+pub fn arb_ops(max: usize) -> impl Strategy<Value = Vec<GraphOp>>{
+    prop::collection::vec(arb_op(), 0..=max)
+}
+
+// Applies `op`, returning the graph's own error when it rejects it
+pub fn apply_op(graph: &mut ProjectGraph, op: &GraphOp) -> Result<(),&'static str>{
+    let mut ids: Vec<Uuid> = graph.nodes().map(|n| n.get_id()).collect();
+    if ids.is_empty(){
+        return Ok(());
+    }
+    ids.sort();
+    let pick = |i: &usize| ids[i % ids.len()];
+
+    match op{
+        GraphOp::SetStatus(n, status) => graph.set_status(pick(n), *status),
+        GraphOp::SetPoints(n, points) => graph.set_points(pick(n), *points),
+        GraphOp::SetOwner(n, owner) => graph.set_owner(pick(n), owner),
+        GraphOp::AddTag(n, tag) => graph.add_tag(pick(n), tag).map(|_| ()),
+        GraphOp::RemoveTag(n, tag) => graph.remove_tag(pick(n), tag).map(|_| ()),
+        GraphOp::Connect(a, b, kind) => graph.connect(pick(a), pick(b), *kind),
+    }
+}
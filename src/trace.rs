@@ -0,0 +1,146 @@
+// Tracing - spans around graph mutations, scheduling runs and syncs, and
+// events for what goes wrong in them, handed to a Tracer the embedder installs
+//
+// Without the `trace` feature every call here compiles to nothing. With it,
+// nothing is reported until a tracer is installed, once per process;
+// forwarding to the `tracing` crate, a log file or stderr is a few lines:
+//
+//   struct Stderr;
+//   impl Tracer for Stderr{
+//       fn span_closed(&self, span: &SpanRecord, elapsed: Duration){ eprintln!("{} took {:?}", span, elapsed); }
+//       fn event(&self, event: &TraceEvent){ eprintln!("{}", event); }
+//   }
+//   trace::set_tracer(Box::new(Stderr))?;
+
+use std::fmt::{self, Display};
+#[cfg(feature = "trace")]
+use std::sync::OnceLock;
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level{
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Display for Level{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        f.write_str(match self{
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        })
+    }
+}
+
+pub type Fields<'a> = &'a [(&'static str, &'a dyn Display)];
+
+fn write_fields(f: &mut fmt::Formatter<'_>, fields: &[(&'static str, String)]) -> fmt::Result{
+    for (key, value) in fields{
+        write!(f, " {}={}", key, value)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "trace")]
+fn owned(fields: Fields) -> Vec<(&'static str, String)>{
+    fields.iter().map(|(k, v)| (*k, v.to_string())).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanRecord{
+    // The module the span is in, e.g. "project_manager::scheduler"
+    pub target: &'static str,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Display for SpanRecord{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        write!(f, "{}::{}", self.target, self.name)?;
+        write_fields(f, &self.fields)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent{
+    pub level: Level,
+    pub target: &'static str,
+    pub message: String,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Display for TraceEvent{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        write!(f, "{} {}: {}", self.level, self.target, self.message)?;
+        write_fields(f, &self.fields)
+    }
+}
+
+pub trait Tracer: Send + Sync{
+    fn span_entered(&self, _span: &SpanRecord){}
+    // Called when the span's guard is dropped, fields recorded since entering included
+    fn span_closed(&self, span: &SpanRecord, elapsed: std::time::Duration);
+    fn event(&self, event: &TraceEvent);
+}
+
+#[cfg(feature = "trace")]
+static TRACER: OnceLock<Box<dyn Tracer>> = OnceLock::new();
+
+// Installs the process-wide tracer; there is no swapping it out afterwards
+#[cfg(feature = "trace")]
+pub fn set_tracer(tracer: Box<dyn Tracer>) -> Result<(),&'static str>{
+    TRACER.set(tracer).map_err(|_| "A tracer is already installed")
+}
+
+// Reports the span's duration to the tracer when dropped
+#[must_use = "the span closes as soon as it is dropped"]
+pub struct Span{
+    #[cfg(feature = "trace")]
+    open: Option<(SpanRecord, Instant)>,
+}
+
+impl Span{
+    // Adds a field known only part way through, such as a count of results
+    pub fn record(&mut self, _key: &'static str, _value: &dyn Display){
+        #[cfg(feature = "trace")]
+        if let Some((span, _)) = self.open.as_mut(){
+            span.fields.push((_key, _value.to_string()));
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Drop for Span{
+    fn drop(&mut self){
+        if let (Some((span, started)), Some(tracer)) = (self.open.take(), TRACER.get()){
+            tracer.span_closed(&span, started.elapsed());
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+pub fn span(target: &'static str, name: &'static str, fields: Fields) -> Span{
+    let open = TRACER.get().map(|tracer| {
+        let span = SpanRecord{ target, name, fields: owned(fields) };
+        tracer.span_entered(&span);
+        (span, Instant::now())
+    });
+    Span{ open }
+}
+
+#[cfg(not(feature = "trace"))]
+pub fn span(_target: &'static str, _name: &'static str, _fields: Fields) -> Span{
+    Span{}
+}
+
+pub fn event(_level: Level, _target: &'static str, _message: &str, _fields: Fields){
+    #[cfg(feature = "trace")]
+    if let Some(tracer) = TRACER.get(){
+        tracer.event(&TraceEvent{ level: _level, target: _target, message: _message.to_string(), fields: owned(_fields) });
+    }
+}
@@ -0,0 +1,142 @@
+// Board - work items as cards in status columns, split into swimlanes
+//
+// Columns are the node's workflow states when its project has a custom
+// workflow (see core::workflow), else the built-in statuses. Containers are
+// left out; their children are the cards.
+
+use super::swimlane::{lanes_for, Grouping, LaneKey};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Scope, Status};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct BoardCard{
+    pub id: Uuid,
+    pub key: Option<String>,
+    pub name: String,
+    pub owner: Option<String>,
+    pub points: Option<u32>,
+    // The points as the project's scale names them, e.g. "M"
+    pub size: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lane{
+    pub name: String,
+    // One list of cards per board column
+    pub cells: Vec<Vec<BoardCard>>,
+}
+
+impl Lane{
+    pub fn len(&self) -> usize{
+        self.cells.iter().map(|c| c.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Board{
+    pub grouping: Grouping,
+    pub columns: Vec<String>,
+    pub lanes: Vec<Lane>,
+}
+
+const STATUSES: [Status; 4] = [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done];
+
+fn column_of(graph: &ProjectGraph, node: &Node) -> Option<String>{
+    match graph.get_state(node.get_id()){
+        Some(state) => Some(state.name.clone()),
+        None => node.get_status().map(|s| s.to_string()),
+    }
+}
+
+// Built-in statuses first, then custom states in the order their workflows list them
+fn columns(graph: &ProjectGraph, cards: &[&Node]) -> Vec<String>{
+    let mut columns: Vec<String> = STATUSES.iter().map(|s| s.to_string()).collect();
+    for node in cards{
+        if let Some(workflow) = graph.workflow_for(node.get_id()){
+            for state in workflow.states(){
+                if !columns.contains(&state.name){
+                    columns.push(state.name.clone());
+                }
+            }
+        }
+    }
+    columns
+}
+
+pub fn board(graph: &ProjectGraph, scope: &Scope, grouping: Grouping) -> Board{
+    let cards: Vec<&Node> = graph.nodes_in_scope(scope).into_iter()
+        .filter(|n| n.get_status().is_some() && !matches!(n, Node::Project{..}))
+        .filter(|n| graph.get_children(n.get_id()).is_empty())
+        .collect();
+    let all_columns = columns(graph, &cards);
+
+    let mut lanes: BTreeMap<LaneKey,Vec<Vec<BoardCard>>> = BTreeMap::new();
+    for node in &cards{
+        let Some(column) = column_of(graph, node).and_then(|c| all_columns.iter().position(|x| *x == c)) else {
+            continue;
+        };
+        let card = BoardCard{
+            id: node.get_id(),
+            key: graph.get_key(node.get_id()).map(str::to_string),
+            name: node.get_name().to_string(),
+            owner: node.get_owner().map(str::to_string),
+            points: node.get_points(),
+            size: node.get_points().map(|p| graph.get_settings().point_scale.label(p)),
+        };
+        for lane in lanes_for(graph, node, grouping){
+            lanes.entry(lane).or_insert_with(|| vec![Vec::new(); all_columns.len()])[column].push(card.clone());
+        }
+    }
+
+    // Drop columns nobody uses, except the built-in ones
+    let used: Vec<bool> = (0..all_columns.len())
+        .map(|i| i < STATUSES.len() || lanes.values().any(|cells| !cells[i].is_empty()))
+        .collect();
+    let keep = |v: Vec<Vec<BoardCard>>| -> Vec<Vec<BoardCard>>{
+        v.into_iter().zip(&used).filter(|(_, u)| **u).map(|(mut c, _)| {
+            c.sort_by(|a, b| a.key.cmp(&b.key).then(a.name.cmp(&b.name)).then(a.id.cmp(&b.id)));
+            c
+        }).collect()
+    };
+    let columns = all_columns.into_iter().zip(&used).filter(|(_, u)| **u).map(|(c, _)| c).collect();
+    let lanes = lanes.into_iter().map(|(key, cells)| Lane{ name: key.name, cells: keep(cells) }).collect();
+    Board{ grouping, columns, lanes }
+}
+
+impl Board{
+    // One section per lane, one bullet list per non-empty column
+    pub fn render_markdown(&self) -> String{
+        let mut out = String::new();
+        for lane in &self.lanes{
+            out.push_str(&format!("## {} ({})\n\n", lane.name, lane.len()));
+            for (column, cards) in self.columns.iter().zip(&lane.cells){
+                if cards.is_empty(){
+                    continue;
+                }
+                out.push_str(&format!("**{}**\n", column));
+                for card in cards{
+                    out.push_str("- ");
+                    if let Some(key) = &card.key{
+                        out.push_str(&format!("{} ", key));
+                    }
+                    out.push_str(&card.name);
+                    if let Some(owner) = &card.owner{
+                        out.push_str(&format!(" @{}", owner));
+                    }
+                    if let Some(size) = &card.size{
+                        out.push_str(&format!(" [{}]", size));
+                    }
+                    out.push('\n');
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,316 @@
+// Project brief - one printable page on a project for a steering committee
+// packet: the objective (the project's description, and the key results its
+// work counts towards), the timeline against plan, milestones, the top risks,
+// who is on it and where it stands
+//
+// Written in the project's report language through the built-in templates
+// or a project's own (template.rs), as markdown, an HTML fragment or a
+// standalone page sized for printing to PDF.
+
+use super::report::RiskSummary;
+use super::template::{Template, TemplateError};
+use super::theme_context;
+use crate::analytics::health_score_at;
+use crate::core::graph::ProjectGraph;
+use crate::core::{Catalog, Node, Scope, Status, Theme};
+use crate::scheduler::schedule;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+// Fewer than the status report, to keep to one page
+const TOP_RISKS: usize = 3;
+
+pub const MARKDOWN_TEMPLATE: &str = include_str!("templates/brief.md");
+pub const HTML_TEMPLATE: &str = include_str!("templates/brief.html");
+pub const PAGE_TEMPLATE: &str = include_str!("templates/brief-page.html");
+
+// A release touching the project, or the planned end of one of its epics
+#[derive(Debug, Clone)]
+pub struct Milestone{
+    pub name: String,
+    pub date: DT,
+    // The release was cut, or the epic is Done
+    pub reached: bool,
+}
+
+// A key result the project or its work contributes to
+#[derive(Debug, Clone)]
+pub struct Goal{
+    pub objective: String,
+    pub key_result: String,
+    pub progress: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamMember{
+    pub name: String,
+    pub team: Option<String>,
+    pub open: usize,
+    pub done: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Brief{
+    pub project_id: Uuid,
+    pub title: String,
+    // The first paragraph of the project's description
+    pub objective: Option<String>,
+    pub goals: Vec<Goal>,
+    pub start: Option<DT>,
+    pub planned_end: Option<DT>,
+    // When the schedule has the project finishing
+    pub forecast_end: Option<DT>,
+    pub completion: f64,
+    pub status_counts: Vec<(Status, usize)>,
+    pub health: Option<f64>,
+    pub milestones: Vec<Milestone>,
+    pub risk_score: f64,
+    pub top_risks: Vec<RiskSummary>,
+    // People owning work in the project, by team then name
+    pub team: Vec<TeamMember>,
+    pub now: DT,
+    pub messages: Catalog,
+    pub theme: Theme,
+}
+
+const STATUSES: [Status; 4] = [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done];
+
+fn label(graph: &ProjectGraph, node: &Node) -> String{
+    match graph.get_key(node.get_id()){
+        Some(key) => format!("{} {}", key, node.get_name()),
+        None => node.get_name().to_string(),
+    }
+}
+
+fn milestones(graph: &ProjectGraph, subtree: &HashSet<Uuid>, work: &[&Node]) -> Vec<Milestone>{
+    let mut milestones: Vec<Milestone> = graph.releases()
+        .filter(|r| r.get_scope().iter().any(|i| subtree.contains(i)))
+        .map(|r| Milestone{ name: r.name.clone(), date: r.target_date, reached: r.cut_date.is_some() })
+        .collect();
+    milestones.extend(work.iter()
+        .filter(|n| matches!(n, Node::Epic{..}))
+        .filter_map(|epic| epic.get_timeline().and_then(|tl| tl.end).map(|end| Milestone{
+            name: label(graph, epic),
+            date: end,
+            reached: epic.is_done(),
+        })));
+    milestones.sort_by(|a, b| (a.date, &a.name).cmp(&(b.date, &b.name)));
+    milestones
+}
+
+fn goals(graph: &ProjectGraph, subtree: &HashSet<Uuid>) -> Vec<Goal>{
+    let mut goals: Vec<Goal> = graph.objectives()
+        .flat_map(|o| o.get_key_results().iter().map(move |kr| (o, kr)))
+        .filter(|(_, kr)| kr.get_contributions().keys().any(|id| subtree.contains(id)))
+        .map(|(o, kr)| Goal{
+            objective: o.title.clone(),
+            key_result: kr.title.clone(),
+            progress: graph.key_result_progress(kr.id).unwrap_or(0.0),
+        })
+        .collect();
+    goals.sort_by(|a, b| (&a.objective, &a.key_result).cmp(&(&b.objective, &b.key_result)));
+    goals
+}
+
+fn team(graph: &ProjectGraph, work: &[&Node]) -> Vec<TeamMember>{
+    let mut people: BTreeMap<&str,(usize, usize)> = BTreeMap::new();
+    let leaves = work.iter().filter(|n| graph.get_children(n.get_id()).is_empty());
+    for node in leaves{
+        if let Some(owner) = node.get_owner(){
+            let counts = people.entry(owner).or_default();
+            if node.is_done() { counts.1 += 1 } else { counts.0 += 1 }
+        }
+    }
+    let mut team: Vec<TeamMember> = people.into_iter()
+        .map(|(name, (open, done))| TeamMember{
+            name: name.to_string(),
+            team: graph.team_of(name).map(|t| t.name.clone()),
+            open,
+            done,
+        })
+        .collect();
+    team.sort_by(|a, b| (a.team.is_none(), &a.team, &a.name).cmp(&(b.team.is_none(), &b.team, &b.name)));
+    team
+}
+
+pub fn brief(graph: &ProjectGraph, project_id: Uuid, now: DT) -> Option<Brief>{
+    let project = graph.get_node(project_id)?;
+    if !matches!(project, Node::Project{..}){
+        return None;
+    }
+
+    let scope = Scope::Subtree(project_id);
+    let subtree: HashSet<Uuid> = graph.get_subtree(project_id).into_iter().collect();
+    let work: Vec<&Node> = graph.nodes_in_scope(&scope)
+        .into_iter()
+        .filter(|n| n.get_id() != project_id && n.get_status().is_some())
+        .collect();
+
+    let status_counts = STATUSES.into_iter()
+        .map(|s| (s, work.iter().filter(|n| n.get_status() == Some(s)).count()))
+        .collect();
+
+    let objective = graph.get_description(project_id)
+        .and_then(|d| d.split("\n\n").map(str::trim).find(|p| !p.is_empty()))
+        .map(str::to_string);
+
+    let scheduled = schedule(graph).ok().and_then(|s| s.get(project_id).cloned());
+    let timeline = project.get_timeline();
+
+    let top_risks = graph.risks_in_scope(&scope)
+        .into_iter()
+        .take(TOP_RISKS)
+        .map(|r| RiskSummary{
+            title: r.get_title().to_string(),
+            score: r.score(),
+            owner: r.get_owner().map(str::to_string),
+            mitigation: r.get_mitigation().map(str::to_string),
+        })
+        .collect();
+
+    Some(Brief{
+        project_id,
+        title: label(graph, project),
+        objective,
+        goals: goals(graph, &subtree),
+        start: timeline.map(|tl| tl.start).or(scheduled.as_ref().map(|s| s.start)),
+        planned_end: timeline.and_then(|tl| tl.end),
+        forecast_end: scheduled.map(|s| s.end),
+        completion: graph.subtree_completion(project_id).unwrap_or(0.0),
+        status_counts,
+        health: health_score_at(graph, project_id, now).ok().map(|h| h.score),
+        milestones: milestones(graph, &subtree, &work),
+        risk_score: graph.project_risk_score(project_id).unwrap_or(0.0),
+        top_risks,
+        team: team(graph, &work),
+        now,
+        messages: graph.get_settings().i18n.catalog(None),
+        theme: graph.get_settings().theme.clone(),
+    })
+}
+
+fn date(d: Option<DT>) -> String{
+    d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+impl Brief{
+    // Forecast against plan, in the report language; None without both dates
+    fn drift(&self) -> Option<String>{
+        let days = (self.forecast_end?.date_naive() - self.planned_end?.date_naive()).num_days();
+        Some(match days{
+            0 => self.messages.get("brief-on-plan").to_string(),
+            d if d > 0 => self.messages.format("brief-behind", &[("days", &d.to_string())]),
+            d => self.messages.format("brief-ahead", &[("days", &(-d).to_string())]),
+        })
+    }
+
+    fn rating(&self, score: f64) -> &str{
+        self.messages.get(match score{
+            s if s >= 75.0 => "brief-healthy",
+            s if s >= 50.0 => "brief-at-risk",
+            _ => "brief-critical",
+        })
+    }
+
+    // What templates see:
+    //   language, title, objective (null without a description), today
+    //   goals        [{objective, key_result, progress (0-100)}]
+    //   start, planned_end, forecast_end  dates, "-" when unknown
+    //   drift        forecast against plan ("12 days behind plan"); null
+    //                without both ends
+    //   late         whether the forecast is past the planned end
+    //   completion (0-100), health (0-100, null when it cannot be scored),
+    //   rating       the health in words
+    //   statuses     [{status, label, count, color}] in workflow order
+    //   milestones   [{name, date, reached, overdue, state}], by date
+    //   risk_score, top_risks  [{title, score, owner, mitigation}]
+    //   team         [{name, team, open, done}]
+    //   theme        {font, font_size, text, muted, line, critical, shade}
+    pub fn context(&self) -> Value{
+        let late = matches!((self.forecast_end, self.planned_end), (Some(f), Some(p)) if f.date_naive() > p.date_naive());
+        json!({
+            "language": self.messages.language,
+            "title": self.title,
+            "objective": self.objective,
+            "goals": self.goals.iter().map(|g| json!({
+                "objective": g.objective,
+                "key_result": g.key_result,
+                "progress": g.progress,
+            })).collect::<Vec<_>>(),
+            "today": self.now.format("%Y-%m-%d").to_string(),
+            "start": date(self.start),
+            "planned_end": date(self.planned_end),
+            "forecast_end": date(self.forecast_end),
+            "drift": self.drift(),
+            "late": late,
+            "completion": self.completion,
+            "health": self.health,
+            "rating": self.health.map(|h| self.rating(h)),
+            "statuses": self.status_counts.iter().map(|(status, count)| json!({
+                "status": status.to_string(),
+                "label": self.messages.status(*status),
+                "count": count,
+                "color": self.theme.status_fill(*status),
+            })).collect::<Vec<_>>(),
+            "milestones": self.milestones.iter().map(|m| {
+                let overdue = !m.reached && m.date < self.now;
+                json!({
+                    "name": m.name,
+                    "date": m.date.format("%Y-%m-%d").to_string(),
+                    "reached": m.reached,
+                    "overdue": overdue,
+                    "state": self.messages.get(match (m.reached, overdue){
+                        (true, _) => "brief-reached",
+                        (_, true) => "brief-overdue",
+                        _ => "brief-due",
+                    }),
+                })
+            }).collect::<Vec<_>>(),
+            "risk_score": self.risk_score,
+            "top_risks": self.top_risks.iter().map(|r| json!({
+                "title": r.title,
+                "score": r.score,
+                "owner": r.owner,
+                "mitigation": r.mitigation,
+            })).collect::<Vec<_>>(),
+            "team": self.team.iter().map(|p| json!({
+                "name": p.name,
+                "team": p.team,
+                "open": p.open,
+                "done": p.done,
+            })).collect::<Vec<_>>(),
+            "theme": theme_context(&self.theme),
+        })
+    }
+
+    pub fn render_with(&self, template: &Template, html: bool) -> Result<String,TemplateError>{
+        template.render(&self.context(), &self.messages, html)
+    }
+
+    pub fn render_markdown(&self) -> String{
+        let template = Template::parse(MARKDOWN_TEMPLATE).expect("the built-in brief template parses");
+        self.render_with(&template, false).expect("the built-in brief template renders")
+    }
+
+    pub fn render_html(&self) -> String{
+        let template = Template::parse(HTML_TEMPLATE).expect("the built-in brief template parses");
+        self.render_with(&template, true).expect("the built-in brief template renders")
+    }
+
+    // A standalone A4 page around `body`, the brief as an HTML fragment, to
+    // print or save as PDF from a browser
+    pub fn render_page(&self, body: &str) -> String{
+        let template = Template::parse(PAGE_TEMPLATE).expect("the brief page parses");
+        let context = json!({
+            "language": self.messages.language,
+            "title": self.messages.format("brief-title", &[("title", &self.title)]),
+            "body": body,
+            "theme": theme_context(&self.theme),
+        });
+        template.render(&context, &self.messages, true).expect("the brief page renders")
+    }
+}
@@ -0,0 +1,254 @@
+// Scenario comparison - two versions of a plan side by side for a decision meeting
+//
+// A scenario is a copy of the project file with changes applied (a person
+// added, scope cut, a date moved). Nodes are matched by id, so copying the
+// file keeps them comparable. Reported: when things end, what they cost, the
+// busiest day each owner has, and how the critical path changes.
+//
+// Money is in the baseline's currency and written for its locale, and the
+// text in its report language; the layout is a template (template.rs).
+
+use super::template::{Template, TemplateError};
+use super::theme_context;
+use crate::analytics::{cost, daily_load};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Catalog, MoneySettings, Node, Scope, Theme};
+use crate::scheduler::{critical_path, schedule};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+pub const MARKDOWN_TEMPLATE: &str = include_str!("templates/comparison.md");
+pub const HTML_TEMPLATE: &str = include_str!("templates/comparison.html");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Peak{
+    pub owner: String,
+    pub date: NaiveDate,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScenarioSummary{
+    pub name: String,
+    pub end: Option<DT>,
+    // Budget of the top-level Projects
+    pub budget: f64,
+    pub actual: f64,
+    // Each owner's busiest day
+    pub peaks: Vec<Peak>,
+    pub critical_path: Vec<String>,
+    pub conflicts: usize,
+}
+
+impl ScenarioSummary{
+    pub fn peak(&self) -> Option<&Peak>{
+        self.peaks.iter().max_by(|a, b| a.hours.total_cmp(&b.hours).then_with(|| b.owner.cmp(&a.owner)))
+    }
+
+    fn peak_of(&self, owner: &str) -> Option<&Peak>{
+        self.peaks.iter().find(|p| p.owner == owner)
+    }
+}
+
+// A Project or Epic whose end differs between the two
+#[derive(Debug, Clone)]
+pub struct EndChange{
+    pub id: Uuid,
+    pub label: String,
+    pub before: Option<DT>,
+    pub after: Option<DT>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison{
+    pub baseline: ScenarioSummary,
+    pub scenario: ScenarioSummary,
+    pub moved: Vec<EndChange>,
+    // Critical path entries only the scenario has, and only the baseline has
+    pub joined_path: Vec<String>,
+    pub left_path: Vec<String>,
+    // The baseline's, for the amounts above and the text
+    pub money: MoneySettings,
+    pub messages: Catalog,
+    pub theme: Theme,
+}
+
+fn label(graph: &ProjectGraph, id: Uuid) -> String{
+    let name = graph.get_node(id).map(|n| n.get_name()).unwrap_or("?");
+    match graph.get_key(id){
+        Some(key) => format!("{} {}", key, name),
+        None => name.to_string(),
+    }
+}
+
+fn summarize(graph: &ProjectGraph, name: &str) -> Result<(ScenarioSummary, Vec<Uuid>),&'static str>{
+    let scheduled = schedule(graph)?;
+    let path = critical_path(graph, &scheduled);
+    let costs = cost(graph, &Scope::All);
+    let top: Vec<_> = costs.rows.iter()
+        .filter(|r| graph.get_parent(r.id).is_none() && matches!(graph.get_node(r.id), Some(Node::Project{..})))
+        .collect();
+
+    let load = daily_load(graph, &Scope::All)?;
+    let peaks = load.hours.iter()
+        .filter_map(|(owner, days)| {
+            let (date, hours) = days.iter().max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))?;
+            Some(Peak{ owner: owner.clone(), date: *date, hours: *hours })
+        })
+        .collect();
+
+    let summary = ScenarioSummary{
+        name: name.to_string(),
+        end: scheduled.end(),
+        budget: top.iter().fold(0.0, |sum, r| sum + r.budget),
+        actual: top.iter().fold(0.0, |sum, r| sum + r.actual),
+        peaks,
+        critical_path: path.iter().map(|id| label(graph, *id)).collect(),
+        conflicts: scheduled.conflicts().len(),
+    };
+    Ok((summary, path))
+}
+
+pub fn compare(baseline: &ProjectGraph, scenario: &ProjectGraph, names: (&str, &str)) -> Result<Comparison,&'static str>{
+    let (before, before_path) = summarize(baseline, names.0)?;
+    let (mut after, after_path) = summarize(scenario, names.1)?;
+    let money = baseline.get_settings().money.clone();
+    let scenario_currency = &scenario.get_settings().money.currency;
+    if *scenario_currency != money.currency{
+        let convert = |amount| money.to_base(amount, scenario_currency).ok_or("The baseline has no exchange rate for the scenario's currency");
+        after.budget = convert(after.budget)?;
+        after.actual = convert(after.actual)?;
+    }
+    let (before_schedule, after_schedule) = (schedule(baseline)?, schedule(scenario)?);
+
+    let mut moved: Vec<EndChange> = baseline.nodes()
+        .chain(scenario.nodes().filter(|n| baseline.get_node(n.get_id()).is_none()))
+        .filter(|n| matches!(n, Node::Project{..} | Node::Epic{..}))
+        .filter_map(|n| {
+            let id = n.get_id();
+            let (old, new) = (before_schedule.get(id).map(|s| s.end), after_schedule.get(id).map(|s| s.end));
+            (old != new).then(|| EndChange{
+                id,
+                label: if scenario.get_node(id).is_some() { label(scenario, id) } else { label(baseline, id) },
+                before: old,
+                after: new,
+            })
+        })
+        .collect();
+    moved.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let joined_path = after_path.iter().filter(|id| !before_path.contains(id)).map(|id| label(scenario, *id)).collect();
+    let left_path = before_path.iter().filter(|id| !after_path.contains(id)).map(|id| label(baseline, *id)).collect();
+
+    let messages = baseline.get_settings().i18n.catalog(None);
+    let theme = baseline.get_settings().theme.clone();
+    Ok(Comparison{ baseline: before, scenario: after, moved, joined_path, left_path, money, messages, theme })
+}
+
+fn date(d: Option<DT>) -> String{
+    d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+fn days_between(messages: &Catalog, before: Option<DT>, after: Option<DT>) -> String{
+    match (before, after){
+        (Some(b), Some(a)) => messages.format("compare-days", &[("days", &format!("{:+.1}", (a - b).num_minutes() as f64 / 1440.0))]),
+        _ => "-".to_string(),
+    }
+}
+
+fn peak(p: Option<&Peak>) -> String{
+    p.map(|p| format!("{:.1}h ({}, {})", p.hours, p.owner, p.date)).unwrap_or_else(|| "-".to_string())
+}
+
+impl Comparison{
+    // Whether the scenario finishes later than the baseline
+    pub fn is_later(&self) -> bool{
+        matches!((self.baseline.end, self.scenario.end), (Some(b), Some(a)) if a > b)
+    }
+
+    // Summary rows: metric, baseline, scenario, change
+    pub fn summary_rows(&self) -> Vec<[String; 4]>{
+        let (b, s, t) = (&self.baseline, &self.scenario, &self.messages);
+        let money = |amount: f64| self.money.format(amount);
+        let change = |amount: f64| match amount > 0.0{
+            true => format!("+{}", money(amount)),
+            false => money(amount),
+        };
+        vec![
+            [t.get("compare-end-date").to_string(), date(b.end), date(s.end), days_between(t, b.end, s.end)],
+            [t.get("compare-budget").to_string(), money(b.budget), money(s.budget), change(s.budget - b.budget)],
+            [t.get("compare-spent").to_string(), money(b.actual), money(s.actual), change(s.actual - b.actual)],
+            [t.get("compare-peak").to_string(), peak(b.peak()), peak(s.peak()), String::new()],
+            [t.get("compare-conflicts").to_string(), b.conflicts.to_string(), s.conflicts.to_string(), format!("{:+}", s.conflicts as i64 - b.conflicts as i64)],
+        ]
+    }
+
+    // Per owner: busiest day in the baseline and in the scenario
+    fn peak_rows(&self) -> Vec<[String; 3]>{
+        let owners: BTreeSet<&str> = self.baseline.peaks.iter().chain(&self.scenario.peaks).map(|p| p.owner.as_str()).collect();
+        owners.into_iter()
+            .map(|o| {
+                let day = |p: Option<&Peak>| p.map(|p| format!("{:.1}h ({})", p.hours, p.date)).unwrap_or_else(|| "-".to_string());
+                [o.to_string(), day(self.baseline.peak_of(o)), day(self.scenario.peak_of(o))]
+            })
+            .collect()
+    }
+
+    // What templates see:
+    //   language
+    //   baseline, scenario  {name, end, budget, actual, conflicts, critical_path: []}
+    //   summary      [{metric, baseline, scenario, change}], formatted for the locale
+    //   moved        [{label, before, after, change}]
+    //   peaks        [{owner, baseline, scenario}]
+    //   joined_path, left_path  critical path entries only the scenario, or
+    //                only the baseline, has
+    //   theme        {font, font_size, text, muted, line, critical, shade}
+    pub fn context(&self) -> Value{
+        let side = |s: &ScenarioSummary| json!({
+            "name": s.name,
+            "end": date(s.end),
+            "budget": s.budget,
+            "actual": s.actual,
+            "conflicts": s.conflicts,
+            "critical_path": s.critical_path,
+        });
+        json!({
+            "language": self.messages.language,
+            "baseline": side(&self.baseline),
+            "scenario": side(&self.scenario),
+            "summary": self.summary_rows().into_iter().map(|[metric, baseline, scenario, change]| json!({
+                "metric": metric, "baseline": baseline, "scenario": scenario, "change": change,
+            })).collect::<Vec<_>>(),
+            "moved": self.moved.iter().map(|m| json!({
+                "label": m.label,
+                "before": date(m.before),
+                "after": date(m.after),
+                "change": days_between(&self.messages, m.before, m.after),
+            })).collect::<Vec<_>>(),
+            "peaks": self.peak_rows().into_iter().map(|[owner, baseline, scenario]| json!({
+                "owner": owner, "baseline": baseline, "scenario": scenario,
+            })).collect::<Vec<_>>(),
+            "joined_path": self.joined_path,
+            "left_path": self.left_path,
+            "theme": theme_context(&self.theme),
+        })
+    }
+
+    pub fn render_with(&self, template: &Template, html: bool) -> Result<String,TemplateError>{
+        template.render(&self.context(), &self.messages, html)
+    }
+
+    pub fn render_markdown(&self) -> String{
+        let template = Template::parse(MARKDOWN_TEMPLATE).expect("the built-in comparison template parses");
+        self.render_with(&template, false).expect("the built-in comparison template renders")
+    }
+
+    pub fn render_html(&self) -> String{
+        let template = Template::parse(HTML_TEMPLATE).expect("the built-in comparison template parses");
+        self.render_with(&template, true).expect("the built-in comparison template renders")
+    }
+}
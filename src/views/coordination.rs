@@ -0,0 +1,142 @@
+// Cross-team coordination - for one team or owner, the outside work their
+// items wait on and their items others wait on, grouped by the team on the
+// other side
+//
+// Only open dependencies count: the blocker and the blocked item are both
+// not done. Work owned by nobody, or by someone on no team, is grouped
+// under "no team".
+
+use super::escape_html;
+use crate::core::graph::{DependencyType, ProjectGraph};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Party{
+    Team(String),
+    Owner(String),
+}
+
+impl Party{
+    pub fn label(&self) -> String{
+        match self{
+            Party::Team(team) => format!("team {}", team),
+            Party::Owner(owner) => owner.clone(),
+        }
+    }
+}
+
+// One open dependency edge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handoff{
+    pub blocker: Uuid,
+    pub blocked: Uuid,
+    pub kind: DependencyType,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Counterpart{
+    // None for work on no team
+    pub team: Option<String>,
+    // Their items the party's work waits on
+    pub waiting_on: Vec<Handoff>,
+    // The party's items their work waits on
+    pub holding_up: Vec<Handoff>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Coordination{
+    pub party: Party,
+    // Teams by name, then work on no team
+    pub counterparts: Vec<Counterpart>,
+}
+
+impl Coordination{
+    pub fn waiting_on(&self) -> usize{
+        self.counterparts.iter().map(|c| c.waiting_on.len()).sum()
+    }
+
+    pub fn holding_up(&self) -> usize{
+        self.counterparts.iter().map(|c| c.holding_up.len()).sum()
+    }
+
+    pub fn render_markdown(&self, graph: &ProjectGraph) -> String{
+        let mut out = format!("# Coordination: {}\n\n", self.party.label());
+        out.push_str(&format!("Waiting on {} outside items, holding up {}\n", self.waiting_on(), self.holding_up()));
+        for counterpart in &self.counterparts{
+            out.push_str(&format!("\n## {}\n", counterpart.team.as_deref().unwrap_or("No team")));
+            for (heading, handoffs) in [("Waiting on", &counterpart.waiting_on), ("Holding up", &counterpart.holding_up)]{
+                if handoffs.is_empty(){
+                    continue;
+                }
+                out.push_str(&format!("\n{}:\n", heading));
+                for h in handoffs{
+                    out.push_str(&format!("- {} blocks {}\n", label(graph, h.blocker), label(graph, h.blocked)));
+                }
+            }
+        }
+        out
+    }
+
+    pub fn render_html(&self, graph: &ProjectGraph) -> String{
+        let mut out = format!("<div class=\"coordination\">\n<h1>Coordination: {}</h1>\n", escape_html(&self.party.label()));
+        for counterpart in &self.counterparts{
+            out.push_str(&format!("<h2>{}</h2>\n<table>\n<tr><th></th><th>Blocker</th><th>Blocked</th></tr>\n",
+                escape_html(counterpart.team.as_deref().unwrap_or("No team"))));
+            for (heading, handoffs) in [("Waiting on", &counterpart.waiting_on), ("Holding up", &counterpart.holding_up)]{
+                for h in handoffs{
+                    out.push_str(&format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        heading, escape_html(&label(graph, h.blocker)), escape_html(&label(graph, h.blocked))));
+                }
+            }
+            out.push_str("</table>\n");
+        }
+        out.push_str("</div>\n");
+        out
+    }
+}
+
+fn label(graph: &ProjectGraph, id: Uuid) -> String{
+    let node = graph.get_node(id);
+    let name = node.map(|n| n.get_name()).unwrap_or("?");
+    let owner = node.and_then(|n| n.get_owner()).map(|o| format!(" @{}", o)).unwrap_or_default();
+    match graph.get_key(id){
+        Some(key) => format!("{} {}{}", key, name, owner),
+        None => format!("{}{}", name, owner),
+    }
+}
+
+pub fn coordination(graph: &ProjectGraph, party: Party) -> Result<Coordination,&'static str>{
+    let members: HashSet<&str> = match &party{
+        Party::Team(name) => graph.get_team(name).ok_or("The team does not exist")?.members.iter().map(String::as_str).collect(),
+        Party::Owner(owner) => HashSet::from([owner.as_str()]),
+    };
+    let owner_of = |id: Uuid| graph.get_node(id).and_then(|n| n.get_owner());
+    let ours = |id: Uuid| owner_of(id).is_some_and(|o| members.contains(o));
+    let open = |id: Uuid| graph.get_node(id).is_some_and(|n| !n.is_done());
+    let team_of = |id: Uuid| owner_of(id).and_then(|o| graph.team_of(o)).map(|t| t.name.clone());
+
+    // Teams sort before None, so "no team" comes last
+    let mut groups: BTreeMap<(bool,Option<String>),Counterpart> = BTreeMap::new();
+    for (blocker, blocked, dependency) in graph.edges(){
+        if !dependency.kind.is_scheduling() || !open(blocker) || !open(blocked) || ours(blocker) == ours(blocked){
+            continue;
+        }
+        let handoff = Handoff{ blocker, blocked, kind: dependency.kind };
+        let team = team_of(if ours(blocked) { blocker } else { blocked });
+        let group = groups.entry((team.is_none(), team.clone())).or_insert_with(|| Counterpart{ team, ..Counterpart::default() });
+        if ours(blocked){
+            group.waiting_on.push(handoff);
+        }else{
+            group.holding_up.push(handoff);
+        }
+    }
+
+    let key = |id: Uuid| (graph.get_key(id).map(str::to_string), id);
+    let mut counterparts: Vec<Counterpart> = groups.into_values().collect();
+    for c in &mut counterparts{
+        c.waiting_on.sort_by_key(|h| (key(h.blocker), key(h.blocked)));
+        c.holding_up.sort_by_key(|h| (key(h.blocker), key(h.blocked)));
+    }
+    Ok(Coordination{ party, counterparts })
+}
@@ -0,0 +1,196 @@
+// Dependency structure matrix - the nodes of a scope on both axes, with a
+// mark where the row node depends on the column node (B for Blocks, R for
+// ResourcesRequiredFor)
+//
+// Rows follow the Contains hierarchy depth first, siblings by start date, so
+// well partitioned work shows up as dense blocks along the diagonal. A mark
+// above the diagonal is feedback: a node waiting on something listed after
+// it, which usually points at a sequencing problem. Containment itself is
+// not marked; the ordering already shows it.
+
+use super::{escape_csv, escape_html};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::Scope;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct DsmEntry{
+    pub id: Uuid,
+    pub label: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Dsm{
+    pub entries: Vec<DsmEntry>,
+    // (row, column) -> kinds of the dependencies of row on column
+    pub marks: BTreeMap<(usize,usize),Vec<DependencyType>>,
+}
+
+fn marker(kind: DependencyType) -> &'static str{
+    match kind{
+        DependencyType::Blocks => "B",
+        DependencyType::ResourcesRequiredFor => "R",
+        DependencyType::Contains => "C",
+        DependencyType::SpecifiedBy => "S",
+    }
+}
+
+pub fn dsm(graph: &ProjectGraph, scope: &Scope) -> Dsm{
+    let mut roots: Vec<Uuid> = match scope{
+        Scope::All => graph.nodes()
+            .map(|n| n.get_id())
+            .filter(|id| graph.get_parent(*id).is_none())
+            .collect(),
+        Scope::Subtree(root) => vec![*root],
+    };
+    roots.retain(|id| graph.get_node(*id).is_some());
+
+    let mut entries = Vec::new();
+    for root in by_start(graph, roots){
+        push_entries(graph, root, 0, &mut entries);
+    }
+
+    let position: HashMap<Uuid,usize> = entries.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+    let mut marks: BTreeMap<(usize,usize),Vec<DependencyType>> = BTreeMap::new();
+    for (from, to, dependency) in graph.edges(){
+        if !dependency.kind.is_scheduling(){
+            continue;
+        }
+        // `to` waits on `from`
+        if let (Some(&row), Some(&column)) = (position.get(&to), position.get(&from)){
+            let kinds = marks.entry((row, column)).or_default();
+            if !kinds.contains(&dependency.kind){
+                kinds.push(dependency.kind);
+            }
+        }
+    }
+    Dsm{ entries, marks }
+}
+
+fn by_start(graph: &ProjectGraph, mut ids: Vec<Uuid>) -> Vec<Uuid>{
+    ids.sort_by_key(|id| (graph.get_node(*id).and_then(|n| n.get_timeline()).map(|t| t.start), *id));
+    ids
+}
+
+fn push_entries(graph: &ProjectGraph, id: Uuid, depth: usize, entries: &mut Vec<DsmEntry>){
+    let Some(node) = graph.get_node(id) else {
+        return;
+    };
+    let label = match graph.get_key(id){
+        Some(key) => format!("{} {}", key, node.get_name()),
+        None => node.get_name().to_string(),
+    };
+    entries.push(DsmEntry{ id, label, depth });
+    for child in by_start(graph, graph.get_children(id)){
+        push_entries(graph, child, depth + 1, entries);
+    }
+}
+
+impl Dsm{
+    pub fn len(&self) -> usize{
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.entries.is_empty()
+    }
+
+    // The markers in a cell, e.g. "B" or "BR"; empty when there is no dependency
+    pub fn cell(&self, row: usize, column: usize) -> String{
+        self.marks.get(&(row, column))
+            .map(|kinds| kinds.iter().map(|k| marker(*k)).collect())
+            .unwrap_or_default()
+    }
+
+    // Marks above the diagonal, as (row, column)
+    pub fn feedback(&self) -> Vec<(usize,usize)>{
+        self.marks.keys().filter(|(row, column)| column > row).copied().collect()
+    }
+
+    // Share of the off-diagonal cells that carry a mark
+    pub fn density(&self) -> f64{
+        let n = self.len();
+        if n < 2{
+            return 0.0;
+        }
+        self.marks.len() as f64 / (n * (n - 1)) as f64
+    }
+
+    // How many nodes a row depends on and how many depend on it
+    pub fn coupling(&self, index: usize) -> (usize,usize){
+        let depends_on = self.marks.keys().filter(|(row, _)| *row == index).count();
+        let depended_on = self.marks.keys().filter(|(_, column)| *column == index).count();
+        (depends_on, depended_on)
+    }
+
+    // Columns are numbered to keep the matrix narrow; the legend maps them to rows
+    pub fn render_text(&self) -> String{
+        let n = self.len();
+        let number_width = n.to_string().len();
+        let label_width = self.entries.iter().map(|e| e.depth * 2 + e.label.chars().count()).max().unwrap_or(0);
+        let cell_width = number_width.max(2);
+
+        let mut out = format!("{:w$}  ", "", w = number_width + 1 + label_width);
+        for column in 0..n{
+            out.push_str(&format!("{:>w$}", column + 1, w = cell_width + 1));
+        }
+        out.push('\n');
+        for (row, entry) in self.entries.iter().enumerate(){
+            let label = format!("{}{}", "  ".repeat(entry.depth), entry.label);
+            out.push_str(&format!("{:>nw$} {:<lw$}  ", row + 1, label, nw = number_width, lw = label_width));
+            for column in 0..n{
+                let cell = if row == column { "■".to_string() } else {
+                    let cell = self.cell(row, column);
+                    if cell.is_empty() { ".".to_string() } else { cell }
+                };
+                out.push_str(&format!("{:>w$}", cell, w = cell_width + 1));
+            }
+            out.push('\n');
+        }
+        let feedback = self.feedback().len();
+        out.push_str(&format!("\n{} dependencies, density {:.1}%, {} above the diagonal\n",
+            self.marks.len(), self.density() * 100.0, feedback));
+        out
+    }
+
+    // Labels on both axes; cells hold the markers
+    pub fn render_csv(&self) -> String{
+        let labels: Vec<String> = self.entries.iter().map(|e| escape_csv(&e.label)).collect();
+        let mut out = format!(",{}\n", labels.join(","));
+        for (row, label) in labels.iter().enumerate(){
+            let cells: Vec<String> = (0..self.len()).map(|column| self.cell(row, column)).collect();
+            out.push_str(&format!("{},{}\n", label, cells.join(",")));
+        }
+        out
+    }
+
+    pub fn render_html(&self) -> String{
+        let mut out = String::from("<table class=\"dsm\">\n<tr><th></th>");
+        for column in 0..self.len(){
+            out.push_str(&format!("<th>{}</th>", column + 1));
+        }
+        out.push_str("</tr>\n");
+        for (row, entry) in self.entries.iter().enumerate(){
+            out.push_str(&format!("<tr><th style=\"text-align:left;padding-left:{}em\">{} {}</th>",
+                entry.depth, row + 1, escape_html(&entry.label)));
+            for column in 0..self.len(){
+                let cell = self.cell(row, column);
+                let class = if row == column{
+                    " class=\"diagonal\""
+                }else if cell.is_empty(){
+                    ""
+                }else if column > row{
+                    " class=\"feedback\""
+                }else{
+                    " class=\"mark\""
+                };
+                out.push_str(&format!("<td{}>{}</td>", class, cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+        out
+    }
+}
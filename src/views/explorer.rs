@@ -0,0 +1,140 @@
+// Plan explorer - a standalone HTML page for sharing a plan people can
+// explore: the network diagram with pan and zoom, the Contains hierarchy
+// beside it with subtrees that fold into their container, and the critical
+// path at a click
+//
+// The page carries the laid-out network as JSON and draws it with a small
+// script of its own, so it opens from a file with no network access. What
+// the script reads is `data()`: the outline (key, name, short, status, fill,
+// depth, parent), the nodes (entry, x, y, critical), the edges (from, to,
+// path, critical, dashed) and the theme's colours.
+
+use super::network::{network, shorten, Network, NODE_HEIGHT, NODE_WIDTH};
+use super::{theme_fill, Template};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Catalog, Scope, Status};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const EXPLORER_TEMPLATE: &str = include_str!("templates/explorer.html");
+
+#[derive(Debug, Clone)]
+pub struct OutlineEntry{
+    pub id: Uuid,
+    pub key: Option<String>,
+    pub name: String,
+    pub status: Option<Status>,
+    pub fill: String,
+    pub depth: usize,
+    // Index of the containing entry
+    pub parent: Option<usize>,
+    // Index into the network's nodes, when the entry is drawn there
+    pub node: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Explorer{
+    pub title: String,
+    pub network: Network,
+    // The hierarchy depth first, siblings by start date; only entries with
+    // something drawn at or below them
+    pub outline: Vec<OutlineEntry>,
+}
+
+pub fn explorer(graph: &ProjectGraph, scope: &Scope) -> Explorer{
+    let network = network(graph, scope);
+    let drawn: HashMap<Uuid,usize> = network.nodes.iter().enumerate().map(|(i, n)| (n.id, i)).collect();
+    let roots = match scope{
+        Scope::All => graph.nodes().map(|n| n.get_id()).filter(|id| graph.get_parent(*id).is_none()).collect(),
+        Scope::Subtree(root) => vec![*root],
+    };
+
+    let mut outline = Vec::new();
+    for root in by_start(graph, roots){
+        push_entries(graph, root, 0, None, &drawn, &mut outline);
+    }
+
+    let tops: Vec<&OutlineEntry> = outline.iter().filter(|e| e.parent.is_none()).collect();
+    let title = match tops.as_slice(){
+        [top] => top.name.clone(),
+        _ => "Plan".to_string(),
+    };
+    Explorer{ title, network, outline }
+}
+
+fn by_start(graph: &ProjectGraph, mut ids: Vec<Uuid>) -> Vec<Uuid>{
+    ids.sort_by_key(|id| (graph.get_node(*id).and_then(|n| n.get_timeline()).map(|t| t.start), *id));
+    ids
+}
+
+fn push_entries(graph: &ProjectGraph, id: Uuid, depth: usize, parent: Option<usize>, drawn: &HashMap<Uuid,usize>, outline: &mut Vec<OutlineEntry>){
+    let Some(node) = graph.get_node(id) else {
+        return;
+    };
+    let index = outline.len();
+    outline.push(OutlineEntry{
+        id,
+        key: graph.get_key(id).map(str::to_string),
+        name: node.get_name().to_string(),
+        status: node.get_status(),
+        fill: theme_fill(graph, id),
+        depth,
+        parent,
+        node: drawn.get(&id).copied(),
+    });
+    for child in by_start(graph, graph.get_children(id)){
+        push_entries(graph, child, depth + 1, Some(index), drawn, outline);
+    }
+    // Nothing drawn here or below
+    if outline.len() == index + 1 && outline[index].node.is_none(){
+        outline.pop();
+    }
+}
+
+impl Explorer{
+    pub fn data(&self) -> Value{
+        let entry_of: HashMap<usize,usize> = self.outline.iter().enumerate()
+            .filter_map(|(i, e)| e.node.map(|n| (n, i)))
+            .collect();
+        let theme = &self.network.theme;
+        json!({
+            "box": [NODE_WIDTH, NODE_HEIGHT],
+            "colors": { "text": theme.text, "line": theme.line, "critical": theme.critical },
+            "width": self.network.width,
+            "height": self.network.height,
+            "tree": self.outline.iter().map(|entry| json!({
+                "key": entry.key,
+                "name": entry.name,
+                "short": shorten(&entry.name),
+                "status": entry.status.map(|s| s.to_string()),
+                "fill": entry.fill,
+                "depth": entry.depth,
+                "parent": entry.parent,
+            })).collect::<Vec<_>>(),
+            "nodes": self.network.nodes.iter().enumerate().map(|(i, node)| json!({
+                "entry": entry_of.get(&i),
+                "x": node.x,
+                "y": node.y,
+                "critical": node.critical,
+            })).collect::<Vec<_>>(),
+            "edges": self.network.edges.iter().map(|edge| json!({
+                "from": edge.from,
+                "to": edge.to,
+                "path": self.network.path(edge),
+                "critical": edge.critical,
+                "dashed": edge.kind == DependencyType::ResourcesRequiredFor,
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn render_html(&self) -> String{
+        // Nothing in the data may end the script early
+        let data = self.data().to_string().replace("</", "<\\/");
+        let template = Template::parse(EXPLORER_TEMPLATE).expect("the explorer page parses");
+        let theme = &self.network.theme;
+        let context = json!({ "title": self.title, "data": data, "font": theme.font, "font_size": theme.font_size, "text": theme.text, "line": theme.line, "critical": theme.critical, "shade": theme.shade });
+        template.render(&context, &Catalog::default(), true)
+            .expect("the explorer page renders")
+    }
+}
@@ -0,0 +1,474 @@
+// Gantt view - scheduled dates (lag included) laid out along the Contains hierarchy
+//
+// The timeline is drawn at a time scale: a column per day, week, month or
+// (fiscal) quarter. Labels are left out where they would run into the one
+// before, days off in the graph's calendar are shaded where days are wide
+// enough to see, and today is marked when it falls inside the plan. Bars,
+// lines and fonts follow the project's theme.
+
+use super::{escape_html, theme_fill};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Calendar, FiscalCalendar, Scope, Status, Theme};
+use crate::scheduler::{critical_path, schedule, Schedule};
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+const ROW_HEIGHT: f64 = 22.0;
+const HEADER_HEIGHT: f64 = 40.0;
+const CHAR_WIDTH: f64 = 7.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeScale{
+    Day,
+    Week,
+    Month,
+    // Quarters follow the graph's fiscal calendar
+    Quarter,
+}
+
+const SCALES: [TimeScale; 4] = [TimeScale::Day, TimeScale::Week, TimeScale::Month, TimeScale::Quarter];
+
+impl TimeScale{
+    // The finest scale that shows `days` in at most `columns` periods
+    pub fn fit(days: i64, columns: i64) -> Self{
+        SCALES.into_iter()
+            .find(|scale| (days + scale.typical_days() - 1) / scale.typical_days() <= columns)
+            .unwrap_or(TimeScale::Quarter)
+    }
+
+    fn typical_days(self) -> i64{
+        match self{
+            TimeScale::Day => 1,
+            TimeScale::Week => 7,
+            TimeScale::Month => 30,
+            TimeScale::Quarter => 91,
+        }
+    }
+
+    // Characters a period is wide in the text chart
+    fn text_width(self) -> usize{
+        match self{
+            TimeScale::Day | TimeScale::Week => 1,
+            TimeScale::Month | TimeScale::Quarter => 3,
+        }
+    }
+
+    // Pixels a day is wide in the SVG chart
+    fn day_width(self) -> f64{
+        match self{
+            TimeScale::Day => 24.0,
+            TimeScale::Week => 5.0,
+            TimeScale::Month => 1.6,
+            TimeScale::Quarter => 0.6,
+        }
+    }
+
+    // The period a date falls in, and the coarser one the header groups it by
+    fn period(self, date: NaiveDate, fiscal: &FiscalCalendar) -> (i32,u32){
+        match self{
+            TimeScale::Day => (date.year(), date.ordinal()),
+            TimeScale::Week => (date.iso_week().year(), date.iso_week().week()),
+            TimeScale::Month => (date.year(), date.month()),
+            TimeScale::Quarter => (fiscal.fiscal_year(date), fiscal.quarter(date)),
+        }
+    }
+
+    fn group(self, date: NaiveDate, fiscal: &FiscalCalendar) -> (i32,u32){
+        match self{
+            TimeScale::Day | TimeScale::Week => (date.year(), date.month()),
+            TimeScale::Month => (date.year(), 0),
+            TimeScale::Quarter => (fiscal.fiscal_year(date), 0),
+        }
+    }
+
+    fn label(self, start: NaiveDate, fiscal: &FiscalCalendar) -> String{
+        match self{
+            TimeScale::Day => start.day().to_string(),
+            TimeScale::Week => format!("W{:02}", start.iso_week().week()),
+            TimeScale::Month => start.format("%b").to_string(),
+            TimeScale::Quarter => format!("Q{}", fiscal.quarter(start)),
+        }
+    }
+
+    fn group_label(self, start: NaiveDate, fiscal: &FiscalCalendar) -> String{
+        match self{
+            TimeScale::Day | TimeScale::Week => start.format("%b %Y").to_string(),
+            TimeScale::Month => start.year().to_string(),
+            TimeScale::Quarter if fiscal.is_calendar_year() => fiscal.fiscal_year(start).to_string(),
+            TimeScale::Quarter => format!("FY{}", fiscal.fiscal_year(start)),
+        }
+    }
+}
+
+impl std::str::FromStr for TimeScale{
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self,Self::Err>{
+        match s{
+            "day" => Ok(TimeScale::Day),
+            "week" => Ok(TimeScale::Week),
+            "month" => Ok(TimeScale::Month),
+            "quarter" => Ok(TimeScale::Quarter),
+            _ => Err("The time scale is day, week, month or quarter"),
+        }
+    }
+}
+
+// The periods the plan's dates fall in: when each starts and its label, and
+// the same for the groups above them; `end` is the day after the last period
+struct Axis{
+    origin: NaiveDate,
+    end: NaiveDate,
+    periods: Vec<(NaiveDate,String)>,
+    groups: Vec<(NaiveDate,String)>,
+}
+
+impl Axis{
+    fn new(scale: TimeScale, first: NaiveDate, last: NaiveDate, fiscal: &FiscalCalendar) -> Self{
+        let same = |a: NaiveDate, b: NaiveDate| scale.period(a, fiscal) == scale.period(b, fiscal);
+        let mut origin = first;
+        while let Some(before) = origin.pred_opt().filter(|d| same(*d, first)){
+            origin = before;
+        }
+
+        let mut periods = Vec::new();
+        let mut groups: Vec<(NaiveDate,String)> = Vec::new();
+        let mut date = origin;
+        loop{
+            if periods.last().is_none_or(|(start, _)| !same(*start, date)){
+                if date > last{
+                    break;
+                }
+                periods.push((date, scale.label(date, fiscal)));
+                if groups.last().is_none_or(|(start, _)| scale.group(*start, fiscal) != scale.group(date, fiscal)){
+                    groups.push((date, scale.group_label(date, fiscal)));
+                }
+            }
+            match date.succ_opt(){
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+        Axis{ origin, end: date, periods, groups }
+    }
+
+    // Index of the period a date falls in
+    fn column(&self, date: NaiveDate) -> usize{
+        self.periods.partition_point(|(start, _)| *start <= date).saturating_sub(1)
+    }
+
+    fn days(&self, date: NaiveDate) -> f64{
+        (date - self.origin).num_days() as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GanttRow{
+    pub id: Uuid,
+    pub label: String,
+    pub depth: usize,
+    pub start: DT,
+    pub end: DT,
+    pub summary: bool,
+    pub critical: bool,
+    pub status: Option<Status>,
+    pub fill: String,
+    // Share of the owner's day the work gets, when it is planned by effort;
+    // the scheduler has already stretched the bar to match
+    pub allocation: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Gantt{
+    pub rows: Vec<GanttRow>,
+    // Days off to shade, and the quarters to draw
+    pub calendar: Calendar,
+    pub fiscal: FiscalCalendar,
+    pub theme: Theme,
+}
+
+pub fn gantt(graph: &ProjectGraph, scope: &Scope) -> Result<Gantt,&'static str>{
+    let schedule = schedule(graph)?;
+    let critical = critical_path(graph, &schedule);
+
+    let roots: Vec<Uuid> = match scope{
+        Scope::All => graph.nodes()
+            .map(|n| n.get_id())
+            .filter(|id| graph.get_parent(*id).is_none())
+            .collect(),
+        Scope::Subtree(root) => vec![*root],
+    };
+
+    let mut rows = Vec::new();
+    for root in sorted_by_start(&schedule, roots){
+        push_rows(graph, &schedule, &critical, root, 0, &mut rows);
+    }
+    Ok(Gantt{
+        rows,
+        calendar: graph.get_calendar().clone(),
+        fiscal: graph.get_fiscal_calendar().clone(),
+        theme: graph.get_settings().theme.clone(),
+    })
+}
+
+fn sorted_by_start(schedule: &Schedule, mut ids: Vec<Uuid>) -> Vec<Uuid>{
+    ids.retain(|id| schedule.get(*id).is_some());
+    ids.sort_by_key(|id| (schedule.get(*id).map(|n| n.start), *id));
+    ids
+}
+
+fn push_rows(graph: &ProjectGraph, schedule: &Schedule, critical: &[Uuid], id: Uuid, depth: usize, rows: &mut Vec<GanttRow>){
+    let (Some(node), Some(scheduled)) = (graph.get_node(id), schedule.get(id)) else {
+        return;
+    };
+
+    let children = sorted_by_start(schedule, graph.get_children(id));
+    let label = match graph.get_key(id){
+        Some(key) => format!("{} {}", key, node.get_name()),
+        None => node.get_name().to_string(),
+    };
+
+    // A lead can leave a schedule ending before it starts; such a bar is
+    // drawn as its start day rather than with a negative width
+    rows.push(GanttRow{
+        id,
+        label,
+        depth,
+        start: scheduled.start,
+        end: scheduled.end.max(scheduled.start),
+        summary: !children.is_empty(),
+        critical: critical.contains(&id),
+        status: node.get_status(),
+        fill: theme_fill(graph, id),
+        allocation: graph.get_effort(id).map(|e| e.allocation),
+    });
+
+    for child in children{
+        push_rows(graph, schedule, critical, child, depth + 1, rows);
+    }
+}
+
+impl Gantt{
+    pub fn start(&self) -> Option<DT>{
+        self.rows.iter().map(|r| r.start).min()
+    }
+
+    pub fn end(&self) -> Option<DT>{
+        self.rows.iter().map(|r| r.end).max()
+    }
+
+    fn axis(&self, scale: TimeScale) -> Option<Axis>{
+        let (start, end) = (self.start()?, self.end()?);
+        Some(Axis::new(scale, start.date_naive(), end.date_naive(), &self.fiscal))
+    }
+
+    // The finest scale that keeps the plan within `columns` periods
+    pub fn fit(&self, columns: i64) -> TimeScale{
+        match (self.start(), self.end()){
+            (Some(start), Some(end)) => TimeScale::fit((end.date_naive() - start.date_naive()).num_days() + 1, columns),
+            _ => TimeScale::Day,
+        }
+    }
+
+    // One column per period of the scale, under a line of group labels and a
+    // line of period labels: `=` summary rows, `#` work, `+` part-time work,
+    // `*` work on the critical path; part-time bars end with the allocation.
+    // At the day scale `·` marks days off, and `|` marks today.
+    pub fn render_text(&self, scale: TimeScale, today: NaiveDate) -> String{
+        let Some(axis) = self.axis(scale) else {
+            return String::new();
+        };
+        let cell = scale.text_width();
+        let columns = axis.periods.len() * cell;
+        let label_width = self.rows.iter().map(|r| r.label.len() + 2 * r.depth).max().unwrap_or(0);
+        let today_column = (axis.origin..axis.end).contains(&today).then(|| axis.column(today));
+
+        let mut out = String::new();
+        for labels in [&axis.groups, &axis.periods]{
+            let mut line = vec![' '; columns];
+            let mut free = 0;
+            for (start, label) in labels{
+                let column = axis.column(*start) * cell;
+                if column < free || column + label.chars().count() > columns{
+                    continue;
+                }
+                for (i, c) in label.chars().enumerate(){
+                    line[column + i] = c;
+                }
+                free = column + label.chars().count() + 1;
+            }
+            let line: String = line.into_iter().collect();
+            out.push_str(&format!("{:width$} |{}\n", "", line.trim_end(), width = label_width));
+        }
+
+        for row in &self.rows{
+            let first = axis.column(row.start.date_naive());
+            let last = axis.column(row.end.date_naive());
+            let part_time = row.allocation.filter(|a| *a < 100);
+            let fill = if row.summary { '=' } else if row.critical { '*' } else if part_time.is_some() { '+' } else { '#' };
+
+            let cells: String = (0..axis.periods.len()).map(|column| {
+                if (first..=last).contains(&column){
+                    fill.to_string().repeat(cell)
+                }else if today_column == Some(column){
+                    format!("{:^width$}", '|', width = cell)
+                }else if scale == TimeScale::Day && !self.calendar.is_working_day(axis.periods[column].0){
+                    '·'.to_string()
+                }else{
+                    " ".repeat(cell)
+                }
+            }).collect();
+            let label = format!("{}{}", "  ".repeat(row.depth), row.label);
+            out.push_str(&format!("{:<width$} |{}", label, cells.trim_end(), width = label_width));
+            if let Some(allocation) = part_time{
+                out.push_str(&format!(" {}%", allocation));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // A chart with the labels on the left and the timeline on the right
+    pub fn render_svg(&self, scale: TimeScale, today: NaiveDate) -> String{
+        let theme = &self.theme;
+        let Some(axis) = self.axis(scale) else {
+            return String::from("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"0\" height=\"0\"></svg>\n");
+        };
+        let day_width = scale.day_width();
+        let label_width = self.rows.iter()
+            .map(|r| (r.label.chars().count() + 2 * r.depth) as f64 * CHAR_WIDTH + 16.0)
+            .fold(120.0, f64::max)
+            .min(360.0);
+        let x = |date: NaiveDate| label_width + axis.days(date) * day_width;
+        let chart_bottom = HEADER_HEIGHT + self.rows.len() as f64 * ROW_HEIGHT;
+        let width = x(axis.end) + 10.0;
+        let height = chart_bottom + 24.0;
+
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"gantt\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"{}\" font-size=\"{}\" fill=\"{}\">\n",
+            escape_html(&theme.font), theme.font_size, theme.text, w = width, h = height);
+
+        // Days off, a run of them at a time, when a day is wide enough to see
+        if day_width >= 4.0{
+            let mut date = axis.origin;
+            while date < axis.end{
+                let mut after = date;
+                while after < axis.end && !self.calendar.is_working_day(after){
+                    after = after + Days::new(1);
+                }
+                if after > date{
+                    out.push_str(&format!("<rect class=\"day-off\" x=\"{:.1}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"/>\n",
+                        x(date), HEADER_HEIGHT, (after - date).num_days() as f64 * day_width, chart_bottom - HEADER_HEIGHT, theme.shade));
+                }
+                date = after + Days::new(1);
+            }
+        }
+
+        for (labels, y, top, opacity) in [(&axis.periods, 32.0, 20.0, 0.25), (&axis.groups, 14.0, 0.0, 0.6)]{
+            let mut free = 0.0;
+            for (start, label) in labels{
+                let left = x(*start);
+                out.push_str(&format!("<line x1=\"{:.1}\" y1=\"{}\" x2=\"{:.1}\" y2=\"{}\" stroke=\"{}\" stroke-opacity=\"{}\"/>\n", left, top, left, chart_bottom, theme.line, opacity));
+                if left >= free{
+                    out.push_str(&format!("<text x=\"{:.1}\" y=\"{}\" fill=\"{}\">{}</text>\n", left + 3.0, y, theme.muted, escape_html(label)));
+                    free = left + label.chars().count() as f64 * CHAR_WIDTH + 6.0;
+                }
+            }
+        }
+        out.push_str(&format!("<line x1=\"0\" y1=\"{h}\" x2=\"{:.1}\" y2=\"{h}\" stroke=\"{}\"/>\n", width, theme.line, h = HEADER_HEIGHT));
+
+        for (i, row) in self.rows.iter().enumerate(){
+            let top = HEADER_HEIGHT + i as f64 * ROW_HEIGHT;
+            let weight = if row.summary { " font-weight=\"bold\"" } else { "" };
+            out.push_str(&format!("<text x=\"{}\" y=\"{:.1}\"{}>{}</text>\n", 8 + 12 * row.depth, top + 15.0, weight, escape_html(&row.label)));
+
+            let left = x(row.start.date_naive());
+            let right = x(row.end.date_naive() + Days::new(1));
+            let part_time = row.allocation.filter(|a| *a < 100);
+            if row.summary{
+                out.push_str(&format!("<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"6\" fill=\"{}\"/>\n", left, top + 8.0, right - left, theme.summary));
+                continue;
+            }
+            // Part-time bars are paler; critical ones outlined
+            let (stroke, stroke_width) = if row.critical { (&theme.critical, 2) } else { (&theme.line, 1) };
+            let opacity = if part_time.is_some() { 0.6 } else { 1.0 };
+            out.push_str(&format!("<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"14\" rx=\"3\" fill=\"{}\" fill-opacity=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+                left, top + 4.0, right - left, row.fill, opacity, stroke, stroke_width));
+            if let Some(allocation) = part_time{
+                out.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\">{}%</text>\n", right + 4.0, top + 15.0, theme.muted, allocation));
+            }
+        }
+
+        if (axis.origin..axis.end).contains(&today){
+            let at = x(today) + day_width / 2.0;
+            out.push_str(&format!("<line class=\"today\" x1=\"{a:.1}\" y1=\"{}\" x2=\"{a:.1}\" y2=\"{}\" stroke=\"{}\" stroke-dasharray=\"4,2\"/>\n", HEADER_HEIGHT, chart_bottom, theme.critical, a = at));
+            out.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\" text-anchor=\"middle\">today</text>\n", at, chart_bottom + 16.0, theme.critical));
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    // Mermaid marks weekends and holidays itself once it is told which they
+    // are. It colours tasks by state alone, so the theme's status colours
+    // apply whatever the theme fills by.
+    pub fn render_mermaid(&self, scale: TimeScale) -> String{
+        let theme = &self.theme;
+        let (axis_format, tick) = match scale{
+            TimeScale::Day => ("%d", "1day"),
+            TimeScale::Week => ("%d %b", "1week"),
+            TimeScale::Month => ("%b %Y", "1month"),
+            TimeScale::Quarter => ("%b %Y", "3month"),
+        };
+        let variables = json!({
+            "fontFamily": theme.font,
+            "fontSize": format!("{}px", theme.font_size),
+            "taskBkgColor": theme.status_fill(Status::NotStarted),
+            "activeTaskBkgColor": theme.status_fill(Status::InProgress),
+            "doneTaskBkgColor": theme.status_fill(Status::Done),
+            "critBkgColor": theme.status_fill(Status::NotStarted),
+            "taskBorderColor": theme.line,
+            "activeTaskBorderColor": theme.line,
+            "doneTaskBorderColor": theme.line,
+            "critBorderColor": theme.critical,
+            "taskTextColor": theme.text,
+            "taskTextDarkColor": theme.text,
+            "todayLineColor": theme.critical,
+            "excludeBkgColor": theme.shade,
+        });
+        let mut out = format!("%%{{init: {}}}%%\n", json!({ "theme": "base", "themeVariables": variables }));
+        out.push_str(&format!("gantt\n    dateFormat YYYY-MM-DD\n    axisFormat {}\n    tickInterval {}\n", axis_format, tick));
+        let holidays: Vec<String> = match (self.start(), self.end()){
+            (Some(start), Some(end)) => self.calendar.get_holidays()
+                .range(start.date_naive()..=end.date_naive())
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        out.push_str(&format!("    excludes weekends{}\n", holidays.iter().map(|d| format!(", {}", d)).collect::<String>()));
+        for (i, row) in self.rows.iter().enumerate(){
+            let mut label = row.label.replace([':', '#', ';'], " ");
+            if let Some(allocation) = row.allocation.filter(|a| *a < 100){
+                label.push_str(&format!(" ({} pct)", allocation));
+            }
+            if row.depth == 0{
+                out.push_str(&format!("    section {}\n", label));
+            }
+
+            let mut tags = String::new();
+            match row.status{
+                Some(Status::Done) => tags.push_str("done, "),
+                Some(Status::InProgress) => tags.push_str("active, "),
+                _ => {}
+            }
+            if row.critical{
+                tags.push_str("crit, ");
+            }
+            out.push_str(&format!("    {} :{}t{}, {}, {}\n", label, tags, i,
+                row.start.format("%Y-%m-%d"), row.end.format("%Y-%m-%d")));
+        }
+        out
+    }
+}
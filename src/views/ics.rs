@@ -0,0 +1,123 @@
+// iCalendar feed - a project's or an owner's dates as all-day events that a
+// calendar app can subscribe to
+//
+//   project   release target dates and sprint boundaries touching the
+//             project, planned ends of its open epics, and deadlines of its
+//             open leaf work
+//   owner     deadlines of the owner's open leaf work, and the boundaries of
+//             sprints holding any of it
+//
+// UIDs come from the Uuids behind the events, so a calendar refreshing the
+// feed updates events in place; finished work simply drops out.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Node;
+use chrono::{DateTime, Days, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedScope{
+    Project(Uuid),
+    Owner(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEvent{
+    pub uid: String,
+    pub summary: String,
+    pub day: DateTime<Utc>,
+}
+
+fn escape(value: &str) -> String{
+    value.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a space
+fn fold(line: &str, out: &mut String){
+    let mut width = 0;
+    for c in line.chars(){
+        if width + c.len_utf8() > 75{
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn label(graph: &ProjectGraph, node: &Node) -> String{
+    match graph.get_key(node.get_id()){
+        Some(key) => format!("{} {}", key, node.get_name()),
+        None => node.get_name().to_string(),
+    }
+}
+
+// The calendar's name and its events, by day
+pub fn feed_events(graph: &ProjectGraph, scope: &FeedScope) -> Result<(String, Vec<FeedEvent>),&'static str>{
+    let (name, work): (String, HashSet<Uuid>) = match scope{
+        FeedScope::Project(id) => match graph.get_node(*id){
+            Some(project @ Node::Project{..}) => (label(graph, project), graph.get_subtree(*id).into_iter().collect()),
+            _ => return Err("Project feeds are made for Projects"),
+        },
+        FeedScope::Owner(owner) => {
+            let owned = graph.nodes().filter(|n| n.get_owner() == Some(owner.as_str())).map(|n| n.get_id()).collect();
+            (owner.clone(), owned)
+        }
+    };
+
+    let mut events = Vec::new();
+    for id in &work{
+        let Some(node) = graph.get_node(*id).filter(|n| !n.is_done()) else {
+            continue;
+        };
+        let Some(end) = node.get_timeline().and_then(|tl| tl.end) else {
+            continue;
+        };
+        let leaf = graph.get_children(*id).is_empty() && node.get_status().is_some();
+        let summary = match node{
+            Node::Epic{..} if matches!(scope, FeedScope::Project(_)) => format!("{} ends", label(graph, node)),
+            _ if leaf => match node.get_owner(){
+                Some(owner) if matches!(scope, FeedScope::Project(_)) => format!("Due: {} @{}", label(graph, node), owner),
+                _ => format!("Due: {}", label(graph, node)),
+            },
+            _ => continue,
+        };
+        events.push(FeedEvent{ uid: id.to_string(), summary, day: end });
+    }
+    for sprint in graph.sprints().filter(|s| s.get_items().iter().any(|i| work.contains(i))){
+        events.push(FeedEvent{ uid: format!("{}-start", sprint.id), summary: format!("{} starts", sprint.name), day: sprint.start });
+        events.push(FeedEvent{ uid: format!("{}-end", sprint.id), summary: format!("{} ends", sprint.name), day: sprint.end });
+    }
+    if matches!(scope, FeedScope::Project(_)){
+        for release in graph.releases().filter(|r| r.get_scope().iter().any(|i| work.contains(i))){
+            events.push(FeedEvent{ uid: release.id.to_string(), summary: format!("Release {}", release.name), day: release.target_date });
+        }
+    }
+    events.sort_by(|a, b| (a.day, &a.uid).cmp(&(b.day, &b.uid)));
+    Ok((name, events))
+}
+
+pub fn ics_feed(graph: &ProjectGraph, scope: &FeedScope, now: DateTime<Utc>) -> Result<String,&'static str>{
+    let (name, events) = feed_events(graph, scope)?;
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//ProjectManager//pm//EN", "CALSCALE:GREGORIAN"]{
+        fold(line, &mut out);
+    }
+    fold(&format!("X-WR-CALNAME:{}", escape(&name)), &mut out);
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    for event in events{
+        let end = event.day.checked_add_days(Days::new(1)).unwrap_or(event.day);
+        fold("BEGIN:VEVENT", &mut out);
+        fold(&format!("UID:{}@projectmanager", event.uid), &mut out);
+        fold(&format!("DTSTAMP:{}", stamp), &mut out);
+        fold(&format!("DTSTART;VALUE=DATE:{}", event.day.format("%Y%m%d")), &mut out);
+        fold(&format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")), &mut out);
+        fold(&format!("SUMMARY:{}", escape(&event.summary)), &mut out);
+        fold("TRANSP:TRANSPARENT", &mut out);
+        fold("END:VEVENT", &mut out);
+    }
+    fold("END:VCALENDAR", &mut out);
+    Ok(out)
+}
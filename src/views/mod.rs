@@ -1 +1,82 @@
+// Views module - renders the graph into human-facing layouts
 
+pub mod board;
+pub mod brief;
+pub mod comparison;
+pub mod coordination;
+pub mod dsm;
+pub mod explorer;
+pub mod gantt;
+pub mod ics;
+pub mod network;
+pub mod report;
+pub mod roadmap;
+pub mod specs;
+pub mod standup;
+pub mod swimlane;
+pub mod template;
+
+pub use board::{board, Board, BoardCard, Lane};
+pub use brief::{brief, Brief, Goal, Milestone, TeamMember};
+pub use comparison::{compare, Comparison, EndChange, Peak, ScenarioSummary};
+pub use coordination::{coordination, Coordination, Counterpart, Handoff, Party};
+pub use dsm::{dsm, Dsm, DsmEntry};
+pub use explorer::{explorer, Explorer, OutlineEntry};
+pub use gantt::{gantt, Gantt, TimeScale};
+pub use ics::{feed_events, ics_feed, FeedEvent, FeedScope};
+pub use network::{network, Network, NetworkEdge, NetworkNode};
+pub use report::{status_report, status_report_by, DecisionSummary, LaneSummary, StatusReport};
+pub use roadmap::{roadmap, Granularity, Roadmap};
+pub use specs::{spec_coverage, SpecCoverage};
+pub use standup::{standup, Standup, StandupItem};
+pub use swimlane::Grouping;
+pub use template::{Template, TemplateError};
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Theme;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+// What the project's theme fills a node's box or bar with
+pub(crate) fn theme_fill(graph: &ProjectGraph, id: Uuid) -> String{
+    let theme = &graph.get_settings().theme;
+    let Some(node) = graph.get_node(id) else {
+        return theme.neutral.clone();
+    };
+    let team = node.get_owner().and_then(|owner| graph.team_of(owner)).map(|team| team.name.as_str());
+    theme.fill(node.get_status(), node.get_kind_name(), team).to_string()
+}
+
+// The theme as report templates see it
+pub(crate) fn theme_context(theme: &Theme) -> Value{
+    json!({
+        "font": theme.font,
+        "font_size": theme.font_size,
+        "text": theme.text,
+        "muted": theme.muted,
+        "line": theme.line,
+        "critical": theme.critical,
+        "shade": theme.shade,
+    })
+}
+
+pub(crate) fn escape_html(s: &str) -> String{
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Text for a markdown table cell: a pipe would end the cell, a newline the row
+pub(crate) fn escape_cell(s: &str) -> String{
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+// Text for a CSV field: quoted when it holds a comma, quote or newline
+pub(crate) fn escape_csv(cell: &str) -> String{
+    if cell.contains([',', '"', '\n']){
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    }else{
+        cell.to_string()
+    }
+}
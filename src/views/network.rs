@@ -0,0 +1,491 @@
+// Network diagram - the scheduling dependencies of a scope (Blocks and
+// ResourcesRequiredFor) laid out in layers from left to right, and drawn as
+// SVG without Graphviz
+//
+// The layout is the usual layered (Sugiyama) one: edges that close a cycle
+// are turned around, every node goes one layer right of the furthest node it
+// waits on, edges that span layers get a bend point in each layer they cross,
+// barycenter sweeps reorder the layers to cut crossings, and nodes are then
+// pulled level with the nodes they connect to. Containers are left out unless
+// a dependency touches them; the outline and the Gantt show the hierarchy.
+// Colours and fonts come from the project's theme.
+
+use super::{escape_html, theme_fill};
+use crate::core::graph::{DependencyType, ProjectGraph};
+use crate::core::{Scope, Status, Theme};
+use crate::scheduler::{critical_path, schedule};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+pub(crate) const NODE_WIDTH: f64 = 180.0;
+pub(crate) const NODE_HEIGHT: f64 = 44.0;
+const LAYER_GAP: f64 = 70.0;
+const ROW_GAP: f64 = 24.0;
+const MARGIN: f64 = 20.0;
+const SWEEPS: usize = 12;
+const PLACEMENT_PASSES: usize = 8;
+const LABEL_CHARS: usize = 26;
+
+#[derive(Debug, Clone)]
+pub struct NetworkNode{
+    pub id: Uuid,
+    pub key: Option<String>,
+    pub name: String,
+    pub status: Option<Status>,
+    pub critical: bool,
+    pub fill: String,
+    pub layer: usize,
+    // Centre of the node's box
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkEdge{
+    // Indices into `nodes`; `to` waits on `from`
+    pub from: usize,
+    pub to: usize,
+    pub kind: DependencyType,
+    // Where the edge passes the layers between its ends, from `from` on
+    pub bends: Vec<(f64,f64)>,
+    pub critical: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Network{
+    pub nodes: Vec<NetworkNode>,
+    pub edges: Vec<NetworkEdge>,
+    pub width: f64,
+    pub height: f64,
+    pub theme: Theme,
+}
+
+pub fn network(graph: &ProjectGraph, scope: &Scope) -> Network{
+    let in_scope = scope_ids(graph, scope);
+
+    // One edge per pair; Blocks wins over ResourcesRequiredFor
+    let mut links: BTreeMap<(Uuid,Uuid),DependencyType> = BTreeMap::new();
+    for (from, to, dependency) in graph.edges(){
+        if !dependency.kind.is_scheduling() || from == to || !in_scope.contains(&from) || !in_scope.contains(&to){
+            continue;
+        }
+        let kind = links.entry((from, to)).or_insert(dependency.kind);
+        if dependency.kind == DependencyType::Blocks{
+            *kind = DependencyType::Blocks;
+        }
+    }
+
+    let linked: HashSet<Uuid> = links.keys().flat_map(|(from, to)| [*from, *to]).collect();
+    let mut ids: Vec<Uuid> = in_scope.into_iter()
+        .filter(|id| linked.contains(id) || is_work(graph, *id))
+        .collect();
+    ids.sort_by_key(|id| (graph.get_node(*id).and_then(|n| n.get_timeline()).map(|t| t.start), *id));
+
+    let path = schedule(graph).map(|s| critical_path(graph, &s)).unwrap_or_default();
+    let critical: HashSet<Uuid> = path.iter().copied().collect();
+    // An edge is on the path only between consecutive steps; two critical
+    // nodes can also be linked by a dependency that did not drive anything
+    let critical_links: HashSet<(Uuid,Uuid)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+
+    let index: HashMap<Uuid,usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let pairs: Vec<(usize,usize)> = links.keys().map(|(from, to)| (index[from], index[to])).collect();
+    let layout = layout(ids.len(), &pairs);
+
+    let nodes = ids.iter().enumerate().map(|(i, id)| {
+        let node = graph.get_node(*id);
+        NetworkNode{
+            id: *id,
+            key: graph.get_key(*id).map(str::to_string),
+            name: node.map(|n| n.get_name().to_string()).unwrap_or_default(),
+            status: node.and_then(|n| n.get_status()),
+            critical: critical.contains(id),
+            fill: theme_fill(graph, *id),
+            layer: layout.layers[i],
+            x: layout.positions[i].0,
+            y: layout.positions[i].1,
+        }
+    }).collect::<Vec<_>>();
+
+    let edges = links.iter().zip(pairs).zip(layout.bends).map(|(((ends, kind), (from, to)), bends)| NetworkEdge{
+        from,
+        to,
+        kind: *kind,
+        bends,
+        critical: critical_links.contains(ends),
+    }).collect();
+
+    Network{ nodes, edges, width: layout.width, height: layout.height, theme: graph.get_settings().theme.clone() }
+}
+
+fn scope_ids(graph: &ProjectGraph, scope: &Scope) -> Vec<Uuid>{
+    match scope{
+        Scope::All => graph.nodes().map(|n| n.get_id()).collect(),
+        Scope::Subtree(root) => {
+            let mut ids = Vec::new();
+            let mut stack = vec![*root];
+            while let Some(id) = stack.pop(){
+                if graph.get_node(id).is_some() && !ids.contains(&id){
+                    ids.push(id);
+                    stack.extend(graph.get_children(id));
+                }
+            }
+            ids
+        }
+    }
+}
+
+// Leaves that carry a status: the work itself
+fn is_work(graph: &ProjectGraph, id: Uuid) -> bool{
+    graph.get_children(id).is_empty() && graph.get_node(id).and_then(|n| n.get_status()).is_some()
+}
+
+struct Layout{
+    layers: Vec<usize>,
+    positions: Vec<(f64,f64)>,
+    bends: Vec<Vec<(f64,f64)>>,
+    width: f64,
+    height: f64,
+}
+
+// Places `n` nodes joined by `edges` (from, to); edges run left to right
+// except where they close a cycle
+fn layout(n: usize, edges: &[(usize,usize)]) -> Layout{
+    let reversed = feedback_edges(n, edges);
+    let directed: Vec<(usize,usize)> = edges.iter().zip(&reversed)
+        .map(|(&(from, to), &back)| if back { (to, from) } else { (from, to) })
+        .collect();
+    let node_layers = longest_path_layers(n, &directed);
+    let layer_count = node_layers.iter().max().map_or(0, |l| l + 1);
+
+    // Vertices are the nodes, then one dummy per layer a long edge crosses
+    let mut vertex_layer = node_layers.clone();
+    let mut chains: Vec<Vec<usize>> = Vec::with_capacity(directed.len());
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(from, to) in &directed{
+        let mut chain = Vec::new();
+        let mut previous = from;
+        for layer in node_layers[from] + 1..node_layers[to]{
+            let dummy = vertex_layer.len();
+            vertex_layer.push(layer);
+            successors.push(Vec::new());
+            predecessors.push(Vec::new());
+            successors[previous].push(dummy);
+            predecessors[dummy].push(previous);
+            chain.push(dummy);
+            previous = dummy;
+        }
+        successors[previous].push(to);
+        predecessors[to].push(previous);
+        chains.push(chain);
+    }
+
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (vertex, &layer) in vertex_layer.iter().enumerate(){
+        layers[layer].push(vertex);
+    }
+    order_layers(&mut layers, &successors, &predecessors, vertex_layer.len());
+
+    let heights: Vec<f64> = (0..vertex_layer.len()).map(|v| if v < n { NODE_HEIGHT } else { 0.0 }).collect();
+    let ys = place(&layers, &successors, &predecessors, &heights);
+    let x = |layer: usize| MARGIN + layer as f64 * (NODE_WIDTH + LAYER_GAP) + NODE_WIDTH / 2.0;
+
+    let positions = (0..n).map(|v| (x(vertex_layer[v]), ys[v])).collect();
+    let bends = chains.iter().zip(&reversed).map(|(chain, &back)| {
+        let mut points: Vec<(f64,f64)> = chain.iter().map(|&v| (x(vertex_layer[v]), ys[v])).collect();
+        if back{
+            points.reverse();
+        }
+        points
+    }).collect();
+    let width = match layer_count{
+        0 => 2.0 * MARGIN,
+        count => 2.0 * MARGIN + count as f64 * NODE_WIDTH + (count - 1) as f64 * LAYER_GAP,
+    };
+    let height = (0..vertex_layer.len()).map(|v| ys[v] + heights[v] / 2.0).fold(0.0, f64::max) + MARGIN;
+    Layout{ layers: node_layers, positions, bends, width, height: height.max(2.0 * MARGIN) }
+}
+
+// Edges a depth-first search finds pointing back up its path; turning them
+// around leaves no cycle
+fn feedback_edges(n: usize, edges: &[(usize,usize)]) -> Vec<bool>{
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, &(from, _)) in edges.iter().enumerate(){
+        outgoing[from].push(i);
+    }
+    // 0 unvisited, 1 on the path, 2 finished
+    let mut state = vec![0u8; n];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..n{
+        if state[root] != 0{
+            continue;
+        }
+        state[root] = 1;
+        let mut stack = vec![(root, 0usize)];
+        while let Some(top) = stack.last_mut(){
+            let vertex = top.0;
+            if top.1 == outgoing[vertex].len(){
+                state[vertex] = 2;
+                stack.pop();
+                continue;
+            }
+            let edge = outgoing[vertex][top.1];
+            top.1 += 1;
+            let next = edges[edge].1;
+            match state[next]{
+                0 => {
+                    state[next] = 1;
+                    stack.push((next, 0));
+                }
+                1 => reversed[edge] = true,
+                _ => {}
+            }
+        }
+    }
+    reversed
+}
+
+// Each node one layer right of the furthest node it waits on
+fn longest_path_layers(n: usize, edges: &[(usize,usize)]) -> Vec<usize>{
+    let mut incoming = vec![0usize; n];
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(from, to) in edges{
+        incoming[to] += 1;
+        outgoing[from].push(to);
+    }
+    let mut layers = vec![0usize; n];
+    let mut ready: Vec<usize> = (0..n).filter(|v| incoming[*v] == 0).collect();
+    while let Some(vertex) = ready.pop(){
+        for &next in &outgoing[vertex]{
+            layers[next] = layers[next].max(layers[vertex] + 1);
+            incoming[next] -= 1;
+            if incoming[next] == 0{
+                ready.push(next);
+            }
+        }
+    }
+    layers
+}
+
+// Barycenter sweeps, down then up, keeping the order with the fewest crossings
+fn order_layers(layers: &mut [Vec<usize>], successors: &[Vec<usize>], predecessors: &[Vec<usize>], vertices: usize){
+    let mut position = vec![0usize; vertices];
+    let index = |layers: &[Vec<usize>], position: &mut Vec<usize>| {
+        for layer in layers{
+            for (i, &v) in layer.iter().enumerate(){
+                position[v] = i;
+            }
+        }
+    };
+    index(layers, &mut position);
+    let mut best = layers.to_vec();
+    let mut fewest = crossings(layers, successors, &position);
+
+    for sweep in 0..SWEEPS{
+        if fewest == 0{
+            break;
+        }
+        let down = sweep % 2 == 0;
+        let order: Vec<usize> = if down { (1..layers.len()).collect() } else { (0..layers.len().saturating_sub(1)).rev().collect() };
+        for l in order{
+            let neighbours = if down { predecessors } else { successors };
+            let mut keyed: Vec<(f64,usize)> = layers[l].iter().map(|&v| {
+                let around = &neighbours[v];
+                let barycenter = match around.len(){
+                    0 => position[v] as f64,
+                    count => around.iter().map(|u| position[*u] as f64).sum::<f64>() / count as f64,
+                };
+                (barycenter, v)
+            }).collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[l] = keyed.into_iter().map(|(_, v)| v).collect();
+            for (i, &v) in layers[l].iter().enumerate(){
+                position[v] = i;
+            }
+        }
+        let count = crossings(layers, successors, &position);
+        if count < fewest{
+            fewest = count;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+fn crossings(layers: &[Vec<usize>], successors: &[Vec<usize>], position: &[usize]) -> usize{
+    let mut count = 0;
+    for layer in layers{
+        let segments: Vec<(usize,usize)> = layer.iter()
+            .flat_map(|&u| successors[u].iter().map(move |&v| (position[u], position[v])))
+            .collect();
+        for (i, a) in segments.iter().enumerate(){
+            count += segments[i + 1..].iter().filter(|b| (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1)).count();
+        }
+    }
+    count
+}
+
+// Vertical centres: each vertex is pulled towards the mean of its neighbours
+// in the layer before (or after), then the layer is spread out in order. The
+// mean of packing downwards and packing upwards keeps the gaps and centres
+// the layer on what it wants.
+fn place(layers: &[Vec<usize>], successors: &[Vec<usize>], predecessors: &[Vec<usize>], heights: &[f64]) -> Vec<f64>{
+    let mut ys = vec![0.0; heights.len()];
+    for layer in layers{
+        let mut y = 0.0;
+        for (i, &v) in layer.iter().enumerate(){
+            if i > 0{
+                y += gap(heights[layer[i - 1]], heights[v]);
+            }
+            ys[v] = y;
+        }
+    }
+
+    for pass in 0..PLACEMENT_PASSES{
+        let neighbours = if pass % 2 == 0 { predecessors } else { successors };
+        for layer in layers{
+            let wanted: Vec<f64> = layer.iter().map(|&v| match neighbours[v].len(){
+                0 => ys[v],
+                count => neighbours[v].iter().map(|u| ys[*u]).sum::<f64>() / count as f64,
+            }).collect();
+            let mut downward = wanted.clone();
+            for i in 1..layer.len(){
+                downward[i] = downward[i].max(downward[i - 1] + gap(heights[layer[i - 1]], heights[layer[i]]));
+            }
+            let mut upward = wanted;
+            for i in (0..layer.len().saturating_sub(1)).rev(){
+                upward[i] = upward[i].min(upward[i + 1] - gap(heights[layer[i]], heights[layer[i + 1]]));
+            }
+            for (i, &v) in layer.iter().enumerate(){
+                ys[v] = (downward[i] + upward[i]) / 2.0;
+            }
+        }
+    }
+
+    let top = ys.iter().zip(heights).map(|(y, h)| y - h / 2.0).fold(f64::INFINITY, f64::min);
+    if top.is_finite(){
+        for y in &mut ys{
+            *y += MARGIN - top;
+        }
+    }
+    ys
+}
+
+fn gap(above: f64, below: f64) -> f64{
+    (above + below) / 2.0 + ROW_GAP
+}
+
+pub(crate) fn shorten(text: &str) -> String{
+    match text.chars().count() > LABEL_CHARS{
+        true => format!("{}…", text.chars().take(LABEL_CHARS - 1).collect::<String>()),
+        false => text.to_string(),
+    }
+}
+
+impl Network{
+    // The edge as a path of curves from the side of `from` facing its next
+    // point to the side of `to` facing its last
+    pub(crate) fn path(&self, edge: &NetworkEdge) -> String{
+        let (from, to) = (&self.nodes[edge.from], &self.nodes[edge.to]);
+        let side = |node: &NetworkNode, toward: f64| match toward >= node.x{
+            true => node.x + NODE_WIDTH / 2.0,
+            false => node.x - NODE_WIDTH / 2.0,
+        };
+        let first = edge.bends.first().map_or(to.x, |b| b.0);
+        let last = edge.bends.last().map_or(from.x, |b| b.0);
+        let mut points = vec![(side(from, first), from.y)];
+        points.extend(&edge.bends);
+        points.push((side(to, last), to.y));
+
+        let mut d = format!("M{:.1},{:.1}", points[0].0, points[0].1);
+        for pair in points.windows(2){
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            let middle = (x1 + x2) / 2.0;
+            d.push_str(&format!(" C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}", middle, y1, middle, y2, x2, y2));
+        }
+        d
+    }
+
+    // Boxes filled as the theme says and outlined in its critical colour on
+    // the critical path; dashed edges are ResourcesRequiredFor
+    pub fn render_svg(&self) -> String{
+        let theme = &self.theme;
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"{}\" font-size=\"{}\" fill=\"{}\">\n",
+            escape_html(&theme.font), theme.font_size, theme.text, w = self.width, h = self.height);
+        out.push_str("<defs>\n");
+        for (id, color) in [("arrow", &theme.line), ("arrow-critical", &theme.critical)]{
+            out.push_str(&format!(
+                "<marker id=\"{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker>\n",
+                id, color));
+        }
+        out.push_str("</defs>\n");
+
+        for edge in &self.edges{
+            let (color, marker) = if edge.critical { (&theme.critical, "arrow-critical") } else { (&theme.line, "arrow") };
+            let dash = if edge.kind == DependencyType::ResourcesRequiredFor { " stroke-dasharray=\"5,3\"" } else { "" };
+            out.push_str(&format!("<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"{} marker-end=\"url(#{})\"/>\n",
+                self.path(edge), color, if edge.critical { 2 } else { 1 }, dash, marker));
+        }
+
+        for node in &self.nodes{
+            let title = match &node.key{
+                Some(key) => format!("{} {}", key, node.name),
+                None => node.name.clone(),
+            };
+            let status = node.status.map(|s| format!(" ({})", s)).unwrap_or_default();
+            let (left, top) = (node.x - NODE_WIDTH / 2.0, node.y - NODE_HEIGHT / 2.0);
+            out.push_str(&format!("<g class=\"node\"><title>{}{}</title>\n", escape_html(&title), status));
+            out.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+                left, top, NODE_WIDTH, NODE_HEIGHT, node.fill,
+                if node.critical { &theme.critical } else { &theme.line }, if node.critical { 2 } else { 1 }));
+            match &node.key{
+                Some(key) => {
+                    out.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\" font-weight=\"bold\">{}</text>\n", left + 8.0, top + 18.0, escape_html(key)));
+                    out.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n", left + 8.0, top + 34.0, escape_html(&shorten(&node.name))));
+                }
+                None => out.push_str(&format!("<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n", left + 8.0, node.y + 4.0, escape_html(&shorten(&node.name)))),
+            }
+            out.push_str("</g>\n");
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+    // For Graphviz: the same nodes, edges and colours, laid out by dot
+    pub fn render_dot(&self) -> String{
+        let theme = &self.theme;
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let quote = |s: &str| format!("\"{}\"", escape(s));
+        let mut out = String::from("digraph plan {\n    rankdir=LR;\n");
+        out.push_str(&format!("    node [shape=box, style=\"rounded,filled\", fontname={}, fontsize={}, fontcolor={}, color={}];\n",
+            quote(&theme.font), theme.font_size, quote(&theme.text), quote(&theme.line)));
+        out.push_str(&format!("    edge [color={}];\n", quote(&theme.line)));
+        for (i, node) in self.nodes.iter().enumerate(){
+            let label = match &node.key{
+                Some(key) => format!("\"{}\\n{}\"", escape(key), escape(&shorten(&node.name))),
+                None => quote(&shorten(&node.name)),
+            };
+            let critical = match node.critical{
+                true => format!(", color={}, penwidth=2", quote(&theme.critical)),
+                false => String::new(),
+            };
+            out.push_str(&format!("    n{} [label={}, fillcolor={}{}];\n", i, label, quote(&node.fill), critical));
+        }
+        for edge in &self.edges{
+            let mut attributes = Vec::new();
+            if edge.kind == DependencyType::ResourcesRequiredFor{
+                attributes.push("style=dashed".to_string());
+            }
+            if edge.critical{
+                attributes.push(format!("color={}, penwidth=2", quote(&theme.critical)));
+            }
+            match attributes.is_empty(){
+                true => out.push_str(&format!("    n{} -> n{};\n", edge.from, edge.to)),
+                false => out.push_str(&format!("    n{} -> n{} [{}];\n", edge.from, edge.to, attributes.join(", "))),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
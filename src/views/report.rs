@@ -0,0 +1,280 @@
+// Status report - a per-project summary rendered as markdown or HTML, in the
+// project's report language (core/i18n.rs), through the built-in templates
+// or a project's own (template.rs)
+
+use super::{escape_html, theme_context};
+use super::swimlane::{lanes_for, Grouping, LaneKey};
+use super::template::{Template, TemplateError};
+use crate::analytics::{scope_churn, ChurnSubject, ChurnWeek};
+use crate::core::graph::ProjectGraph;
+use crate::core::{Catalog, Node, Scope, Status, Theme};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const TOP_RISKS: usize = 5;
+
+pub const MARKDOWN_TEMPLATE: &str = include_str!("templates/status.md");
+pub const HTML_TEMPLATE: &str = include_str!("templates/status.html");
+
+#[derive(Debug, Clone)]
+pub struct RiskSummary{
+    pub title: String,
+    pub score: f64,
+    pub owner: Option<String>,
+    pub mitigation: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecisionSummary{
+    pub key: String,
+    pub question: String,
+    // Chosen option, the day and who decided; None while open
+    pub chosen: Option<(String, NaiveDate, Vec<String>)>,
+    // Keys of the work it affects
+    pub affects: Vec<String>,
+}
+
+// Work in one swimlane of the report
+#[derive(Debug, Clone)]
+pub struct LaneSummary{
+    pub name: String,
+    pub status_counts: Vec<(Status, usize)>,
+    pub completion: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusReport{
+    pub project_id: Uuid,
+    pub title: String,
+    pub status_counts: Vec<(Status, usize)>,
+    pub completion: f64,
+    pub risk_score: f64,
+    pub top_risks: Vec<RiskSummary>,
+    pub grouping: Grouping,
+    // Empty unless grouped
+    pub lanes: Vec<LaneSummary>,
+    // Points added to and removed from the project's Epics after planning,
+    // summed per week
+    pub scope_churn: Vec<ChurnWeek>,
+    // Decisions in the project or affecting its work, open ones first
+    pub decisions: Vec<DecisionSummary>,
+    // What the report is written with; the project's language by default
+    pub messages: Catalog,
+    pub theme: Theme,
+}
+
+const STATUSES: [Status; 4] = [Status::NotStarted, Status::InProgress, Status::Blocked, Status::Done];
+
+fn count_statuses(work: &[&Node]) -> Vec<(Status, usize)>{
+    STATUSES.into_iter()
+        .map(|s| (s, work.iter().filter(|n| n.get_status() == Some(s)).count()))
+        .collect()
+}
+
+// Lanes hold leaf work only, so an epic is not counted next to its own stories
+fn lanes(graph: &ProjectGraph, work: &[&Node], grouping: Grouping) -> Vec<LaneSummary>{
+    if grouping == Grouping::None{
+        return Vec::new();
+    }
+    let mut lanes: BTreeMap<LaneKey,Vec<&Node>> = BTreeMap::new();
+    for node in work.iter().filter(|n| graph.get_children(n.get_id()).is_empty()){
+        for lane in lanes_for(graph, node, grouping){
+            lanes.entry(lane).or_default().push(node);
+        }
+    }
+    lanes.into_iter()
+        .map(|(key, nodes)| LaneSummary{
+            name: key.name,
+            status_counts: count_statuses(&nodes),
+            completion: ProjectGraph::completion_of(&nodes),
+        })
+        .collect()
+}
+
+fn project_churn(graph: &ProjectGraph, work: &[&Node]) -> Vec<ChurnWeek>{
+    let mut weeks: BTreeMap<_,ChurnWeek> = BTreeMap::new();
+    let epics = work.iter().filter(|n| matches!(n, Node::Epic{..}));
+    for churn in epics.filter_map(|e| scope_churn(graph, ChurnSubject::Epic(e.get_id())).ok()){
+        for week in churn.weeks{
+            let total = weeks.entry(week.week).or_insert(ChurnWeek{ week: week.week, added: 0, removed: 0 });
+            total.added += week.added;
+            total.removed += week.removed;
+        }
+    }
+    weeks.into_values().collect()
+}
+
+fn decisions(graph: &ProjectGraph, scope: &Scope) -> Vec<DecisionSummary>{
+    let key = |id: Uuid| graph.get_key(id).map(str::to_string).unwrap_or_else(|| id.to_string());
+    graph.decisions_in_scope(scope)
+        .into_iter()
+        .map(|d| DecisionSummary{
+            key: key(d.get_id()),
+            question: d.get_name().to_string(),
+            chosen: d.get_decision().map(|(chosen, on)| (chosen.to_string(), on, d.get_deciders().into_iter().map(str::to_string).collect())),
+            affects: graph.affected_by(d.get_id()).into_iter().map(key).collect(),
+        })
+        .collect()
+}
+
+pub fn status_report(graph: &ProjectGraph, project_id: Uuid) -> Option<StatusReport>{
+    status_report_by(graph, project_id, Grouping::None)
+}
+
+// Same report with a per-lane breakdown of the project's work
+pub fn status_report_by(graph: &ProjectGraph, project_id: Uuid, grouping: Grouping) -> Option<StatusReport>{
+    let project = graph.get_node(project_id)?;
+    if !matches!(project, Node::Project{..}){
+        return None;
+    }
+
+    let scope = Scope::Subtree(project_id);
+    let work: Vec<&Node> = graph.nodes_in_scope(&scope)
+        .into_iter()
+        .filter(|n| n.get_id() != project_id && n.get_status().is_some())
+        .collect();
+
+    let status_counts = count_statuses(&work);
+
+    let completion = graph.subtree_completion(project_id).unwrap_or(0.0);
+
+    let top_risks = graph.risks_in_scope(&scope)
+        .into_iter()
+        .take(TOP_RISKS)
+        .map(|r| RiskSummary{
+            title: r.get_title().to_string(),
+            score: r.score(),
+            owner: r.get_owner().map(str::to_string),
+            mitigation: r.get_mitigation().map(str::to_string),
+        })
+        .collect();
+
+    let title = match graph.get_key(project_id){
+        Some(key) => format!("{} {}", key, project.get_name()),
+        None => project.get_name().to_string(),
+    };
+
+    Some(StatusReport{
+        project_id,
+        title,
+        status_counts,
+        completion,
+        risk_score: graph.project_risk_score(project_id).unwrap_or(0.0),
+        top_risks,
+        grouping,
+        lanes: lanes(graph, &work, grouping),
+        scope_churn: project_churn(graph, &work),
+        decisions: decisions(graph, &scope),
+        messages: graph.get_settings().i18n.catalog(None),
+        theme: graph.get_settings().theme.clone(),
+    })
+}
+
+impl StatusReport{
+    fn grouping(&self) -> &str{
+        self.messages.get(match self.grouping{
+            Grouping::Epic => "group-epic",
+            Grouping::Owner => "group-owner",
+            Grouping::Tag => "group-tag",
+            Grouping::Priority => "group-priority",
+            Grouping::None => "",
+        })
+    }
+
+    // What templates see:
+    //   language, title, completion (0-100), risk_score
+    //   statuses     [{status, label, count, color}] in workflow order
+    //   grouping     the lane grouping's name; null when ungrouped
+    //   lanes        [{name, counts: [per status], completion}]
+    //   scope_churn  [{week, added, removed, net, changed}]; churn_chart its SVG
+    //   decisions    [{key, question, chosen: null | {option, on, by: []}, affects: []}]
+    //   top_risks    [{title, score, owner, mitigation}]
+    //   theme        {font, font_size, text, muted, line, critical, shade}
+    pub fn context(&self) -> Value{
+        json!({
+            "language": self.messages.language,
+            "title": self.title,
+            "completion": self.completion,
+            "statuses": self.status_counts.iter().map(|(status, count)| json!({
+                "status": status.to_string(),
+                "label": self.messages.status(*status),
+                "count": count,
+                "color": self.theme.status_fill(*status),
+            })).collect::<Vec<_>>(),
+            "grouping": (self.grouping != Grouping::None).then(|| self.grouping()),
+            "lanes": self.lanes.iter().map(|lane| json!({
+                "name": lane.name,
+                "counts": lane.status_counts.iter().map(|(_, c)| c).collect::<Vec<_>>(),
+                "completion": lane.completion,
+            })).collect::<Vec<_>>(),
+            "scope_churn": self.scope_churn.iter().map(|week| json!({
+                "week": week.week.to_string(),
+                "added": week.added,
+                "removed": week.removed,
+                "net": week.net(),
+                "changed": week.added + week.removed > 0,
+            })).collect::<Vec<_>>(),
+            "churn_chart": if self.scope_churn.is_empty() { String::new() } else { self.churn_chart().trim_end().to_string() },
+            "decisions": self.decisions.iter().map(|d| json!({
+                "key": d.key,
+                "question": d.question,
+                "chosen": d.chosen.as_ref().map(|(option, on, by)| json!({ "option": option, "on": on.to_string(), "by": by })),
+                "affects": d.affects,
+            })).collect::<Vec<_>>(),
+            "risk_score": self.risk_score,
+            "top_risks": self.top_risks.iter().map(|r| json!({
+                "title": r.title,
+                "score": r.score,
+                "owner": r.owner,
+                "mitigation": r.mitigation,
+            })).collect::<Vec<_>>(),
+            "theme": theme_context(&self.theme),
+        })
+    }
+
+    pub fn render_with(&self, template: &Template, html: bool) -> Result<String,TemplateError>{
+        template.render(&self.context(), &self.messages, html)
+    }
+
+    pub fn render_markdown(&self) -> String{
+        let template = Template::parse(MARKDOWN_TEMPLATE).expect("the built-in status template parses");
+        self.render_with(&template, false).expect("the built-in status template renders")
+    }
+
+    pub fn render_html(&self) -> String{
+        let template = Template::parse(HTML_TEMPLATE).expect("the built-in status template parses");
+        self.render_with(&template, true).expect("the built-in status template renders")
+    }
+
+    // Bars per week: points added above the axis, removed below
+    fn churn_chart(&self) -> String{
+        const BAR: usize = 24;
+        const HALF: f64 = 60.0;
+        let largest = self.scope_churn.iter().map(|w| w.added.max(w.removed)).max().unwrap_or(0).max(1) as f64;
+        let width = self.scope_churn.len() * BAR;
+        let mut out = format!("<svg class=\"scope-churn\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n", width, HALF * 2.0 + 16.0, width, HALF * 2.0 + 16.0);
+        for (i, week) in self.scope_churn.iter().enumerate(){
+            let x = i * BAR + 2;
+            let up = HALF * week.added as f64 / largest;
+            let down = HALF * week.removed as f64 / largest;
+            let title = self.messages.format("report-churn-week", &[
+                ("week", &week.week.to_string()), ("added", &week.added.to_string()), ("removed", &week.removed.to_string()),
+            ]);
+            out.push_str(&format!("<g><title>{}</title>", escape_html(&title)));
+            out.push_str(&format!("<rect x=\"{}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"#f0ad4e\"/>", x, HALF - up, BAR - 4, up));
+            out.push_str(&format!("<rect x=\"{}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"#5bc0de\"/></g>\n", x, HALF, BAR - 4, down));
+        }
+        out.push_str(&format!("<line x1=\"0\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#333\"/>\n", HALF, width, HALF));
+        if let (Some(first), Some(last)) = (self.scope_churn.first(), self.scope_churn.last()){
+            out.push_str(&format!("<text x=\"0\" y=\"{}\" font-size=\"10\">{}</text>", HALF * 2.0 + 12.0, first.week));
+            if self.scope_churn.len() > 4{
+                out.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"end\">{}</text>", width, HALF * 2.0 + 12.0, last.week));
+            }
+            out.push('\n');
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
@@ -0,0 +1,237 @@
+// Roadmap view - buckets Epics and Projects into months, fiscal quarters or custom periods
+
+use super::escape_html;
+use crate::core::graph::ProjectGraph;
+use crate::core::timezone::{format_local, local_date};
+use crate::core::{FiscalCalendar, Node, Scope};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity{
+    Month,
+    // Quarters follow the graph's fiscal calendar
+    Quarter,
+    // The fiscal calendar's custom named periods
+    Custom,
+}
+
+// A roadmap period: `index` is the month (1-12), the fiscal quarter (1-4),
+// or the position of a custom period in the fiscal calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Period{
+    pub year: i32,
+    pub index: u32,
+}
+
+impl Period{
+    pub fn containing(date: NaiveDate, granularity: Granularity, fiscal: &FiscalCalendar) -> Option<Self>{
+        match granularity{
+            Granularity::Month => Some(Period{ year: date.year(), index: date.month() }),
+            Granularity::Quarter => Some(Period{ year: fiscal.fiscal_year(date), index: fiscal.quarter(date) }),
+            Granularity::Custom => fiscal.get_periods().iter()
+                .position(|p| p.contains(date))
+                .map(|i| Period{ year: 0, index: i as u32 }),
+        }
+    }
+
+    pub fn next(self, granularity: Granularity) -> Self{
+        let last = match granularity{
+            Granularity::Month => 12,
+            Granularity::Quarter => 4,
+            Granularity::Custom => u32::MAX,
+        };
+
+        if self.index == last{
+            Period{ year: self.year + 1, index: 1 }
+        }else{
+            Period{ year: self.year, index: self.index + 1 }
+        }
+    }
+
+    pub fn label(&self, granularity: Granularity, fiscal: &FiscalCalendar) -> String{
+        match granularity{
+            Granularity::Month => format!("{}-{:02}", self.year, self.index),
+            Granularity::Quarter if fiscal.is_calendar_year() => format!("{} Q{}", self.year, self.index),
+            Granularity::Quarter => format!("FY{} Q{}", self.year, self.index),
+            Granularity::Custom => fiscal.get_periods()
+                .get(self.index as usize)
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoadmapItem{
+    pub id: Uuid,
+    pub key: Option<String>,
+    pub name: String,
+    pub owner: Option<String>,
+    pub start: DT,
+    pub end: DT,
+    // Dates are bucketed and rendered in the item's own timezone
+    pub timezone: Tz,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoadmapBucket{
+    pub period: Period,
+    pub label: String,
+    pub items: Vec<RoadmapItem>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Roadmap{
+    pub granularity: Granularity,
+    pub buckets: Vec<RoadmapBucket>,
+    // Projects in scope that have no timeline yet
+    pub unscheduled: Vec<RoadmapItem>,
+}
+
+// Items spanning several periods appear in every bucket they overlap
+pub fn roadmap(graph: &ProjectGraph, scope: &Scope, granularity: Granularity) -> Roadmap{
+    let mut scheduled: Vec<RoadmapItem> = Vec::new();
+    let mut unscheduled = Vec::new();
+
+    for node in graph.nodes_in_scope(scope){
+        if !matches!(node, Node::Epic{..} | Node::Project{..}){
+            continue;
+        }
+
+        let (start, end) = match node.get_timeline(){
+            Some(tl) => (tl.start, tl.end.unwrap_or(tl.start)),
+            None => (DT::default(), DT::default()),
+        };
+
+        let item = RoadmapItem{
+            id: node.get_id(),
+            key: graph.get_key(node.get_id()).map(str::to_string),
+            name: node.get_name().to_string(),
+            owner: node.get_owner().map(str::to_string),
+            start,
+            end,
+            timezone: graph.timezone_for(node.get_id()),
+        };
+
+        if node.get_timeline().is_some(){
+            scheduled.push(item);
+        }else{
+            unscheduled.push(item);
+        }
+    }
+
+    scheduled.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.name.cmp(&b.name)));
+
+    let fiscal = graph.get_fiscal_calendar();
+    let spans: Vec<(Period, Period, &RoadmapItem)> = scheduled.iter()
+        .filter_map(|i| i.period_span(granularity, fiscal).map(|(first, last)| (first, last, i)))
+        .collect();
+
+    let mut buckets: Vec<RoadmapBucket> = Vec::new();
+    let Some(mut period) = spans.iter().map(|(first, _, _)| *first).min() else {
+        return Roadmap{ granularity, buckets, unscheduled };
+    };
+    let last_period = spans.iter().map(|(_, last, _)| *last).max().unwrap_or(period);
+
+    while period <= last_period{
+        let items = spans.iter()
+            .filter(|(first, last, _)| *first <= period && period <= *last)
+            .map(|(_, _, i)| (*i).clone())
+            .collect();
+
+        buckets.push(RoadmapBucket{ period, label: period.label(granularity, fiscal), items });
+        period = period.next(granularity);
+    }
+
+    Roadmap{ granularity, buckets, unscheduled }
+}
+
+impl RoadmapItem{
+    // First and last periods the item overlaps; None when it falls outside
+    // every custom period
+    fn period_span(&self, granularity: Granularity, fiscal: &FiscalCalendar) -> Option<(Period, Period)>{
+        let start = local_date(self.start, self.timezone);
+        let end = local_date(self.end, self.timezone);
+
+        if granularity == Granularity::Custom{
+            let periods = fiscal.get_periods();
+            let first = periods.iter().position(|p| p.end >= start)?;
+            let last = periods.iter().rposition(|p| p.start <= end)?;
+            return (first <= last).then_some((
+                Period{ year: 0, index: first as u32 },
+                Period{ year: 0, index: last as u32 },
+            ));
+        }
+
+        Some((
+            Period::containing(start, granularity, fiscal)?,
+            Period::containing(end, granularity, fiscal)?,
+        ))
+    }
+
+    fn date_range(&self) -> (String, String){
+        (format_local(self.start, self.timezone, "%Y-%m-%d"), format_local(self.end, self.timezone, "%Y-%m-%d"))
+    }
+
+    fn display_name(&self) -> String{
+        match &self.key{
+            Some(key) => format!("{} {}", key, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl Roadmap{
+    pub fn render_text(&self) -> String{
+        let mut out = String::new();
+        for bucket in &self.buckets{
+            out.push_str(&format!("== {} ==\n", bucket.label));
+            if bucket.items.is_empty(){
+                out.push_str("  (nothing planned)\n");
+            }
+            for item in &bucket.items{
+                let (start, end) = item.date_range();
+                out.push_str(&format!("  - {} [{} -> {}]", item.display_name(), start, end));
+                if let Some(owner) = &item.owner{
+                    out.push_str(&format!(" @{}", owner));
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.unscheduled.is_empty(){
+            out.push_str("== Unscheduled ==\n");
+            for item in &self.unscheduled{
+                out.push_str(&format!("  - {}\n", item.display_name()));
+            }
+        }
+        out
+    }
+
+    pub fn render_html(&self) -> String{
+        let mut out = String::from("<div class=\"roadmap\">\n");
+        for bucket in &self.buckets{
+            out.push_str(&format!("<section>\n<h2>{}</h2>\n<ul>\n", escape_html(&bucket.label)));
+            for item in &bucket.items{
+                let (start, end) = item.date_range();
+                out.push_str(&format!("<li>{} <small>{} &rarr; {}</small></li>\n",
+                    escape_html(&item.display_name()), start, end));
+            }
+            out.push_str("</ul>\n</section>\n");
+        }
+
+        if !self.unscheduled.is_empty(){
+            out.push_str("<section>\n<h2>Unscheduled</h2>\n<ul>\n");
+            for item in &self.unscheduled{
+                out.push_str(&format!("<li>{}</li>\n", escape_html(&item.display_name())));
+            }
+            out.push_str("</ul>\n</section>\n");
+        }
+        out.push_str("</div>\n");
+        out
+    }
+}
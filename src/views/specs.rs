@@ -0,0 +1,45 @@
+// Spec coverage - the Epics and Stories linked to each Spec by SpecifiedBy,
+// and the specs nothing implements yet
+//
+// A spec's work includes that of the specs it contains, so an umbrella spec
+// split into parts counts as implemented once any part is.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::Node;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct SpecCoverage{
+    pub spec: Uuid,
+    pub name: String,
+    // Linked Epics and Stories, sorted by key
+    pub work: Vec<Uuid>,
+    pub done: usize,
+}
+
+impl SpecCoverage{
+    pub fn is_implemented(&self) -> bool{
+        !self.work.is_empty()
+    }
+}
+
+// Every spec, those without implementing work first
+pub fn spec_coverage(graph: &ProjectGraph) -> Vec<SpecCoverage>{
+    let key = |id: &Uuid| graph.get_key(*id).unwrap_or_default().to_string();
+    let mut coverage: Vec<SpecCoverage> = graph.nodes()
+        .filter(|n| matches!(n, Node::Spec{..}))
+        .map(|spec| {
+            let mut work: Vec<Uuid> = graph.get_subtree(spec.get_id()).into_iter()
+                .filter(|id| matches!(graph.get_node(*id), Some(Node::Spec{..})))
+                .flat_map(|id| graph.specified_work(id))
+                .collect();
+            // Ids tie-break nodes without a key, so duplicates end up adjacent
+            work.sort_by_key(|id| (key(id), *id));
+            work.dedup();
+            let done = work.iter().filter(|id| graph.get_node(**id).is_some_and(Node::is_done)).count();
+            SpecCoverage{ spec: spec.get_id(), name: spec.get_name().to_string(), work, done }
+        })
+        .collect();
+    coverage.sort_by(|a, b| a.is_implemented().cmp(&b.is_implemented()).then_with(|| key(&a.spec).cmp(&key(&b.spec))));
+    coverage
+}
@@ -0,0 +1,166 @@
+// Personal daily views for one owner: what is due today and this week,
+// what got unblocked and what was finished since yesterday. Days are taken
+// in the owner's timezone; due dates come from the schedule.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::timezone::{end_of_day, local_date, start_of_day};
+use crate::core::{Node, Status};
+use crate::scheduler::schedule;
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+type DT = DateTime<Utc>;
+
+#[derive(Debug, Clone)]
+pub struct StandupItem{
+    pub id: Uuid,
+    pub key: Option<String>,
+    pub name: String,
+    pub status: Option<Status>,
+    pub due: Option<DT>,
+    // When it was unblocked or completed, for the history sections
+    pub at: Option<DT>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Standup{
+    pub owner: String,
+    pub timezone: Tz,
+    pub today: NaiveDate,
+    // Open items due by the end of today, overdue ones included
+    pub due_today: Vec<StandupItem>,
+    // Open items due later this week (through Sunday)
+    pub due_this_week: Vec<StandupItem>,
+    pub unblocked: Vec<StandupItem>,
+    pub completed: Vec<StandupItem>,
+}
+
+fn item(graph: &ProjectGraph, node: &Node, due: Option<DT>, at: Option<DT>) -> StandupItem{
+    let id = node.get_id();
+    StandupItem{
+        id,
+        key: graph.get_key(id).map(str::to_string),
+        name: node.get_name().to_string(),
+        status: node.get_status(),
+        due,
+        at,
+    }
+}
+
+// When an open node stopped waiting: it left Blocked, or the last of its
+// predecessors was finished (logged as Unblocked, or worked out here). None if that is not recorded in the event log.
+fn unblocked_at(graph: &ProjectGraph, id: Uuid) -> Option<DT>{
+    let events = graph.get_events();
+    let left_blocked = events.last_transition_from(id, Status::Blocked);
+
+    let predecessors = graph.get_predecessors(id);
+    let cleared = if predecessors.is_empty(){
+        None
+    }else{
+        let mut latest = None;
+        for (pred, _) in predecessors{
+            if !graph.get_node(pred).is_some_and(|n| n.is_done()){
+                return None;
+            }
+            latest = latest.max(Some(events.last_transition_to(pred, Status::Done)?));
+        }
+        latest
+    };
+    left_blocked.max(cleared).max(events.last_unblocked(id))
+}
+
+pub fn standup(graph: &ProjectGraph, owner: &str, now: DT) -> Standup{
+    let timezone = graph.get_person(owner).and_then(|p| p.timezone).unwrap_or(Tz::UTC);
+    let today = local_date(now, timezone);
+    let end_today = end_of_day(today, timezone);
+    let sunday = today.checked_add_days(Days::new(6 - today.weekday().num_days_from_monday() as u64)).unwrap_or(today);
+    let end_week = end_of_day(sunday, timezone);
+    let since = start_of_day(today.pred_opt().unwrap_or(today), timezone);
+
+    // Scheduled ends when the graph can be scheduled, planned ones otherwise
+    let scheduled: HashMap<Uuid,DT> = schedule(graph)
+        .map(|s| s.iter().map(|n| (n.id, n.end)).collect())
+        .unwrap_or_default();
+    let due_of = |node: &Node| scheduled.get(&node.get_id()).copied()
+        .or_else(|| node.get_timeline().and_then(|tl| tl.end));
+
+    let mut standup = Standup{
+        owner: owner.to_string(), timezone, today,
+        due_today: Vec::new(), due_this_week: Vec::new(), unblocked: Vec::new(), completed: Vec::new(),
+    };
+
+    for node in graph.query().owner(owner).run(){
+        let id = node.get_id();
+        let due = due_of(node);
+        if node.is_done(){
+            if let Some(done) = graph.get_events().last_transition_to(id, Status::Done).filter(|at| *at >= since){
+                standup.completed.push(item(graph, node, due, Some(done)));
+            }
+            continue;
+        }
+        if node.get_status().is_none(){
+            continue;
+        }
+
+        match due{
+            Some(due) if due <= end_today => standup.due_today.push(item(graph, node, Some(due), None)),
+            Some(due) if due <= end_week => standup.due_this_week.push(item(graph, node, Some(due), None)),
+            _ => {}
+        }
+        if node.get_status() != Some(Status::Blocked){
+            if let Some(at) = unblocked_at(graph, id).filter(|at| *at >= since){
+                standup.unblocked.push(item(graph, node, due, Some(at)));
+            }
+        }
+    }
+
+    standup.due_today.sort_by_key(|i| (i.due, i.id));
+    standup.due_this_week.sort_by_key(|i| (i.due, i.id));
+    standup.unblocked.sort_by_key(|i| (i.at, i.id));
+    standup.completed.sort_by_key(|i| (i.at, i.id));
+    standup
+}
+
+impl StandupItem{
+    fn label(&self) -> String{
+        match &self.key{
+            Some(key) => format!("{} {}", key, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+impl Standup{
+    fn section(&self, out: &mut String, title: &str, items: &[StandupItem], show_due: bool){
+        out.push_str(&format!("{}:\n", title));
+        if items.is_empty(){
+            out.push_str("  (nothing)\n");
+        }
+        for item in items{
+            out.push_str(&format!("  - {}", item.label()));
+            if let (true, Some(due)) = (show_due, item.due){
+                out.push_str(&format!(" (due {})", local_date(due, self.timezone)));
+            }
+            out.push('\n');
+        }
+    }
+
+    // `pm today`: what is due
+    pub fn render_today(&self) -> String{
+        let mut out = format!("{} - {}\n\n", self.owner, self.today);
+        self.section(&mut out, "Due today", &self.due_today, true);
+        self.section(&mut out, "Later this week", &self.due_this_week, true);
+        out
+    }
+
+    // `pm standup`: done since yesterday, newly unblocked, due today
+    pub fn render_standup(&self) -> String{
+        let mut out = format!("Standup for {} - {}\n\n", self.owner, self.today);
+        self.section(&mut out, "Completed since yesterday", &self.completed, false);
+        self.section(&mut out, "Unblocked since yesterday", &self.unblocked, true);
+        self.section(&mut out, "Due today", &self.due_today, true);
+        out
+    }
+}
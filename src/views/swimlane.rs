@@ -0,0 +1,79 @@
+// Swimlanes - the dimension board and report views slice work by
+//
+// Every item lands in one lane per grouping, except by tag, where an item
+// shows up under each of its tags. Items without a value go to a catch-all
+// lane that sorts last.
+
+use crate::core::graph::ProjectGraph;
+use crate::core::{Node, Priority};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping{
+    #[default]
+    None,
+    Epic,
+    Owner,
+    Tag,
+    Priority,
+}
+
+impl Grouping{
+    pub fn label(&self) -> &'static str{
+        match self{
+            Grouping::None => "none",
+            Grouping::Epic => "epic",
+            Grouping::Owner => "owner",
+            Grouping::Tag => "tag",
+            Grouping::Priority => "priority",
+        }
+    }
+}
+
+// Lane names in display order, with the position used for sorting
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LaneKey{
+    // Lanes with a value first, then the catch-all
+    rank: (bool, u8),
+    pub name: String,
+}
+
+impl LaneKey{
+    fn named(name: String) -> Self{
+        LaneKey{ rank: (false, 0), name }
+    }
+
+    fn missing(name: &str) -> Self{
+        LaneKey{ rank: (true, 0), name: name.to_string() }
+    }
+}
+
+fn epic_of(graph: &ProjectGraph, node: &Node) -> Option<String>{
+    std::iter::once(node.get_id())
+        .chain(graph.get_ancestors(node.get_id()))
+        .filter_map(|id| graph.get_node(id))
+        .find(|n| matches!(n, Node::Epic{..}))
+        .map(|epic| match graph.get_key(epic.get_id()){
+            Some(key) => format!("{} {}", key, epic.get_name()),
+            None => epic.get_name().to_string(),
+        })
+}
+
+pub fn lanes_for(graph: &ProjectGraph, node: &Node, grouping: Grouping) -> Vec<LaneKey>{
+    match grouping{
+        Grouping::None => vec![LaneKey::named("All".to_string())],
+        Grouping::Epic => vec![epic_of(graph, node).map_or_else(|| LaneKey::missing("No epic"), LaneKey::named)],
+        Grouping::Owner => vec![node.get_owner().map_or_else(|| LaneKey::missing("Unassigned"), |o| LaneKey::named(o.to_string()))],
+        Grouping::Tag => {
+            let mut tags: Vec<LaneKey> = node.get_tags().iter().map(|t| LaneKey::named(t.to_string())).collect();
+            if tags.is_empty(){
+                tags.push(LaneKey::missing("Untagged"));
+            }
+            tags
+        }
+        // Most urgent first
+        Grouping::Priority => vec![match graph.get_priority(node.get_id()){
+            Some(p) => LaneKey{ rank: (false, Priority::Critical as u8 - p as u8), name: p.to_string() },
+            None => LaneKey::missing("No priority"),
+        }],
+    }
+}
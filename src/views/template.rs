@@ -0,0 +1,565 @@
+// Report templates - a small Jinja-like language the reports are rendered
+// with, so a project can ship its own layouts without code changes
+//
+//   {{ expr }}                                  a value; escaped in HTML templates
+//   {% if expr %} .. {% else %} .. {% endif %}
+//   {% for item in expr %} .. {% endfor %}      loop.index, loop.first, loop.last inside
+//   {# a comment #}
+//
+// An expression is a path into the context (title, risk.owner, lanes.0.name),
+// a "string" or a number, optionally preceded by `not` and followed by
+// filters: raw (no escaping), upper, lower, join(", "), fixed(1), signed,
+// signed(1), default("-"), length, cell (pipes escaped for a markdown
+// table); fixed and signed print at most 20 decimals. t("message-key",
+// name=expr, ..) is the message from the report's catalog with its
+// placeholders filled in.
+//
+// A line holding nothing but a {% %} or {# #} tag leaves no blank line
+// behind. What each report puts in the context is described next to its
+// built-in templates; `pm template context` prints it for a project.
+
+use crate::core::Catalog;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError{
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        write!(f, "template line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TemplateError{}
+
+fn error<T>(line: usize, message: impl Into<String>) -> Result<T,TemplateError>{
+    Err(TemplateError{ line, message: message.into() })
+}
+
+// Most decimals fixed and signed print; more than any report needs, and
+// few enough that the formatter never refuses them
+const MAX_DECIMALS: usize = 20;
+
+const FILTERS: [&str; 10] = ["raw", "upper", "lower", "join", "fixed", "signed", "default", "length", "escape", "cell"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr{
+    Path(Vec<String>),
+    Str(String),
+    Num(f64),
+    Not(Box<Expr>),
+    Translate{ key: Box<Expr>, args: Vec<(String, Expr)> },
+    Filtered{ value: Box<Expr>, filters: Vec<(String, Vec<Expr>)> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part{
+    Text(String),
+    Value{ expr: Expr, line: usize },
+    If{ condition: Expr, then: Vec<Part>, otherwise: Vec<Part>, line: usize },
+    For{ name: String, list: Expr, body: Vec<Part>, line: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template{
+    parts: Vec<Part>,
+}
+
+// Source split into text, {{ }} values and {% %} tags
+enum Token{
+    Text(String),
+    Value(String, usize),
+    Tag(String, usize),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>,TemplateError>{
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let line_at = |i: usize| source[..i].matches('\n').count() + 1;
+    while let Some(offset) = ["{{", "{%", "{#"].iter().filter_map(|open| source[pos..].find(open)).min(){
+        let start = pos + offset;
+        let open = &source[start..start + 2];
+        let close = match open{
+            "{{" => "}}",
+            "{%" => "%}",
+            _ => "#}",
+        };
+        let Some(length) = source[start + 2..].find(close) else {
+            return error(line_at(start), format!("{} is never closed", open));
+        };
+        let end = start + 2 + length + 2;
+        let inner = source[start + 2..end - 2].trim().to_string();
+
+        // A tag alone on its line takes the line with it
+        let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[end..].find('\n').map(|i| end + i + 1).unwrap_or(source.len());
+        let standalone = open != "{{" && line_start >= pos
+            && source[line_start..start].trim().is_empty()
+            && source[end..line_end].trim().is_empty();
+        let (text_end, next) = if standalone { (line_start, line_end) } else { (start, end) };
+        if text_end > pos{
+            tokens.push(Token::Text(source[pos..text_end].to_string()));
+        }
+        match open{
+            "{{" => tokens.push(Token::Value(inner, line_at(start))),
+            "{%" => tokens.push(Token::Tag(inner, line_at(start))),
+            _ => {}
+        }
+        pos = next;
+    }
+    if pos < source.len(){
+        tokens.push(Token::Text(source[pos..].to_string()));
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme{
+    Word(String),
+    Str(String),
+    Num(f64),
+    Open,
+    Close,
+    Comma,
+    Equals,
+    Pipe,
+}
+
+fn lex(source: &str, line: usize) -> Result<Vec<Lexeme>,TemplateError>{
+    let mut lexemes = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek(){
+        match c{
+            ' ' | '\t' | '\n' | '\r' => { chars.next(); }
+            '(' => { chars.next(); lexemes.push(Lexeme::Open); }
+            ')' => { chars.next(); lexemes.push(Lexeme::Close); }
+            ',' => { chars.next(); lexemes.push(Lexeme::Comma); }
+            '=' => { chars.next(); lexemes.push(Lexeme::Equals); }
+            '|' => { chars.next(); lexemes.push(Lexeme::Pipe); }
+            '"' | '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop{
+                    match chars.next(){
+                        Some(q) if q == c => break,
+                        Some('\\') => text.extend(chars.next()),
+                        Some(other) => text.push(other),
+                        None => return error(line, "a string is never closed"),
+                    }
+                }
+                lexemes.push(Lexeme::Str(text));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.' || **d == '-'){
+                    number.push(d);
+                    chars.next();
+                }
+                let value = number.parse().map_err(|_| TemplateError{ line, message: format!("'{}' is not a number", number) })?;
+                lexemes.push(Lexeme::Num(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&w) = chars.peek().filter(|w| w.is_alphanumeric() || matches!(w, '_' | '-' | '.')){
+                    word.push(w);
+                    chars.next();
+                }
+                lexemes.push(Lexeme::Word(word));
+            }
+            other => return error(line, format!("unexpected '{}'", other)),
+        }
+    }
+    Ok(lexemes)
+}
+
+struct ExprParser{
+    lexemes: Vec<Lexeme>,
+    pos: usize,
+    line: usize,
+}
+
+impl ExprParser{
+    fn peek(&self) -> Option<&Lexeme>{
+        self.lexemes.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Lexeme>{
+        let lexeme = self.lexemes.get(self.pos).cloned();
+        self.pos += 1;
+        lexeme
+    }
+
+    fn expect(&mut self, lexeme: Lexeme, what: &str) -> Result<(),TemplateError>{
+        match self.next(){
+            Some(l) if l == lexeme => Ok(()),
+            _ => error(self.line, format!("expected {}", what)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr,TemplateError>{
+        if self.peek() == Some(&Lexeme::Word("not".to_string())){
+            self.next();
+            return Ok(Expr::Not(Box::new(self.expr()?)));
+        }
+        let value = self.primary()?;
+        let mut filters = Vec::new();
+        while self.peek() == Some(&Lexeme::Pipe){
+            self.next();
+            let Some(Lexeme::Word(name)) = self.next() else {
+                return error(self.line, "expected a filter name after |");
+            };
+            if !FILTERS.contains(&name.as_str()){
+                return error(self.line, format!("unknown filter '{}'", name));
+            }
+            let mut args = Vec::new();
+            if self.peek() == Some(&Lexeme::Open){
+                self.next();
+                while self.peek() != Some(&Lexeme::Close){
+                    args.push(self.primary()?);
+                    if self.peek() == Some(&Lexeme::Comma){
+                        self.next();
+                    }
+                }
+                self.expect(Lexeme::Close, ")")?;
+            }
+            filters.push((name, args));
+        }
+        Ok(match filters.is_empty(){
+            true => value,
+            false => Expr::Filtered{ value: Box::new(value), filters },
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr,TemplateError>{
+        match self.next(){
+            Some(Lexeme::Str(s)) => Ok(Expr::Str(s)),
+            Some(Lexeme::Num(n)) => Ok(Expr::Num(n)),
+            Some(Lexeme::Word(w)) if w == "t" && self.peek() == Some(&Lexeme::Open) => {
+                self.next();
+                let key = self.expr()?;
+                let mut args = Vec::new();
+                while self.peek() == Some(&Lexeme::Comma){
+                    self.next();
+                    let Some(Lexeme::Word(name)) = self.next() else {
+                        return error(self.line, "expected name=value in t()");
+                    };
+                    self.expect(Lexeme::Equals, "= after a t() argument name")?;
+                    args.push((name, self.expr()?));
+                }
+                self.expect(Lexeme::Close, ") to close t(")?;
+                Ok(Expr::Translate{ key: Box::new(key), args })
+            }
+            Some(Lexeme::Word(w)) => Ok(Expr::Path(w.split('.').map(str::to_string).collect())),
+            _ => error(self.line, "expected a value"),
+        }
+    }
+}
+
+fn parse_expr(source: &str, line: usize) -> Result<Expr,TemplateError>{
+    let mut parser = ExprParser{ lexemes: lex(source, line)?, pos: 0, line };
+    let expr = parser.expr()?;
+    if parser.pos < parser.lexemes.len(){
+        return error(line, format!("unexpected text in '{}'", source));
+    }
+    Ok(expr)
+}
+
+// Parts up to one of the `ends` tags, and which one it was
+fn parse_parts(tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>, ends: &[&str], opened: usize) -> Result<(Vec<Part>, Option<String>),TemplateError>{
+    let mut parts = Vec::new();
+    while let Some(token) = tokens.next(){
+        match token{
+            Token::Text(text) => parts.push(Part::Text(text)),
+            Token::Value(source, line) => parts.push(Part::Value{ expr: parse_expr(&source, line)?, line }),
+            Token::Tag(source, line) => {
+                let (keyword, rest) = source.split_once(char::is_whitespace).unwrap_or((&source, ""));
+                match keyword{
+                    end if ends.contains(&end) => return Ok((parts, Some(end.to_string()))),
+                    "if" => {
+                        let condition = parse_expr(rest, line)?;
+                        let (then, end) = parse_parts(tokens, &["else", "endif"], line)?;
+                        let otherwise = match end.as_deref(){
+                            Some("else") => parse_parts(tokens, &["endif"], line)?.0,
+                            _ => Vec::new(),
+                        };
+                        parts.push(Part::If{ condition, then, otherwise, line });
+                    }
+                    "for" => {
+                        let (name, list) = rest.trim().split_once(char::is_whitespace).unwrap_or((rest, ""));
+                        let Some(list) = list.trim_start().strip_prefix("in ") else {
+                            return error(line, "write loops as {% for item in list %}");
+                        };
+                        let list = parse_expr(list, line)?;
+                        let (body, _) = parse_parts(tokens, &["endfor"], line)?;
+                        parts.push(Part::For{ name: name.to_string(), list, body, line });
+                    }
+                    other => return error(line, format!("unexpected {{% {} %}}", other)),
+                }
+            }
+        }
+    }
+    match ends.last(){
+        Some(end) => error(opened, format!("missing {{% {} %}}", end)),
+        None => Ok((parts, None)),
+    }
+}
+
+fn truthy(value: &Value) -> bool{
+    match value{
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn number_text(n: f64) -> String{
+    match n.fract() == 0.0 && n.abs() < 1e15{
+        true => format!("{}", n as i64),
+        false => n.to_string(),
+    }
+}
+
+fn text(value: &Value) -> String{
+    match value{
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => number_text(n.as_f64().unwrap_or_default()),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(", "),
+        Value::Object(_) => value.to_string(),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64>{
+    match value{
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+struct Renderer<'a>{
+    messages: &'a Catalog,
+    html: bool,
+    // Loop variables, innermost last, then the context
+    scopes: Vec<(String, Value)>,
+    root: &'a Value,
+}
+
+impl Renderer<'_>{
+    fn lookup(&self, path: &[String]) -> Value{
+        let first = path[0].as_str();
+        let mut value = self.scopes.iter().rev().find(|(name, _)| name == first).map(|(_, v)| v).unwrap_or_else(|| &self.root[first]);
+        for segment in &path[1..]{
+            value = match (value, segment.parse::<usize>()){
+                (Value::Array(items), Ok(i)) => items.get(i).unwrap_or(&Value::Null),
+                (Value::Object(map), _) => map.get(segment).unwrap_or(&Value::Null),
+                _ => &Value::Null,
+            };
+        }
+        value.clone()
+    }
+
+    // The value, and whether it is to be printed unescaped
+    fn eval(&self, expr: &Expr, line: usize) -> Result<(Value, bool),TemplateError>{
+        Ok(match expr{
+            Expr::Path(path) => (self.lookup(path), false),
+            Expr::Str(s) => (Value::String(s.clone()), false),
+            Expr::Num(n) => (serde_json::json!(n), false),
+            Expr::Not(inner) => (Value::Bool(!truthy(&self.eval(inner, line)?.0)), false),
+            Expr::Translate{ key, args } => {
+                let key = text(&self.eval(key, line)?.0);
+                let mut filled = Vec::new();
+                for (name, arg) in args{
+                    filled.push((name.as_str(), text(&self.eval(arg, line)?.0)));
+                }
+                let args: Vec<(&str, &str)> = filled.iter().map(|(n, v)| (*n, v.as_str())).collect();
+                (Value::String(self.messages.format(&key, &args)), false)
+            }
+            Expr::Filtered{ value, filters } => {
+                let (mut value, mut raw) = self.eval(value, line)?;
+                for (name, args) in filters{
+                    let arg = |i: usize| -> Result<Value,TemplateError>{
+                        match args.get(i){
+                            Some(a) => Ok(self.eval(a, line)?.0),
+                            None => error(line, format!("{} needs an argument", name)),
+                        }
+                    };
+                    let decimals = |i: usize| args.get(i).map(|_| arg(i).map(|v| as_number(&v).unwrap_or(0.0).clamp(0.0, MAX_DECIMALS as f64) as usize)).transpose();
+                    value = match name.as_str(){
+                        "raw" => { raw = true; value }
+                        "escape" => { raw = false; value }
+                        "upper" => Value::String(text(&value).to_uppercase()),
+                        "lower" => Value::String(text(&value).to_lowercase()),
+                        "cell" => Value::String(super::escape_cell(&text(&value))),
+                        "join" => {
+                            let separator = text(&arg(0)?);
+                            match &value{
+                                Value::Array(items) => Value::String(items.iter().map(text).collect::<Vec<_>>().join(&separator)),
+                                _ => value,
+                            }
+                        }
+                        "fixed" => match as_number(&value){
+                            Some(n) => Value::String(format!("{:.*}", decimals(0)?.unwrap_or(0), n)),
+                            None => value,
+                        },
+                        "signed" => match (as_number(&value), decimals(0)?){
+                            (Some(n), Some(d)) => Value::String(format!("{:+.*}", d, n)),
+                            (Some(n), None) if n >= 0.0 => Value::String(format!("+{}", number_text(n))),
+                            (Some(n), None) => Value::String(number_text(n)),
+                            (None, _) => value,
+                        },
+                        "default" => if truthy(&value) { value } else { arg(0)? },
+                        "length" => serde_json::json!(match &value{
+                            Value::Array(a) => a.len(),
+                            Value::Object(o) => o.len(),
+                            Value::String(s) => s.chars().count(),
+                            _ => 0,
+                        }),
+                        other => return error(line, format!("unknown filter '{}'", other)),
+                    };
+                }
+                (value, raw)
+            }
+        })
+    }
+
+    fn render(&mut self, parts: &[Part], out: &mut String) -> Result<(),TemplateError>{
+        for part in parts{
+            match part{
+                Part::Text(t) => out.push_str(t),
+                Part::Value{ expr, line } => {
+                    let (value, raw) = self.eval(expr, *line)?;
+                    match self.html && !raw{
+                        true => out.push_str(&super::escape_html(&text(&value))),
+                        false => out.push_str(&text(&value)),
+                    }
+                }
+                Part::If{ condition, then, otherwise, line } => {
+                    let branch = if truthy(&self.eval(condition, *line)?.0) { then } else { otherwise };
+                    self.render(branch, out)?;
+                }
+                Part::For{ name, list, body, line } => {
+                    let items = match self.eval(list, *line)?.0{
+                        Value::Array(items) => items,
+                        Value::Null => Vec::new(),
+                        _ => return error(*line, format!("{} is not a list", name)),
+                    };
+                    let count = items.len();
+                    for (i, item) in items.into_iter().enumerate(){
+                        self.scopes.push(("loop".to_string(), serde_json::json!({ "index": i + 1, "first": i == 0, "last": i + 1 == count })));
+                        self.scopes.push((name.clone(), item));
+                        let rendered = self.render(body, out);
+                        self.scopes.truncate(self.scopes.len() - 2);
+                        rendered?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Template{
+    pub fn parse(source: &str) -> Result<Template,TemplateError>{
+        let mut tokens = tokenize(source)?.into_iter().peekable();
+        let (parts, _) = parse_parts(&mut tokens, &[], 1)?;
+        Ok(Template{ parts })
+    }
+
+    // HTML templates escape every value not passed through `raw`
+    pub fn render(&self, context: &Value, messages: &Catalog, html: bool) -> Result<String,TemplateError>{
+        let mut renderer = Renderer{ messages, html, scopes: Vec::new(), root: context };
+        let mut out = String::new();
+        renderer.render(&self.parts, &mut out)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use serde_json::json;
+
+    fn render(source: &str, context: Value, html: bool) -> String{
+        Template::parse(source).expect("parses").render(&context, &Catalog::default(), html).expect("renders")
+    }
+
+    fn parse_error(source: &str) -> TemplateError{
+        Template::parse(source).expect_err("does not parse")
+    }
+
+    #[test]
+    fn standalone_tags_leave_no_blank_lines(){
+        let source = "before\n{% if yes %}\ninside\n{% endif %}\n{# note #}\nafter\n";
+        assert_eq!(render(source, json!({ "yes": true }), false), "before\ninside\nafter\n");
+        assert_eq!(render(source, json!({ "yes": false }), false), "before\nafter\n");
+    }
+
+    #[test]
+    fn tags_sharing_a_line_with_text_keep_it(){
+        assert_eq!(render("a {% if yes %}b{% endif %}\nc", json!({ "yes": true }), false), "a b\nc");
+        assert_eq!(render("{{ x }}\n", json!({ "x": 1 }), false), "1\n");
+    }
+
+    #[test]
+    fn html_templates_escape_values_unless_raw(){
+        let context = json!({ "name": "<b>&</b>" });
+        assert_eq!(render("{{ name }}", context.clone(), true), "&lt;b&gt;&amp;&lt;/b&gt;");
+        assert_eq!(render("{{ name | raw }}", context.clone(), true), "<b>&</b>");
+        assert_eq!(render("{{ name | raw | escape }}", context.clone(), true), "&lt;b&gt;&amp;&lt;/b&gt;");
+        assert_eq!(render("{{ name }}", context, false), "<b>&</b>");
+    }
+
+    #[test]
+    fn cells_escape_pipes_and_newlines(){
+        assert_eq!(render("| {{ name | cell }} |", json!({ "name": "a|b\nc" }), false), "| a\\|b c |");
+    }
+
+    #[test]
+    fn loops_expose_their_position(){
+        let source = "{% for item in items %}{{ loop.index }}:{{ item.name }}{% if loop.first %}<{% endif %}{% if not loop.last %},{% endif %}{% endfor %}";
+        let context = json!({ "items": [{ "name": "a" }, { "name": "b" }, { "name": "c" }] });
+        assert_eq!(render(source, context, false), "1:a<,2:b,3:c");
+        assert_eq!(render("{% for i in missing %}x{% endfor %}", json!({}), false), "");
+    }
+
+    #[test]
+    fn nested_loops_see_the_outer_item(){
+        let source = "{% for row in rows %}{% for cell in row.cells %}{{ row.name }}{{ cell }} {% endfor %}{% endfor %}";
+        let context = json!({ "rows": [{ "name": "r", "cells": [1, 2] }] });
+        assert_eq!(render(source, context, false), "r1 r2 ");
+    }
+
+    #[test]
+    fn filters_format_numbers(){
+        let context = json!({ "n": 2.5, "list": ["a", "b"] });
+        assert_eq!(render("{{ n | fixed(2) }} {{ n | signed }} {{ list | join(\"/\") }} {{ list | length }}", context, false), "2.50 +2.5 a/b 2");
+        assert_eq!(render("{{ missing | default(\"-\") }}", json!({}), false), "-");
+    }
+
+    #[test]
+    fn huge_decimal_counts_are_clamped(){
+        let fixed = render("{{ n | fixed(99999999999999999999) }}", json!({ "n": 1 }), false);
+        assert_eq!(fixed, format!("1.{}", "0".repeat(MAX_DECIMALS)));
+        assert_eq!(render("{{ n | signed(-3) }}", json!({ "n": 1.4 }), false), "+1");
+    }
+
+    #[test]
+    fn parse_errors_name_the_line(){
+        assert_eq!(parse_error("a\n{{ x").line, 2);
+        assert_eq!(parse_error("{% if x %}\nbody").message, "missing {% endif %}");
+        assert_eq!(parse_error("{% for x %}{% endfor %}").message, "write loops as {% for item in list %}");
+        assert_eq!(parse_error("{{ x | shout }}").message, "unknown filter 'shout'");
+        assert_eq!(parse_error("{% endif %}").message, "unexpected {% endif %}");
+        assert_eq!(parse_error("{{ \"open }}").message, "a string is never closed");
+        assert_eq!(parse_error("\n\n{{ x y }}").line, 3);
+    }
+}
@@ -0,0 +1,25 @@
+// Property tests over randomly generated graphs
+//
+//   cargo test --features testing
+
+#![cfg(feature = "testing")]
+
+use project_manager::testing::*;
+use proptest::prelude::*;
+
+proptest!{
+    #[test]
+    fn generated_graphs_hold_invariants(graph in arb_graph(4)){
+        prop_assert_eq!(check_all(&graph), Ok(()));
+    }
+
+    #[test]
+    fn invariants_survive_any_sequence_of_ops(mut graph in arb_graph(3), ops in arb_ops(30)){
+        for op in &ops{
+            // Rejected operations must leave the graph valid too
+            let _ = apply_op(&mut graph, op);
+            prop_assert_eq!(check_acyclic(&graph), Ok(()));
+        }
+        prop_assert_eq!(check_all(&graph), Ok(()));
+    }
+}